@@ -1,15 +1,39 @@
 //! Command implementations
 
-use crate::drift::{DriftDetector, DriftEvent, DriftSeverity};
-use crate::extract::{CodeExtractor, DocExtractor};
-use crate::repo::Repository;
-use crate::storage::Database;
+use super::{CheckCliArgs, CleanArgs, EmbedArgs, FixArgs, Style, SymbolSort};
+use crate::drift::{
+    backfill_embeddings, diff_range, scan_repository_with_progress, DocCoverage, DriftConfig,
+    DriftDetector, DriftDiff, DriftEvent, DriftSeverity, HardDriftRules, ScanOptions, ScanPhase,
+    ScanProgress, SimilarityMetric,
+};
+use crate::drift::{
+    doc_relates_to, short_id, similarity, sort_events, Baseline, CliHelp, EmbeddingBackend,
+    LocalEmbedding,
+};
+use crate::error::DocSentinelError;
+use crate::extract::{
+    heading_anchor, CodeChunk, CodeExtractor, DocChunk, DocExtractor, Language, SymbolType,
+};
+use crate::llm::LlmClient;
+use crate::repo::{ConfigOverrides, Repository};
+use crate::storage::{Database, DatabaseExport, DATA_VERSION};
 use anyhow::{Context, Result};
-use std::path::Path;
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
 
 /// Initialize DocSentinel in a repository
-pub fn init(path: &Path, force: bool, quick: bool) -> Result<()> {
-    let repo = Repository::open(path)?;
+pub fn init(
+    path: &Path,
+    scope: Option<&Path>,
+    force: bool,
+    quick: bool,
+    style: Style,
+) -> Result<()> {
+    let repo = Repository::open_scoped(path, scope)?;
 
     let sentinel_dir = repo.sentinel_dir();
     if sentinel_dir.exists() && !force {
@@ -17,14 +41,14 @@ pub fn init(path: &Path, force: bool, quick: bool) -> Result<()> {
     }
 
     if !quick {
-        println!("\n🚀 Initializing DocSentinel...\n");
+        println!("\n{}Initializing DocSentinel...\n", style.icon("🚀 "));
 
         // Auto-detect project type
-        let detected = detect_project_type(path);
-        println!("📦 Project Detection:");
+        let detected = detect_project_type(&repo.scope_dir());
+        println!("{}Project Detection:", style.icon("📦 "));
         for (lang, found) in &detected {
             if *found {
-                println!("   ✓ {} detected", lang);
+                println!("   {} {} detected", style.ok(), lang);
             }
         }
         println!();
@@ -38,26 +62,30 @@ pub fn init(path: &Path, force: bool, quick: bool) -> Result<()> {
     let _db = Database::open(&db_path)?;
 
     // Save default config
-    repo.config().save(repo.root())?;
+    repo.stored_config()?.save(&repo.scope_dir())?;
 
     if quick {
-        println!("✓ DocSentinel initialized");
+        println!("{} DocSentinel initialized", style.ok());
     } else {
-        println!("✅ Setup Complete!");
-        println!("   📁 Config: .docsentinel/config.toml");
-        println!("   🗄️  Database: .docsentinel/docsentinel.db");
+        println!("{}Setup Complete!", style.icon("✅ "));
+        println!("   {}Config: .docsentinel/config.toml", style.icon("📁 "));
+        println!(
+            "   {}Database: .docsentinel/docsentinel.db",
+            style.icon("🗄️  ")
+        );
         println!();
-        println!("📋 Quick Start:");
+        println!("{}Quick Start:", style.icon("📋 "));
         println!("   1. Run initial scan:     docsentinel scan --full");
         println!("   2. View status:          docsentinel status --all");
         println!("   3. Browse docs in TUI:   docsentinel tui");
         println!("   4. Generate API docs:    docsentinel generate --readme");
         println!();
-        println!("⚙️  Configure AI (optional):");
+        println!("{}Configure AI (optional):", style.icon("⚙️  "));
         println!("   Edit .docsentinel/config.toml and set:");
         println!("   - endpoint: http://localhost:11434 (Ollama)");
         println!("   - model: llama2, codellama, or gpt-4");
-        println!("   - api_key: sk-... (for OpenAI)");
+        println!("   Keep api_key (for OpenAI) out of the repository: put it in");
+        println!("   ~/.config/docsentinel/config.toml or DOCSENTINEL_API_KEY");
         println!();
     }
 
@@ -82,175 +110,752 @@ fn detect_project_type(path: &Path) -> Vec<(&'static str, bool)> {
 }
 
 /// Scan the repository for drift
+///
+/// Embeddings are generated for extracted chunks unless `options.embeddings`
+/// is unset or no endpoint is configured. With `with_llm`, detected events are
+/// refined by the configured LLM before being stored. With `reembed`, stored
+/// embeddings are discarded and every file is embedded again. Plain output
+/// hides the progress bar. Progress and the summary are logged through
+/// `tracing` to stderr, leaving stdout for the formatted results.
 pub fn scan(
     path: &Path,
-    full: bool,
-    range: Option<&str>,
-    uncommitted: bool,
+    scope: Option<&Path>,
+    options: &ScanOptions,
+    overrides: &ConfigOverrides,
+    style: Style,
 ) -> Result<Vec<DriftEvent>> {
-    let repo = Repository::open(path)?;
-    let sentinel_dir = repo.sentinel_dir();
+    let repo = Repository::open_scoped(path, scope)?.with_overrides(overrides);
 
-    if !sentinel_dir.exists() {
-        anyhow::bail!("DocSentinel not initialized. Run 'docsentinel init' first.");
+    tracing::info!("Scanning repository...");
+    let progress = ScanProgressBar::new(style);
+    let report = scan_repository_with_progress(&repo, options, &progress);
+    progress.bar.finish_and_clear();
+    let report = report?;
+    let stats = &report.stats;
+
+    if let Some(ref from) = stats.from_commit {
+        tracing::info!("From: {}", from);
+    }
+    tracing::info!("To: {}", stats.to_commit);
+    if let Some(ref base) = stats.base_commit {
+        tracing::info!("Compared against: {}", base);
+    }
+    if options.uncommitted {
+        tracing::info!("Uncommitted files: {}", stats.uncommitted_files);
+    }
+    tracing::info!("Code files changed: {}", stats.code_files);
+    tracing::info!("Doc files changed: {}", stats.doc_files);
+    if stats.config_files > 0 {
+        tracing::info!("Config files checked: {}", stats.config_files);
+    }
+    tracing::info!("Code chunks: {}", report.code_chunks.len());
+    if stats.unchanged_chunks > 0 {
+        tracing::info!(
+            "Unchanged chunks kept: {} (outside the diff)",
+            stats.unchanged_chunks
+        );
+    }
+    tracing::info!("Doc chunks: {}", report.doc_chunks.len());
+    if stats.embedded {
+        tracing::info!("Generated embeddings");
+    }
+    if stats.llm_refined > 0 {
+        tracing::info!("Analyzed {} event(s) with LLM", stats.llm_refined);
     }
 
-    let db_path = sentinel_dir.join("docsentinel.db");
-    let db = Database::open(&db_path)?;
+    for warning in &report.warnings {
+        tracing::warn!("{}", warning);
+    }
+    if let Some(ref mismatch) = report.embedding_mismatch {
+        tracing::warn!(
+            "Run 'docsentinel scan --reembed' to re-embed all chunks with {}",
+            mismatch.model
+        );
+    }
 
-    // Determine what to scan
-    let (from_commit, to_commit) = if let Some(range_str) = range {
-        // Parse range like "HEAD~5..HEAD"
-        let parts: Vec<&str> = range_str.split("..").collect();
-        if parts.len() == 2 {
-            (Some(parts[0].to_string()), parts[1].to_string())
-        } else {
-            (None, range_str.to_string())
-        }
-    } else if full {
-        (None, repo.head_commit()?)
+    if stats.interrupted {
+        tracing::warn!("{} Scan interrupted", style.warn());
     } else {
-        let last_scan = db.get_last_scan_commit()?;
-        (last_scan, repo.head_commit()?)
-    };
+        tracing::info!("{} Scan complete", style.ok());
+    }
+    tracing::info!("Drift events detected: {}", report.events.len());
+
+    Ok(report.events)
+}
 
-    println!("Scanning repository...");
-    if let Some(ref from) = from_commit {
-        println!("  From: {}", from);
+/// Generate embeddings for stored chunks
+///
+/// With `backfill`, chunks stored without an embedding are embedded with the
+/// configured provider, showing a progress bar that Ctrl-C interrupts.
+pub fn embed(
+    path: &Path,
+    scope: Option<&Path>,
+    args: &EmbedArgs,
+    overrides: &ConfigOverrides,
+    style: Style,
+) -> Result<()> {
+    if !args.backfill {
+        anyhow::bail!("Nothing to embed. Pass --backfill.");
     }
-    println!("  To: {}", to_commit);
+    let repo = Repository::open_scoped(path, scope)?.with_overrides(overrides);
 
-    // Get changed files from commits
-    let mut changes = repo.changes_between(from_commit.as_deref(), &to_commit)?;
+    let progress = ScanProgressBar::new(style);
+    let report = backfill_embeddings(&repo, &progress);
+    progress.bar.finish_and_clear();
+    let report = report?;
 
-    // Include uncommitted changes if requested
-    if uncommitted {
-        let uncommitted_changes = repo.uncommitted_changes()?;
-        println!("  Uncommitted files: {}", uncommitted_changes.len());
-        // Merge uncommitted changes, avoiding duplicates
-        for uc in uncommitted_changes {
-            if !changes.iter().any(|c| c.path == uc.path) {
-                changes.push(uc);
-            }
+    if let Some(ref mismatch) = report.embedding_mismatch {
+        eprintln!(
+            "Warning: {}; similarity against older chunks is unreliable until they are re-embedded",
+            mismatch
+        );
+        eprintln!(
+            "  Run 'docsentinel scan --reembed' to re-embed all chunks with {}",
+            mismatch.model
+        );
+    }
+    if report.remaining > 0 {
+        println!(
+            "{} Embedding interrupted, {} chunk(s) still without an embedding",
+            style.warn(),
+            report.remaining
+        );
+    }
+    println!(
+        "{} Embedded {} code chunk(s) and {} doc chunk(s)",
+        style.ok(),
+        report.code_chunks,
+        report.doc_chunks
+    );
+    Ok(())
+}
+
+/// Set by the Ctrl-C handler, which is installed once per process
+static INTERRUPTED: OnceLock<Arc<AtomicBool>> = OnceLock::new();
+
+/// Flag set by Ctrl-C, installing the handler on first use
+///
+/// The first Ctrl-C only sets the flag, so long-running commands can stop at
+/// a safe point; a second one exits immediately with status 130.
+pub fn interrupt_flag() -> Arc<AtomicBool> {
+    INTERRUPTED
+        .get_or_init(|| {
+            let flag = Arc::new(AtomicBool::new(false));
+            let handler_flag = Arc::clone(&flag);
+            // Without a handler (e.g. one is already installed) Ctrl-C keeps
+            // its default behavior
+            let _ = ctrlc::set_handler(move || {
+                if handler_flag.swap(true, Ordering::SeqCst) {
+                    std::process::exit(130);
+                }
+            });
+            flag
+        })
+        .clone()
+}
+
+/// Progress bar for `scan` on stderr, cancelled by Ctrl-C
+///
+/// The first Ctrl-C asks the scan to stop and save what it has; a second one
+/// exits immediately.
+struct ScanProgressBar {
+    bar: ProgressBar,
+    interrupted: Arc<AtomicBool>,
+}
+
+impl ScanProgressBar {
+    fn new(style: Style) -> Self {
+        let interrupted = interrupt_flag();
+        interrupted.store(false, Ordering::SeqCst);
+
+        let bar = if style.is_fancy() {
+            ProgressBar::new(0)
+        } else {
+            ProgressBar::hidden()
+        };
+        if let Ok(style) = ProgressStyle::with_template("{prefix:>10} [{bar:30}] {pos}/{len} {msg}")
+        {
+            bar.set_style(style.progress_chars("=> "));
         }
+
+        Self { bar, interrupted }
+    }
+}
+
+impl ScanProgress for ScanProgressBar {
+    fn start_phase(&self, phase: ScanPhase, total: usize) {
+        self.bar.set_prefix(phase.to_string());
+        self.bar.set_length(total as u64);
+        self.bar.set_position(0);
+    }
+
+    fn advance(&self, count: usize) {
+        self.bar.inc(count as u64);
     }
 
-    let code_changes: Vec<_> = changes.iter().filter(|c| c.is_code()).collect();
-    let doc_changes: Vec<_> = changes.iter().filter(|c| c.is_documentation()).collect();
+    fn cancelled(&self) -> bool {
+        let cancelled = self.interrupted.load(Ordering::SeqCst);
+        if cancelled {
+            self.bar
+                .set_message("interrupted, saving progress (Ctrl-C again to abort)");
+        }
+        cancelled
+    }
+}
 
-    println!("  Code files changed: {}", code_changes.len());
-    println!("  Doc files changed: {}", doc_changes.len());
+/// Compare drift between two revisions
+///
+/// `range` is `BASE..HEAD`; a bare revision is compared against `HEAD`. Only
+/// symbols changed in the range are checked, and the repository does not need
+/// to be initialized.
+pub fn diff(path: &Path, scope: Option<&Path>, range: &str) -> Result<DriftDiff> {
+    let repo = Repository::open_scoped(path, scope)?;
+    let (base, head) = match range.split_once("..") {
+        Some((base, head)) => (base, if head.is_empty() { "HEAD" } else { head }),
+        None => (range, "HEAD"),
+    };
+    if base.is_empty() || head.starts_with('.') {
+        anyhow::bail!("Invalid range '{}', expected BASE..HEAD", range);
+    }
 
-    // Extract code chunks
-    let mut code_extractor = CodeExtractor::new()?;
-    let mut all_code_chunks = Vec::new();
+    let diff = diff_range(&repo, base, head)?;
+    for warning in &diff.warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    Ok(diff)
+}
 
-    for change in &code_changes {
-        if let Some(content) = repo.read_file_current(&change.path)? {
-            match code_extractor.extract_file(&change.path, &content) {
-                Ok(chunks) => {
-                    for chunk in chunks {
-                        db.upsert_code_chunk(&chunk)?;
-                        all_code_chunks.push(chunk);
-                    }
-                }
-                Err(e) => {
-                    eprintln!("Warning: Failed to extract {:?}: {}", change.path, e);
-                }
+/// Print a drift diff in text format
+pub fn print_diff_text(diff: &DriftDiff) {
+    println!("Drift in {}..{}", diff.base, diff.head);
+    println!("  Code files changed: {}", diff.code_files);
+    println!("  Doc files changed: {}", diff.doc_files);
+    println!("  New drift: {}", diff.introduced.len());
+    println!("  Resolved drift: {}", diff.resolved.len());
+    println!("  Pre-existing drift: {}", diff.pre_existing.len());
+
+    for (title, events) in [
+        ("New drift", &diff.introduced),
+        ("Resolved drift", &diff.resolved),
+        ("Pre-existing drift", &diff.pre_existing),
+    ] {
+        if events.is_empty() {
+            continue;
+        }
+        println!("\n{} ({}):", title, events.len());
+        for event in events {
+            println!("  [{}] {}", event.severity, event.description);
+            if let Some(location) = event.location() {
+                println!("     Location: {}", location);
             }
         }
     }
+}
 
-    // Extract doc chunks
-    let doc_extractor = DocExtractor::new();
-    let mut all_doc_chunks = Vec::new();
+/// Print a drift diff in JSON format
+pub fn print_diff_json(diff: &DriftDiff, compact: bool) -> Result<()> {
+    print_json(diff, compact)
+}
 
-    for change in &doc_changes {
-        if let Some(content) = repo.read_file_current(&change.path)? {
-            match doc_extractor.extract_file(&change.path, &content) {
-                Ok(chunks) => {
-                    for chunk in chunks {
-                        db.upsert_doc_chunk(&chunk)?;
-                        all_doc_chunks.push(chunk);
-                    }
-                }
-                Err(e) => {
-                    eprintln!("Warning: Failed to extract {:?}: {}", change.path, e);
-                }
-            }
+/// A symbol found in an editor buffer
+#[derive(Debug, Clone, Serialize)]
+pub struct BufferSymbol {
+    /// Chunk ID (`path::symbol`), matching the stored chunk once scanned
+    pub id: String,
+    pub symbol_name: String,
+    pub symbol_type: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub is_public: bool,
+    pub signature: Option<String>,
+    pub doc_comment: Option<String>,
+    /// Stored doc sections referencing the symbol (only with `--docs`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub related_docs: Option<Vec<BufferDocReference>>,
+}
+
+/// A stored doc section that references a buffer symbol
+#[derive(Debug, Clone, Serialize)]
+pub struct BufferDocReference {
+    pub id: String,
+    pub file_path: String,
+    pub heading: String,
+    pub start_line: usize,
+}
+
+/// Extract the symbols of an unsaved buffer
+///
+/// `virtual_path` is where the buffer lives in the repository; it names the
+/// chunks and picks the language when `language` is not given, but is never
+/// read. With `with_docs`, each symbol lists the stored doc sections that link
+/// to it, show its signature, or mention it in inline code, which requires an
+/// initialized repository.
+pub fn analyze_buffer(
+    path: &Path,
+    scope: Option<&Path>,
+    virtual_path: Option<&str>,
+    language: Option<Language>,
+    source: &str,
+    with_docs: bool,
+) -> Result<Vec<BufferSymbol>> {
+    let extension = virtual_path
+        .and_then(|p| Path::new(p).extension())
+        .and_then(|e| e.to_str());
+    let language = language
+        .or_else(|| extension.and_then(Language::from_extension))
+        .context("Cannot tell the buffer's language; pass --lang")?;
+
+    let mut db = None;
+    let mut chunk_path = PathBuf::from(virtual_path.unwrap_or("<stdin>"));
+    if with_docs {
+        let repo = Repository::open_scoped(path, scope)?;
+        if !repo.sentinel_dir().exists() {
+            return Err(DocSentinelError::NotInitialized.into());
+        }
+        // Editors usually send absolute paths; stored chunks use relative ones
+        if let Ok(relative) = chunk_path.strip_prefix(repo.root()) {
+            chunk_path = relative.to_path_buf();
         }
+        db = Some(Database::open(repo.sentinel_dir().join("docsentinel.db"))?);
     }
 
-    println!("  Code chunks: {}", all_code_chunks.len());
-    println!("  Doc chunks: {}", all_doc_chunks.len());
+    let chunks = CodeExtractor::new()?.extract_source(&chunk_path, source, language)?;
+    chunks
+        .into_iter()
+        .map(|chunk| {
+            let related_docs = db
+                .as_ref()
+                .map(|db| referencing_docs(db, &chunk))
+                .transpose()?;
+            Ok(BufferSymbol {
+                id: chunk.id,
+                symbol_name: chunk.symbol_name,
+                symbol_type: chunk.symbol_type.to_string(),
+                start_line: chunk.start_line,
+                end_line: chunk.end_line,
+                is_public: chunk.is_public,
+                signature: chunk.signature,
+                doc_comment: chunk.doc_comment,
+                related_docs,
+            })
+        })
+        .collect()
+}
 
-    // Detect drift
-    let _detector = DriftDetector::new();
+/// A stored symbol in the `analyze --all` inventory
+#[derive(Debug, Clone, Serialize)]
+pub struct InventorySymbol {
+    /// Stored chunk ID (`path::symbol`)
+    pub id: String,
+    pub file_path: String,
+    pub symbol_name: String,
+    pub symbol_type: String,
+    pub is_public: bool,
+    pub signature: Option<String>,
+    pub start_line: usize,
+    pub end_line: usize,
+    /// Stored doc sections referencing the symbol (only with `--docs`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub related_docs: Option<Vec<BufferDocReference>>,
+}
 
-    // For now, use a simplified detection without embeddings
-    let mut events = Vec::new();
+/// List every stored code symbol, ordered by file and line
+///
+/// Uses the chunks from the last scan. With `with_docs`, each symbol lists
+/// the stored doc sections that link to it, show its signature, or mention
+/// it in inline code.
+pub fn symbol_inventory(
+    path: &Path,
+    scope: Option<&Path>,
+    with_docs: bool,
+) -> Result<Vec<InventorySymbol>> {
+    let repo = Repository::open_scoped(path, scope)?;
+    if !repo.sentinel_dir().exists() {
+        return Err(DocSentinelError::NotInitialized.into());
+    }
+    let db = Database::open(repo.sentinel_dir().join("docsentinel.db"))?;
+
+    let mut chunks = db.get_all_code_chunks()?;
+    chunks.sort_by(|a, b| {
+        (&a.file_path, a.start_line, &a.symbol_name).cmp(&(
+            &b.file_path,
+            b.start_line,
+            &b.symbol_name,
+        ))
+    });
+
+    chunks
+        .into_iter()
+        .map(|chunk| {
+            let related_docs = with_docs
+                .then(|| referencing_docs(&db, &chunk))
+                .transpose()?;
+            Ok(InventorySymbol {
+                id: chunk.id,
+                file_path: chunk.file_path,
+                symbol_name: chunk.symbol_name,
+                symbol_type: chunk.symbol_type.to_string(),
+                is_public: chunk.is_public,
+                signature: chunk.signature,
+                start_line: chunk.start_line,
+                end_line: chunk.end_line,
+                related_docs,
+            })
+        })
+        .collect()
+}
 
-    // Check for code changes without corresponding doc changes
-    if !code_changes.is_empty() && doc_changes.is_empty() {
-        for code_change in &code_changes {
-            // Check if this is a public API file
-            let chunks: Vec<_> = all_code_chunks
-                .iter()
-                .filter(|c| c.file_path == code_change.path.to_string_lossy())
-                .filter(|c| c.is_public)
-                .collect();
+/// Print the symbol inventory as a table, one symbol per row
+///
+/// Referencing doc sections, when listed, follow their symbol's row.
+pub fn print_inventory_text(symbols: &[InventorySymbol], style: Style) {
+    print!("{}", render_inventory_text(symbols, style));
+}
 
-            if !chunks.is_empty() {
-                let event = DriftEvent::new(
-                    DriftSeverity::Medium,
-                    &format!(
-                        "Code changed in {:?} but no documentation was updated",
-                        code_change.path
-                    ),
-                    &format!(
-                        "{} public symbols modified: {}",
-                        chunks.len(),
-                        chunks
-                            .iter()
-                            .map(|c| c.symbol_name.as_str())
-                            .collect::<Vec<_>>()
-                            .join(", ")
-                    ),
-                    0.7,
-                );
-                events.push(event);
-            }
+/// Render the symbol inventory as a table, one symbol per row
+pub fn render_inventory_text(symbols: &[InventorySymbol], style: Style) -> String {
+    let location = |s: &InventorySymbol| format!("{}:{}-{}", s.file_path, s.start_line, s.end_line);
+    let location_width = symbols
+        .iter()
+        .map(|s| location(s).len())
+        .chain(["LOCATION".len()])
+        .max()
+        .unwrap_or(0);
+    let type_width = symbols
+        .iter()
+        .map(|s| s.symbol_type.len())
+        .chain(["TYPE".len()])
+        .max()
+        .unwrap_or(0);
+
+    let mut output = format!(
+        "{:<location_width$}  {:<7}  {:<type_width$}  SYMBOL\n",
+        "LOCATION", "VIS", "TYPE"
+    );
+    for symbol in symbols {
+        output.push_str(&format!(
+            "{:<location_width$}  {:<7}  {:<type_width$}  {}\n",
+            location(symbol),
+            if symbol.is_public {
+                "public"
+            } else {
+                "private"
+            },
+            symbol.symbol_type,
+            symbol.symbol_name
+        ));
+        for doc in symbol.related_docs.iter().flatten() {
+            output.push_str(&format!(
+                "    {} {}:{} ({})\n",
+                style.bullet(),
+                doc.file_path,
+                doc.start_line,
+                doc.heading
+            ));
         }
     }
+    output.push_str(&format!("\n{} symbol(s)\n", symbols.len()));
+    output
+}
+
+/// Stored doc sections ranked by embedding similarity to one symbol
+#[derive(Debug, Clone)]
+pub struct SymbolSimilarity {
+    /// Stored chunk ID (`path::symbol`)
+    pub id: String,
+    pub symbol_name: String,
+    /// Doc sections with their scores, most similar first
+    pub docs: Vec<(DocChunk, f64)>,
+}
+
+/// Rank doc sections with embeddings by similarity to `embedding`
+///
+/// Returns at most `limit` sections, most similar first.
+pub fn most_similar_docs(
+    docs: &[DocChunk],
+    embedding: &[f32],
+    metric: SimilarityMetric,
+    limit: usize,
+) -> Vec<(DocChunk, f64)> {
+    let mut scored: Vec<(DocChunk, f64)> = docs
+        .iter()
+        .filter_map(|doc| {
+            let doc_embedding = doc.embedding.as_ref()?;
+            Some((doc.clone(), similarity(embedding, doc_embedding, metric)))
+        })
+        .collect();
+    scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    scored.truncate(limit);
+    scored
+}
+
+/// Rank stored doc sections against each embedded symbol of a source file
+///
+/// `file` may be absolute or relative to the current directory. Symbols come
+/// from the last scan, so unscanned edits are not reflected, and symbols
+/// without a stored embedding are left out. An empty result means there is
+/// nothing to compare: no embedded symbols in the file or no embedded docs.
+pub fn file_similarity(
+    repo: &Repository,
+    db: &Database,
+    file: &Path,
+    limit: usize,
+) -> Result<Vec<SymbolSimilarity>> {
+    let docs = db.get_all_doc_chunks_with_embeddings()?;
+    if docs.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Stored chunks use paths relative to the repository root
+    let relative = file
+        .canonicalize()
+        .ok()
+        .zip(repo.root().canonicalize().ok())
+        .and_then(|(file, root)| file.strip_prefix(root).ok().map(Path::to_path_buf))
+        .unwrap_or_else(|| file.to_path_buf());
+
+    let metric = repo.config().drift.similarity_metric;
+    Ok(db
+        .get_code_chunks_for_file(&relative.to_string_lossy())?
+        .into_iter()
+        .filter_map(|chunk| {
+            let docs = most_similar_docs(&docs, chunk.embedding.as_ref()?, metric, limit);
+            Some(SymbolSimilarity {
+                id: chunk.id,
+                symbol_name: chunk.symbol_name,
+                docs,
+            })
+        })
+        .collect())
+}
+
+/// Stored doc sections that reference a symbol, as listed by `analyze`
+///
+/// Every way of referencing a symbol spells out its bare name, so the
+/// database narrows the sections down to those containing it first.
+fn referencing_docs(db: &Database, chunk: &CodeChunk) -> Result<Vec<BufferDocReference>> {
+    Ok(db
+        .get_doc_chunks_referencing_symbol(bare_symbol_name(chunk))?
+        .iter()
+        .filter(|doc| references_symbol(doc, chunk))
+        .map(|doc| BufferDocReference {
+            id: doc.id.clone(),
+            file_path: doc.file_path.clone(),
+            heading: doc.heading.clone(),
+            start_line: doc.start_line,
+        })
+        .collect())
+}
+
+/// A symbol's name without its `Type::` or `Class.` prefix
+fn bare_symbol_name(chunk: &CodeChunk) -> &str {
+    chunk
+        .symbol_name
+        .rsplit(['.', ':'])
+        .next()
+        .unwrap_or(&chunk.symbol_name)
+}
+
+/// Whether a doc section links to, shows the signature of, or names a symbol
+/// in inline code
+fn references_symbol(doc: &DocChunk, chunk: &CodeChunk) -> bool {
+    let name = bare_symbol_name(chunk);
+    doc_relates_to(doc, chunk)
+        || doc.content.contains(&format!("`{}`", name))
+        || doc.content.contains(&format!("`{}(", name))
+}
+
+/// Structured result of `analyze` for `--format json`
+#[derive(Debug, Clone, Serialize)]
+pub struct AnalyzeReport {
+    /// The file path or symbol ID that was analyzed
+    pub target: String,
+    /// `code`, `doc`, or `symbol`; `unsupported` for other files and
+    /// `not_found` when nothing matched
+    pub kind: String,
+    pub symbols: Vec<AnalyzedSymbol>,
+    pub sections: Vec<AnalyzedSection>,
+}
+
+/// A code symbol in an [`AnalyzeReport`]
+#[derive(Debug, Clone, Serialize)]
+pub struct AnalyzedSymbol {
+    pub id: String,
+    pub symbol_name: String,
+    pub symbol_type: String,
+    pub file_path: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub is_public: bool,
+    pub signature: Option<String>,
+    pub doc_comment: Option<String>,
+    /// Most similar stored doc sections (only with `--docs`/`--similarity`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub related_docs: Option<Vec<ScoredDoc>>,
+}
+
+/// A stored doc section with its similarity to a symbol
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoredDoc {
+    pub id: String,
+    pub file_path: String,
+    pub section: String,
+    pub score: f64,
+}
+
+/// A doc section in an [`AnalyzeReport`]
+#[derive(Debug, Clone, Serialize)]
+pub struct AnalyzedSection {
+    pub id: String,
+    pub section: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub level: String,
+}
 
-    // Store drift events
-    for event in &events {
-        db.insert_drift_event(event)?;
+/// Analyze a source or doc file, or a stored symbol, for `--format json`
+///
+/// `target` is a file path (relative to the current directory) or a stored
+/// chunk ID. With `with_docs`, symbols list their most similar stored doc
+/// sections with scores; symbols without an embedding get an empty list.
+pub fn analyze_target(
+    path: &Path,
+    scope: Option<&Path>,
+    target: &str,
+    with_docs: bool,
+) -> Result<AnalyzeReport> {
+    let repo = Repository::open_scoped(path, scope)?;
+    if !repo.sentinel_dir().exists() {
+        return Err(DocSentinelError::NotInitialized.into());
     }
+    let db = Database::open(repo.sentinel_dir().join("docsentinel.db"))?;
 
-    // Update last scan commit
-    db.set_last_scan_commit(&to_commit)?;
+    let mut report = AnalyzeReport {
+        target: target.to_string(),
+        kind: "not_found".to_string(),
+        symbols: Vec::new(),
+        sections: Vec::new(),
+    };
+    let scored = |docs: &[(DocChunk, f64)]| {
+        docs.iter()
+            .map(|(doc, score)| ScoredDoc {
+                id: doc.id.clone(),
+                file_path: doc.file_path.clone(),
+                section: doc.full_path(),
+                score: *score,
+            })
+            .collect::<Vec<_>>()
+    };
+    let analyzed = |chunk: CodeChunk, related_docs: Option<Vec<ScoredDoc>>| AnalyzedSymbol {
+        id: chunk.id,
+        symbol_name: chunk.symbol_name,
+        symbol_type: chunk.symbol_type.to_string(),
+        file_path: chunk.file_path,
+        start_line: chunk.start_line,
+        end_line: chunk.end_line,
+        is_public: chunk.is_public,
+        signature: chunk.signature,
+        doc_comment: chunk.doc_comment,
+        related_docs,
+    };
 
-    println!("\n✓ Scan complete");
-    println!("  Drift events detected: {}", events.len());
+    let target_path = Path::new(target);
+    if target_path.exists() {
+        let content = std::fs::read_to_string(target_path)?;
+        let ext = target_path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("");
+
+        if matches!(ext, "rs" | "py") {
+            report.kind = "code".to_string();
+            let similarities = if with_docs {
+                file_similarity(&repo, &db, target_path, 5)?
+            } else {
+                Vec::new()
+            };
+            let chunks = CodeExtractor::new()?.extract_file(target_path, &content)?;
+            report.symbols = chunks
+                .into_iter()
+                .map(|chunk| {
+                    let related = with_docs.then(|| {
+                        similarities
+                            .iter()
+                            .find(|s| s.symbol_name == chunk.symbol_name)
+                            .map(|s| scored(&s.docs))
+                            .unwrap_or_default()
+                    });
+                    analyzed(chunk, related)
+                })
+                .collect();
+        } else if matches!(ext, "md" | "mdx" | "rst") {
+            report.kind = "doc".to_string();
+            report.sections = DocExtractor::new()
+                .extract_file(target_path, &content)?
+                .into_iter()
+                .map(|chunk| AnalyzedSection {
+                    section: chunk.full_path(),
+                    id: chunk.id,
+                    start_line: chunk.start_line,
+                    end_line: chunk.end_line,
+                    level: chunk.level.to_string(),
+                })
+                .collect();
+        } else {
+            report.kind = "unsupported".to_string();
+        }
+    } else if let Some(chunk) = db.get_code_chunk(target)? {
+        report.kind = "symbol".to_string();
+        let related = if with_docs {
+            let docs = db.get_all_doc_chunks_with_embeddings()?;
+            let metric = repo.config().drift.similarity_metric;
+            Some(
+                chunk
+                    .embedding
+                    .as_ref()
+                    .map(|embedding| scored(&most_similar_docs(&docs, embedding, metric, 5)))
+                    .unwrap_or_default(),
+            )
+        } else {
+            None
+        };
+        report.symbols.push(analyzed(chunk, related));
+    }
 
-    Ok(events)
+    Ok(report)
 }
 
 /// Show status of drift issues
-pub fn status(path: &Path, _all: bool, severity: Option<&str>) -> Result<()> {
-    let repo = Repository::open(path)?;
+///
+/// With `all`, resolved (ignored, fixed, accepted) events are listed too, each
+/// with its status. With `detail_context`, each event also shows its related
+/// doc sections, `N` lines either side of the event's line, and the signatures
+/// of its related code.
+pub fn status(
+    path: &Path,
+    scope: Option<&Path>,
+    all: bool,
+    severity: Option<&str>,
+    tag: Option<&str>,
+    detail_context: Option<usize>,
+    style: Style,
+) -> Result<()> {
+    let repo = Repository::open_scoped(path, scope)?;
     let sentinel_dir = repo.sentinel_dir();
 
     if !sentinel_dir.exists() {
-        anyhow::bail!("DocSentinel not initialized. Run 'docsentinel init' first.");
+        return Err(DocSentinelError::NotInitialized.into());
     }
 
     let db_path = sentinel_dir.join("docsentinel.db");
     let db = Database::open(&db_path)?;
 
     let stats = db.get_stats()?;
-    let events = db.get_unresolved_drift_events()?;
+    let events = if all {
+        db.get_all_drift_events()?
+    } else {
+        db.get_unresolved_drift_events()?
+    };
 
     println!("DocSentinel Status");
     println!("==================\n");
@@ -260,259 +865,1795 @@ pub fn status(path: &Path, _all: bool, severity: Option<&str>) -> Result<()> {
     println!("Doc chunks: {}", stats.doc_chunks);
     println!("Total drift events: {}", stats.drift_events);
     println!("Pending events: {}", stats.pending_events);
+    println!("Database size: {} KiB", stats.size_bytes / 1024);
 
     if events.is_empty() {
-        println!("\n✓ No pending drift issues!");
+        if all {
+            println!("\n{} No drift issues recorded!", style.ok());
+        } else {
+            println!("\n{} No pending drift issues!", style.ok());
+        }
         return Ok(());
     }
 
-    println!("\nPending Issues:");
-    println!("---------------\n");
-
-    for event in &events {
-        // Filter by severity if specified
-        if let Some(sev) = severity {
-            let event_sev = format!("{:?}", event.severity).to_lowercase();
-            if !event_sev.contains(&sev.to_lowercase()) {
-                continue;
-            }
-        }
-
-        let severity_icon = match event.severity {
-            DriftSeverity::Critical => "🔴",
-            DriftSeverity::High => "🟠",
-            DriftSeverity::Medium => "🟡",
-            DriftSeverity::Low => "🟢",
-        };
+    if all {
+        println!("\nAll Issues:");
+        println!("-----------\n");
+    } else {
+        println!("\nPending Issues:");
+        println!("---------------\n");
+    }
 
+    for event in events
+        .iter()
+        .filter(|event| status_filter_matches(event, severity, tag))
+    {
         println!(
-            "{} [{}] {}",
-            severity_icon, event.severity, event.description
+            "{} {}",
+            style.severity_label(event.severity),
+            event.description
         );
-        println!("   ID: {}", &event.id[..8]);
+        println!("   ID: {}", short_id(&event.id));
+        if all {
+            println!("   Status: {}", event.status);
+        }
+        if let Some(location) = event.location() {
+            println!("   Location: {}", location);
+        }
+        if !event.tags.is_empty() {
+            println!("   Tags: {}", event.tags.join(", "));
+        }
         println!("   Confidence: {:.0}%", event.confidence * 100.0);
         println!("   Evidence: {}", event.evidence);
+        if let Some(context) = detail_context {
+            for line in event_detail_lines(&db, event, context)? {
+                println!("   {}", line);
+            }
+        }
         println!();
     }
 
     Ok(())
 }
 
-/// Apply a fix to a drift issue
-pub fn fix(path: &Path, issue_id: &str, content: Option<&str>, commit: bool) -> Result<()> {
-    let repo = Repository::open(path)?;
-    let sentinel_dir = repo.sentinel_dir();
+/// Lines showing the commit behind an event and the stored doc sections and
+/// code it relates to
+///
+/// Doc sections are windowed to `context` lines around the event's line when
+/// it falls inside the section, and around the heading otherwise. Chunks that
+/// are no longer stored are reported by ID.
+fn event_detail_lines(db: &Database, event: &DriftEvent, context: usize) -> Result<Vec<String>> {
+    let mut lines = Vec::new();
+
+    if let Some(commit) = &event.introduced_by {
+        lines.push(format!("Code last changed in: {}", commit));
+    }
 
-    if !sentinel_dir.exists() {
-        anyhow::bail!("DocSentinel not initialized. Run 'docsentinel init' first.");
+    for id in &event.related_doc_chunks {
+        let Some(chunk) = db.get_doc_chunk(id)? else {
+            lines.push(format!("Doc: {} (no longer stored)", id));
+            continue;
+        };
+        lines.push(format!(
+            "Doc: {}:{}-{} ({})",
+            chunk.file_path, chunk.start_line, chunk.end_line, chunk.heading
+        ));
+        let focus = event
+            .primary_line
+            .filter(|line| {
+                event.primary_file.as_deref() == Some(chunk.file_path.as_str())
+                    && (chunk.start_line..=chunk.end_line).contains(line)
+            })
+            .unwrap_or(chunk.start_line);
+        let first = focus.saturating_sub(context).max(chunk.start_line);
+        let last = (focus + context).min(chunk.end_line);
+        for (offset, text) in chunk.content.lines().enumerate() {
+            let line = chunk.start_line + offset;
+            if (first..=last).contains(&line) {
+                lines.push(format!("{:>5} | {}", line, text));
+            }
+        }
     }
 
-    let db_path = sentinel_dir.join("docsentinel.db");
-    let db = Database::open(&db_path)?;
+    for id in &event.related_code_chunks {
+        let Some(chunk) = db.get_code_chunk(id)? else {
+            lines.push(format!("Code: {} (no longer stored)", id));
+            continue;
+        };
+        lines.push(format!(
+            "Code: {}:{} {}",
+            chunk.file_path, chunk.start_line, chunk.symbol_name
+        ));
+        let signature = chunk
+            .signature
+            .as_deref()
+            .or_else(|| chunk.content.lines().next())
+            .unwrap_or_default();
+        lines.push(format!("{:>5} | {}", chunk.start_line, signature));
+    }
 
-    // Find the event
-    let event = db
-        .get_drift_event(issue_id)
-        .context("Failed to find drift event")?
-        .ok_or_else(|| anyhow::anyhow!("Drift event not found: {}", issue_id))?;
+    Ok(lines)
+}
 
-    println!("Fixing: {}", event.description);
+/// Whether `event` passes the `status` severity and tag filters
+fn status_filter_matches(event: &DriftEvent, severity: Option<&str>, tag: Option<&str>) -> bool {
+    if let Some(sev) = severity {
+        let event_sev = format!("{:?}", event.severity).to_lowercase();
+        if !event_sev.contains(&sev.to_lowercase()) {
+            return false;
+        }
+    }
+    tag.is_none_or(|tag| event.has_tag(tag))
+}
 
-    // Get the fix content
-    let fix_content = if let Some(c) = content {
-        c.to_string()
-    } else if let Some(ref suggested) = event.suggested_fix {
-        suggested.clone()
-    } else {
-        anyhow::bail!("No fix content provided and no suggested fix available");
-    };
+/// Show drift statistics by file and severity
+pub fn stats(path: &Path, scope: Option<&Path>, top: usize) -> Result<()> {
+    let repo = Repository::open_scoped(path, scope)?;
+    let sentinel_dir = repo.sentinel_dir();
 
-    // Apply the fix
-    if let Some(doc_id) = event.related_doc_chunks.first() {
-        if let Some(doc_chunk) = db.get_doc_chunk(doc_id)? {
-            let file_path = repo.root().join(&doc_chunk.file_path);
+    if !sentinel_dir.exists() {
+        return Err(DocSentinelError::NotInitialized.into());
+    }
 
-            // Read current content
-            let current = std::fs::read_to_string(&file_path)?;
+    let db_path = sentinel_dir.join("docsentinel.db");
+    let db = Database::open(&db_path)?;
 
-            // Replace the section
-            let updated = current.replace(&doc_chunk.content, &fix_content);
+    let stats = db.get_stats()?;
+    let by_file = db.get_drift_by_file()?;
+    let histogram = db.get_severity_histogram()?;
 
-            // Write back
-            std::fs::write(&file_path, updated)?;
+    println!("DocSentinel Statistics");
+    println!("======================\n");
 
-            println!("✓ Updated {:?}", file_path);
+    println!("Pending events: {}", stats.pending_events);
+    println!("Total drift events: {}", stats.drift_events);
 
-            // Update event status
-            db.update_drift_event_status(issue_id, "Fixed")?;
+    println!("\nBy severity:");
+    for severity in [
+        DriftSeverity::Critical,
+        DriftSeverity::High,
+        DriftSeverity::Medium,
+        DriftSeverity::Low,
+    ] {
+        let count = histogram.get(&severity).copied().unwrap_or(0);
+        println!("  {:<10} {}", severity.to_string(), count);
+    }
 
-            if commit {
-                let commit_msg = format!(
-                    "docsentinel: Fix documentation drift - {}",
-                    event.description
-                );
-                let commit_id = repo.commit_file(&file_path, &commit_msg)?;
-                println!("✓ Committed as {}", commit_id);
-            }
-        }
+    println!("\nFiles with most pending drift:");
+    if by_file.is_empty() {
+        println!("  (none)");
+    }
+    for (file, count) in by_file.iter().take(top) {
+        println!("  {:>4}  {}", count, file);
     }
 
     Ok(())
 }
 
-/// Ignore a drift issue
-pub fn ignore(path: &Path, issue_id: &str, reason: Option<&str>) -> Result<()> {
-    let repo = Repository::open(path)?;
+/// Measure documentation coverage of the stored public symbols
+///
+/// Uses the chunks from the last scan, so run `scan` first for current
+/// numbers. Related docs are matched with the configured similarity
+/// threshold.
+pub fn coverage(path: &Path, scope: Option<&Path>) -> Result<DocCoverage> {
+    let repo = Repository::open_scoped(path, scope)?;
     let sentinel_dir = repo.sentinel_dir();
 
     if !sentinel_dir.exists() {
-        anyhow::bail!("DocSentinel not initialized. Run 'docsentinel init' first.");
+        return Err(DocSentinelError::NotInitialized.into());
     }
 
     let db_path = sentinel_dir.join("docsentinel.db");
     let db = Database::open(&db_path)?;
 
-    db.update_drift_event_status(issue_id, "Ignored")?;
-
-    println!("✓ Ignored drift event: {}", issue_id);
-    if let Some(r) = reason {
-        println!("  Reason: {}", r);
-    }
-
-    Ok(())
+    let detector = DriftDetector::with_config(DriftConfig::from_repo_config(repo.config()));
+    Ok(detector.coverage(&db.get_public_code_chunks()?, &db.get_all_doc_chunks()?))
 }
 
-/// Install or manage git hooks
-pub fn hooks(path: &Path, install: bool, uninstall: bool) -> Result<()> {
-    let repo = Repository::open(path)?;
-    let hooks_dir = repo.root().join(".git").join("hooks");
-
-    if install {
-        let post_commit = hooks_dir.join("post-commit");
-
-        let hook_content = r#"#!/bin/sh
-# DocSentinel post-commit hook
-docsentinel scan --uncommitted
-"#;
-
-        std::fs::write(&post_commit, hook_content)?;
+/// Find doc sections that use subcommands or flags the tool no longer accepts
+///
+/// The accepted ones come from `--help-file`, or from running `--help-from`
+/// in the repository (through `sh -c`) and then each listed subcommand with
+/// `--help`. Events are returned, not stored.
+pub fn check_cli(
+    path: &Path,
+    scope: Option<&Path>,
+    args: &CheckCliArgs,
+) -> Result<Vec<DriftEvent>> {
+    let repo = Repository::open_scoped(path, scope)?;
+    let sentinel_dir = repo.sentinel_dir();
 
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            let mut perms = std::fs::metadata(&post_commit)?.permissions();
-            perms.set_mode(0o755);
-            std::fs::set_permissions(&post_commit, perms)?;
-        }
+    if !sentinel_dir.exists() {
+        return Err(DocSentinelError::NotInitialized.into());
+    }
 
-        println!("✓ Installed post-commit hook");
+    let help = match (&args.help_file, &args.help_from) {
+        (Some(file), _) => CliHelp::parse(
+            &std::fs::read_to_string(file).with_context(|| format!("Failed to read {}", file))?,
+        ),
+        (None, Some(command)) => cli_help_from_command(&repo.scope_dir(), command)?,
+        (None, None) => anyhow::bail!("Pass --help-from or --help-file"),
+    };
+    if help.flags.is_empty() && help.commands.is_empty() {
+        anyhow::bail!("No subcommands or flags found in the help text");
     }
 
-    if uninstall {
-        let post_commit = hooks_dir.join("post-commit");
-        if post_commit.exists() {
-            std::fs::remove_file(&post_commit)?;
-            println!("✓ Removed post-commit hook");
+    let db = Database::open(sentinel_dir.join("docsentinel.db"))?;
+    let docs = db.get_all_doc_chunks()?;
+    Ok(help.check_docs(&docs.iter().collect::<Vec<_>>()))
+}
+
+/// Read a tool's help, and that of each of its subcommands, by running
+/// `command` in `dir`
+///
+/// A trailing `--help` on `command` is optional.
+fn cli_help_from_command(dir: &Path, command: &str) -> Result<CliHelp> {
+    let command = command.trim();
+    let base = command.strip_suffix("--help").unwrap_or(command).trim_end();
+
+    let mut help = CliHelp::parse(&run_help_command(dir, &format!("{} --help", base))?);
+    for subcommand in help.commands.clone() {
+        if subcommand == "help" {
+            continue;
         }
+        let text = run_help_command(dir, &format!("{} {} --help", base, subcommand))?;
+        help.merge_flags(&CliHelp::parse(&text));
     }
+    Ok(help)
+}
 
-    if !install && !uninstall {
-        // Show status
-        let post_commit = hooks_dir.join("post-commit");
-        if post_commit.exists() {
-            println!("post-commit hook: installed");
-        } else {
-            println!("post-commit hook: not installed");
-        }
+/// Run a shell command and return what it printed: stdout, or stderr for
+/// tools that print their help there
+fn run_help_command(dir: &Path, command: &str) -> Result<String> {
+    let output = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .current_dir(dir)
+        .output()
+        .with_context(|| format!("Failed to run `{}`", command))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "`{}` failed ({}): {}",
+            command,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
     }
 
-    Ok(())
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(if stdout.trim().is_empty() {
+        String::from_utf8_lossy(&output.stderr).into_owned()
+    } else {
+        stdout.into_owned()
+    })
 }
 
-/// Print events in JSON format
-pub fn print_events_json(events: &[DriftEvent]) -> Result<()> {
-    let json = serde_json::to_string_pretty(events)?;
-    println!("{}", json);
-    Ok(())
-}
+/// Print documentation coverage in text format
+///
+/// With `detailed`, the undocumented symbols of each file are listed.
+pub fn print_coverage_text(coverage: &DocCoverage, detailed: bool) {
+    println!("Documentation Coverage");
+    println!("======================\n");
 
-/// Print events in text format
-pub fn print_events_text(events: &[DriftEvent]) {
-    if events.is_empty() {
-        println!("No drift events detected.");
+    if coverage.files.is_empty() {
+        println!("No public symbols found. Run 'docsentinel scan' first.");
         return;
     }
 
-    println!("\nDetected Drift Events:");
-    println!("======================\n");
-
-    for event in events {
-        let severity_icon = match event.severity {
-            DriftSeverity::Critical => "🔴",
-            DriftSeverity::High => "🟠",
-            DriftSeverity::Medium => "🟡",
-            DriftSeverity::Low => "🟢",
-        };
-
+    for file in &coverage.files {
         println!(
-            "{} [{}] {}",
-            severity_icon, event.severity, event.description
+            "  {:>5.1}%  {:>3}/{:<3}  {}",
+            file.percent(),
+            file.documented,
+            file.total,
+            file.file_path
         );
-        println!("   Confidence: {:.0}%", event.confidence * 100.0);
-        println!("   Evidence: {}", event.evidence);
-        println!();
+        if detailed {
+            for symbol in &file.undocumented {
+                println!("           - {}", symbol);
+            }
+        }
     }
+
+    println!(
+        "\nTotal: {:.1}% ({} of {} public symbols documented)",
+        coverage.percent(),
+        coverage.documented(),
+        coverage.total()
+    );
 }
 
-/// Generate documentation from code chunks
-pub fn generate(
-    path: &Path,
-    readme: bool,
-    _docs: bool,
-    output: Option<&str>,
-    include_private: bool,
-    with_llm: bool,
-) -> Result<()> {
-    let repo = Repository::open(path)?;
+/// Remove stored scan data
+///
+/// At least one of the flags must be set. With `all`, `events` removes
+/// pending events as well as resolved ones.
+pub fn clean(path: &Path, scope: Option<&Path>, args: &CleanArgs, style: Style) -> Result<()> {
+    let CleanArgs {
+        events,
+        all: all_events,
+        chunks,
+        scan_state,
+        vacuum,
+    } = *args;
+    if !events && !chunks && !scan_state && !vacuum {
+        anyhow::bail!("Nothing to clean. Pass --events, --chunks, --scan-state, or --vacuum.");
+    }
+
+    let repo = Repository::open_scoped(path, scope)?;
     let sentinel_dir = repo.sentinel_dir();
 
     if !sentinel_dir.exists() {
-        anyhow::bail!("DocSentinel not initialized. Run 'docsentinel init' first.");
+        return Err(DocSentinelError::NotInitialized.into());
     }
 
     let db_path = sentinel_dir.join("docsentinel.db");
     let db = Database::open(&db_path)?;
 
-    // Get all code chunks from database
-    let code_chunks = db.get_all_code_chunks()?;
+    if events {
+        let removed = db.clear_drift_events(all_events)?;
+        let kind = if all_events { "" } else { "resolved " };
+        println!("{} Removed {} {}drift event(s)", style.ok(), removed, kind);
+    }
 
-    let output_content = if with_llm {
-        // Load LLM config
-        let config = repo.config();
-        if config.llm.endpoint.is_none() || config.llm.model.is_none() {
-            anyhow::bail!("LLM not configured. Set endpoint and model in .docsentinel/config.toml");
+    if chunks {
+        let (code, doc) = db.clear_chunks()?;
+        println!(
+            "{} Removed {} code chunk(s) and {} doc chunk(s)",
+            style.ok(),
+            code,
+            doc
+        );
+        if !scan_state {
+            println!("  Run 'docsentinel scan --full' to re-extract them.");
         }
+    }
+
+    if scan_state {
+        db.reset_scan_state()?;
+        println!(
+            "{} Reset scan state; the next scan will cover the whole repository",
+            style.ok()
+        );
+    }
+
+    if vacuum {
+        let before = std::fs::metadata(&db_path).map(|m| m.len()).unwrap_or(0);
+        db.vacuum()?;
+        let after = std::fs::metadata(&db_path).map(|m| m.len()).unwrap_or(0);
+        println!(
+            "{} Vacuumed database ({} KiB -> {} KiB)",
+            style.ok(),
+            before / 1024,
+            after / 1024
+        );
+    }
+
+    Ok(())
+}
+
+/// Write the stored chunks, drift events, and scan state to `output` as JSON
+pub fn export(path: &Path, scope: Option<&Path>, output: &Path, style: Style) -> Result<()> {
+    let repo = Repository::open_scoped(path, scope)?;
+    if !repo.sentinel_dir().exists() {
+        return Err(DocSentinelError::NotInitialized.into());
+    }
+    let db = Database::open(repo.sentinel_dir().join("docsentinel.db"))?;
+
+    let export = db.export_all()?;
+    std::fs::write(output, serde_json::to_string_pretty(&export)?)
+        .with_context(|| format!("Failed to write {}", output.display()))?;
+
+    println!(
+        "{} Exported {} code chunk(s), {} doc chunk(s), and {} drift event(s) to {}",
+        style.ok(),
+        export.code_chunks.len(),
+        export.doc_chunks.len(),
+        export.events.len(),
+        output.display()
+    );
+    Ok(())
+}
+
+/// Load a file written by [`export`] into the repository's database
+///
+/// The database must be empty unless `force` is set, which first removes the
+/// stored chunks, drift events, and scan state.
+pub fn import(
+    path: &Path,
+    scope: Option<&Path>,
+    file: &Path,
+    force: bool,
+    style: Style,
+) -> Result<()> {
+    let repo = Repository::open_scoped(path, scope)?;
+    if !repo.sentinel_dir().exists() {
+        return Err(DocSentinelError::NotInitialized.into());
+    }
+    let db = Database::open(repo.sentinel_dir().join("docsentinel.db"))?;
+
+    let json = std::fs::read_to_string(file)
+        .with_context(|| format!("Failed to read {}", file.display()))?;
+    let export = DatabaseExport::from_json(&json)?;
+
+    let stats = db.get_stats()?;
+    if stats.code_chunks + stats.doc_chunks + stats.drift_events > 0 {
+        if !force {
+            anyhow::bail!(
+                "The database already holds scan data. Pass --force to replace it with the import."
+            );
+        }
+        db.clear_drift_events(true)?;
+        db.clear_chunks()?;
+        db.reset_scan_state()?;
+    }
+    db.import_all(&export)?;
+
+    println!(
+        "{} Imported {} code chunk(s), {} doc chunk(s), and {} drift event(s) from {}",
+        style.ok(),
+        export.code_chunks.len(),
+        export.doc_chunks.len(),
+        export.events.len(),
+        file.display()
+    );
+    Ok(())
+}
+
+/// Write pending drift events to a self-contained HTML report
+pub fn report(path: &Path, scope: Option<&Path>, output: &str) -> Result<()> {
+    let repo = Repository::open_scoped(path, scope)?;
+    let sentinel_dir = repo.sentinel_dir();
+
+    if !sentinel_dir.exists() {
+        return Err(DocSentinelError::NotInitialized.into());
+    }
+
+    let db_path = sentinel_dir.join("docsentinel.db");
+    let db = Database::open(&db_path)?;
+
+    let events = db.get_unresolved_drift_events()?;
+    let locations = resolve_event_locations(&db, &events)?;
+    let html = render_report_html(&events, &locations);
+
+    std::fs::write(output, html).with_context(|| format!("Failed to write {}", output))?;
+    println!("Wrote report with {} event(s) to {}", events.len(), output);
+
+    Ok(())
+}
+
+/// Resolve each event's related chunk IDs to `file:start-end` locations
+///
+/// Chunks that no longer exist in the database fall back to their raw ID.
+fn resolve_event_locations(
+    db: &Database,
+    events: &[DriftEvent],
+) -> Result<HashMap<String, Vec<String>>> {
+    let mut locations = HashMap::new();
+
+    for event in events {
+        let mut resolved = Vec::new();
+        for id in &event.related_code_chunks {
+            resolved.push(match db.get_code_chunk(id)? {
+                Some(chunk) => format!(
+                    "{}:{}-{} ({})",
+                    chunk.file_path, chunk.start_line, chunk.end_line, chunk.symbol_name
+                ),
+                None => id.clone(),
+            });
+        }
+        for id in &event.related_doc_chunks {
+            resolved.push(match db.get_doc_chunk(id)? {
+                Some(chunk) => format!(
+                    "{}:{}-{} ({})",
+                    chunk.file_path,
+                    chunk.start_line,
+                    chunk.end_line,
+                    chunk.full_path()
+                ),
+                None => id.clone(),
+            });
+        }
+        locations.insert(event.id.clone(), resolved);
+    }
+
+    Ok(locations)
+}
+
+/// Apply a fix to a drift issue
+///
+/// With `dry_run`, prints a unified diff of the change instead of writing it.
+/// With `verify`, the fixed section is checked again after writing; the
+/// issue is only marked fixed (and committed) if the drift no longer
+/// triggers, and otherwise stays pending with the new evidence.
+pub fn fix(path: &Path, scope: Option<&Path>, args: &FixArgs, style: Style) -> Result<()> {
+    let FixArgs {
+        ref issue_id,
+        ref content,
+        commit,
+        dry_run,
+        verify,
+        ..
+    } = *args;
+
+    let repo = Repository::open_scoped(path, scope)?;
+    let sentinel_dir = repo.sentinel_dir();
+
+    if !sentinel_dir.exists() {
+        return Err(DocSentinelError::NotInitialized.into());
+    }
+
+    let db_path = sentinel_dir.join("docsentinel.db");
+    let db = Database::open(&db_path)?;
+
+    // Find the event
+    let event = db
+        .get_drift_event(issue_id)
+        .context("Failed to find drift event")?
+        .ok_or_else(|| anyhow::anyhow!("Drift event not found: {}", issue_id))?;
+
+    println!("Fixing: {}", event.description);
+
+    // Get the fix content
+    let fix_content = if let Some(c) = content {
+        c.to_string()
+    } else if let Some(ref suggested) = event.suggested_fix {
+        suggested.clone()
+    } else {
+        anyhow::bail!("No fix content provided and no suggested fix available");
+    };
+
+    // Apply the fix
+    if let Some(plan) = plan_fix(&repo, &db, &event, &fix_content)? {
+        if dry_run {
+            print!("{}", plan.diff());
+            return Ok(());
+        }
+
+        let file_path = plan.write(&repo)?;
+        println!("{} Updated {:?}", style.ok(), file_path);
+
+        if verify {
+            if let Some(remaining) = verify_fix(&repo, &db, &event, &plan, &fix_content)? {
+                db.update_drift_event_status(issue_id, "Pending")?;
+                db.update_drift_event_evidence(issue_id, &remaining.evidence)?;
+                println!(
+                    "{} Drift still detected after the fix: {}",
+                    style.warn(),
+                    remaining.description
+                );
+                println!("  The issue was left pending with the new evidence.");
+                return Ok(());
+            }
+            println!("{} Verified: the drift no longer triggers", style.ok());
+        }
+
+        // Update event status
+        db.update_drift_event_status(issue_id, "Fixed")?;
+
+        if commit {
+            let commit_msg = format!(
+                "docsentinel: Fix documentation drift - {}",
+                event.description
+            );
+            let commit_id = repo.commit_file(&file_path, &commit_msg)?;
+            println!("{} Committed as {}", style.ok(), commit_id);
+        }
+    }
+
+    Ok(())
+}
+
+/// Check whether a written fix resolved its drift
+///
+/// The fixed file is extracted again and every section overlapping the
+/// rewritten lines is run through the hard rules' doc checks against the
+/// event's code as it is now (e.g. signatures the docs show against the
+/// current ones). Returns the first drift that still triggers.
+fn verify_fix(
+    repo: &Repository,
+    db: &Database,
+    event: &DriftEvent,
+    plan: &FixPlan,
+    fix_content: &str,
+) -> Result<Option<DriftEvent>> {
+    let Some(old_doc) = event
+        .related_doc_chunks
+        .first()
+        .map(|id| db.get_doc_chunk(id))
+        .transpose()?
+        .flatten()
+    else {
+        return Ok(None);
+    };
+    let mut code = Vec::new();
+    for id in &event.related_code_chunks {
+        code.extend(db.get_code_chunk(id)?);
+    }
+    let code_refs: Vec<&CodeChunk> = code.iter().collect();
+
+    let sections = DocExtractor::from_repo_config(repo.config())
+        .extract_file(Path::new(&plan.file_path), &plan.updated)?;
+    let start = old_doc.start_line;
+    let end = start + fix_content.lines().count().max(1) - 1;
+    let rules = HardDriftRules::new();
+    Ok(sections
+        .iter()
+        .filter(|doc| doc.start_line <= end && doc.end_line >= start)
+        .flat_map(|doc| rules.check_doc_change(Some(&old_doc), Some(doc), &code_refs))
+        .next())
+}
+
+/// A pending rewrite of a doc file for a fix
+pub struct FixPlan {
+    /// Doc file path relative to the repository root
+    pub file_path: String,
+    /// Current file contents
+    pub current: String,
+    /// File contents with the fix applied
+    pub updated: String,
+}
+
+impl FixPlan {
+    /// Unified diff of the change
+    pub fn diff(&self) -> String {
+        render_fix_diff(&self.file_path, &self.current, &self.updated)
+    }
+
+    /// Write the updated contents, returning the absolute file path
+    pub fn write(&self, repo: &Repository) -> Result<std::path::PathBuf> {
+        let file_path = repo.root().join(&self.file_path);
+        std::fs::write(&file_path, &self.updated)
+            .with_context(|| format!("Failed to write {:?}", file_path))?;
+        Ok(file_path)
+    }
+}
+
+/// Work out how a fix would rewrite the event's first related doc section
+///
+/// Returns `None` when the event has no related doc section (or it is no
+/// longer stored), since there is nothing to rewrite.
+pub fn plan_fix(
+    repo: &Repository,
+    db: &Database,
+    event: &DriftEvent,
+    fix_content: &str,
+) -> Result<Option<FixPlan>> {
+    let doc_chunk = match event.related_doc_chunks.first() {
+        Some(doc_id) => match db.get_doc_chunk(doc_id)? {
+            Some(chunk) => chunk,
+            None => return Ok(None),
+        },
+        None => return Ok(None),
+    };
+
+    let file_path = repo.root().join(&doc_chunk.file_path);
+    let current = std::fs::read_to_string(&file_path)?;
+    let updated = replace_doc_section(&current, &doc_chunk, fix_content)
+        .with_context(|| format!("Failed to apply fix to {:?}", file_path))?;
+
+    Ok(Some(FixPlan {
+        file_path: doc_chunk.file_path,
+        current,
+        updated,
+    }))
+}
+
+/// Replace a stored doc section within the current file contents
+///
+/// Prefers an exact match on the stored content. If that fails (e.g. the file
+/// uses CRLF line endings), falls back to the stored line range as long as
+/// those lines still match. Errors if the section has changed since it was
+/// stored, rather than silently leaving the file untouched.
+fn replace_doc_section(current: &str, chunk: &DocChunk, replacement: &str) -> Result<String> {
+    if current.contains(&chunk.content) {
+        return Ok(current.replace(&chunk.content, replacement));
+    }
+
+    let lines: Vec<&str> = current.split_inclusive('\n').collect();
+    let start = chunk.start_line.saturating_sub(1);
+    let end = chunk.end_line.min(lines.len());
+
+    let range_matches = start < end
+        && lines[start..end]
+            .iter()
+            .map(|line| line.trim_end_matches(['\r', '\n']))
+            .eq(chunk.content.split('\n'));
+
+    if !range_matches {
+        anyhow::bail!(
+            "Section '{}' no longer matches the file (lines {}-{}). Re-run 'docsentinel scan' and try again.",
+            chunk.full_path(),
+            chunk.start_line,
+            chunk.end_line
+        );
+    }
+
+    let line_ending = if lines[start].ends_with("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let trailing = if lines[end - 1].ends_with('\n') {
+        line_ending
+    } else {
+        ""
+    };
+
+    let mut updated: String = lines[..start].concat();
+    updated.push_str(&replacement.replace("\r\n", "\n").replace('\n', line_ending));
+    updated.push_str(trailing);
+    updated.push_str(&lines[end..].concat());
+    Ok(updated)
+}
+
+/// Render a unified diff between the current and fixed file contents
+fn render_fix_diff(file_path: &str, current: &str, updated: &str) -> String {
+    similar::TextDiff::from_lines(current, updated)
+        .unified_diff()
+        .context_radius(3)
+        .header(&format!("a/{}", file_path), &format!("b/{}", file_path))
+        .to_string()
+}
+
+/// Ignore a drift issue
+///
+/// With `permanent`, also records an ignore rule in the repository config so
+/// the same symbol (or file) is not flagged again on later scans.
+pub fn ignore(
+    path: &Path,
+    scope: Option<&Path>,
+    issue_id: &str,
+    reason: Option<&str>,
+    permanent: bool,
+    style: Style,
+) -> Result<()> {
+    let repo = Repository::open_scoped(path, scope)?;
+    let sentinel_dir = repo.sentinel_dir();
+
+    if !sentinel_dir.exists() {
+        return Err(DocSentinelError::NotInitialized.into());
+    }
+
+    let db_path = sentinel_dir.join("docsentinel.db");
+    let db = Database::open(&db_path)?;
+
+    let event = db
+        .get_drift_event(issue_id)?
+        .ok_or_else(|| anyhow::anyhow!("Drift event not found: {}", issue_id))?;
+
+    db.update_drift_event_status(issue_id, "Ignored")?;
+
+    println!("{} Ignored drift event: {}", style.ok(), issue_id);
+    if let Some(r) = reason {
+        println!("  Reason: {}", r);
+    }
+
+    if permanent {
+        let mut config = repo.stored_config()?;
+        let added = config.ignore_event(&event);
+        if added.is_empty() {
+            println!("  Already covered by an ignore rule");
+        } else {
+            config.save(&repo.scope_dir())?;
+            for rule in added {
+                println!("  Added ignore rule: {}", rule);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Record the pending drift events as the baseline
+///
+/// Later `scan --fail-on` runs only fail on drift missing from the baseline.
+/// Taking a new baseline replaces the old one, so resolved drift drops out.
+pub fn baseline(path: &Path, scope: Option<&Path>, style: Style) -> Result<()> {
+    let repo = Repository::open_scoped(path, scope)?;
+    if !repo.sentinel_dir().exists() {
+        return Err(DocSentinelError::NotInitialized.into());
+    }
+    let db = Database::open(repo.sentinel_dir().join("docsentinel.db"))?;
+
+    let baseline = Baseline::from_events(&db.get_unresolved_drift_events()?);
+    baseline.save(&repo.sentinel_dir())?;
+    eprintln!(
+        "{} Baselined {} pending drift event(s) in {}",
+        style.ok(),
+        baseline.identities.len(),
+        Baseline::path(&repo.sentinel_dir()).display()
+    );
+    Ok(())
+}
+
+/// The events missing from the repository's baseline
+///
+/// Without a baseline, every event is returned.
+pub fn unbaselined_events(
+    path: &Path,
+    scope: Option<&Path>,
+    events: &[DriftEvent],
+) -> Result<Vec<DriftEvent>> {
+    let repo = Repository::open_scoped(path, scope)?;
+    let Some(baseline) = Baseline::load(&repo.sentinel_dir())? else {
+        return Ok(events.to_vec());
+    };
+    Ok(events
+        .iter()
+        .filter(|event| !baseline.contains(event))
+        .cloned()
+        .collect())
+}
+
+/// Outcome of a `doctor` check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    /// Set up correctly
+    Pass,
+    /// Usable, but a feature is off or there is nothing to work with yet
+    Warn,
+    /// Broken; commands depending on it will fail
+    Fail,
+}
+
+/// One line of the `doctor` checklist
+#[derive(Debug, Clone)]
+pub struct DoctorCheck {
+    /// What was checked
+    pub name: &'static str,
+    pub status: CheckStatus,
+    /// What was found
+    pub detail: String,
+    /// How to fix a warning or failure
+    pub hint: Option<String>,
+}
+
+impl DoctorCheck {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Pass,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Warn,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Fail,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+}
+
+/// Check the repository, database, and configured endpoints
+///
+/// Checks that need an earlier one to pass (the database needs an
+/// initialized repository) are left out when it fails. An unconfigured LLM
+/// or embedding endpoint is a warning, since scans work without them.
+pub fn doctor_checks(
+    path: &Path,
+    scope: Option<&Path>,
+    overrides: &ConfigOverrides,
+) -> Vec<DoctorCheck> {
+    let mut checks = Vec::new();
+
+    let repo = match Repository::open_scoped(path, scope) {
+        Ok(repo) => {
+            let repo = repo.with_overrides(overrides);
+            checks.push(DoctorCheck::pass(
+                "Git repository",
+                repo.root().display().to_string(),
+            ));
+            repo
+        }
+        Err(e) => {
+            checks.push(DoctorCheck::fail(
+                "Git repository",
+                e.to_string(),
+                "Run DocSentinel inside a git repository, or create one with 'git init'",
+            ));
+            return checks;
+        }
+    };
+
+    let sentinel_dir = repo.sentinel_dir();
+    if !sentinel_dir.exists() {
+        checks.push(DoctorCheck::fail(
+            "Initialized",
+            format!("{} does not exist", sentinel_dir.display()),
+            "Run 'docsentinel init'",
+        ));
+        return checks;
+    }
+    checks.push(DoctorCheck::pass(
+        "Initialized",
+        sentinel_dir.display().to_string(),
+    ));
+
+    let db_path = sentinel_dir.join("docsentinel.db");
+    if let Some(db) = check_database(&db_path, &mut checks) {
+        match db.get_stats() {
+            Ok(stats) if stats.code_chunks + stats.doc_chunks == 0 => {
+                checks.push(DoctorCheck::warn(
+                    "Scan data",
+                    "no chunks stored",
+                    "Run 'docsentinel scan --full'",
+                ));
+            }
+            Ok(stats) => {
+                checks.push(DoctorCheck::pass(
+                    "Scan data",
+                    format!(
+                        "{} code chunks, {} doc chunks, {} drift events ({} pending)",
+                        stats.code_chunks,
+                        stats.doc_chunks,
+                        stats.drift_events,
+                        stats.pending_events
+                    ),
+                ));
+            }
+            Err(e) => {
+                checks.push(DoctorCheck::fail(
+                    "Scan data",
+                    e.to_string(),
+                    "Delete the database and run 'docsentinel scan --full'",
+                ));
+            }
+        }
+    }
+
+    let rt = match tokio::runtime::Runtime::new() {
+        Ok(rt) => rt,
+        Err(e) => {
+            checks.push(DoctorCheck::fail(
+                "Endpoints",
+                format!("could not start the async runtime: {}", e),
+                "Re-run 'docsentinel doctor'",
+            ));
+            return checks;
+        }
+    };
+    checks.push(check_llm_endpoint(repo.config(), &rt));
+    checks.push(check_embedding_endpoint(repo.config(), &rt));
+
+    checks
+}
+
+/// Open the database and check its data version, recording the outcome
+fn check_database(db_path: &Path, checks: &mut Vec<DoctorCheck>) -> Option<Database> {
+    if !db_path.exists() {
+        checks.push(DoctorCheck::fail(
+            "Database",
+            format!("{} does not exist", db_path.display()),
+            "Run 'docsentinel init --force'",
+        ));
+        return None;
+    }
+
+    let opened = Database::open(db_path).and_then(|db| Ok((db.data_version()?, db)));
+    match opened {
+        Ok((version, _)) if version > DATA_VERSION => {
+            checks.push(DoctorCheck::fail(
+                "Database",
+                format!(
+                    "data version {} is newer than this build supports ({})",
+                    version, DATA_VERSION
+                ),
+                "Upgrade DocSentinel, or delete the database and run 'docsentinel scan --full'",
+            ));
+            None
+        }
+        Ok((version, db)) => {
+            checks.push(DoctorCheck::pass(
+                "Database",
+                format!("{} (data version {})", db_path.display(), version),
+            ));
+            Some(db)
+        }
+        Err(e) => {
+            checks.push(DoctorCheck::fail(
+                "Database",
+                format!("cannot read {}: {}", db_path.display(), e),
+                "Delete the database and run 'docsentinel scan --full'",
+            ));
+            None
+        }
+    }
+}
+
+/// Check that the configured LLM endpoint answers
+fn check_llm_endpoint(
+    config: &crate::repo::RepoConfig,
+    rt: &tokio::runtime::Runtime,
+) -> DoctorCheck {
+    let Ok(client) = LlmClient::from_repo_config(config) else {
+        return DoctorCheck::warn(
+            "LLM endpoint",
+            "not configured; drift is reported without LLM analysis",
+            "Set llm.endpoint and llm.model in .docsentinel/config.toml",
+        );
+    };
+    let endpoint = config.llm.endpoint.as_deref().unwrap_or_default();
+    if rt.block_on(client.is_available()) {
+        DoctorCheck::pass("LLM endpoint", format!("{} is reachable", endpoint))
+    } else {
+        DoctorCheck::fail(
+            "LLM endpoint",
+            format!("{} is not reachable", endpoint),
+            endpoint_hint(endpoint, "llm.endpoint"),
+        )
+    }
+}
+
+/// Check that the configured embedding endpoint answers
+fn check_embedding_endpoint(
+    config: &crate::repo::RepoConfig,
+    rt: &tokio::runtime::Runtime,
+) -> DoctorCheck {
+    let Some(endpoint) = config.embedding_endpoint() else {
+        return DoctorCheck::warn(
+            "Embedding endpoint",
+            "not configured; scans run without embeddings",
+            "Set embedding.endpoint (or llm.endpoint) in .docsentinel/config.toml",
+        );
+    };
+    let model = config.embedding_model();
+    let available = match config.embedding.provider {
+        EmbeddingBackend::Ollama => {
+            rt.block_on(LocalEmbedding::new(endpoint, model).is_available())
+        }
+        EmbeddingBackend::OpenAI => {
+            let api_key = config
+                .embedding
+                .api_key
+                .as_deref()
+                .or(config.llm.api_key.as_deref());
+            let client = LlmClient::openai_compatible(endpoint, model, api_key);
+            rt.block_on(client.is_available())
+        }
+    };
+    if available {
+        DoctorCheck::pass(
+            "Embedding endpoint",
+            format!("{} is reachable ({})", endpoint, model),
+        )
+    } else {
+        DoctorCheck::fail(
+            "Embedding endpoint",
+            format!("{} is not reachable", endpoint),
+            endpoint_hint(endpoint, "embedding.endpoint"),
+        )
+    }
+}
+
+/// Remediation for an endpoint that does not answer
+fn endpoint_hint(endpoint: &str, key: &str) -> String {
+    if endpoint.contains("11434") {
+        "Start Ollama with 'ollama serve'".to_string()
+    } else {
+        format!("Check {} in .docsentinel/config.toml", key)
+    }
+}
+
+/// Print the `doctor` checklist
+///
+/// Returns whether every check passed or only warned.
+pub fn doctor(
+    path: &Path,
+    scope: Option<&Path>,
+    overrides: &ConfigOverrides,
+    style: Style,
+) -> bool {
+    let checks = doctor_checks(path, scope, overrides);
+
+    println!("DocSentinel Doctor");
+    println!("==================\n");
+
+    for check in &checks {
+        let marker = match check.status {
+            CheckStatus::Pass => style.ok(),
+            CheckStatus::Warn => style.warn(),
+            CheckStatus::Fail => style.fail(),
+        };
+        println!("{} {}: {}", marker, check.name, check.detail);
+        if let Some(hint) = &check.hint {
+            println!("   Hint: {}", hint);
+        }
+    }
+
+    checks.iter().all(|check| check.status != CheckStatus::Fail)
+}
+
+/// First line of the hook section managed by DocSentinel
+const HOOK_BLOCK_START: &str = "# >>> docsentinel >>>";
+/// Last line of the hook section managed by DocSentinel
+const HOOK_BLOCK_END: &str = "# <<< docsentinel <<<";
+/// Hook written by older versions, which owned the whole file
+const LEGACY_POST_COMMIT_HOOK: &str =
+    "#!/bin/sh\n# DocSentinel post-commit hook\ndocsentinel scan --uncommitted\n";
+
+/// Install, uninstall, or show git hooks
+///
+/// Hooks are written as a marked block so existing hook content is kept:
+/// installing adds (or refreshes) the block, uninstalling removes only it.
+/// `pre_commit` selects the pre-commit hook, which blocks commits with
+/// high-severity drift, instead of the post-commit hook.
+pub fn hooks(
+    path: &Path,
+    scope: Option<&Path>,
+    install: bool,
+    uninstall: bool,
+    pre_commit: bool,
+    status: bool,
+    style: Style,
+) -> Result<()> {
+    let repo = Repository::open_scoped(path, scope)?;
+    let hooks_dir = repo.root().join(".git").join("hooks");
+
+    let scan_command = match repo.scope() {
+        Some(scope) => format!("docsentinel --scope '{}' scan", scope.display()),
+        None => "docsentinel scan".to_string(),
+    };
+    let (hook_name, command) = if pre_commit {
+        (
+            "pre-commit",
+            format!("{} --uncommitted --fail-on high || exit 1", scan_command),
+        )
+    } else {
+        ("post-commit", format!("{} --uncommitted", scan_command))
+    };
+    let hook_path = hooks_dir.join(hook_name);
+
+    if install {
+        std::fs::create_dir_all(&hooks_dir)?;
+        let existing = if hook_path.exists() {
+            Some(std::fs::read_to_string(&hook_path)?)
+        } else {
+            None
+        };
+
+        std::fs::write(&hook_path, add_hook_block(existing.as_deref(), &command))?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mut perms = std::fs::metadata(&hook_path)?.permissions();
+            perms.set_mode(0o755);
+            std::fs::set_permissions(&hook_path, perms)?;
+        }
+
+        println!("{} Installed {} hook", style.ok(), hook_name);
+    }
+
+    if uninstall && hook_path.exists() {
+        let existing = std::fs::read_to_string(&hook_path)?;
+        if has_hook_block(&existing) {
+            match remove_hook_block(&existing) {
+                Some(remaining) => std::fs::write(&hook_path, remaining)?,
+                None => std::fs::remove_file(&hook_path)?,
+            }
+            println!("{} Removed {} hook", style.ok(), hook_name);
+        }
+    }
+
+    if status || (!install && !uninstall) {
+        for name in ["pre-commit", "post-commit"] {
+            let hook_path = hooks_dir.join(name);
+            let state = if !hook_path.exists() {
+                "not installed"
+            } else if has_hook_block(&std::fs::read_to_string(&hook_path)?) {
+                "installed"
+            } else {
+                "not installed (other hook present)"
+            };
+            println!("{} hook: {}", name, state);
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether hook content contains the DocSentinel section
+fn has_hook_block(content: &str) -> bool {
+    content == LEGACY_POST_COMMIT_HOOK || content.lines().any(|line| line == HOOK_BLOCK_START)
+}
+
+/// Add or replace the DocSentinel section in a hook script
+///
+/// The section goes right after the shebang so it still runs when the rest of
+/// the script ends with `exit` or `exec`.
+fn add_hook_block(existing: Option<&str>, command: &str) -> String {
+    let block = format!("{}\n{}\n{}\n", HOOK_BLOCK_START, command, HOOK_BLOCK_END);
+    let existing = existing
+        .and_then(remove_hook_block)
+        .unwrap_or_else(|| "#!/bin/sh\n".to_string());
+
+    match existing.split_once('\n') {
+        Some((shebang, rest)) if shebang.starts_with("#!") => {
+            format!("{}\n{}{}", shebang, block, rest)
+        }
+        _ => format!("#!/bin/sh\n{}{}", block, existing),
+    }
+}
+
+/// Remove the DocSentinel section from a hook script
+///
+/// Returns `None` when nothing but the shebang and blank lines would remain.
+fn remove_hook_block(content: &str) -> Option<String> {
+    if content == LEGACY_POST_COMMIT_HOOK {
+        return None;
+    }
+
+    let mut remaining = String::new();
+    let mut in_block = false;
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim_end_matches(['\r', '\n']);
+        if trimmed == HOOK_BLOCK_START {
+            in_block = true;
+        } else if trimmed == HOOK_BLOCK_END {
+            in_block = false;
+        } else if !in_block {
+            remaining.push_str(line);
+        }
+    }
+
+    let has_content = remaining
+        .lines()
+        .any(|line| !line.trim().is_empty() && !line.starts_with("#!"));
+    has_content.then_some(remaining)
+}
+
+/// Version of the JSON event output format
+///
+/// Bumped whenever a field is removed, renamed, or changes meaning; adding
+/// fields does not change it.
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+/// Envelope around JSON event output
+#[derive(Debug, Serialize)]
+pub struct EventsJson<'a> {
+    /// See [`JSON_SCHEMA_VERSION`]
+    pub schema_version: u32,
+    /// Always `docsentinel`
+    pub tool: &'static str,
+    /// Version of the docsentinel binary that produced the output
+    pub version: &'static str,
+    pub events: &'a [DriftEvent],
+}
+
+/// Print events in JSON format
+///
+/// With `compact`, the output is a single line.
+pub fn print_events_json(events: &[DriftEvent], compact: bool) -> Result<()> {
+    println!("{}", render_events_json(events, compact)?);
+    Ok(())
+}
+
+/// Render events as JSON wrapped in an [`EventsJson`] envelope
+///
+/// Events are listed in a stable order (see [`DriftEvent::cmp_priority`]),
+/// so identical results render identically.
+pub fn render_events_json(events: &[DriftEvent], compact: bool) -> Result<String> {
+    let mut events = events.to_vec();
+    sort_events(&mut events);
+    let envelope = EventsJson {
+        schema_version: JSON_SCHEMA_VERSION,
+        tool: crate::APP_NAME,
+        version: crate::VERSION,
+        events: &events,
+    };
+    render_json(&envelope, compact)
+}
+
+/// Print any serializable value as JSON, on one line with `compact`
+pub fn print_json<T: Serialize + ?Sized>(value: &T, compact: bool) -> Result<()> {
+    println!("{}", render_json(value, compact)?);
+    Ok(())
+}
+
+fn render_json<T: Serialize + ?Sized>(value: &T, compact: bool) -> Result<String> {
+    Ok(if compact {
+        serde_json::to_string(value)?
+    } else {
+        serde_json::to_string_pretty(value)?
+    })
+}
+
+/// Print events in text format
+pub fn print_events_text(events: &[DriftEvent], style: Style) {
+    print!("{}", render_events_text(events, style));
+}
+
+/// Render events as plain text, with the total up front and a per-severity
+/// summary at the end
+pub fn render_events_text(events: &[DriftEvent], style: Style) -> String {
+    if events.is_empty() {
+        return "No drift events detected.\n".to_string();
+    }
+
+    let mut output = String::new();
+    output.push_str(&format!("\nDetected Drift Events ({}):\n", events.len()));
+    output.push_str("======================\n\n");
+
+    for event in events {
+        output.push_str(&format!(
+            "{} {}\n",
+            style.severity_label(event.severity),
+            event.description
+        ));
+        if let Some(location) = event.location() {
+            output.push_str(&format!("   Location: {}\n", location));
+        }
+        output.push_str(&format!(
+            "   Confidence: {:.0}%\n",
+            event.confidence * 100.0
+        ));
+        output.push_str(&format!("   Evidence: {}\n\n", event.evidence));
+    }
+
+    let counts: Vec<String> = [
+        DriftSeverity::Critical,
+        DriftSeverity::High,
+        DriftSeverity::Medium,
+        DriftSeverity::Low,
+    ]
+    .iter()
+    .map(|severity| {
+        let count = events.iter().filter(|e| e.severity == *severity).count();
+        format!("{} {}", count, severity.to_string().to_lowercase())
+    })
+    .collect();
+    output.push_str(&format!(
+        "Summary: {} event(s): {}\n",
+        events.len(),
+        counts.join(", ")
+    ));
+    if let Some(highest) = events.iter().map(|e| e.severity).min() {
+        output.push_str(&format!(
+            "Highest severity: {}\n",
+            style.severity_name(highest)
+        ));
+    }
+
+    output
+}
+
+/// Print events as a Markdown report (suitable for PR comments)
+pub fn print_events_markdown(events: &[DriftEvent]) {
+    print!("{}", render_events_markdown(events));
+}
+
+/// Render events as a Markdown report
+///
+/// Events are grouped by severity and sorted by confidence so repeated runs
+/// produce identical output for identical findings.
+pub fn render_events_markdown(events: &[DriftEvent]) -> String {
+    let mut output = String::new();
+    output.push_str("## DocSentinel Drift Report\n\n");
+
+    if events.is_empty() {
+        output.push_str("No drift events detected.\n");
+        return output;
+    }
+
+    let sorted = sorted_for_report(events);
+
+    output.push_str(&format!("**{} drift event(s) detected**\n\n", events.len()));
+
+    for severity in [
+        DriftSeverity::Critical,
+        DriftSeverity::High,
+        DriftSeverity::Medium,
+        DriftSeverity::Low,
+    ] {
+        let group: Vec<&DriftEvent> = sorted
+            .iter()
+            .copied()
+            .filter(|e| e.severity == severity)
+            .collect();
+
+        if group.is_empty() {
+            continue;
+        }
+
+        output.push_str(&format!("### {} ({})\n\n", severity, group.len()));
+        output.push_str("| Severity | Description | Confidence | Location |\n");
+        output.push_str("|----------|-------------|------------|----------|\n");
+
+        for event in &group {
+            output.push_str(&format!(
+                "| {} | {} | {:.0}% | {} |\n",
+                event.severity,
+                escape_markdown_cell(&event.description),
+                event.confidence * 100.0,
+                escape_markdown_cell(&event_location(event)),
+            ));
+        }
+        output.push('\n');
+
+        for event in &group {
+            output.push_str("<details>\n");
+            output.push_str(&format!(
+                "<summary>{} <code>{}</code></summary>\n\n",
+                escape_markdown_cell(&event.description),
+                short_id(&event.id)
+            ));
+            output.push_str("**Evidence:**\n\n```\n");
+            output.push_str(&event.evidence);
+            output.push_str("\n```\n");
+
+            if let Some(ref fix) = event.suggested_fix {
+                output.push_str("\n**Suggested fix:**\n\n```diff\n");
+                for line in fix.lines() {
+                    output.push_str(&format!("+{}\n", line));
+                }
+                output.push_str("```\n");
+            }
+
+            output.push_str("\n</details>\n\n");
+        }
+    }
+
+    output
+}
+
+/// Print events as a JUnit XML report (for CI test dashboards)
+pub fn print_events_junit(events: &[DriftEvent]) {
+    print!("{}", render_events_junit(events));
+}
+
+/// Render events as a JUnit XML test suite
+///
+/// Each event becomes a test case. Critical and High events are failures;
+/// lower severities are recorded as passing cases so totals stay visible.
+pub fn render_events_junit(events: &[DriftEvent]) -> String {
+    let sorted = sorted_for_report(events);
+    let failures = sorted.iter().filter(|e| is_junit_failure(e)).count();
+
+    let mut output = String::new();
+    output.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    output.push_str(&format!(
+        "<testsuite name=\"docsentinel\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"0\">\n",
+        sorted.len(),
+        failures
+    ));
+
+    for event in &sorted {
+        output.push_str(&format!(
+            "  <testcase name=\"{}\" classname=\"{}\"",
+            escape_xml(&event.description),
+            escape_xml(&event_location(event))
+        ));
+
+        if is_junit_failure(event) {
+            output.push_str(">\n");
+            output.push_str(&format!(
+                "    <failure message=\"{}\" type=\"{}\">{}</failure>\n",
+                escape_xml(&event.evidence),
+                event.severity,
+                escape_xml(&event.evidence)
+            ));
+            output.push_str("  </testcase>\n");
+        } else {
+            output.push_str("/>\n");
+        }
+    }
+
+    output.push_str("</testsuite>\n");
+    output
+}
+
+/// Whether an event counts as a failing JUnit test case
+fn is_junit_failure(event: &DriftEvent) -> bool {
+    matches!(
+        event.severity,
+        DriftSeverity::Critical | DriftSeverity::High
+    )
+}
+
+/// Escape text for use in XML attributes and content
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+        .replace('\n', "&#10;")
+}
+
+/// Sort events by severity, then confidence, for stable report output
+fn sorted_for_report(events: &[DriftEvent]) -> Vec<&DriftEvent> {
+    let mut sorted: Vec<&DriftEvent> = events.iter().collect();
+    sorted.sort_by(|a, b| {
+        a.severity
+            .cmp(&b.severity)
+            .then(
+                b.confidence
+                    .partial_cmp(&a.confidence)
+                    .unwrap_or(std::cmp::Ordering::Equal),
+            )
+            .then_with(|| a.description.cmp(&b.description))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    sorted
+}
+
+/// Best-effort location for an event
+///
+/// Uses the event's primary location, falling back to its related chunk IDs
+/// for events stored before locations were recorded.
+fn event_location(event: &DriftEvent) -> String {
+    event
+        .location()
+        .or_else(|| event.related_code_chunks.first().cloned())
+        .or_else(|| event.related_doc_chunks.first().cloned())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// Escape characters that would break a Markdown table cell
+fn escape_markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+/// Inline stylesheet for the HTML report
+const REPORT_CSS: &str = r#"
+body { font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; margin: 2rem; color: #1f2328; }
+h1 { font-size: 1.6rem; }
+.summary { display: flex; gap: 1rem; margin-bottom: 1.5rem; }
+.summary div { border-radius: 6px; padding: 0.6rem 1rem; color: #fff; min-width: 6rem; }
+.summary strong { display: block; font-size: 1.4rem; }
+.critical { background: #b60205; }
+.high { background: #d93f0b; }
+.medium { background: #c29100; }
+.low { background: #2da44e; }
+table { border-collapse: collapse; width: 100%; }
+th, td { border: 1px solid #d0d7de; padding: 0.4rem 0.6rem; text-align: left; vertical-align: top; }
+th { background: #f6f8fa; cursor: pointer; user-select: none; }
+td.badge span { border-radius: 4px; color: #fff; padding: 0.1rem 0.4rem; font-size: 0.85rem; }
+pre { background: #f6f8fa; padding: 0.6rem; overflow-x: auto; white-space: pre-wrap; }
+summary { cursor: pointer; }
+.muted { color: #656d76; }
+"#;
+
+/// Inline script that sorts the report table when a header is clicked
+const REPORT_JS: &str = r#"
+function sortTable(column) {
+  const table = document.getElementById("events");
+  const body = table.tBodies[0];
+  const ascending = table.dataset.column !== String(column) || table.dataset.order !== "asc";
+  const rows = Array.from(body.rows);
+  rows.sort((a, b) => {
+    const x = a.cells[column].dataset.sort;
+    const y = b.cells[column].dataset.sort;
+    const cmp = isNaN(x) || isNaN(y) ? x.localeCompare(y) : x - y;
+    return ascending ? cmp : -cmp;
+  });
+  rows.forEach((row) => body.appendChild(row));
+  table.dataset.column = column;
+  table.dataset.order = ascending ? "asc" : "desc";
+}
+"#;
+
+/// Render events as a self-contained HTML page
+///
+/// `locations` maps event IDs to resolved chunk locations. The page has no
+/// external dependencies so it can be attached to CI runs or shared as-is.
+pub fn render_report_html(
+    events: &[DriftEvent],
+    locations: &HashMap<String, Vec<String>>,
+) -> String {
+    let sorted = sorted_for_report(events);
+
+    let mut output = String::new();
+    output.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n");
+    output.push_str("<meta charset=\"utf-8\">\n");
+    output.push_str("<title>DocSentinel Drift Report</title>\n");
+    output.push_str(&format!("<style>{}</style>\n", REPORT_CSS));
+    output.push_str(&format!("<script>{}</script>\n", REPORT_JS));
+    output.push_str("</head>\n<body>\n");
+    output.push_str("<h1>DocSentinel Drift Report</h1>\n");
+
+    output.push_str("<div class=\"summary\">\n");
+    for severity in [
+        DriftSeverity::Critical,
+        DriftSeverity::High,
+        DriftSeverity::Medium,
+        DriftSeverity::Low,
+    ] {
+        let count = sorted.iter().filter(|e| e.severity == severity).count();
+        output.push_str(&format!(
+            "<div class=\"{}\"><strong>{}</strong>{}</div>\n",
+            severity_css_class(severity),
+            count,
+            severity
+        ));
+    }
+    output.push_str("</div>\n");
+
+    if sorted.is_empty() {
+        output.push_str("<p>No drift events detected.</p>\n</body>\n</html>\n");
+        return output;
+    }
+
+    output.push_str("<table id=\"events\">\n<thead>\n<tr>");
+    for (column, header) in ["Severity", "Description", "Confidence", "Location"]
+        .iter()
+        .enumerate()
+    {
+        output.push_str(&format!(
+            "<th onclick=\"sortTable({})\">{}</th>",
+            column, header
+        ));
+    }
+    output.push_str("</tr>\n</thead>\n<tbody>\n");
+
+    for event in &sorted {
+        let event_locations = locations
+            .get(&event.id)
+            .cloned()
+            .unwrap_or_else(|| vec![event_location(event)]);
+
+        output.push_str("<tr>");
+        output.push_str(&format!(
+            "<td class=\"badge\" data-sort=\"{}\"><span class=\"{}\">{}</span></td>",
+            event.severity as u8,
+            severity_css_class(event.severity),
+            event.severity
+        ));
+
+        output.push_str(&format!(
+            "<td data-sort=\"{}\"><details><summary>{}</summary>\n",
+            escape_html(&event.description),
+            escape_html(&event.description)
+        ));
+        output.push_str(&format!(
+            "<p class=\"muted\">ID: <code>{}</code></p>\n",
+            escape_html(&event.id)
+        ));
+        output.push_str(&format!(
+            "<h4>Evidence</h4>\n<pre>{}</pre>\n",
+            escape_html(&event.evidence)
+        ));
+        if let Some(ref fix) = event.suggested_fix {
+            output.push_str(&format!(
+                "<h4>Suggested fix</h4>\n<pre>{}</pre>\n",
+                escape_html(fix)
+            ));
+        }
+        output.push_str("</details></td>");
+
+        output.push_str(&format!(
+            "<td data-sort=\"{:.4}\">{:.0}%</td>",
+            event.confidence,
+            event.confidence * 100.0
+        ));
+
+        let location_sort = event_locations.first().cloned().unwrap_or_default();
+        let location_cell = event_locations
+            .iter()
+            .map(|l| escape_html(l))
+            .collect::<Vec<_>>()
+            .join("<br>");
+        output.push_str(&format!(
+            "<td data-sort=\"{}\">{}</td>",
+            escape_html(&location_sort),
+            location_cell
+        ));
+        output.push_str("</tr>\n");
+    }
+
+    output.push_str("</tbody>\n</table>\n</body>\n</html>\n");
+    output
+}
+
+/// CSS class used for a severity in the HTML report
+fn severity_css_class(severity: DriftSeverity) -> &'static str {
+    match severity {
+        DriftSeverity::Critical => "critical",
+        DriftSeverity::High => "high",
+        DriftSeverity::Medium => "medium",
+        DriftSeverity::Low => "low",
+    }
+}
+
+/// Escape text for use in HTML content and attribute values
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Options for [`generate`]
+#[derive(Debug, Clone, Default)]
+pub struct GenerateOptions {
+    /// Generate a README instead of full documentation
+    pub readme: bool,
+    /// File to write to (defaults to stdout)
+    pub output: Option<String>,
+    /// Directory to write one Markdown file per source file into, plus an
+    /// `index.md` linking them
+    pub output_dir: Option<PathBuf>,
+    /// Include private symbols
+    pub include_private: bool,
+    /// Use the LLM to write symbol descriptions
+    pub with_llm: bool,
+    /// Symbol kinds to document (all when empty)
+    pub only: Vec<SymbolType>,
+    /// Order of the symbols listed under each file
+    pub sort: SymbolSort,
+}
+
+/// Generate documentation from code chunks
+pub fn generate(
+    path: &Path,
+    scope: Option<&Path>,
+    options: &GenerateOptions,
+    overrides: &ConfigOverrides,
+    style: Style,
+) -> Result<()> {
+    let repo = Repository::open_scoped(path, scope)?.with_overrides(overrides);
+    let sentinel_dir = repo.sentinel_dir();
+
+    if !sentinel_dir.exists() {
+        return Err(DocSentinelError::NotInitialized.into());
+    }
+
+    let db_path = sentinel_dir.join("docsentinel.db");
+    let db = Database::open(&db_path)?;
+
+    // Get all code chunks from database
+    let code_chunks = select_symbols(db.get_all_code_chunks()?, &options.only, options.sort);
+
+    if let Some(ref dir) = options.output_dir {
+        let written = write_docs_dir(&code_chunks, options.include_private, dir)?;
+        println!(
+            "{} Generated {} documentation file(s) in {}",
+            style.ok(),
+            written.len(),
+            dir.display()
+        );
+        return Ok(());
+    }
+
+    let output_content = if options.with_llm {
+        let client = LlmClient::from_repo_config(repo.config())?;
 
-        println!("Generating documentation with LLM (this may take a while)...");
-
         // Use tokio runtime for async LLM calls
         let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(client.ensure_ready())?;
+
+        println!("Generating documentation with LLM (this may take a while)...");
+
         rt.block_on(generate_readme_with_llm(
             &code_chunks,
-            include_private,
-            config,
+            options.include_private,
+            client,
         ))?
-    } else if readme {
-        generate_readme(&code_chunks, include_private)
+    } else if options.readme {
+        generate_readme(&code_chunks, options.include_private)
     } else {
-        generate_full_docs(&code_chunks, include_private)
+        generate_full_docs(&code_chunks, options.include_private)
     };
 
     // Output the result
-    if let Some(file_path) = output {
+    if let Some(ref file_path) = options.output {
         std::fs::write(file_path, &output_content)
             .with_context(|| format!("Failed to write to {}", file_path))?;
-        println!("✓ Generated documentation to {}", file_path);
+        println!("{} Generated documentation to {}", style.ok(), file_path);
     } else {
         println!("{}", output_content);
     }
@@ -522,156 +2663,1158 @@ pub fn generate(
 
 /// Generate a README from code chunks with LLM descriptions
 async fn generate_readme_with_llm(
-    chunks: &[crate::extract::CodeChunk],
+    chunks: &[CodeChunk],
     include_private: bool,
-    config: &crate::repo::RepoConfig,
+    client: crate::llm::LlmClient,
 ) -> Result<String> {
-    use crate::llm::{LlmClient, LlmConfig};
-    use std::collections::HashMap;
-
-    let llm_config = LlmConfig {
-        endpoint: config.llm.endpoint.clone().unwrap_or_default(),
-        model: config.llm.model.clone().unwrap_or_default(),
-        api_key: config.llm.api_key.clone(),
-        max_tokens: config.llm.max_tokens,
-        temperature: config.llm.temperature,
-    };
-
-    let client = LlmClient::new(llm_config);
+    use std::io::Write;
+
+    let by_file = chunks_by_file(chunks, include_private);
+    let total = by_file
+        .iter()
+        .map(|(_, chunks)| chunks.len())
+        .sum::<usize>();
+    let mut descriptions = HashMap::new();
+
+    for (processed, chunk) in by_file.iter().flat_map(|(_, chunks)| chunks).enumerate() {
+        eprintln!(
+            "Processing {}/{}: {}",
+            processed + 1,
+            total,
+            chunk.symbol_name
+        );
 
-    let mut output = String::new();
-    output.push_str("# API Documentation\n\n");
-    output.push_str("*Generated by DocSentinel with LLM-powered descriptions*\n\n");
+        // Generate LLM description
+        let prompt = format!(
+            "Generate a brief, clear description (2-3 sentences) for this {} named '{}'. \
+            Focus on what it does and when to use it.\n\n\
+            Signature: {}\n\
+            Existing doc comment: {}\n\n\
+            Respond with ONLY the description, no markdown formatting.",
+            chunk.symbol_type,
+            chunk.symbol_name,
+            chunk.signature.as_deref().unwrap_or("N/A"),
+            chunk.doc_comment.as_deref().unwrap_or("None")
+        );
 
-    // Group by file
-    let mut by_file: HashMap<&str, Vec<&crate::extract::CodeChunk>> = HashMap::new();
-    for chunk in chunks {
-        if !include_private && !chunk.is_public {
-            continue;
+        let result = client
+            .complete_streaming(&prompt, |token| {
+                eprint!("{}", token);
+                let _ = std::io::stderr().flush();
+            })
+            .await;
+        eprintln!();
+
+        // Fall back to the doc comment if the LLM fails
+        if let Ok(response) = result {
+            descriptions.insert(chunk.id.as_str(), response.content);
         }
-        by_file.entry(&chunk.file_path).or_default().push(chunk);
     }
 
-    // Sort files
-    let mut files: Vec<_> = by_file.keys().collect();
-    files.sort();
+    Ok(render_readme(
+        &by_file,
+        "*Generated by DocSentinel with LLM-powered descriptions*",
+        &descriptions,
+    ))
+}
+
+/// Generate a README from code chunks
+fn generate_readme(chunks: &[CodeChunk], include_private: bool) -> String {
+    render_readme(
+        &chunks_by_file(chunks, include_private),
+        "*Generated by DocSentinel*",
+        &HashMap::new(),
+    )
+}
 
-    let mut processed = 0;
-    let total = by_file.values().map(|v| v.len()).sum::<usize>();
+/// Render the README for chunks grouped by file
+///
+/// Methods are listed under the type they belong to. A symbol's entry in
+/// `descriptions` replaces its doc comment.
+fn render_readme(
+    by_file: &[(&str, Vec<&CodeChunk>)],
+    subtitle: &str,
+    descriptions: &HashMap<&str, String>,
+) -> String {
+    let mut output = String::new();
+    output.push_str("# API Documentation\n\n");
+    output.push_str(subtitle);
+    output.push_str("\n\n");
 
-    for file in files {
-        let file_chunks = by_file.get(file).unwrap();
+    for (file, chunks) in by_file {
         output.push_str(&format!("## `{}`\n\n", file));
 
-        for chunk in file_chunks {
-            processed += 1;
-            eprint!("\rProcessing {}/{}...", processed, total);
+        for group in group_methods(chunks) {
+            match group.item {
+                Some(item) => render_readme_symbol(&mut output, item, 3, descriptions),
+                None => output.push_str(&format!("### `{}`\n\n", group.name)),
+            }
+            for method in group.methods {
+                render_readme_symbol(&mut output, method, 4, descriptions);
+            }
+        }
+    }
 
-            let visibility = if chunk.is_public { "pub " } else { "" };
-            output.push_str(&format!(
-                "### {}{} `{}`\n\n",
-                visibility, chunk.symbol_type, chunk.symbol_name
-            ));
+    output
+}
 
-            if let Some(ref sig) = chunk.signature {
-                output.push_str("```rust\n");
-                output.push_str(sig);
-                output.push_str("\n```\n\n");
-            }
+/// Append one symbol's README section under a heading of the given level
+fn render_readme_symbol(
+    output: &mut String,
+    chunk: &CodeChunk,
+    level: usize,
+    descriptions: &HashMap<&str, String>,
+) {
+    let visibility = if chunk.is_public { "pub " } else { "" };
+    output.push_str(&format!(
+        "{} {}{} `{}`\n\n",
+        "#".repeat(level),
+        visibility,
+        chunk.symbol_type,
+        chunk.symbol_name
+    ));
+
+    if let Some(ref sig) = chunk.signature {
+        output.push_str("```rust\n");
+        output.push_str(sig);
+        output.push_str("\n```\n\n");
+    }
 
-            // Generate LLM description
-            let prompt = format!(
-                "Generate a brief, clear description (2-3 sentences) for this {} named '{}'. \
-                Focus on what it does and when to use it.\n\n\
-                Signature: {}\n\
-                Existing doc comment: {}\n\n\
-                Respond with ONLY the description, no markdown formatting.",
-                chunk.symbol_type,
-                chunk.symbol_name,
-                chunk.signature.as_deref().unwrap_or("N/A"),
-                chunk.doc_comment.as_deref().unwrap_or("None")
-            );
+    let description = descriptions
+        .get(chunk.id.as_str())
+        .map(String::as_str)
+        .or(chunk.doc_comment.as_deref());
+    if let Some(description) = description {
+        output.push_str(description);
+        output.push_str("\n\n");
+    }
 
-            match client.complete(&prompt).await {
-                Ok(response) => {
-                    output.push_str(&response.content);
-                    output.push_str("\n\n");
-                }
-                Err(_) => {
-                    // Fall back to doc comment if LLM fails
-                    if let Some(ref doc) = chunk.doc_comment {
-                        output.push_str(doc);
-                        output.push_str("\n\n");
-                    }
-                }
-            }
+    output.push_str(&format!(
+        "*Lines {}-{}*\n\n",
+        chunk.start_line, chunk.end_line
+    ));
+}
 
-            output.push_str(&format!(
-                "*Lines {}-{}*\n\n",
-                chunk.start_line, chunk.end_line
-            ));
-        }
+/// A top-level symbol and the methods defined on it
+struct SymbolGroup<'a> {
+    /// The symbol itself, or `None` for methods of a type that isn't listed
+    item: Option<&'a CodeChunk>,
+    /// Name the methods belong to
+    name: &'a str,
+    methods: Vec<&'a CodeChunk>,
+}
+
+/// Group methods under their struct, class, enum, or trait
+///
+/// Groups keep the order of `chunks`. Methods whose type isn't among
+/// `chunks` (e.g. filtered out by `--only`) are grouped by type name after
+/// the listed symbols.
+fn group_methods<'a>(chunks: &[&'a CodeChunk]) -> Vec<SymbolGroup<'a>> {
+    let mut groups: Vec<SymbolGroup<'a>> = chunks
+        .iter()
+        .filter(|chunk| chunk.symbol_type != SymbolType::Method)
+        .map(|chunk| SymbolGroup {
+            item: Some(chunk),
+            name: &chunk.symbol_name,
+            methods: Vec::new(),
+        })
+        .collect();
+
+    for method in chunks
+        .iter()
+        .filter(|chunk| chunk.symbol_type == SymbolType::Method)
+    {
+        let owner = method_owner(&method.symbol_name);
+        let index = groups
+            .iter()
+            .position(|group| {
+                group.name == owner
+                    && group.item.is_none_or(|item| {
+                        matches!(
+                            item.symbol_type,
+                            SymbolType::Struct
+                                | SymbolType::Class
+                                | SymbolType::Enum
+                                | SymbolType::Trait
+                        )
+                    })
+            })
+            .unwrap_or_else(|| {
+                groups.push(SymbolGroup {
+                    item: None,
+                    name: owner,
+                    methods: Vec::new(),
+                });
+                groups.len() - 1
+            });
+        groups[index].methods.push(method);
     }
 
-    eprintln!(); // New line after progress
+    groups
+}
 
-    Ok(output)
+/// Type a method belongs to: `Wrapper` for `Wrapper<T>::new` or `Client.get`
+fn method_owner(name: &str) -> &str {
+    let owner = name
+        .rsplit_once("::")
+        .or_else(|| name.rsplit_once('.'))
+        .map_or(name, |(owner, _)| owner);
+    owner.split('<').next().unwrap_or(owner).trim()
 }
 
-/// Generate a README from code chunks
-fn generate_readme(chunks: &[crate::extract::CodeChunk], include_private: bool) -> String {
-    use std::collections::HashMap;
+/// Keep the chunks of the given kinds (all when `only` is empty), ordered by
+/// file and then by `sort`
+fn select_symbols(
+    mut chunks: Vec<CodeChunk>,
+    only: &[SymbolType],
+    sort: SymbolSort,
+) -> Vec<CodeChunk> {
+    if !only.is_empty() {
+        chunks.retain(|chunk| only.contains(&chunk.symbol_type));
+    }
+    match sort {
+        SymbolSort::File => {
+            chunks.sort_by(|a, b| (&a.file_path, a.start_line).cmp(&(&b.file_path, b.start_line)))
+        }
+        SymbolSort::Name => chunks
+            .sort_by(|a, b| (&a.file_path, &a.symbol_name).cmp(&(&b.file_path, &b.symbol_name))),
+        SymbolSort::Kind => chunks.sort_by(|a, b| {
+            (&a.file_path, a.symbol_type as u8, &a.symbol_name).cmp(&(
+                &b.file_path,
+                b.symbol_type as u8,
+                &b.symbol_name,
+            ))
+        }),
+    }
+    chunks
+}
 
+/// Generate full documentation
+///
+/// Unlike the README, each file gets a table of contents, and private
+/// symbols (when included) are listed in their own section after the public
+/// API.
+fn generate_full_docs(chunks: &[CodeChunk], include_private: bool) -> String {
     let mut output = String::new();
     output.push_str("# API Documentation\n\n");
     output.push_str("*Generated by DocSentinel*\n\n");
 
-    // Group by file
-    let mut by_file: HashMap<&str, Vec<&crate::extract::CodeChunk>> = HashMap::new();
-    for chunk in chunks {
-        if !include_private && !chunk.is_public {
-            continue;
+    let mut anchors = HashMap::new();
+    next_anchor(&mut anchors, "API Documentation");
+    for (file, file_chunks) in chunks_by_file(chunks, include_private) {
+        output.push_str(&render_file_docs(file, &file_chunks, 2, &mut anchors));
+    }
+
+    output
+}
+
+/// Write one Markdown file per source file into `dir`, plus an `index.md`
+///
+/// `src/lib.rs` is documented in `<dir>/src/lib.rs.md`. Returns the paths
+/// written, index last.
+fn write_docs_dir(chunks: &[CodeChunk], include_private: bool, dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut written = Vec::new();
+    let mut index = String::new();
+    index.push_str("# API Documentation\n\n");
+    index.push_str("*Generated by DocSentinel*\n\n");
+
+    for (file, file_chunks) in chunks_by_file(chunks, include_private) {
+        let doc_path = format!("{}.md", file);
+        let full_path = dir.join(&doc_path);
+        if let Some(parent) = full_path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
         }
-        by_file.entry(&chunk.file_path).or_default().push(chunk);
+        let content = render_file_docs(file, &file_chunks, 1, &mut HashMap::new());
+        std::fs::write(&full_path, content)
+            .with_context(|| format!("Failed to write to {}", full_path.display()))?;
+        written.push(full_path);
+
+        index.push_str(&format!(
+            "- [`{}`]({}): {} symbol(s)\n",
+            file,
+            doc_path,
+            file_chunks.len()
+        ));
     }
 
-    // Sort files
-    let mut files: Vec<_> = by_file.keys().collect();
-    files.sort();
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let index_path = dir.join("index.md");
+    std::fs::write(&index_path, index)
+        .with_context(|| format!("Failed to write to {}", index_path.display()))?;
+    written.push(index_path);
 
-    for file in files {
-        let chunks = by_file.get(file).unwrap();
-        output.push_str(&format!("## `{}`\n\n", file));
+    Ok(written)
+}
+
+/// Group chunks by file, sorted by path, skipping private symbols unless
+/// `include_private` is set
+fn chunks_by_file(chunks: &[CodeChunk], include_private: bool) -> Vec<(&str, Vec<&CodeChunk>)> {
+    let mut by_file: BTreeMap<&str, Vec<&CodeChunk>> = BTreeMap::new();
+    for chunk in chunks {
+        if include_private || chunk.is_public {
+            by_file.entry(&chunk.file_path).or_default().push(chunk);
+        }
+    }
+    by_file.into_iter().collect()
+}
 
-        for chunk in chunks {
+/// Render one source file's symbols under a heading of the given level
+///
+/// `anchors` counts the heading anchors already used in the same document,
+/// so table of contents links match GitHub's numbering of repeated headings.
+fn render_file_docs(
+    file: &str,
+    chunks: &[&CodeChunk],
+    level: usize,
+    anchors: &mut HashMap<String, usize>,
+) -> String {
+    let heading = |level: usize, text: &str| format!("{} {}\n\n", "#".repeat(level), text);
+    let (public, private): (Vec<&CodeChunk>, Vec<&CodeChunk>) =
+        chunks.iter().copied().partition(|c| c.is_public);
+    let sections: Vec<(&str, Vec<&CodeChunk>)> =
+        [("Public API", public), ("Private Items", private)]
+            .into_iter()
+            .filter(|(_, chunks)| !chunks.is_empty())
+            .collect();
+
+    let title = format!("`{}`", file);
+    next_anchor(anchors, &title);
+
+    // Anchors are assigned in document order: section, then its symbols
+    let mut toc = String::new();
+    let mut body = String::new();
+    for (section, section_chunks) in &sections {
+        let anchor = next_anchor(anchors, section);
+        toc.push_str(&format!("- [{}](#{})\n", section, anchor));
+        body.push_str(&heading(level + 1, section));
+
+        for chunk in section_chunks {
             let visibility = if chunk.is_public { "pub " } else { "" };
-            output.push_str(&format!(
-                "### {}{} `{}`\n\n",
+            let symbol_heading = format!(
+                "{}{} `{}`",
                 visibility, chunk.symbol_type, chunk.symbol_name
-            ));
+            );
+            let anchor = next_anchor(anchors, &symbol_heading);
+            toc.push_str(&format!("  - [`{}`](#{})\n", chunk.symbol_name, anchor));
+            body.push_str(&heading(level + 2, &symbol_heading));
 
             if let Some(ref sig) = chunk.signature {
-                output.push_str("```rust\n");
-                output.push_str(sig);
-                output.push_str("\n```\n\n");
+                body.push_str(&format!("```{}\n{}\n```\n\n", chunk.language, sig));
             }
-
             if let Some(ref doc) = chunk.doc_comment {
-                output.push_str(doc);
-                output.push_str("\n\n");
+                body.push_str(doc);
+                body.push_str("\n\n");
             }
-
-            output.push_str(&format!(
+            body.push_str(&format!(
                 "*Lines {}-{}*\n\n",
                 chunk.start_line, chunk.end_line
             ));
         }
     }
 
+    let mut output = heading(level, &title);
+    if !toc.is_empty() {
+        output.push_str("**Contents**\n\n");
+        output.push_str(&toc);
+        output.push('\n');
+    }
+    output.push_str(&body);
     output
 }
 
-/// Generate full documentation
-fn generate_full_docs(chunks: &[crate::extract::CodeChunk], include_private: bool) -> String {
-    generate_readme(chunks, include_private) // For now, same as readme
+/// Anchor for the next heading with this text, numbered like GitHub when the
+/// same heading appeared before
+fn next_anchor(anchors: &mut HashMap<String, usize>, heading: &str) -> String {
+    let slug = heading_anchor(heading);
+    let count = anchors.entry(slug.clone()).or_insert(0);
+    let anchor = match *count {
+        0 => slug,
+        n => format!("{}-{}", slug, n),
+    };
+    *count += 1;
+    anchor
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drift::DriftStatus;
+    use crate::extract::DocExtractor;
+    use crate::repo::RepoConfig;
+
+    /// Create a committed git repository containing a single public Rust function
+    fn create_test_repo() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let git = git2::Repository::init(dir.path()).unwrap();
+
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(
+            dir.path().join("src/lib.rs"),
+            "/// Greets someone\npub fn greet(name: &str) -> String {\n    format!(\"Hello, {}\", name)\n}\n",
+        )
+        .unwrap();
+
+        let mut index = git.index().unwrap();
+        index.add_path(Path::new("src/lib.rs")).unwrap();
+        index.write().unwrap();
+        let tree = git.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        git.commit(Some("HEAD"), &sig, &sig, "initial", &tree, &[])
+            .unwrap();
+
+        dir
+    }
+
+    #[test]
+    fn test_render_report_html_escapes_content() {
+        let event = DriftEvent::new(
+            DriftSeverity::High,
+            "Signature of <script>alert(1)</script> changed",
+            "old: fn a() & \"b\"",
+            0.9,
+        )
+        .with_code_chunk("src/lib.rs::a")
+        .with_suggested_fix("Use `a(x: i32)` <b>now</b>");
+        let mut locations = HashMap::new();
+        locations.insert(event.id.clone(), vec!["src/lib.rs:3-5 (a)".to_string()]);
+
+        let html = render_report_html(&[event], &locations);
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(!html.contains("<script>alert(1)</script>"));
+        assert!(html.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(html.contains("old: fn a() &amp; &quot;b&quot;"));
+        assert!(html.contains("&lt;b&gt;now&lt;/b&gt;"));
+        assert!(html.contains("src/lib.rs:3-5 (a)"));
+        assert!(html.contains("<strong>1</strong>HIGH"));
+        assert!(html.contains("onclick=\"sortTable(0)\""));
+        assert!(!html.contains("http"));
+    }
+
+    #[test]
+    fn test_render_events_markdown_is_sorted_and_grouped() {
+        let low = DriftEvent::new(DriftSeverity::Low, "Minor | thing", "evidence a", 0.5)
+            .with_code_chunk("src/lib.rs::minor");
+        let critical = DriftEvent::new(DriftSeverity::Critical, "Removed", "evidence b", 1.0)
+            .with_suggested_fix("New docs");
+
+        let first = render_events_markdown(&[low.clone(), critical.clone()]);
+        let second = render_events_markdown(&[critical, low]);
+        assert_eq!(first, second);
+
+        let critical_pos = first.find("### CRITICAL").unwrap();
+        let low_pos = first.find("### LOW").unwrap();
+        assert!(critical_pos < low_pos);
+        assert!(first.contains("| Severity | Description | Confidence | Location |"));
+        assert!(first.contains("Minor \\| thing"));
+        assert!(first.contains("src/lib.rs::minor"));
+        assert!(first.contains("```diff\n+New docs\n```"));
+        assert!(first.contains("<details>"));
+    }
+
+    #[test]
+    fn test_generate_readme_only_struct_and_method_grouping() {
+        let mut extractor = CodeExtractor::new().unwrap();
+        let chunks = extractor
+            .extract_file(
+                Path::new("src/lib.rs"),
+                "/// A client\npub struct Client;\n\n\
+                 impl Client {\n    pub fn get(&self) {}\n}\n\n\
+                 pub fn connect() -> Client {\n    Client\n}\n\n\
+                 pub struct Wrapper<T>(T);\n\n\
+                 impl<T> Wrapper<T> {\n    pub fn new(t: T) -> Self {\n        Self(t)\n    }\n}\n",
+            )
+            .unwrap();
+
+        let structs = select_symbols(chunks.clone(), &[SymbolType::Struct], SymbolSort::File);
+        let readme = generate_readme(&structs, false);
+        assert!(readme.contains("### pub struct `Client`"));
+        assert!(readme.contains("### pub struct `Wrapper`"));
+        assert!(!readme.contains("function"));
+        assert!(!readme.contains("method"));
+
+        let all = select_symbols(chunks, &[], SymbolSort::Kind);
+        let readme = generate_readme(&all, false);
+        let client = readme.find("### pub struct `Client`").unwrap();
+        let get = readme.find("#### pub method `Client::get`").unwrap();
+        let wrapper = readme.find("### pub struct `Wrapper`").unwrap();
+        let new = readme.find("#### pub method `Wrapper<T>::new`").unwrap();
+        let connect = readme.find("### pub function `connect`").unwrap();
+        assert!(connect < client && client < get && get < wrapper && wrapper < new);
+
+        let methods = select_symbols(all, &[SymbolType::Method], SymbolSort::Name);
+        let readme = generate_readme(&methods, false);
+        assert!(readme.contains("### `Client`\n\n#### pub method `Client::get`"));
+    }
+
+    #[test]
+    fn test_write_docs_dir_creates_one_file_per_source_file() {
+        let mut extractor = CodeExtractor::new().unwrap();
+        let mut chunks = extractor
+            .extract_file(
+                Path::new("src/lib.rs"),
+                "/// Adds\npub fn add(a: i32) -> i32 {\n    a\n}\n\nfn helper() {}\n",
+            )
+            .unwrap();
+        chunks.extend(
+            extractor
+                .extract_file(Path::new("src/net/client.rs"), "pub struct Client;\n")
+                .unwrap(),
+        );
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("docs");
+
+        let written = write_docs_dir(&chunks, true, &out).unwrap();
+
+        assert_eq!(
+            written,
+            vec![
+                out.join("src/lib.rs.md"),
+                out.join("src/net/client.rs.md"),
+                out.join("index.md"),
+            ]
+        );
+        let index = std::fs::read_to_string(out.join("index.md")).unwrap();
+        assert!(index.contains("- [`src/lib.rs`](src/lib.rs.md): 2 symbol(s)"));
+        assert!(index.contains("- [`src/net/client.rs`](src/net/client.rs.md): 1 symbol(s)"));
+
+        let lib = std::fs::read_to_string(out.join("src/lib.rs.md")).unwrap();
+        assert!(lib.starts_with("# `src/lib.rs`\n\n**Contents**"));
+        assert!(lib.contains("  - [`add`](#pub-function-add)"));
+        assert!(lib.contains("## Private Items"));
+        assert!(lib.contains("### function `helper`"));
+
+        let public_only = generate_full_docs(&chunks, false);
+        assert!(!public_only.contains("helper"));
+        assert!(!public_only.contains("Private Items"));
+    }
+
+    #[test]
+    fn test_render_events_text_summarizes_severities() {
+        let events = vec![
+            DriftEvent::new(DriftSeverity::Medium, "a", "e", 0.5),
+            DriftEvent::new(DriftSeverity::High, "b", "e", 0.5),
+            DriftEvent::new(DriftSeverity::Medium, "c", "e", 0.5),
+        ];
+        let text = render_events_text(&events, Style::Fancy);
+
+        assert!(text.contains("Detected Drift Events (3):"));
+        assert!(text.contains("🟡 [MEDIUM] a"));
+        assert!(text.contains("Summary: 3 event(s): 0 critical, 1 high, 2 medium, 0 low"));
+        assert!(text.ends_with("Highest severity: 🟠 HIGH\n"));
+        assert_eq!(
+            render_events_text(&[], Style::Fancy),
+            "No drift events detected.\n"
+        );
+    }
+
+    #[test]
+    fn test_status_filter_by_tag() {
+        let events = [
+            DriftEvent::new(DriftSeverity::High, "a", "e", 0.5).with_tag("signature_change"),
+            DriftEvent::new(DriftSeverity::High, "b", "e", 0.5).with_tag("removed_function"),
+            DriftEvent::new(DriftSeverity::Low, "c", "e", 0.5).with_tag("signature_change"),
+            DriftEvent::new(DriftSeverity::High, "d", "e", 0.5),
+        ];
+        let shown = |severity, tag| -> Vec<&str> {
+            events
+                .iter()
+                .filter(|e| status_filter_matches(e, severity, tag))
+                .map(|e| e.description.as_str())
+                .collect()
+        };
+
+        assert_eq!(shown(None, Some("signature_change")), vec!["a", "c"]);
+        assert_eq!(shown(Some("high"), Some("signature_change")), vec!["a"]);
+        assert_eq!(shown(None, None).len(), 4);
+        assert!(shown(None, Some("broken_link")).is_empty());
+    }
+
+    #[test]
+    fn test_status_detail_shows_doc_window_and_signature() {
+        use crate::extract::{CodeChunk, DocChunk, HeadingLevel, Language, SymbolType};
+
+        let db = Database::open_in_memory().unwrap();
+        let doc = DocChunk::new(
+            "README.md",
+            vec!["Usage".to_string()],
+            "Usage",
+            HeadingLevel::H2,
+            "## Usage\n\nIntro.\n\nCall greet(name).\n\nOutro.",
+            10,
+            16,
+        );
+        db.upsert_doc_chunk(&doc).unwrap();
+        let mut code = CodeChunk::new(
+            "src/lib.rs",
+            "greet",
+            SymbolType::Function,
+            "pub fn greet(name: &str, loud: bool) -> String {}",
+            Language::Rust,
+            2,
+            4,
+        );
+        code.signature = Some("pub fn greet(name: &str, loud: bool) -> String".to_string());
+        db.upsert_code_chunk(&code).unwrap();
+
+        let event = DriftEvent::new(DriftSeverity::High, "Signature changed: greet", "e", 0.9)
+            .with_doc_chunk(&doc.id)
+            .with_code_chunk(&code.id)
+            .with_doc_chunk("gone")
+            .with_location("README.md", 14);
+
+        let mut event = event;
+        event.introduced_by = Some(crate::repo::CommitInfo {
+            hash: "0123456789abcdef".to_string(),
+            author: "Ada".to_string(),
+            email: "ada@example.com".to_string(),
+            time: 0,
+        });
+        let lines = event_detail_lines(&db, &event, 1).unwrap();
+        assert_eq!(
+            lines,
+            vec![
+                "Code last changed in: 01234567 Ada <ada@example.com>",
+                "Doc: README.md:10-16 (Usage)",
+                "   13 | ",
+                "   14 | Call greet(name).",
+                "   15 | ",
+                "Doc: gone (no longer stored)",
+                "Code: src/lib.rs:2 greet",
+                "    2 | pub fn greet(name: &str, loud: bool) -> String",
+            ]
+        );
+
+        // Without a line in the section the window starts at the heading
+        let unlocated =
+            DriftEvent::new(DriftSeverity::High, "Stale", "e", 0.9).with_doc_chunk(&doc.id);
+        let lines = event_detail_lines(&db, &unlocated, 1).unwrap();
+        assert_eq!(lines[1..], ["   10 | ## Usage", "   11 | "]);
+    }
+
+    #[test]
+    fn test_render_events_text_plain_is_ascii() {
+        let events = vec![
+            DriftEvent::new(DriftSeverity::Critical, "a", "e", 0.5),
+            DriftEvent::new(DriftSeverity::Low, "b", "e", 0.5),
+        ];
+        let text = render_events_text(&events, Style::Plain);
+
+        assert!(text.is_ascii());
+        assert!(text.contains("[CRIT] a\n"));
+        assert!(text.contains("[LOW] b\n"));
+        assert!(text.ends_with("Highest severity: CRITICAL\n"));
+    }
+
+    #[test]
+    fn test_render_events_json_envelope() {
+        let events = vec![DriftEvent::new(DriftSeverity::High, "a", "e", 0.5)];
+
+        let pretty: serde_json::Value =
+            serde_json::from_str(&render_events_json(&events, false).unwrap()).unwrap();
+        assert_eq!(pretty["schema_version"], JSON_SCHEMA_VERSION);
+        assert_eq!(pretty["tool"], "docsentinel");
+        assert_eq!(pretty["version"], crate::VERSION);
+        assert_eq!(pretty["events"][0]["severity"], "High");
+
+        let compact = render_events_json(&events, true).unwrap();
+        assert!(!compact.contains('\n'));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&compact).unwrap(),
+            pretty
+        );
+    }
+
+    #[test]
+    fn test_render_events_junit() {
+        let high = DriftEvent::new(
+            DriftSeverity::High,
+            "Signature changed: <greet>",
+            "fn greet() -> fn greet(name: &str)",
+            0.9,
+        )
+        .with_code_chunk("src/lib.rs::greet");
+        let low = DriftEvent::new(DriftSeverity::Low, "Minor", "evidence", 0.5);
+
+        let xml = render_events_junit(&[low, high]);
+
+        assert!(xml.contains(
+            r#"<testsuite name="docsentinel" tests="2" failures="1" errors="0" skipped="0">"#
+        ));
+        assert!(xml.contains(
+            r#"<testcase name="Signature changed: &lt;greet&gt;" classname="src/lib.rs::greet">"#
+        ));
+        assert!(xml.contains(
+            r#"<failure message="fn greet() -&gt; fn greet(name: &amp;str)" type="HIGH">"#
+        ));
+        assert!(xml.contains(r#"<testcase name="Minor" classname="-"/>"#));
+        assert!(xml.find("greet").unwrap() < xml.find("Minor").unwrap());
+    }
+
+    #[test]
+    fn test_fix_dry_run_and_stale_section() {
+        let dir = create_test_repo();
+        init(dir.path(), None, false, true, Style::Plain).unwrap();
+
+        let readme = "# Project\n\n## Usage\n\nCall greet() with no arguments.\n";
+        std::fs::write(dir.path().join("README.md"), readme).unwrap();
+
+        let db = Database::open(dir.path().join(".docsentinel/docsentinel.db")).unwrap();
+        let chunks = DocExtractor::new()
+            .extract_file(Path::new("README.md"), readme)
+            .unwrap();
+        let usage = chunks.iter().find(|c| c.heading == "Usage").unwrap();
+        db.upsert_doc_chunk(usage).unwrap();
+
+        let event = DriftEvent::new(DriftSeverity::High, "Signature changed", "e", 0.9)
+            .with_doc_chunk(&usage.id)
+            .with_suggested_fix("## Usage\n\nCall greet(name).");
+        db.insert_drift_event(&event).unwrap();
+
+        let diff = render_fix_diff(
+            "README.md",
+            readme,
+            &readme.replace(&usage.content, event.suggested_fix.as_deref().unwrap()),
+        );
+        assert!(diff.contains("--- a/README.md"));
+        assert!(diff.contains("-Call greet() with no arguments."));
+        assert!(diff.contains("+Call greet(name)."));
+
+        // Dry run leaves the file and the event untouched
+        let args = FixArgs {
+            issue_id: event.id.clone(),
+            yes: true,
+            content: None,
+            commit: false,
+            dry_run: true,
+            verify: false,
+        };
+        fix(dir.path(), None, &args, Style::Plain).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("README.md")).unwrap(),
+            readme
+        );
+
+        // A section that no longer matches is not silently "fixed"
+        let edited = readme.replace("no arguments", "zero arguments");
+        std::fs::write(dir.path().join("README.md"), &edited).unwrap();
+        let args = FixArgs {
+            dry_run: false,
+            ..args
+        };
+        assert!(fix(dir.path(), None, &args, Style::Plain).is_err());
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("README.md")).unwrap(),
+            edited
+        );
+        assert_eq!(db.get_unresolved_drift_events().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_verified_fix_stays_pending_while_drift_remains() {
+        let dir = create_test_repo();
+        init(dir.path(), None, false, true, Style::Plain).unwrap();
+        let options = ScanOptions {
+            full: true,
+            embeddings: false,
+            ..Default::default()
+        };
+        scan(
+            dir.path(),
+            None,
+            &options,
+            &ConfigOverrides::default(),
+            Style::Plain,
+        )
+        .unwrap();
+
+        let readme = "# Project\n\n## Usage\n\nCall `fn greet() -> String` to say hello.\n";
+        std::fs::write(dir.path().join("README.md"), readme).unwrap();
+        let db = Database::open(dir.path().join(".docsentinel/docsentinel.db")).unwrap();
+        let chunks = DocExtractor::new()
+            .extract_file(Path::new("README.md"), readme)
+            .unwrap();
+        let usage = chunks.iter().find(|c| c.heading == "Usage").unwrap();
+        db.upsert_doc_chunk(usage).unwrap();
+        let event = DriftEvent::new(DriftSeverity::High, "Signature changed", "e", 0.9)
+            .with_code_chunk("src/lib.rs::greet")
+            .with_doc_chunk(&usage.id);
+        let event_id = db.insert_drift_event(&event).unwrap();
+
+        // The fix still shows a signature the code no longer has
+        let args = FixArgs {
+            issue_id: event_id.clone(),
+            yes: true,
+            content: Some("## Usage\n\nCall `fn greet() -> String` to greet.".to_string()),
+            commit: false,
+            dry_run: false,
+            verify: true,
+        };
+        fix(dir.path(), None, &args, Style::Plain).unwrap();
+        let stored = db.get_drift_event(&event_id).unwrap().unwrap();
+        assert_eq!(stored.status, DriftStatus::Pending);
+        assert!(stored.evidence.contains("Docs show:"));
+        assert!(stored
+            .evidence
+            .contains("pub fn greet(name: &str) -> String"));
+
+        // A correct fix is verified and resolves the event
+        std::fs::write(dir.path().join("README.md"), readme).unwrap();
+        let args = FixArgs {
+            content: Some("## Usage\n\nCall `fn greet(name: &str) -> String`.".to_string()),
+            ..args
+        };
+        fix(dir.path(), None, &args, Style::Plain).unwrap();
+        let stored = db.get_drift_event(&event_id).unwrap().unwrap();
+        assert_eq!(stored.status, DriftStatus::Fixed);
+    }
+
+    #[test]
+    fn test_replace_doc_section_falls_back_to_line_range() {
+        let crlf = "# Title\r\n\r\n## Usage\r\n\r\nOld text.\r\n\r\n## Other\r\n";
+        let chunk = DocChunk::new(
+            "README.md",
+            vec!["Title".to_string(), "Usage".to_string()],
+            "Usage",
+            crate::extract::HeadingLevel::H2,
+            "## Usage\n\nOld text.\n",
+            3,
+            6,
+        );
+
+        let updated = replace_doc_section(crlf, &chunk, "## Usage\n\nNew text.\n").unwrap();
+        assert_eq!(
+            updated,
+            "# Title\r\n\r\n## Usage\r\n\r\nNew text.\r\n\r\n## Other\r\n"
+        );
+
+        let stale = crlf.replace("Old text.", "Edited text.");
+        assert!(replace_doc_section(&stale, &chunk, "anything").is_err());
+    }
+
+    #[test]
+    fn test_scan_no_embeddings_skips_embedding_writes() {
+        let dir = create_test_repo();
+        init(dir.path(), None, false, true, Style::Plain).unwrap();
+
+        // Configure an endpoint so embeddings would otherwise be attempted
+        let mut config = RepoConfig::default();
+        config.llm.endpoint = Some("http://127.0.0.1:9".to_string());
+        config.llm.model = Some("test-model".to_string());
+        config.save(dir.path()).unwrap();
+
+        scan(
+            dir.path(),
+            None,
+            &ScanOptions {
+                full: true,
+                embeddings: false,
+                ..Default::default()
+            },
+            &ConfigOverrides::default(),
+            Style::Plain,
+        )
+        .unwrap();
+
+        let db = Database::open(dir.path().join(".docsentinel/docsentinel.db")).unwrap();
+        assert!(!db.get_all_code_chunks().unwrap().is_empty());
+        assert!(db.get_all_code_chunks_with_embeddings().unwrap().is_empty());
+        assert!(db.get_all_doc_chunks_with_embeddings().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_file_similarity_ranks_docs_per_symbol() {
+        use crate::extract::HeadingLevel;
+
+        let dir = create_test_repo();
+        init(dir.path(), None, false, true, Style::Plain).unwrap();
+        let options = ScanOptions {
+            full: true,
+            embeddings: false,
+            ..Default::default()
+        };
+        scan(
+            dir.path(),
+            None,
+            &options,
+            &ConfigOverrides::default(),
+            Style::Plain,
+        )
+        .unwrap();
+
+        let repo = Repository::open(dir.path()).unwrap();
+        let db = Database::open(repo.sentinel_dir().join("docsentinel.db")).unwrap();
+        let file = dir.path().join("src/lib.rs");
+
+        // Nothing is embedded yet
+        assert!(file_similarity(&repo, &db, &file, 5).unwrap().is_empty());
+
+        let mut greet = db.get_code_chunk("src/lib.rs::greet").unwrap().unwrap();
+        greet.embedding = Some(vec![1.0, 0.0]);
+        db.upsert_code_chunk(&greet).unwrap();
+        for (heading, embedding) in [("Greeting", vec![0.9, 0.1]), ("Other", vec![0.0, 1.0])] {
+            let mut doc = DocChunk::new(
+                "README.md",
+                vec![heading.to_string()],
+                heading,
+                HeadingLevel::H2,
+                "Text.",
+                1,
+                2,
+            );
+            doc.embedding = Some(embedding);
+            db.upsert_doc_chunk(&doc).unwrap();
+        }
+
+        let similarities = file_similarity(&repo, &db, &file, 5).unwrap();
+        assert_eq!(similarities.len(), 1);
+        assert_eq!(similarities[0].symbol_name, "greet");
+        let ranked: Vec<&str> = similarities[0]
+            .docs
+            .iter()
+            .map(|(doc, _)| doc.heading.as_str())
+            .collect();
+        assert_eq!(ranked, vec!["Greeting", "Other"]);
+        assert!(similarities[0].docs[0].1 > 0.99);
+
+        let top = file_similarity(&repo, &db, &file, 1).unwrap();
+        assert_eq!(top[0].docs.len(), 1);
+    }
+
+    #[test]
+    fn test_analyze_buffer_matches_stored_docs() {
+        let dir = create_test_repo();
+        init(dir.path(), None, false, true, Style::Plain).unwrap();
+        let db = Database::open(dir.path().join(".docsentinel/docsentinel.db")).unwrap();
+        let docs = DocExtractor::new()
+            .extract_file(
+                Path::new("README.md"),
+                "# Demo\n\n## Greeting\n\nUse `greet(name)` to say hello.\n\n## Other\n\nUnrelated.\n",
+            )
+            .unwrap();
+        for doc in &docs {
+            db.upsert_doc_chunk(doc).unwrap();
+        }
+
+        // The buffer's path does not exist on disk
+        let buffer = "pub fn greet(name: &str) -> String {\n    name.to_string()\n}\n";
+        let virtual_path = dir.path().join("src/unsaved.rs");
+        let symbols =
+            analyze_buffer(dir.path(), None, virtual_path.to_str(), None, buffer, true).unwrap();
+
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].id, "src/unsaved.rs::greet");
+        let related = symbols[0].related_docs.as_ref().unwrap();
+        assert_eq!(related.len(), 1);
+        assert_eq!(related[0].heading, "Greeting");
+
+        // Without docs no repository is needed, but the language must be known
+        let symbols = analyze_buffer(
+            Path::new("/nonexistent"),
+            None,
+            None,
+            Some(Language::Rust),
+            buffer,
+            false,
+        )
+        .unwrap();
+        assert!(symbols[0].related_docs.is_none());
+        assert!(
+            analyze_buffer(Path::new("/nonexistent"), None, None, None, buffer, false).is_err()
+        );
+    }
+
+    #[test]
+    fn test_doctor_reports_setup_problems() {
+        let dir = create_test_repo();
+        let statuses = |checks: &[DoctorCheck]| {
+            checks
+                .iter()
+                .map(|check| (check.name, check.status))
+                .collect::<Vec<_>>()
+        };
+
+        let checks = doctor_checks(dir.path(), None, &ConfigOverrides::default());
+        assert_eq!(
+            statuses(&checks),
+            [
+                ("Git repository", CheckStatus::Pass),
+                ("Initialized", CheckStatus::Fail)
+            ]
+        );
+        assert_eq!(checks[1].hint.as_deref(), Some("Run 'docsentinel init'"));
+
+        init(dir.path(), None, false, true, Style::Plain).unwrap();
+        let checks = doctor_checks(dir.path(), None, &ConfigOverrides::default());
+        assert_eq!(
+            statuses(&checks[..4]),
+            [
+                ("Git repository", CheckStatus::Pass),
+                ("Initialized", CheckStatus::Pass),
+                ("Database", CheckStatus::Pass),
+                ("Scan data", CheckStatus::Warn)
+            ]
+        );
+        let names: Vec<_> = checks[4..].iter().map(|check| check.name).collect();
+        assert_eq!(names, ["LLM endpoint", "Embedding endpoint"]);
+
+        // A database written by a newer build is not usable
+        rusqlite::Connection::open(dir.path().join(".docsentinel/docsentinel.db"))
+            .unwrap()
+            .pragma_update(None, "user_version", DATA_VERSION + 1)
+            .unwrap();
+        let checks = doctor_checks(dir.path(), None, &ConfigOverrides::default());
+        assert_eq!(checks[2].status, CheckStatus::Fail);
+        assert_eq!(checks[3].name, "LLM endpoint");
+    }
+
+    #[test]
+    fn test_baselined_events_do_not_count_toward_fail_on() {
+        let dir = create_test_repo();
+        init(dir.path(), None, false, true, Style::Plain).unwrap();
+        let db = Database::open(dir.path().join(".docsentinel/docsentinel.db")).unwrap();
+
+        let old = DriftEvent::new(DriftSeverity::High, "Signature changed: greet", "", 0.9)
+            .with_code_chunk("src/lib.rs::greet");
+        db.insert_drift_event(&old).unwrap();
+
+        // Without a baseline everything counts
+        assert_eq!(
+            unbaselined_events(dir.path(), None, std::slice::from_ref(&old))
+                .unwrap()
+                .len(),
+            1
+        );
+
+        baseline(dir.path(), None, Style::Plain).unwrap();
+        let new = DriftEvent::new(DriftSeverity::Critical, "Function removed: wave", "", 0.9)
+            .with_code_chunk("src/lib.rs::wave");
+        // A re-detected event gets a fresh ID but the same identity
+        let mut redetected = old.clone();
+        redetected.id = "rescanned".to_string();
+        let counted =
+            unbaselined_events(dir.path(), None, &[redetected.clone(), new.clone()]).unwrap();
+
+        assert_eq!(counted.len(), 1);
+        assert_eq!(counted[0].description, new.description);
+        assert!(unbaselined_events(dir.path(), None, &[redetected])
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_analyze_target_reports_file_as_json() {
+        let dir = create_test_repo();
+        init(dir.path(), None, false, true, Style::Plain).unwrap();
+
+        let file = dir.path().join("src/lib.rs");
+        let report = analyze_target(dir.path(), None, file.to_str().unwrap(), true).unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&serde_json::to_string(&report).unwrap()).unwrap();
+
+        assert_eq!(json["kind"], "code");
+        assert_eq!(json["symbols"][0]["symbol_name"], "greet");
+        assert_eq!(json["symbols"][0]["is_public"], true);
+        // Nothing is embedded yet, so there are no scores to report
+        assert_eq!(json["symbols"][0]["related_docs"], serde_json::json!([]));
+
+        let report = analyze_target(dir.path(), None, "no/such::thing", false).unwrap();
+        assert_eq!(report.kind, "not_found");
+        assert!(report.symbols.is_empty());
+    }
+
+    #[test]
+    fn test_symbol_inventory_lists_stored_symbols() {
+        use crate::extract::{HeadingLevel, SymbolType};
+
+        let dir = create_test_repo();
+        init(dir.path(), None, false, true, Style::Plain).unwrap();
+        let db = Database::open(dir.path().join(".docsentinel/docsentinel.db")).unwrap();
+        let chunk = |path: &str, name: &str, line: usize, public: bool| {
+            let mut chunk = CodeChunk::new(
+                path,
+                name,
+                SymbolType::Function,
+                "fn f() {}",
+                Language::Rust,
+                line,
+                line + 2,
+            );
+            chunk.is_public = public;
+            chunk.signature = Some(format!("fn {}()", name));
+            chunk
+        };
+        db.upsert_code_chunk(&chunk("src/lib.rs", "greet", 1, true))
+            .unwrap();
+        db.upsert_code_chunk(&chunk("src/a.rs", "helper", 10, false))
+            .unwrap();
+        db.upsert_code_chunk(&chunk("src/a.rs", "connect", 2, true))
+            .unwrap();
+        db.upsert_doc_chunk(&DocChunk::new(
+            "README.md",
+            vec!["Usage".to_string()],
+            "Usage",
+            HeadingLevel::H2,
+            "Call `greet` with a name.",
+            3,
+            5,
+        ))
+        .unwrap();
+
+        let symbols = symbol_inventory(dir.path(), None, true).unwrap();
+        let listed: Vec<(&str, &str, bool)> = symbols
+            .iter()
+            .map(|s| (s.file_path.as_str(), s.symbol_name.as_str(), s.is_public))
+            .collect();
+        assert_eq!(
+            listed,
+            [
+                ("src/a.rs", "connect", true),
+                ("src/a.rs", "helper", false),
+                ("src/lib.rs", "greet", true)
+            ]
+        );
+        let greet_docs = symbols[2].related_docs.as_ref().unwrap();
+        assert_eq!(greet_docs.len(), 1);
+        assert_eq!(greet_docs[0].heading, "Usage");
+        assert!(symbols[0].related_docs.as_ref().unwrap().is_empty());
+
+        let text = render_inventory_text(&symbols, Style::Plain);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "LOCATION        VIS      TYPE      SYMBOL");
+        assert_eq!(lines[1], "src/a.rs:2-4    public   function  connect");
+        assert_eq!(lines[4], "    - README.md:3 (Usage)");
+        assert!(text.ends_with("3 symbol(s)\n"));
+
+        // Without --docs the field is left out of the JSON
+        let symbols = symbol_inventory(dir.path(), None, false).unwrap();
+        let json = serde_json::to_value(&symbols).unwrap();
+        assert_eq!(json[2]["signature"], "fn greet()");
+        assert!(json[2].get("related_docs").is_none());
+    }
+
+    #[test]
+    fn test_hook_block_preserves_existing_content() {
+        let existing = "#!/bin/bash\nset -e\nnpm test\nexit 0\n";
+        let command = "docsentinel scan --uncommitted --fail-on high || exit 1";
+
+        let installed = add_hook_block(Some(existing), command);
+        assert!(installed.starts_with(&format!(
+            "#!/bin/bash\n{}\n{}\n{}\nset -e\n",
+            HOOK_BLOCK_START, command, HOOK_BLOCK_END
+        )));
+        assert!(installed.ends_with("npm test\nexit 0\n"));
+        assert!(has_hook_block(&installed));
+
+        // Reinstalling refreshes the block instead of duplicating it
+        let reinstalled = add_hook_block(Some(&installed), "docsentinel scan");
+        assert_eq!(reinstalled.matches(HOOK_BLOCK_START).count(), 1);
+        assert!(!reinstalled.contains(command));
+
+        assert_eq!(remove_hook_block(&installed).as_deref(), Some(existing));
+        assert_eq!(remove_hook_block(&add_hook_block(None, command)), None);
+        assert_eq!(remove_hook_block(LEGACY_POST_COMMIT_HOOK), None);
+        assert!(!has_hook_block(existing));
+    }
 }
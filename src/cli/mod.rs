@@ -3,9 +3,12 @@
 //! Provides the command-line interface for DocSentinel
 
 mod commands;
+mod style;
 
 pub use commands::*;
+pub use style::Style;
 
+use crate::repo::ConfigOverrides;
 use clap::{Parser, Subcommand};
 
 /// DocSentinel - Documentation drift detection tool
@@ -18,14 +21,41 @@ pub struct Cli {
     #[arg(short, long, global = true, default_value = ".")]
     pub path: String,
 
+    /// Restrict analysis to a subdirectory of the repository (e.g. a package
+    /// in a monorepo); the scope keeps its own `.docsentinel` directory
+    #[arg(long, global = true, value_name = "SUBPATH")]
+    pub scope: Option<String>,
+
     /// Enable verbose output
     #[arg(short, long, global = true)]
     pub verbose: bool,
 
-    /// Output format (text, json)
+    /// Output format (text, json, markdown, junit)
     #[arg(short = 'o', long, global = true, default_value = "text")]
     pub format: OutputFormat,
 
+    /// Print JSON output on a single line
+    #[arg(long, global = true)]
+    pub json_compact: bool,
+
+    /// Replace emoji with ASCII tags and drop terminal escape codes (also
+    /// enabled by a non-empty NO_COLOR environment variable)
+    #[arg(long, global = true, visible_alias = "plain")]
+    pub no_color: bool,
+
+    /// LLM endpoint to use instead of the configured one (embeddings follow
+    /// it unless embedding.endpoint is set)
+    #[arg(long, global = true, value_name = "URL")]
+    pub llm_endpoint: Option<String>,
+
+    /// LLM model to use instead of the configured one
+    #[arg(long, global = true, value_name = "MODEL")]
+    pub llm_model: Option<String>,
+
+    /// Embedding model to use instead of the configured one
+    #[arg(long, global = true, value_name = "MODEL")]
+    pub embed_model: Option<String>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -42,6 +72,9 @@ pub enum Commands {
     /// Show detected drift issues
     Status(StatusArgs),
 
+    /// Compare drift introduced and resolved between two commits
+    Diff(DiffArgs),
+
     /// Launch the interactive TUI
     Tui(TuiArgs),
 
@@ -65,6 +98,36 @@ pub enum Commands {
 
     /// Generate documentation from code
     Generate(GenerateArgs),
+
+    /// Show drift statistics by file and severity
+    Stats(StatsArgs),
+
+    /// Show what share of the public API is documented
+    Coverage(CoverageArgs),
+
+    /// Find docs that use subcommands or flags missing from a tool's --help
+    CheckCli(CheckCliArgs),
+
+    /// Write an HTML report of pending drift events
+    Report(ReportArgs),
+
+    /// Remove stored scan data and reclaim space
+    Clean(CleanArgs),
+
+    /// Generate embeddings for stored chunks
+    Embed(EmbedArgs),
+
+    /// Accept the pending drift so --fail-on only fails on new drift
+    Baseline,
+
+    /// Check the repository, database, and endpoints for setup problems
+    Doctor,
+
+    /// Write stored chunks, drift events, and scan state to a JSON file
+    Export(ExportArgs),
+
+    /// Load a JSON file written by export into an empty database
+    Import(ImportArgs),
 }
 
 /// Output format
@@ -72,6 +135,56 @@ pub enum Commands {
 pub enum OutputFormat {
     Text,
     Json,
+    Markdown,
+    Junit,
+}
+
+/// Severity threshold for command line flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SeverityLevel {
+    Critical,
+    High,
+    Medium,
+    Low,
+}
+
+impl From<SeverityLevel> for crate::drift::DriftSeverity {
+    fn from(level: SeverityLevel) -> Self {
+        match level {
+            SeverityLevel::Critical => Self::Critical,
+            SeverityLevel::High => Self::High,
+            SeverityLevel::Medium => Self::Medium,
+            SeverityLevel::Low => Self::Low,
+        }
+    }
+}
+
+/// Source language for `analyze --stdin`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum LanguageArg {
+    Rust,
+    Python,
+}
+
+impl From<LanguageArg> for crate::extract::Language {
+    fn from(language: LanguageArg) -> Self {
+        match language {
+            LanguageArg::Rust => Self::Rust,
+            LanguageArg::Python => Self::Python,
+        }
+    }
+}
+
+/// Order of the symbols listed under each file by `generate`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum SymbolSort {
+    /// Source order
+    #[default]
+    File,
+    /// Alphabetical by symbol name
+    Name,
+    /// By symbol kind, then name
+    Kind,
 }
 
 /// Arguments for init command
@@ -101,6 +214,11 @@ pub struct ScanArgs {
     #[arg(short, long)]
     pub range: Option<String>,
 
+    /// Revision a full scan compares symbols against (default: merge-base
+    /// with the default branch)
+    #[arg(long, value_name = "REF", requires = "full", conflicts_with = "range")]
+    pub base: Option<String>,
+
     /// Include uncommitted changes
     #[arg(short, long)]
     pub uncommitted: bool,
@@ -112,6 +230,44 @@ pub struct ScanArgs {
     /// Use LLM for analysis
     #[arg(long)]
     pub with_llm: bool,
+
+    /// Exit with status 1 if any event is at least this severe
+    #[arg(long, value_name = "LEVEL")]
+    pub fail_on: Option<SeverityLevel>,
+
+    /// Record the pending events as the baseline after scanning, so
+    /// --fail-on only counts drift introduced later
+    #[arg(long)]
+    pub update_baseline: bool,
+
+    /// Discard stored embeddings and re-embed every file (use after
+    /// switching embedding models)
+    #[arg(long, conflicts_with = "no_embeddings")]
+    pub reembed: bool,
+
+    /// Only scan files matching this pattern (repeatable)
+    #[arg(long, value_name = "GLOB")]
+    pub include: Vec<String>,
+
+    /// Skip files matching this pattern (repeatable)
+    #[arg(long, value_name = "GLOB")]
+    pub exclude: Vec<String>,
+
+    /// Write the formatted results to this file instead of stdout
+    #[arg(long, value_name = "FILE")]
+    pub output: Option<String>,
+}
+
+/// Arguments for diff command
+#[derive(Parser, Debug)]
+pub struct DiffArgs {
+    /// Commit range to compare (e.g., "main..HEAD"); a single revision is
+    /// compared against HEAD
+    pub range: String,
+
+    /// Exit with status 1 if any new drift is at least this severe
+    #[arg(long, value_name = "LEVEL")]
+    pub fail_on: Option<SeverityLevel>,
 }
 
 /// Arguments for status command
@@ -125,17 +281,29 @@ pub struct StatusArgs {
     #[arg(short, long)]
     pub severity: Option<String>,
 
-    /// Show detailed information
+    /// Only show issues with this tag (e.g. signature_change, broken_link)
+    #[arg(short, long)]
+    pub tag: Option<String>,
+
+    /// Show the related doc sections and code signatures of each issue
     #[arg(short, long)]
     pub detailed: bool,
+
+    /// Lines of doc shown on each side of the issue's line with --detailed
+    #[arg(short = 'C', long, value_name = "N", default_value = "3")]
+    pub context: usize,
 }
 
 /// Arguments for TUI command
 #[derive(Parser, Debug)]
 pub struct TuiArgs {
-    /// Start in a specific view (status, issues, config)
-    #[arg(short = 'V', long)]
+    /// Start in a specific view (dashboard, issues, docs, help)
+    #[arg(long)]
     pub view: Option<String>,
+
+    /// Only show issues with this tag in the issues view
+    #[arg(short, long)]
+    pub tag: Option<String>,
 }
 
 /// Arguments for fix command
@@ -155,6 +323,15 @@ pub struct FixArgs {
     /// Commit the fix automatically
     #[arg(long)]
     pub commit: bool,
+
+    /// Show a diff of the fix without writing it
+    #[arg(long, conflicts_with = "commit")]
+    pub dry_run: bool,
+
+    /// Re-check the fixed section and only mark the issue fixed if the drift
+    /// no longer triggers
+    #[arg(long, conflicts_with = "dry_run")]
+    pub verify: bool,
 }
 
 /// Arguments for ignore command
@@ -183,6 +360,11 @@ pub struct HooksArgs {
     #[arg(long)]
     pub uninstall: bool,
 
+    /// Manage the pre-commit hook (blocks commits with high-severity drift)
+    /// instead of the post-commit hook
+    #[arg(long)]
+    pub pre_commit: bool,
+
     /// Show hook status
     #[arg(long)]
     pub status: bool,
@@ -195,9 +377,18 @@ pub struct WatchArgs {
     #[arg(short, long, default_value = "1000")]
     pub debounce: u64,
 
-    /// Run in background
-    #[arg(short, long)]
+    /// Run detached from the terminal, logging to .docsentinel/watch.log
+    #[arg(short, long, conflicts_with = "once")]
     pub background: bool,
+
+    /// Wait until no files have changed for the debounce interval, scan
+    /// once, print the results, and exit
+    #[arg(long)]
+    pub once: bool,
+
+    /// With --once, exit with status 1 if any event is at least this severe
+    #[arg(long, value_name = "LEVEL", requires = "once")]
+    pub fail_on: Option<SeverityLevel>,
 }
 
 /// Arguments for config command
@@ -207,8 +398,8 @@ pub struct ConfigArgs {
     #[arg(long)]
     pub show: bool,
 
-    /// Set a configuration value
-    #[arg(long)]
+    /// Set a configuration value (key=value)
+    #[arg(long, value_name = "KEY=VALUE")]
     pub set: Option<String>,
 
     /// Get a configuration value
@@ -223,8 +414,22 @@ pub struct ConfigArgs {
 /// Arguments for analyze command
 #[derive(Parser, Debug)]
 pub struct AnalyzeArgs {
-    /// File or symbol to analyze
-    pub target: String,
+    /// File or symbol to analyze; with --stdin, the path of the buffer
+    #[arg(required_unless_present_any = ["stdin", "all"])]
+    pub target: Option<String>,
+
+    /// List every stored symbol with its location, visibility, and
+    /// signature (add --docs for the doc sections referencing each)
+    #[arg(long, conflicts_with_all = ["target", "stdin"])]
+    pub all: bool,
+
+    /// Read source from stdin and print its symbols as JSON (for editors)
+    #[arg(long)]
+    pub stdin: bool,
+
+    /// Language of the stdin source (defaults to the target's extension)
+    #[arg(long, value_name = "LANG", requires = "stdin")]
+    pub lang: Option<LanguageArg>,
 
     /// Show related documentation
     #[arg(short, long)]
@@ -250,6 +455,10 @@ pub struct GenerateArgs {
     #[arg(long)]
     pub output: Option<String>,
 
+    /// Write one Markdown file per source file, plus an index.md, into this directory
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["output", "readme", "with_llm"])]
+    pub output_dir: Option<String>,
+
     /// Include private symbols
     #[arg(long)]
     pub include_private: bool,
@@ -257,6 +466,117 @@ pub struct GenerateArgs {
     /// Use LLM to generate natural language descriptions
     #[arg(long)]
     pub with_llm: bool,
+
+    /// Only document these symbol kinds (comma-separated, e.g.
+    /// "function,struct,trait")
+    #[arg(long, value_name = "KINDS", value_delimiter = ',')]
+    pub only: Vec<crate::extract::SymbolType>,
+
+    /// Order of the symbols listed under each file
+    #[arg(long, value_enum, default_value = "file")]
+    pub sort: SymbolSort,
+}
+
+/// Arguments for stats command
+#[derive(Parser, Debug)]
+pub struct StatsArgs {
+    /// Number of files to show
+    #[arg(short = 'n', long, default_value = "10")]
+    pub top: usize,
+}
+
+/// Arguments for check-cli command
+#[derive(Parser, Debug)]
+pub struct CheckCliArgs {
+    /// Command that prints the tool's help (e.g. "cargo run -q -- --help");
+    /// each listed subcommand's help is read as well
+    #[arg(
+        long,
+        value_name = "COMMAND",
+        required_unless_present = "help_file",
+        conflicts_with = "help_file"
+    )]
+    pub help_from: Option<String>,
+
+    /// Read the help text (or a list of flags) from this file instead
+    #[arg(long, value_name = "FILE")]
+    pub help_file: Option<String>,
+
+    /// Exit with status 1 if any event is at least this severe
+    #[arg(long, value_name = "LEVEL")]
+    pub fail_on: Option<SeverityLevel>,
+}
+
+/// Arguments for coverage command
+#[derive(Parser, Debug)]
+pub struct CoverageArgs {
+    /// Exit with status 1 if coverage is below this percentage
+    #[arg(long, value_name = "PCT")]
+    pub fail_under: Option<f64>,
+
+    /// List the undocumented symbols in each file
+    #[arg(long)]
+    pub detailed: bool,
+}
+
+/// Arguments for report command
+#[derive(Parser, Debug)]
+pub struct ReportArgs {
+    /// Output file for the HTML report
+    #[arg(long, default_value = "docsentinel-report.html")]
+    pub output: String,
+}
+
+/// Arguments for clean command
+#[derive(Parser, Debug)]
+pub struct CleanArgs {
+    /// Delete resolved drift events
+    #[arg(long)]
+    pub events: bool,
+
+    /// With --events, delete pending events too
+    #[arg(long, requires = "events")]
+    pub all: bool,
+
+    /// Delete all extracted code and doc chunks
+    #[arg(long)]
+    pub chunks: bool,
+
+    /// Reset scan state so the next scan is a full scan
+    #[arg(long)]
+    pub scan_state: bool,
+
+    /// Run SQLite VACUUM to reclaim disk space
+    #[arg(long)]
+    pub vacuum: bool,
+}
+
+/// Arguments for embed command
+#[derive(Parser, Debug)]
+pub struct EmbedArgs {
+    /// Embed the stored chunks that have no embedding yet (e.g. after a scan
+    /// with --no-embeddings), without re-extracting files
+    #[arg(long)]
+    pub backfill: bool,
+}
+
+/// Arguments for export command
+#[derive(Parser, Debug)]
+pub struct ExportArgs {
+    /// Output file for the export
+    #[arg(long, default_value = "docsentinel-export.json")]
+    pub output: String,
+}
+
+/// Arguments for import command
+#[derive(Parser, Debug)]
+pub struct ImportArgs {
+    /// File written by `docsentinel export`
+    pub file: String,
+
+    /// Delete stored chunks, events, and scan state before importing
+    #[arg(long)]
+    pub force: bool,
 }
 
 impl Cli {
@@ -264,12 +584,32 @@ impl Cli {
     pub fn parse_args() -> Self {
         Self::parse()
     }
+
+    /// Output style selected by `--no-color` and `NO_COLOR`
+    pub fn style(&self) -> Style {
+        Style::detect(self.no_color)
+    }
+
+    /// LLM and embedding settings overridden on the command line
+    pub fn config_overrides(&self) -> ConfigOverrides {
+        ConfigOverrides {
+            llm_endpoint: self.llm_endpoint.clone(),
+            llm_model: self.llm_model.clone(),
+            embed_model: self.embed_model.clone(),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_cli_definition_is_valid() {
+        use clap::CommandFactory;
+        Cli::command().debug_assert();
+    }
+
     #[test]
     fn test_cli_parsing() {
         let cli = Cli::parse_from(["docsentinel", "scan", "--full"]);
@@ -280,6 +620,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_config_override_flags() {
+        let cli = Cli::parse_from([
+            "docsentinel",
+            "scan",
+            "--llm-endpoint",
+            "http://gpu-box:11434",
+            "--embed-model",
+            "mxbai-embed-large",
+        ]);
+        assert_eq!(
+            cli.config_overrides(),
+            ConfigOverrides {
+                llm_endpoint: Some("http://gpu-box:11434".to_string()),
+                llm_model: None,
+                embed_model: Some("mxbai-embed-large".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_tui_view_flag() {
+        let cli = Cli::parse_from(["docsentinel", "tui", "--view", "issues", "--tag", "x"]);
+        assert!(
+            matches!(cli.command, Commands::Tui(ref args) if args.view.as_deref() == Some("issues"))
+        );
+    }
+
     #[test]
     fn test_init_command() {
         let cli = Cli::parse_from(["docsentinel", "init", "--force"]);
@@ -287,4 +655,17 @@ mod tests {
             assert!(args.force);
         }
     }
+
+    #[test]
+    fn test_export_and_import_commands() {
+        let cli = Cli::parse_from(["docsentinel", "export", "--output", "baseline.json"]);
+        assert!(
+            matches!(cli.command, Commands::Export(ref args) if args.output == "baseline.json")
+        );
+
+        let cli = Cli::parse_from(["docsentinel", "import", "baseline.json", "--force"]);
+        assert!(
+            matches!(cli.command, Commands::Import(ref args) if args.file == "baseline.json" && args.force)
+        );
+    }
 }
@@ -0,0 +1,109 @@
+//! Text output decoration
+//!
+//! Text output marks severities and outcomes with emoji and shows a progress
+//! bar while scanning. [`Style::Plain`] swaps the emoji for ASCII tags and
+//! drops terminal escape codes, for CI logs and terminals without UTF-8.
+
+use crate::drift::DriftSeverity;
+
+/// How text output is decorated
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Style {
+    /// Emoji markers and a live progress bar
+    #[default]
+    Fancy,
+    /// ASCII tags and no escape codes
+    Plain,
+}
+
+impl Style {
+    /// Pick the style from the `--no-color` flag and the `NO_COLOR`
+    /// environment variable (any non-empty value selects plain output)
+    pub fn detect(no_color: bool) -> Self {
+        let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        if no_color || no_color_env {
+            Style::Plain
+        } else {
+            Style::Fancy
+        }
+    }
+
+    /// Whether emoji and escape codes may be written
+    pub fn is_fancy(self) -> bool {
+        self == Style::Fancy
+    }
+
+    /// Marker for a step that succeeded
+    pub fn ok(self) -> &'static str {
+        match self {
+            Style::Fancy => "✓",
+            Style::Plain => "[OK]",
+        }
+    }
+
+    /// Marker for a warning or an incomplete step
+    pub fn warn(self) -> &'static str {
+        match self {
+            Style::Fancy => "⚠",
+            Style::Plain => "[WARN]",
+        }
+    }
+
+    /// Marker for a failed check
+    pub fn fail(self) -> &'static str {
+        match self {
+            Style::Fancy => "✗",
+            Style::Plain => "[FAIL]",
+        }
+    }
+
+    /// Marker for a list item
+    pub fn bullet(self) -> &'static str {
+        match self {
+            Style::Fancy => "•",
+            Style::Plain => "-",
+        }
+    }
+
+    /// A decorative emoji (with its trailing spacing), or nothing when plain
+    pub fn icon(self, emoji: &'static str) -> &'static str {
+        match self {
+            Style::Fancy => emoji,
+            Style::Plain => "",
+        }
+    }
+
+    /// Label leading an event line, e.g. `🟠 [HIGH]` or `[HIGH]`
+    pub fn severity_label(self, severity: DriftSeverity) -> String {
+        match self {
+            Style::Fancy => format!("{} [{}]", severity_emoji(severity), severity),
+            Style::Plain => severity_tag(severity).to_string(),
+        }
+    }
+
+    /// A severity named on its own, e.g. `🟠 HIGH` or `HIGH`
+    pub fn severity_name(self, severity: DriftSeverity) -> String {
+        match self {
+            Style::Fancy => format!("{} {}", severity_emoji(severity), severity),
+            Style::Plain => severity.to_string(),
+        }
+    }
+}
+
+fn severity_emoji(severity: DriftSeverity) -> &'static str {
+    match severity {
+        DriftSeverity::Critical => "🔴",
+        DriftSeverity::High => "🟠",
+        DriftSeverity::Medium => "🟡",
+        DriftSeverity::Low => "🟢",
+    }
+}
+
+fn severity_tag(severity: DriftSeverity) -> &'static str {
+    match severity {
+        DriftSeverity::Critical => "[CRIT]",
+        DriftSeverity::High => "[HIGH]",
+        DriftSeverity::Medium => "[MED]",
+        DriftSeverity::Low => "[LOW]",
+    }
+}
@@ -0,0 +1,90 @@
+//! Baseline of accepted drift
+//!
+//! A baseline records the [identities](DriftEvent::identity) of the drift
+//! pending when it was taken, so `scan --fail-on` can fail only on drift
+//! introduced since. It lives in `.docsentinel/baseline.json` and is meant to
+//! be committed.
+
+use super::DriftEvent;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// Identities of drift events accepted as pre-existing
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Baseline {
+    /// Event identities, sorted so the file diffs cleanly
+    pub identities: BTreeSet<String>,
+}
+
+impl Baseline {
+    /// File name of the baseline inside the `.docsentinel` directory
+    pub const FILE_NAME: &'static str = "baseline.json";
+
+    /// Baseline accepting every one of `events`
+    pub fn from_events(events: &[DriftEvent]) -> Self {
+        Self {
+            identities: events.iter().map(DriftEvent::identity).collect(),
+        }
+    }
+
+    /// Path of the baseline in `sentinel_dir`
+    pub fn path(sentinel_dir: &Path) -> PathBuf {
+        sentinel_dir.join(Self::FILE_NAME)
+    }
+
+    /// Load the baseline from `sentinel_dir`, or `None` when none was taken
+    pub fn load(sentinel_dir: &Path) -> Result<Option<Self>> {
+        let path = Self::path(sentinel_dir);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let baseline = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        Ok(Some(baseline))
+    }
+
+    /// Write the baseline to `sentinel_dir`
+    pub fn save(&self, sentinel_dir: &Path) -> Result<()> {
+        let path = Self::path(sentinel_dir);
+        std::fs::write(&path, serde_json::to_string_pretty(self)? + "\n")
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Whether `event` was pending when the baseline was taken
+    pub fn contains(&self, event: &DriftEvent) -> bool {
+        self.identities.contains(&event.identity())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drift::DriftSeverity;
+
+    #[test]
+    fn test_baseline_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(Baseline::load(dir.path()).unwrap(), None);
+
+        let old = DriftEvent::new(DriftSeverity::High, "Signature changed", "", 0.9)
+            .with_code_chunk("src/lib.rs::add");
+        let baseline = Baseline::from_events(std::slice::from_ref(&old));
+        baseline.save(dir.path()).unwrap();
+
+        let loaded = Baseline::load(dir.path()).unwrap().unwrap();
+        assert_eq!(loaded, baseline);
+        // Identity ignores the per-run ID and the evidence
+        let mut redetected = old.clone();
+        redetected.id = "other".to_string();
+        redetected.evidence = "new evidence".to_string();
+        assert!(loaded.contains(&redetected));
+
+        let new = DriftEvent::new(DriftSeverity::High, "Signature changed", "", 0.9)
+            .with_code_chunk("src/lib.rs::sub");
+        assert!(!loaded.contains(&new));
+    }
+}
@@ -0,0 +1,310 @@
+//! Drift between a command line tool's documentation and its `--help` output
+//!
+//! READMEs of command line tools document subcommands and flags. When a flag
+//! is removed from the argument parser, examples that still use it break. The
+//! accepted subcommands and long flags are read from the tool's help text and
+//! compared with the invocations shown in doc sections.
+
+use super::{DriftEvent, DriftSeverity};
+use crate::extract::DocChunk;
+use std::collections::BTreeSet;
+
+/// Subcommands and long flags a command line tool accepts
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CliHelp {
+    /// Program name from the `Usage:` line
+    pub binary: Option<String>,
+    /// Subcommands listed under a `Commands:` heading
+    pub commands: BTreeSet<String>,
+    /// Long flags (`--name`) mentioned anywhere in the help text
+    pub flags: BTreeSet<String>,
+}
+
+impl CliHelp {
+    /// Read the program name, subcommands, and long flags from help text
+    ///
+    /// Understands clap's layout (`Usage:` line, `Commands:` and `Options:`
+    /// sections). Any text works for flags, so a plain list of flags is
+    /// accepted too.
+    pub fn parse(help: &str) -> Self {
+        let mut parsed = CliHelp::default();
+        let mut in_commands = false;
+        let mut command_indent = None;
+
+        for line in help.lines() {
+            let trimmed = line.trim();
+            let indent = line.len() - line.trim_start().len();
+            parsed.flags.extend(long_flags(line));
+
+            if let Some(usage) = strip_prefix_ignore_case(trimmed, "usage:") {
+                if parsed.binary.is_none() {
+                    parsed.binary = usage.split_whitespace().next().map(program_name);
+                }
+                continue;
+            }
+
+            // Section headings start in the first column and end with a colon
+            if indent == 0 && trimmed.ends_with(':') {
+                let heading = trimmed.trim_end_matches(':').to_ascii_lowercase();
+                in_commands = heading == "commands" || heading == "subcommands";
+                command_indent = None;
+                continue;
+            }
+
+            if !in_commands || trimmed.is_empty() {
+                continue;
+            }
+            // Wrapped descriptions are indented further than the names
+            if *command_indent.get_or_insert(indent) != indent {
+                continue;
+            }
+            if let Some(name) = trimmed.split_whitespace().next() {
+                if is_command_name(name) {
+                    parsed.commands.insert(name.to_string());
+                }
+            }
+        }
+
+        parsed
+    }
+
+    /// Add the flags of a subcommand's help text
+    pub fn merge_flags(&mut self, subcommand_help: &CliHelp) {
+        self.flags.extend(subcommand_help.flags.iter().cloned());
+    }
+
+    /// Report doc sections that use flags or subcommands the tool no longer
+    /// accepts
+    ///
+    /// Flags are read from inline code starting with `--` and from
+    /// invocations of the program, in inline code or fenced code blocks.
+    /// Subcommands are only checked when the help text listed some.
+    pub fn check_docs(&self, docs: &[&DocChunk]) -> Vec<DriftEvent> {
+        let mut events = Vec::new();
+
+        for doc in docs {
+            let mut reported = BTreeSet::new();
+            for (line, code) in doc_invocations(doc, self.binary.as_deref()) {
+                let line = doc.start_line + line - 1;
+                let (command, flags) = match &self.binary {
+                    Some(binary) => match invocation_args(&code, binary) {
+                        Some(args) => (subcommand(args), long_flags(args)),
+                        None => (None, long_flags(&code)),
+                    },
+                    None => (None, long_flags(&code)),
+                };
+
+                if let Some(command) = command {
+                    if !self.commands.is_empty()
+                        && !self.commands.contains(command)
+                        && reported.insert(command.to_string())
+                    {
+                        events.push(self.removed_event(doc, line, "subcommand", command));
+                    }
+                }
+                for flag in flags {
+                    if !self.flags.contains(&flag) && reported.insert(flag.clone()) {
+                        events.push(self.removed_event(doc, line, "flag", &flag));
+                    }
+                }
+            }
+        }
+
+        events
+    }
+
+    fn removed_event(&self, doc: &DocChunk, line: usize, kind: &str, name: &str) -> DriftEvent {
+        let binary = self.binary.as_deref().unwrap_or("the command");
+        DriftEvent::new(
+            DriftSeverity::High,
+            &format!("Documented CLI {} no longer exists: {}", kind, name),
+            &format!(
+                "`{}` at {}:{} is not in the --help output of `{}`",
+                name, doc.file_path, line, binary
+            ),
+            0.9,
+        )
+        .with_doc_chunk(&doc.id)
+        .with_tag(&format!("removed_cli_{}", kind))
+        .with_location(&doc.file_path, line)
+        .with_suggested_fix(&format!(
+            "Remove `{}` from the documentation or replace it with its current equivalent",
+            name
+        ))
+    }
+}
+
+/// Code in a doc section that may mention flags, with its line in the
+/// section (1-based)
+///
+/// Inline spans count when they start with `--` or invoke the program.
+/// Fenced block lines count when they invoke the program.
+fn doc_invocations(doc: &DocChunk, binary: Option<&str>) -> Vec<(usize, String)> {
+    let invokes = |code: &str| binary.is_some_and(|binary| invocation_args(code, binary).is_some());
+
+    let mut code = Vec::new();
+    for span in doc.inline_code_spans() {
+        if span.code.trim_start().starts_with("--") || invokes(&span.code) {
+            code.push((span.line, span.code));
+        }
+    }
+    for block in doc.fenced_blocks() {
+        for (offset, line) in block.content.lines().enumerate() {
+            if invokes(line) {
+                code.push((block.line + offset, line.to_string()));
+            }
+        }
+    }
+    code.sort_by_key(|(line, _)| *line);
+    code
+}
+
+/// Arguments after the program name, if `code` runs `binary`
+///
+/// A leading shell prompt (`$ `) is skipped, and the program may be given
+/// with a path (`./target/release/tool`).
+fn invocation_args<'a>(code: &'a str, binary: &str) -> Option<&'a str> {
+    let code = code.trim_start();
+    let code = code.strip_prefix("$ ").unwrap_or(code).trim_start();
+    let (program, args) = code.split_once(char::is_whitespace).unwrap_or((code, ""));
+    (program_name(program) == binary).then_some(args)
+}
+
+/// First argument, when it names a subcommand rather than a flag or a
+/// placeholder
+fn subcommand(args: &str) -> Option<&str> {
+    args.split_whitespace()
+        .next()
+        .filter(|arg| is_command_name(arg))
+}
+
+/// Whether `name` looks like a subcommand: lowercase letters, digits, and
+/// dashes, starting with a letter
+fn is_command_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// File name of a program path
+fn program_name(program: &str) -> String {
+    program
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or(program)
+        .to_string()
+}
+
+/// Long flags (`--name`) in `text`, without any `=value`
+fn long_flags(text: &str) -> Vec<String> {
+    let mut flags = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("--") {
+        let before = rest[..start].chars().next_back();
+        let candidate = &rest[start + 2..];
+        let len = candidate
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+            .unwrap_or(candidate.len());
+        let name = candidate[..len].trim_end_matches('-');
+        // `a--b` and a bare `--` are not flags
+        if !name.is_empty()
+            && name.starts_with(|c: char| c.is_ascii_alphanumeric())
+            && !before.is_some_and(|c| c.is_ascii_alphanumeric() || c == '-')
+        {
+            flags.push(format!("--{}", name));
+        }
+        rest = &candidate[len.max(1).min(candidate.len())..];
+    }
+    flags
+}
+
+fn strip_prefix_ignore_case<'a>(text: &'a str, prefix: &str) -> Option<&'a str> {
+    text.get(..prefix.len())
+        .filter(|start| start.eq_ignore_ascii_case(prefix))
+        .map(|_| &text[prefix.len()..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extract::DocExtractor;
+    use std::path::Path;
+
+    const HELP: &str = "\
+Detects documentation drift
+
+Usage: tool [OPTIONS] <COMMAND>
+
+Commands:
+  scan    Scan the repository for drift, a long description that wraps
+          onto a second line
+  status  Show status
+  help    Print this message or the help of the given subcommand(s)
+
+Options:
+  -p, --path <PATH>     Path to the repository [default: .]
+  -o, --format <FORMAT> Output format (requires --path)
+      --json-compact    Print JSON on one line
+  -h, --help            Print help
+";
+
+    #[test]
+    fn test_parse_clap_help() {
+        let help = CliHelp::parse(HELP);
+
+        assert_eq!(help.binary.as_deref(), Some("tool"));
+        assert_eq!(
+            help.commands.iter().map(String::as_str).collect::<Vec<_>>(),
+            vec!["help", "scan", "status"]
+        );
+        assert_eq!(
+            help.flags.iter().map(String::as_str).collect::<Vec<_>>(),
+            vec!["--format", "--help", "--json-compact", "--path"]
+        );
+    }
+
+    #[test]
+    fn test_check_docs_reports_removed_flags_and_commands() {
+        let mut help = CliHelp::parse(HELP);
+        help.merge_flags(&CliHelp::parse(
+            "Usage: tool scan [OPTIONS]\n\nOptions:\n  -f, --full  Scan everything\n",
+        ));
+
+        let readme = "# Tool\n\n## Usage\n\n\
+            Run `tool scan --full --fast` or `tool watch`.\n\n\
+            ```bash\n$ ./target/release/tool --format=json status --all\ncargo build --release\n```\n\n\
+            - `--json-compact`: one line\n- `--verbose`: more output\n";
+        let docs = DocExtractor::new()
+            .extract_file(Path::new("README.md"), readme)
+            .unwrap();
+        let docs: Vec<&DocChunk> = docs.iter().collect();
+
+        let events = help.check_docs(&docs);
+        let found: Vec<(&str, Option<usize>)> = events
+            .iter()
+            .map(|e| (e.description.as_str(), e.primary_line))
+            .collect();
+
+        assert_eq!(
+            found,
+            vec![
+                ("Documented CLI flag no longer exists: --fast", Some(5)),
+                ("Documented CLI subcommand no longer exists: watch", Some(5)),
+                ("Documented CLI flag no longer exists: --all", Some(8)),
+                ("Documented CLI flag no longer exists: --verbose", Some(13)),
+            ]
+        );
+        assert!(events[1].has_tag("removed_cli_subcommand"));
+        assert!(events[0].has_tag("removed_cli_flag"));
+        assert_eq!(events[0].related_doc_chunks, vec![docs[0].id.clone()]);
+    }
+
+    #[test]
+    fn test_long_flags() {
+        assert_eq!(
+            long_flags("--a, --b-c=1 [--d_e] a--b -- --- x"),
+            vec!["--a", "--b-c", "--d_e"]
+        );
+    }
+}
@@ -0,0 +1,65 @@
+//! Documentation coverage of the public API
+//!
+//! Coverage is the share of public symbols that have any documentation: a
+//! non-empty doc comment, or a doc section related to the symbol (an explicit
+//! link, a shown signature, or an embedding above the similarity threshold).
+
+use serde::Serialize;
+
+/// Documentation coverage across the repository, broken down by file
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DocCoverage {
+    /// Per-file coverage, sorted by file path
+    pub files: Vec<FileCoverage>,
+}
+
+impl DocCoverage {
+    /// Number of public symbols counted
+    pub fn total(&self) -> usize {
+        self.files.iter().map(|f| f.total).sum()
+    }
+
+    /// Number of public symbols with any documentation
+    pub fn documented(&self) -> usize {
+        self.files.iter().map(|f| f.documented).sum()
+    }
+
+    /// Percentage of public symbols with any documentation
+    ///
+    /// An empty public API counts as fully covered.
+    pub fn percent(&self) -> f64 {
+        percent(self.documented(), self.total())
+    }
+}
+
+/// Documentation coverage of one source file
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FileCoverage {
+    /// File path relative to the repository root
+    pub file_path: String,
+    /// Public symbols in the file
+    pub total: usize,
+    /// Symbols with a non-empty doc comment
+    pub with_doc_comment: usize,
+    /// Symbols with at least one related doc section
+    pub with_related_docs: usize,
+    /// Symbols with a doc comment, related docs, or both
+    pub documented: usize,
+    /// Names of the symbols with no documentation
+    pub undocumented: Vec<String>,
+}
+
+impl FileCoverage {
+    /// Percentage of the file's public symbols with any documentation
+    pub fn percent(&self) -> f64 {
+        percent(self.documented, self.total)
+    }
+}
+
+fn percent(documented: usize, total: usize) -> f64 {
+    if total == 0 {
+        100.0
+    } else {
+        documented as f64 * 100.0 / total as f64
+    }
+}
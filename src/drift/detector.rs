@@ -5,10 +5,16 @@
 //! - Rule-based detection
 //! - Evidence collection
 
+use super::rules::{
+    ambiguous_names, doc_relates_to, documented_signature_names, pair_renames, short_name,
+    RenamedSymbolRule,
+};
 use super::{
-    cosine_similarity, DriftEvent, DriftSeverity, HardDriftRules, SimilarityResult, SoftDriftRules,
+    similarity, ConflictingDocsRule, DocCoverage, DriftEvent, DriftRule, DriftSeverity,
+    FileCoverage, HardDriftRules, SimilarityMetric, SimilarityResult, SoftDriftRules,
+    DEFAULT_DIFF_CONTEXT_LINES,
 };
-use crate::extract::{CodeChunk, DocChunk};
+use crate::extract::{CodeChunk, DocChunk, SymbolType};
 use crate::storage::Database;
 use anyhow::Result;
 use std::collections::HashMap;
@@ -26,6 +32,15 @@ pub struct DriftConfig {
     pub use_hard_rules: bool,
     /// Whether to use soft rules
     pub use_soft_rules: bool,
+    /// How embeddings are compared
+    pub metric: SimilarityMetric,
+    /// Added to a doc section's similarity when its heading or content names
+    /// the symbol
+    pub lexical_weight: f64,
+    /// Unchanged lines kept around each change in evidence diffs
+    pub diff_context_lines: usize,
+    /// Events with a lower confidence are not reported
+    pub min_confidence: f64,
 }
 
 impl Default for DriftConfig {
@@ -36,6 +51,27 @@ impl Default for DriftConfig {
             top_k: 5,
             use_hard_rules: true,
             use_soft_rules: true,
+            metric: SimilarityMetric::Cosine,
+            lexical_weight: 0.15,
+            diff_context_lines: DEFAULT_DIFF_CONTEXT_LINES,
+            min_confidence: 0.3,
+        }
+    }
+}
+
+impl DriftConfig {
+    /// Build detection settings from a repository configuration
+    pub fn from_repo_config(config: &crate::repo::RepoConfig) -> Self {
+        Self {
+            similarity_threshold: config.similarity_threshold as f64,
+            drop_threshold: config.drift.drop_threshold,
+            top_k: config.top_k,
+            use_hard_rules: config.drift.use_hard_rules,
+            use_soft_rules: config.drift.use_soft_rules,
+            metric: config.drift.similarity_metric,
+            lexical_weight: config.drift.lexical_weight,
+            diff_context_lines: config.drift.diff_context_lines,
+            min_confidence: config.drift.min_confidence,
         }
     }
 }
@@ -60,12 +96,24 @@ impl DriftDetector {
     /// Create with custom configuration
     pub fn with_config(config: DriftConfig) -> Self {
         Self {
-            config,
             hard_rules: HardDriftRules::new(),
-            soft_rules: SoftDriftRules::new(),
+            soft_rules: SoftDriftRules::with_diff_context(config.diff_context_lines),
+            config,
         }
     }
 
+    /// Replace the hard rules, e.g. defaults plus custom rules
+    pub fn with_hard_rules(mut self, rules: HardDriftRules) -> Self {
+        self.hard_rules = rules;
+        self
+    }
+
+    /// Replace the soft rules, e.g. defaults plus custom rules
+    pub fn with_soft_rules(mut self, rules: SoftDriftRules) -> Self {
+        self.soft_rules = rules;
+        self
+    }
+
     /// Detect drift for changed code chunks
     pub fn detect_code_drift(
         &self,
@@ -76,9 +124,40 @@ impl DriftDetector {
     ) -> Result<Vec<DriftEvent>> {
         let mut events = Vec::new();
 
+        // A removed symbol whose body reappears under a new name was renamed;
+        // report that instead of a removal and an addition
+        let mut removed: Vec<&CodeChunk> = old_chunks
+            .iter()
+            .filter(|(id, _)| !new_chunks.contains_key(*id))
+            .map(|(_, chunk)| chunk)
+            .collect();
+        let mut added: Vec<&CodeChunk> = new_chunks
+            .iter()
+            .filter(|(id, _)| !old_chunks.contains_key(*id))
+            .map(|(_, chunk)| chunk)
+            .collect();
+        removed.sort_by(|a, b| a.id.cmp(&b.id));
+        added.sort_by(|a, b| a.id.cmp(&b.id));
+        let renames = pair_renames(&removed, &added);
+        let mut renamed_ids = std::collections::HashSet::new();
+        for (old, new) in renames {
+            renamed_ids.insert(&old.id);
+            renamed_ids.insert(&new.id);
+            if self.config.use_hard_rules {
+                let related_docs = self.find_related_docs(old, doc_chunks);
+                let related_doc_refs: Vec<&DocChunk> = related_docs.iter().collect();
+                let rule = RenamedSymbolRule;
+                events.extend(
+                    rule.check_code_change(Some(old), Some(new), &related_doc_refs)
+                        .map(|event| event.with_tag(rule.name())),
+                );
+            }
+        }
+
         // Find all changed, added, and removed code chunks
         let mut all_ids: std::collections::HashSet<&String> = old_chunks.keys().collect();
         all_ids.extend(new_chunks.keys());
+        all_ids.retain(|id| !renamed_ids.contains(id));
 
         for id in all_ids {
             let old_chunk = old_chunks.get(id);
@@ -124,6 +203,7 @@ impl DriftDetector {
 
         // Deduplicate events by related chunks
         events = self.deduplicate_events(events);
+        events.retain(|event| event.confidence >= self.config.min_confidence);
 
         Ok(events)
     }
@@ -156,7 +236,16 @@ impl DriftDetector {
             let related_code =
                 self.find_related_code(new_chunk.or(old_chunk).unwrap(), code_chunks);
 
-            let _related_code_refs: Vec<&CodeChunk> = related_code.iter().collect();
+            let related_code_refs: Vec<&CodeChunk> = related_code.iter().collect();
+
+            // Apply hard rules (e.g. docs showing a signature the code no longer has)
+            if self.config.use_hard_rules {
+                events.extend(self.hard_rules.check_doc_change(
+                    old_chunk,
+                    new_chunk,
+                    &related_code_refs,
+                ));
+            }
 
             // Check if doc was removed but code still exists
             if let (Some(old), None) = (old_chunk.as_ref(), new_chunk.as_ref()) {
@@ -167,21 +256,114 @@ impl DriftDetector {
                         "Documentation was removed but related code still exists",
                         0.8,
                     )
-                    .with_doc_chunk(&old.id);
+                    .with_tag("doc_removed")
+                    .with_doc_chunk(&old.id)
+                    .with_location(&old.file_path, old.start_line);
 
                     events.push(event);
                 }
             }
         }
 
+        events.retain(|event| event.confidence >= self.config.min_confidence);
         Ok(events)
     }
 
-    /// Find doc chunks related to a code chunk using embeddings
+    /// Find public symbols whose related doc sections show conflicting
+    /// signatures
+    ///
+    /// Related sections are those that link to the symbol or show its
+    /// signature, plus its nearest sections above the similarity threshold.
+    /// Names shared by several public symbols are skipped. Returns nothing
+    /// when hard rules are disabled.
+    pub fn detect_conflicting_docs(
+        &self,
+        code_chunks: &[CodeChunk],
+        doc_chunks: &[DocChunk],
+    ) -> Vec<DriftEvent> {
+        if !self.config.use_hard_rules {
+            return Vec::new();
+        }
+
+        let public: Vec<&CodeChunk> = code_chunks.iter().filter(|c| c.is_public).collect();
+        let ambiguous = ambiguous_names(public.iter().copied());
+
+        let mut events = Vec::new();
+        for chunk in public {
+            if ambiguous.contains(short_name(&chunk.symbol_name)) {
+                continue;
+            }
+            let similar = self.find_related_docs(chunk, doc_chunks);
+            let related: Vec<&DocChunk> = doc_chunks
+                .iter()
+                .filter(|doc| doc_relates_to(doc, chunk) || similar.iter().any(|s| s.id == doc.id))
+                .collect();
+            events.extend(ConflictingDocsRule.check_symbol(chunk, &related));
+        }
+
+        events
+    }
+
+    /// Measure how much of the public API is documented
+    ///
+    /// A public symbol counts as documented when it has a non-empty doc
+    /// comment or a related doc section: one that links to it or shows its
+    /// signature, or one of its nearest sections above the similarity
+    /// threshold. `impl` blocks are not counted.
+    pub fn coverage(&self, code_chunks: &[CodeChunk], doc_chunks: &[DocChunk]) -> DocCoverage {
+        let mut files: Vec<FileCoverage> = Vec::new();
+
+        for chunk in code_chunks
+            .iter()
+            .filter(|c| c.is_public && c.symbol_type != SymbolType::Impl)
+        {
+            let has_doc_comment = chunk
+                .doc_comment
+                .as_deref()
+                .is_some_and(|doc| !doc.trim().is_empty());
+            let has_related_docs = doc_chunks.iter().any(|doc| doc_relates_to(doc, chunk))
+                || !self.find_related_docs(chunk, doc_chunks).is_empty();
+
+            let file = match files.iter_mut().find(|f| f.file_path == chunk.file_path) {
+                Some(file) => file,
+                None => {
+                    files.push(FileCoverage {
+                        file_path: chunk.file_path.clone(),
+                        ..Default::default()
+                    });
+                    files.last_mut().unwrap()
+                }
+            };
+            file.total += 1;
+            file.with_doc_comment += has_doc_comment as usize;
+            file.with_related_docs += has_related_docs as usize;
+            if has_doc_comment || has_related_docs {
+                file.documented += 1;
+            } else {
+                file.undocumented.push(chunk.symbol_name.clone());
+            }
+        }
+
+        files.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        DocCoverage { files }
+    }
+
+    /// Find doc chunks related to a code chunk
+    ///
+    /// Sections that explicitly link to the symbol come first and are always
+    /// included; the rest are the nearest sections by embedding similarity,
+    /// raised by `lexical_weight` for sections whose heading or content names
+    /// the symbol.
     fn find_related_docs(&self, code_chunk: &CodeChunk, doc_chunks: &[DocChunk]) -> Vec<DocChunk> {
+        let mut related: Vec<DocChunk> = doc_chunks
+            .iter()
+            .filter(|doc| doc.links_to(code_chunk))
+            .cloned()
+            .collect();
+
         let code_embedding = match &code_chunk.embedding {
             Some(e) => e,
-            None => return Vec::new(),
+            None => return related,
         };
 
         let mut similarities: Vec<(usize, f64)> = doc_chunks
@@ -189,29 +371,59 @@ impl DriftDetector {
             .enumerate()
             .filter_map(|(i, doc)| {
                 doc.embedding.as_ref().map(|doc_emb| {
-                    let sim = cosine_similarity(code_embedding, doc_emb);
-                    (i, sim)
+                    let sim = similarity(code_embedding, doc_emb, self.config.metric);
+                    (i, sim + self.lexical_boost(code_chunk, doc))
                 })
             })
             .collect();
 
-        // Sort by similarity descending
+        // Sort by score descending
         similarities.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
 
         // Take top K that are above threshold
-        similarities
+        for (i, _) in similarities
             .into_iter()
             .take(self.config.top_k)
             .filter(|(_, sim)| *sim >= self.config.similarity_threshold)
-            .map(|(i, _)| doc_chunks[i].clone())
-            .collect()
+        {
+            if !related.iter().any(|doc| doc.id == doc_chunks[i].id) {
+                related.push(doc_chunks[i].clone());
+            }
+        }
+
+        related
+    }
+
+    /// Score added for a doc section that names the code chunk's symbol
+    fn lexical_boost(&self, code_chunk: &CodeChunk, doc: &DocChunk) -> f64 {
+        if self.config.lexical_weight > 0.0 && names_symbol(doc, &code_chunk.symbol_name) {
+            self.config.lexical_weight
+        } else {
+            0.0
+        }
     }
 
-    /// Find code chunks related to a doc chunk using embeddings
+    /// Find code chunks related to a doc chunk
+    ///
+    /// Symbols the section explicitly links to or shows a signature for come
+    /// first and are always included; the rest are the nearest symbols by
+    /// embedding similarity.
     fn find_related_code(&self, doc_chunk: &DocChunk, code_chunks: &[CodeChunk]) -> Vec<CodeChunk> {
+        let documented = documented_signature_names(doc_chunk);
+        let mut related: Vec<CodeChunk> = code_chunks
+            .iter()
+            .filter(|code| {
+                doc_chunk.links_to(code)
+                    || documented
+                        .iter()
+                        .any(|name| name == short_name(&code.symbol_name))
+            })
+            .cloned()
+            .collect();
+
         let doc_embedding = match &doc_chunk.embedding {
             Some(e) => e,
-            None => return Vec::new(),
+            None => return related,
         };
 
         let mut similarities: Vec<(usize, f64)> = code_chunks
@@ -219,7 +431,7 @@ impl DriftDetector {
             .enumerate()
             .filter_map(|(i, code)| {
                 code.embedding.as_ref().map(|code_emb| {
-                    let sim = cosine_similarity(doc_embedding, code_emb);
+                    let sim = similarity(doc_embedding, code_emb, self.config.metric);
                     (i, sim)
                 })
             })
@@ -229,15 +441,24 @@ impl DriftDetector {
         similarities.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
 
         // Take top K that are above threshold
-        similarities
+        for (i, _) in similarities
             .into_iter()
             .take(self.config.top_k)
             .filter(|(_, sim)| *sim >= self.config.similarity_threshold)
-            .map(|(i, _)| code_chunks[i].clone())
-            .collect()
+        {
+            if !related.iter().any(|code| code.id == code_chunks[i].id) {
+                related.push(code_chunks[i].clone());
+            }
+        }
+
+        related
     }
 
     /// Check for similarity-based drift
+    ///
+    /// Events are confidence that the docs have drifted, so a lower
+    /// similarity gives a higher confidence: `1 - similarity`, clamped to
+    /// 0..1.
     fn check_similarity_drift(
         &self,
         code_chunk: &CodeChunk,
@@ -249,7 +470,7 @@ impl DriftDetector {
 
         for doc in related_docs {
             if let Some(ref doc_embedding) = doc.embedding {
-                let similarity = cosine_similarity(embedding, doc_embedding);
+                let similarity = similarity(embedding, doc_embedding, self.config.metric);
 
                 // If similarity is low, there might be drift
                 if similarity < self.config.similarity_threshold {
@@ -263,10 +484,12 @@ impl DriftDetector {
                             "Similarity score: {:.2}. Documentation may not accurately describe the code.",
                             similarity
                         ),
-                        similarity,
+                        (1.0 - similarity).clamp(0.0, 1.0),
                     )
+                    .with_tag("low_similarity")
                     .with_code_chunk(&code_chunk.id)
-                    .with_doc_chunk(&doc.id);
+                    .with_doc_chunk(&doc.id)
+                    .with_location(&doc.file_path, doc.start_line);
 
                     events.push(event);
                 }
@@ -278,7 +501,9 @@ impl DriftDetector {
 
     /// Deduplicate events that refer to the same chunks
     fn deduplicate_events(&self, events: Vec<DriftEvent>) -> Vec<DriftEvent> {
-        let mut seen: HashMap<String, DriftEvent> = HashMap::new();
+        // Index into `kept` by key, so events keep their first-seen order
+        let mut seen: HashMap<String, usize> = HashMap::new();
+        let mut kept: Vec<DriftEvent> = Vec::new();
 
         for event in events {
             // Create a key from related chunks
@@ -287,20 +512,23 @@ impl DriftDetector {
             key_parts.sort();
             let key = key_parts.join("|");
 
-            // Keep the event with higher severity/confidence
-            if let Some(existing) = seen.get(&key) {
-                if event.severity > existing.severity
+            // Keep the event with higher severity/confidence; severities
+            // order from Critical down
+            if let Some(&index) = seen.get(&key) {
+                let existing = &kept[index];
+                if event.severity < existing.severity
                     || (event.severity == existing.severity
                         && event.confidence > existing.confidence)
                 {
-                    seen.insert(key, event);
+                    kept[index] = event;
                 }
             } else {
-                seen.insert(key, event);
+                seen.insert(key, kept.len());
+                kept.push(event);
             }
         }
 
-        seen.into_values().collect()
+        kept
     }
 
     /// Get similarity results for all code-doc pairs
@@ -315,7 +543,7 @@ impl DriftDetector {
             if let Some(ref code_emb) = code.embedding {
                 for doc in doc_chunks {
                     if let Some(ref doc_emb) = doc.embedding {
-                        let similarity = cosine_similarity(code_emb, doc_emb);
+                        let similarity = similarity(code_emb, doc_emb, self.config.metric);
 
                         results.push(SimilarityResult {
                             code_chunk_id: code.id.clone(),
@@ -332,6 +560,9 @@ impl DriftDetector {
     }
 
     /// Find the best matching doc chunks for a code chunk
+    ///
+    /// Scores are embedding similarity plus the `lexical_weight` boost for
+    /// sections that name the symbol, as in related-doc detection.
     pub fn find_best_matches(
         &self,
         code_chunk: &CodeChunk,
@@ -347,8 +578,8 @@ impl DriftDetector {
             .iter()
             .filter_map(|doc| {
                 doc.embedding.as_ref().map(|doc_emb| {
-                    let sim = cosine_similarity(code_embedding, doc_emb);
-                    (doc.clone(), sim)
+                    let sim = similarity(code_embedding, doc_emb, self.config.metric);
+                    (doc.clone(), sim + self.lexical_boost(code_chunk, doc))
                 })
             })
             .collect();
@@ -366,9 +597,26 @@ impl Default for DriftDetector {
     }
 }
 
+/// Whether a doc section's heading or content contains the symbol's short
+/// name as a whole word, ignoring case
+fn names_symbol(doc: &DocChunk, symbol_name: &str) -> bool {
+    let name = short_name(symbol_name).to_lowercase();
+    if name.is_empty() {
+        return false;
+    }
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    [&doc.heading, &doc.content].into_iter().any(|text| {
+        let text = text.to_lowercase();
+        text.match_indices(&name).any(|(start, _)| {
+            !text[..start].ends_with(is_word) && !text[start + name.len()..].starts_with(is_word)
+        })
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::drift::DriftRule;
     use crate::extract::code::{Language, SymbolType};
     use crate::extract::doc::HeadingLevel;
 
@@ -401,6 +649,28 @@ mod tests {
         chunk
     }
 
+    #[test]
+    fn test_deduplicate_events_keeps_order_and_most_severe() {
+        let detector = DriftDetector::new();
+        let event = |severity, description: &str, chunk: &str| {
+            DriftEvent::new(severity, description, "", 0.8).with_code_chunk(chunk)
+        };
+
+        let events = vec![
+            event(DriftSeverity::Low, "b low", "b"),
+            event(DriftSeverity::Medium, "a", "a"),
+            event(DriftSeverity::High, "b high", "b"),
+            event(DriftSeverity::Low, "c", "c"),
+        ];
+        let descriptions: Vec<String> = detector
+            .deduplicate_events(events)
+            .into_iter()
+            .map(|e| e.description)
+            .collect();
+
+        assert_eq!(descriptions, vec!["b high", "a", "c"]);
+    }
+
     #[test]
     fn test_find_related_docs() {
         let detector = DriftDetector::new();
@@ -420,6 +690,274 @@ mod tests {
         assert_eq!(related[0].heading, "Related");
     }
 
+    #[test]
+    fn test_find_related_docs_boosts_sections_naming_the_symbol() {
+        let code = create_test_code_chunk("parse_config", vec![1.0, 0.0, 0.0]);
+        let overview = create_test_doc_chunk("Overview", vec![0.8, 0.6, 0.0]);
+        let named = create_test_doc_chunk("Parse_Config", vec![0.75, 0.661_437_8, 0.0]);
+        // A longer identifier is not a mention
+        let mut prefixed = create_test_doc_chunk("Parsing", vec![0.6, 0.8, 0.0]);
+        prefixed.content = "See parse_configuration for details".to_string();
+        let docs = vec![overview, named, prefixed];
+
+        let headings = |config: DriftConfig| -> Vec<String> {
+            DriftDetector::with_config(config)
+                .find_related_docs(&code, &docs)
+                .into_iter()
+                .map(|doc| doc.heading)
+                .collect()
+        };
+
+        let semantic_only = DriftConfig {
+            lexical_weight: 0.0,
+            ..Default::default()
+        };
+        assert_eq!(headings(semantic_only), ["Overview", "Parse_Config"]);
+        assert_eq!(
+            headings(DriftConfig::default()),
+            ["Parse_Config", "Overview"]
+        );
+    }
+
+    #[test]
+    fn test_linked_symbol_signature_change_without_embeddings() {
+        let detector = DriftDetector::new();
+        let db = Database::open_in_memory().unwrap();
+
+        let mut old = create_test_code_chunk("connect", vec![]);
+        old.embedding = None;
+        old.signature = Some("pub fn connect()".to_string());
+        let mut new = old.clone();
+        new.signature = Some("pub fn connect(timeout: u64)".to_string());
+        new.hash = "changed".to_string();
+
+        let mut doc = create_test_doc_chunk("Connecting", vec![]);
+        doc.embedding = None;
+        doc.linked_symbols = vec![old.id.clone()];
+
+        let old_chunks = HashMap::from([(old.id.clone(), old)]);
+        let new_chunks = HashMap::from([(new.id.clone(), new)]);
+
+        let events = detector
+            .detect_code_drift(&old_chunks, &new_chunks, &[doc.clone()], &db)
+            .unwrap();
+
+        assert!(events
+            .iter()
+            .any(|e| e.severity == DriftSeverity::High
+                && e.related_doc_chunks == vec![doc.id.clone()]));
+    }
+
+    #[test]
+    fn test_renamed_symbol_is_not_reported_as_removed() {
+        let detector = DriftDetector::new();
+        let db = Database::open_in_memory().unwrap();
+
+        let function = |name: &str| {
+            let mut chunk = create_test_code_chunk(name, vec![]);
+            chunk.embedding = None;
+            chunk.content = format!(
+                "pub fn {}(path: &Path) -> Result<Config> {{\n    let text = fs::read_to_string(path)?;\n    toml::from_str(&text)\n}}",
+                name
+            );
+            chunk.signature = Some(format!("pub fn {}(path: &Path) -> Result<Config>", name));
+            chunk
+        };
+        let old = function("load_config");
+        let new = function("read_config");
+
+        let mut doc = create_test_doc_chunk("Configuration", vec![]);
+        doc.embedding = None;
+        doc.linked_symbols = vec![old.id.clone()];
+
+        let old_chunks = HashMap::from([(old.id.clone(), old)]);
+        let new_chunks = HashMap::from([(new.id.clone(), new.clone())]);
+        let events = detector
+            .detect_code_drift(&old_chunks, &new_chunks, &[doc.clone()], &db)
+            .unwrap();
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].severity, DriftSeverity::High);
+        assert_eq!(events[0].tags, vec!["renamed_symbol"]);
+        assert_eq!(events[0].related_code_chunks, vec![new.id]);
+        assert_eq!(events[0].related_doc_chunks, vec![doc.id]);
+    }
+
+    #[test]
+    fn test_low_similarity_means_high_confidence() {
+        let detector = DriftDetector::new();
+        let db = Database::open_in_memory().unwrap();
+        let code = create_test_code_chunk("parse", vec![1.0, 0.0, 0.0]);
+        let docs = vec![
+            create_test_doc_chunk("Barely", vec![0.6, 0.8, 0.0]),
+            create_test_doc_chunk("Unrelated", vec![0.0, 1.0, 0.0]),
+        ];
+
+        let events = detector
+            .check_similarity_drift(&code, &[1.0, 0.0, 0.0], &docs, &db)
+            .unwrap();
+
+        let confidences: Vec<f64> = events.iter().map(|e| e.confidence).collect();
+        assert_eq!(confidences.len(), 2);
+        assert!((confidences[0] - 0.4).abs() < 1e-6);
+        assert!((confidences[1] - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_events_below_min_confidence_are_dropped() {
+        let db = Database::open_in_memory().unwrap();
+        let mut old = create_test_code_chunk("connect", vec![]);
+        old.embedding = None;
+        let mut new = old.clone();
+        new.content = "pub fn connect() { retry() }".to_string();
+        new.hash = "changed".to_string();
+        let old_chunks = HashMap::from([(old.id.clone(), old)]);
+        let new_chunks = HashMap::from([(new.id.clone(), new)]);
+
+        let detect = |min_confidence| {
+            let mut rules = HardDriftRules::with_rules(Vec::new());
+            rules.push_rule(FixedConfidenceRule);
+            DriftDetector::with_config(DriftConfig {
+                min_confidence,
+                ..Default::default()
+            })
+            .with_hard_rules(rules)
+            .with_soft_rules(SoftDriftRules::with_rules(Vec::new()))
+            .detect_code_drift(&old_chunks, &new_chunks, &[], &db)
+            .unwrap()
+        };
+
+        assert_eq!(detect(0.3).len(), 1);
+        assert!(detect(0.5).is_empty());
+    }
+
+    /// Reports every content change with a confidence of 0.4
+    struct FixedConfidenceRule;
+
+    impl DriftRule for FixedConfidenceRule {
+        fn name(&self) -> &str {
+            "fixed_confidence"
+        }
+
+        fn check_code_change(
+            &self,
+            old_chunk: Option<&CodeChunk>,
+            new_chunk: Option<&CodeChunk>,
+            _related_docs: &[&DocChunk],
+        ) -> Option<DriftEvent> {
+            let (old, new) = (old_chunk?, new_chunk?);
+            (old.content != new.content)
+                .then(|| DriftEvent::new(DriftSeverity::Low, "changed", "", 0.4))
+        }
+
+        fn check_doc_change(
+            &self,
+            _old_chunk: Option<&DocChunk>,
+            _new_chunk: Option<&DocChunk>,
+            _related_code: &[&CodeChunk],
+        ) -> Option<DriftEvent> {
+            None
+        }
+    }
+
+    #[test]
+    fn test_custom_rule_fires_through_detector() {
+        struct RenamedRule;
+
+        impl DriftRule for RenamedRule {
+            fn name(&self) -> &str {
+                "renamed"
+            }
+
+            fn check_code_change(
+                &self,
+                old_chunk: Option<&CodeChunk>,
+                new_chunk: Option<&CodeChunk>,
+                _related_docs: &[&DocChunk],
+            ) -> Option<DriftEvent> {
+                let (old, new) = (old_chunk?, new_chunk?);
+                (old.content != new.content).then(|| {
+                    DriftEvent::new(DriftSeverity::Low, "custom", "content changed", 1.0)
+                        .with_code_chunk(&new.id)
+                })
+            }
+
+            fn check_doc_change(
+                &self,
+                _old_chunk: Option<&DocChunk>,
+                _new_chunk: Option<&DocChunk>,
+                _related_code: &[&CodeChunk],
+            ) -> Option<DriftEvent> {
+                None
+            }
+        }
+
+        let mut rules = HardDriftRules::with_rules(Vec::new());
+        rules.push_rule(RenamedRule);
+        assert_eq!(rules.rule_names(), vec!["renamed"]);
+        let detector = DriftDetector::new()
+            .with_hard_rules(rules)
+            .with_soft_rules(SoftDriftRules::with_rules(Vec::new()));
+        let db = Database::open_in_memory().unwrap();
+
+        let mut old = create_test_code_chunk("connect", vec![]);
+        old.embedding = None;
+        let mut new = old.clone();
+        new.content = "fn test() { todo!() }".to_string();
+        new.hash = "changed".to_string();
+
+        let old_chunks = HashMap::from([(old.id.clone(), old)]);
+        let new_chunks = HashMap::from([(new.id.clone(), new)]);
+        let events = detector
+            .detect_code_drift(&old_chunks, &new_chunks, &[], &db)
+            .unwrap();
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].description, "custom");
+    }
+
+    #[test]
+    fn test_coverage_counts_doc_comments_and_related_docs() {
+        let detector = DriftDetector::new();
+        let symbol = |file: &str, name: &str, doc: Option<&str>| {
+            let mut chunk = CodeChunk::new(
+                file,
+                name,
+                SymbolType::Function,
+                "fn f() {}",
+                Language::Rust,
+                1,
+                1,
+            );
+            chunk.is_public = true;
+            chunk.doc_comment = doc.map(String::from);
+            chunk
+        };
+        let mut private = symbol("src/a.rs", "helper", None);
+        private.is_public = false;
+        let code = vec![
+            symbol("src/b.rs", "connect", None),
+            symbol("src/a.rs", "open", Some("Opens it")),
+            symbol("src/a.rs", "close", Some("  ")),
+            private,
+        ];
+        let mut doc = create_test_doc_chunk("Connecting", vec![]);
+        doc.embedding = None;
+        doc.linked_symbols = vec!["connect".to_string()];
+
+        let coverage = detector.coverage(&code, &[doc]);
+
+        assert_eq!(coverage.total(), 3);
+        assert_eq!(coverage.documented(), 2);
+        assert!((coverage.percent() - 200.0 / 3.0).abs() < 1e-9);
+        assert_eq!(coverage.files[0].file_path, "src/a.rs");
+        assert_eq!(coverage.files[0].with_doc_comment, 1);
+        assert_eq!(coverage.files[0].undocumented, vec!["close"]);
+        assert_eq!(coverage.files[1].with_related_docs, 1);
+        assert_eq!(coverage.files[1].percent(), 100.0);
+        assert_eq!(DocCoverage::default().percent(), 100.0);
+    }
+
     #[test]
     fn test_compute_similarities() {
         let detector = DriftDetector::new();
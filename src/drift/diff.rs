@@ -0,0 +1,343 @@
+//! Drift comparison between two commits
+//!
+//! Answers "what drift did this range introduce?" for PR review:
+//! - Extracts the code files changed in the range at both ends
+//! - Checks docs at each end against the symbols the range touched
+//! - Splits the results into introduced, resolved, and pre-existing drift
+//!
+//! Unlike a scan this reads everything from git, so it does not need an
+//! initialized `.docsentinel` directory and does not touch the database.
+
+use super::rules::{doc_relates_to, pair_renames, RenamedSymbolRule};
+use super::scan::skip_unreadable;
+use super::{DriftConfig, DriftEvent, DriftRule, HardDriftRules, SoftDriftRules};
+use crate::extract::{CodeChunk, CodeExtractor, DocChunk, DocExtractor};
+use crate::repo::{FileType, Repository};
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// Drift introduced, resolved, and carried over by a commit range
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DriftDiff {
+    /// Base revision of the range
+    pub base: String,
+    /// Head revision of the range
+    pub head: String,
+    /// Number of code files changed in the range
+    pub code_files: usize,
+    /// Number of doc files changed in the range
+    pub doc_files: usize,
+    /// Drift present at head but not at base
+    pub introduced: Vec<DriftEvent>,
+    /// Drift present at base but gone at head
+    pub resolved: Vec<DriftEvent>,
+    /// Drift present at both ends of the range
+    pub pre_existing: Vec<DriftEvent>,
+    /// Non-fatal problems (unparseable files)
+    pub warnings: Vec<String>,
+}
+
+/// Compare drift between two revisions
+///
+/// Only symbols whose code changed in `base..head` are considered, so drift
+/// elsewhere in the repository is not reported. Events pass through the
+/// configured severity floor and ignore rules, as in a scan.
+pub fn diff_range(repo: &Repository, base: &str, head: &str) -> Result<DriftDiff> {
+    let mut diff = DriftDiff {
+        base: base.to_string(),
+        head: head.to_string(),
+        ..Default::default()
+    };
+
+    let changes = repo.changes_between(Some(base), head)?;
+    let code_paths: Vec<_> = changes
+        .iter()
+        .filter(|c| c.is_code())
+        .map(|c| c.path.clone())
+        .collect();
+    diff.code_files = code_paths.len();
+    diff.doc_files = changes.iter().filter(|c| c.is_documentation()).count();
+
+    let base_code = extract_code_at(repo, &code_paths, base, &mut diff.warnings)?;
+    let head_code = extract_code_at(repo, &code_paths, head, &mut diff.warnings)?;
+
+    // Symbols added, removed, or modified by the range
+    let base_by_id: HashMap<&str, &CodeChunk> =
+        base_code.iter().map(|c| (c.id.as_str(), c)).collect();
+    let head_by_id: HashMap<&str, &CodeChunk> =
+        head_code.iter().map(|c| (c.id.as_str(), c)).collect();
+    let mut touched: Vec<&str> = base_by_id
+        .keys()
+        .chain(head_by_id.keys())
+        .copied()
+        .filter(|id| base_by_id.get(id).map(|c| &c.hash) != head_by_id.get(id).map(|c| &c.hash))
+        .collect();
+    touched.sort_unstable();
+    touched.dedup();
+    let touched_ids: HashSet<&str> = touched.iter().copied().collect();
+
+    let base_docs = extract_docs_at(repo, base, &mut diff.warnings)?;
+    let head_docs = extract_docs_at(repo, head, &mut diff.warnings)?;
+
+    let drift_config = DriftConfig::from_repo_config(repo.config());
+    let hard_rules = HardDriftRules::new();
+    let soft_rules = SoftDriftRules::with_diff_context(drift_config.diff_context_lines);
+
+    // Drift visible in each snapshot: docs that misdescribe a touched symbol
+    let base_touched: Vec<&CodeChunk> = base_code
+        .iter()
+        .filter(|c| touched_ids.contains(c.id.as_str()))
+        .collect();
+    let head_touched: Vec<&CodeChunk> = head_code
+        .iter()
+        .filter(|c| touched_ids.contains(c.id.as_str()))
+        .collect();
+    let (base_events, head_events) = if drift_config.use_hard_rules {
+        (
+            snapshot_drift(&hard_rules, &base_docs, &base_touched),
+            snapshot_drift(&hard_rules, &head_docs, &head_touched),
+        )
+    } else {
+        (Vec::new(), Vec::new())
+    };
+
+    // Drift from the change itself, e.g. a signature change under linked docs.
+    // When every related section was also edited in the range the docs were
+    // updated alongside the code; the head snapshot covers whether they are
+    // now correct.
+    let base_doc_hashes: HashMap<&str, &str> = base_docs
+        .iter()
+        .map(|d| (d.id.as_str(), d.hash.as_str()))
+        .collect();
+    let related_to = |chunk: &CodeChunk| -> Option<Vec<&DocChunk>> {
+        let related: Vec<&DocChunk> = head_docs
+            .iter()
+            .filter(|doc| doc_relates_to(doc, chunk))
+            .collect();
+        let docs_updated = !related.is_empty()
+            && related
+                .iter()
+                .all(|doc| base_doc_hashes.get(doc.id.as_str()) != Some(&doc.hash.as_str()));
+        (!docs_updated).then_some(related)
+    };
+    let mut change_events = Vec::new();
+
+    // Symbols renamed in the range are reported once as renames
+    let removed: Vec<&CodeChunk> = touched
+        .iter()
+        .filter(|id| !head_by_id.contains_key(*id))
+        .filter_map(|id| base_by_id.get(id).copied())
+        .collect();
+    let added: Vec<&CodeChunk> = touched
+        .iter()
+        .filter(|id| !base_by_id.contains_key(*id))
+        .filter_map(|id| head_by_id.get(id).copied())
+        .collect();
+    let mut renamed_ids = HashSet::new();
+    for (old, new) in pair_renames(&removed, &added) {
+        renamed_ids.insert(old.id.as_str());
+        renamed_ids.insert(new.id.as_str());
+        let Some(related) = related_to(old) else {
+            continue;
+        };
+        if drift_config.use_hard_rules {
+            let rule = RenamedSymbolRule;
+            change_events.extend(
+                rule.check_code_change(Some(old), Some(new), &related)
+                    .map(|event| event.with_tag(rule.name())),
+            );
+        }
+    }
+
+    for id in &touched {
+        if renamed_ids.contains(id) {
+            continue;
+        }
+        let old = base_by_id.get(id).copied();
+        let new = head_by_id.get(id).copied();
+        let Some(chunk) = new.or(old) else {
+            continue;
+        };
+        let Some(related) = related_to(chunk) else {
+            continue;
+        };
+
+        if drift_config.use_hard_rules {
+            change_events.extend(hard_rules.check_code_change(old, new, &related));
+        }
+        if drift_config.use_soft_rules {
+            change_events.extend(soft_rules.check_code_change(old, new, &related));
+        }
+    }
+
+    let base_ids: HashSet<String> = base_events.iter().map(|e| e.identity()).collect();
+    let head_ids: HashSet<String> = head_events.iter().map(|e| e.identity()).collect();
+
+    let mut seen = HashSet::new();
+    for event in head_events.into_iter().chain(change_events) {
+        let identity = event.identity();
+        if !seen.insert(identity.clone()) {
+            continue;
+        }
+        if base_ids.contains(&identity) {
+            diff.pre_existing.push(event);
+        } else {
+            diff.introduced.push(event);
+        }
+    }
+    diff.resolved = base_events
+        .into_iter()
+        .filter(|e| !head_ids.contains(&e.identity()))
+        .collect();
+
+    let config = repo.config();
+    let (min_severity, min_confidence) = (config.drift.min_severity, config.drift.min_confidence);
+    for events in [
+        &mut diff.introduced,
+        &mut diff.resolved,
+        &mut diff.pre_existing,
+    ] {
+        config.apply_severity_overrides(events);
+        events.retain(|e| {
+            e.severity <= min_severity
+                && e.confidence >= min_confidence
+                && !config.is_event_ignored(e)
+        });
+    }
+
+    Ok(diff)
+}
+
+/// Extract code chunks from `paths` as they were at `commit`
+pub(super) fn extract_code_at(
+    repo: &Repository,
+    paths: &[std::path::PathBuf],
+    commit: &str,
+    warnings: &mut Vec<String>,
+) -> Result<Vec<CodeChunk>> {
+    let mut files = Vec::new();
+    for path in paths {
+        if let Some(content) = skip_unreadable(repo.read_file_at_commit(path, commit), warnings)? {
+            files.push((path.clone(), content));
+        }
+    }
+
+    let mut chunks = Vec::new();
+    for ((path, _), result) in files.iter().zip(CodeExtractor::extract_files(&files)) {
+        match result {
+            Ok(extracted) => chunks.extend(extracted),
+            Err(e) => warnings.push(format!("Failed to extract {:?} at {}: {}", path, commit, e)),
+        }
+    }
+    Ok(chunks)
+}
+
+/// Extract every doc chunk in the tree at `commit`
+fn extract_docs_at(
+    repo: &Repository,
+    commit: &str,
+    warnings: &mut Vec<String>,
+) -> Result<Vec<DocChunk>> {
+    let extractor = DocExtractor::from_repo_config(repo.config());
+    let paths = repo.list_files_at_commit(commit, Some(FileType::Documentation))?;
+    extract_doc_files_at(repo, &extractor, &paths, commit, warnings)
+}
+
+/// Extract doc chunks from `paths` as they were at `commit`
+pub(super) fn extract_doc_files_at(
+    repo: &Repository,
+    extractor: &DocExtractor,
+    paths: &[std::path::PathBuf],
+    commit: &str,
+    warnings: &mut Vec<String>,
+) -> Result<Vec<DocChunk>> {
+    let mut chunks = Vec::new();
+    for path in paths {
+        let Some(content) = skip_unreadable(repo.read_file_at_commit(path, commit), warnings)?
+        else {
+            continue;
+        };
+        match extractor.extract_file(path, &content) {
+            Ok(extracted) => chunks.extend(extracted),
+            Err(e) => warnings.push(format!("Failed to extract {:?} at {}: {}", path, commit, e)),
+        }
+    }
+    Ok(chunks)
+}
+
+/// Check every doc section against the related symbols in one snapshot
+fn snapshot_drift(
+    rules: &HardDriftRules,
+    docs: &[DocChunk],
+    code: &[&CodeChunk],
+) -> Vec<DriftEvent> {
+    let mut events = Vec::new();
+    for doc in docs {
+        let related: Vec<&CodeChunk> = code
+            .iter()
+            .copied()
+            .filter(|chunk| doc_relates_to(doc, chunk))
+            .collect();
+        if !related.is_empty() {
+            events.extend(rules.check_doc_change(None, Some(doc), &related));
+        }
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drift::test_support::commit_files;
+
+    #[test]
+    fn test_diff_separates_new_resolved_and_existing_drift() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = git2::Repository::init(dir.path()).unwrap();
+        let readme = "# Demo\n\n## Add\n\n```rust\npub fn add(a: i32, b: i32) -> i32\n```\n\n\
+                      ## Sub\n\n```rust\npub fn sub(a: i32) -> i32\n```\n\n\
+                      ## Mul\n\n```rust\npub fn mul(a: i32) -> i32\n```\n";
+        let base = commit_files(
+            &git,
+            &[
+                (
+                    "src/lib.rs",
+                    "pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n\n\
+                     pub fn sub(a: i32, b: i32) -> i32 {\n    a - b\n}\n\n\
+                     pub fn mul(a: i32, b: i32) -> i32 {\n    a * b\n}\n",
+                ),
+                ("README.md", readme),
+            ],
+            "initial",
+        );
+        let head = commit_files(
+            &git,
+            &[(
+                "src/lib.rs",
+                "pub fn add(a: i32) -> i32 {\n    a\n}\n\n\
+                 pub fn sub(a: i32) -> i32 {\n    -a\n}\n\n\
+                 pub fn mul(a: i32, b: i32) -> i32 {\n    b * a\n}\n",
+            )],
+            "rework",
+        );
+
+        let repo = Repository::open(dir.path()).unwrap();
+        let diff = diff_range(&repo, &base, &head).unwrap();
+
+        let described = |events: &[DriftEvent]| -> Vec<String> {
+            events.iter().map(|e| e.description.clone()).collect()
+        };
+        assert_eq!(diff.code_files, 1);
+        assert!(described(&diff.introduced)
+            .contains(&"Documented signature out of date: add".to_string()));
+        assert_eq!(
+            described(&diff.resolved),
+            vec!["Documented signature out of date: sub".to_string()]
+        );
+        assert_eq!(
+            described(&diff.pre_existing),
+            vec!["Documented signature out of date: mul".to_string()]
+        );
+    }
+}
@@ -2,8 +2,11 @@
 //!
 //! Supports local embedding via Ollama or compatible OpenAI-style endpoints
 
+use crate::repo::RepoConfig;
 use anyhow::{Context, Result};
+use futures_util::stream::{self, StreamExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
+use std::future::Future;
 
 /// Trait for embedding providers
 #[async_trait::async_trait]
@@ -22,6 +25,102 @@ pub trait EmbeddingProvider: Send + Sync {
 
     /// Get the embedding dimension
     fn dimension(&self) -> usize;
+
+    /// Get the model name recorded alongside stored embeddings
+    fn model(&self) -> &str;
+}
+
+/// Which service generates embeddings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddingBackend {
+    /// Ollama's `/api/embeddings`
+    #[default]
+    Ollama,
+    /// An OpenAI-compatible `/v1/embeddings`
+    OpenAI,
+}
+
+impl EmbeddingBackend {
+    /// Endpoint used when neither the embedding nor the LLM config sets one
+    pub fn default_endpoint(self) -> &'static str {
+        match self {
+            EmbeddingBackend::Ollama => "http://localhost:11434",
+            EmbeddingBackend::OpenAI => "https://api.openai.com",
+        }
+    }
+
+    /// Model used when the embedding config does not name one
+    pub fn default_model(self) -> &'static str {
+        match self {
+            EmbeddingBackend::Ollama => "nomic-embed-text",
+            EmbeddingBackend::OpenAI => "text-embedding-3-small",
+        }
+    }
+}
+
+impl std::fmt::Display for EmbeddingBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EmbeddingBackend::Ollama => write!(f, "ollama"),
+            EmbeddingBackend::OpenAI => write!(f, "openai"),
+        }
+    }
+}
+
+impl std::str::FromStr for EmbeddingBackend {
+    type Err = crate::error::DocSentinelError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "ollama" => Ok(EmbeddingBackend::Ollama),
+            "openai" => Ok(EmbeddingBackend::OpenAI),
+            _ => Err(crate::error::DocSentinelError::Config(format!(
+                "Unknown embedding provider: {} (expected ollama or openai)",
+                s
+            ))),
+        }
+    }
+}
+
+/// Build the embedding provider selected by a repository configuration
+///
+/// Settings left unset in `[embedding]` fall back to the LLM endpoint and API
+/// key, then to the provider's defaults (Ollama with `nomic-embed-text`).
+pub fn build_embedding_provider(config: &RepoConfig) -> Box<dyn EmbeddingProvider> {
+    let settings = &config.embedding;
+    let backend = settings.provider;
+    let endpoint = config
+        .embedding_endpoint()
+        .unwrap_or(backend.default_endpoint());
+    let model = settings.model.as_deref().unwrap_or(backend.default_model());
+
+    match backend {
+        EmbeddingBackend::Ollama => {
+            let mut provider = LocalEmbedding::new(endpoint, model);
+            if let Some(batch_size) = config.llm.embedding_batch_size {
+                provider = provider.with_batch_size(batch_size);
+            }
+            if let Some(concurrency) = config.llm.embedding_concurrency {
+                provider = provider.with_concurrency(concurrency);
+            }
+            if let Some(dimension) = settings.dimension {
+                provider = provider.with_dimension(dimension);
+            }
+            Box::new(provider)
+        }
+        EmbeddingBackend::OpenAI => {
+            let api_key = settings
+                .api_key
+                .as_deref()
+                .or(config.llm.api_key.as_deref());
+            let mut provider = OpenAIEmbedding::new(endpoint, model, api_key);
+            if let Some(dimension) = settings.dimension {
+                provider = provider.with_dimension(dimension);
+            }
+            Box::new(provider)
+        }
+    }
 }
 
 /// Local embedding provider using Ollama or compatible API
@@ -34,6 +133,10 @@ pub struct LocalEmbedding {
     client: reqwest::Client,
     /// Embedding dimension
     dimension: usize,
+    /// Number of texts embedded per group
+    batch_size: usize,
+    /// Maximum number of groups in flight at once
+    concurrency: usize,
 }
 
 impl LocalEmbedding {
@@ -44,6 +147,8 @@ impl LocalEmbedding {
             model: model.to_string(),
             client: reqwest::Client::new(),
             dimension: 384, // Default for many sentence-transformer models
+            batch_size: 16,
+            concurrency: 4,
         }
     }
 
@@ -58,6 +163,18 @@ impl LocalEmbedding {
         self
     }
 
+    /// Set how many texts are embedded per group
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Set how many groups may be embedded concurrently
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
     /// Check if the embedding service is available
     pub async fn is_available(&self) -> bool {
         let url = format!("{}/api/tags", self.endpoint);
@@ -68,19 +185,19 @@ impl LocalEmbedding {
 #[async_trait::async_trait]
 impl EmbeddingProvider for LocalEmbedding {
     async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
-        let mut embeddings = Vec::with_capacity(texts.len());
-
-        for text in texts {
-            let embedding = self.embed_single(text).await?;
-            embeddings.push(embedding);
-        }
-
-        Ok(embeddings)
+        embed_in_groups(texts, self.batch_size, self.concurrency, |text| {
+            self.embed_single(text)
+        })
+        .await
     }
 
     fn dimension(&self) -> usize {
         self.dimension
     }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
 }
 
 impl LocalEmbedding {
@@ -116,6 +233,93 @@ impl LocalEmbedding {
     }
 }
 
+/// Stored embeddings that were produced by a different model than the one
+/// now configured
+///
+/// Vectors from different models are not comparable: with different
+/// dimensions every similarity is 0.0, and with equal dimensions the scores
+/// are meaningless.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbeddingMismatch {
+    /// Model recorded for the stored embeddings (`unknown` for older rows)
+    pub stored_model: String,
+    /// Dimension of the stored embeddings
+    pub stored_dim: usize,
+    /// Model now configured
+    pub model: String,
+    /// Dimension produced by the configured model
+    pub dim: usize,
+}
+
+impl EmbeddingMismatch {
+    /// Compare the stored model and dimension against the configured ones
+    ///
+    /// Returns `None` when nothing is stored yet or they match. A stored model
+    /// of `unknown` only counts as a mismatch when the dimension differs.
+    pub fn detect(stored: Option<(String, usize)>, model: &str, dim: usize) -> Option<Self> {
+        let (stored_model, stored_dim) = stored?;
+        let model_differs = stored_model != "unknown" && stored_model != model;
+        if !model_differs && stored_dim == dim {
+            return None;
+        }
+        Some(Self {
+            stored_model,
+            stored_dim,
+            model: model.to_string(),
+            dim,
+        })
+    }
+}
+
+impl std::fmt::Display for EmbeddingMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "stored embeddings come from {} ({} dimensions), but the configured model {} produces {} dimensions",
+            self.stored_model, self.stored_dim, self.model, self.dim
+        )
+    }
+}
+
+/// Embed texts in groups of `batch_size`, running up to `concurrency` groups at once
+///
+/// Texts within a group are embedded one after another; groups may finish in
+/// any order but the result always matches the order of `texts`.
+async fn embed_in_groups<'a, F, Fut>(
+    texts: &'a [String],
+    batch_size: usize,
+    concurrency: usize,
+    embed: F,
+) -> Result<Vec<Vec<f32>>>
+where
+    F: Fn(&'a str) -> Fut,
+    Fut: Future<Output = Result<Vec<f32>>>,
+{
+    let embed = &embed;
+    let tasks: Vec<_> = texts
+        .chunks(batch_size.max(1))
+        .enumerate()
+        .map(|(index, group)| async move {
+            let mut embeddings = Vec::with_capacity(group.len());
+            for text in group {
+                embeddings.push(embed(text).await?);
+            }
+            Ok::<_, anyhow::Error>((index, embeddings))
+        })
+        .collect();
+
+    let mut groups: Vec<(usize, Vec<Vec<f32>>)> = stream::iter(tasks)
+        .buffer_unordered(concurrency.max(1))
+        .try_collect()
+        .await?;
+
+    groups.sort_by_key(|(index, _)| *index);
+    Ok(groups
+        .into_iter()
+        .flat_map(|(_, embeddings)| embeddings)
+        .collect())
+}
+
 /// Ollama embedding request
 #[derive(Debug, Serialize)]
 struct OllamaEmbeddingRequest {
@@ -130,7 +334,6 @@ struct OllamaEmbeddingResponse {
 }
 
 /// OpenAI-compatible embedding provider
-#[allow(dead_code)]
 pub struct OpenAIEmbedding {
     /// API endpoint URL
     endpoint: String,
@@ -144,7 +347,6 @@ pub struct OpenAIEmbedding {
     dimension: usize,
 }
 
-#[allow(dead_code)]
 impl OpenAIEmbedding {
     /// Create a new OpenAI-compatible embedding provider
     pub fn new(endpoint: &str, model: &str, api_key: Option<&str>) -> Self {
@@ -164,7 +366,6 @@ impl OpenAIEmbedding {
     }
 }
 
-#[allow(dead_code)]
 #[async_trait::async_trait]
 impl EmbeddingProvider for OpenAIEmbedding {
     async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
@@ -212,6 +413,10 @@ impl EmbeddingProvider for OpenAIEmbedding {
     fn dimension(&self) -> usize {
         self.dimension
     }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
 }
 
 /// OpenAI embedding request
@@ -237,6 +442,19 @@ struct OpenAIEmbeddingData {
     embedding: Vec<f32>,
 }
 
+/// Scale `vec` to unit length (L2 norm 1) in place
+///
+/// Cosine similarity between normalized vectors equals their dot product.
+/// All-zero vectors have no direction and are left unchanged.
+pub fn normalize(vec: &mut [f32]) {
+    let norm = vec.iter().map(|x| (*x as f64).powi(2)).sum::<f64>().sqrt();
+    if norm > 0.0 {
+        for x in vec.iter_mut() {
+            *x = (*x as f64 / norm) as f32;
+        }
+    }
+}
+
 /// Mock embedding provider for testing
 #[allow(dead_code)]
 pub struct MockEmbedding {
@@ -275,6 +493,10 @@ impl EmbeddingProvider for MockEmbedding {
     fn dimension(&self) -> usize {
         self.dimension
     }
+
+    fn model(&self) -> &str {
+        "mock"
+    }
 }
 
 // We need to add async-trait to Cargo.toml
@@ -318,6 +540,25 @@ impl SyncEmbeddingProvider for MockEmbedding {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_normalize_gives_unit_length() {
+        let mut embedding = vec![3.0, -4.0, 12.0];
+        normalize(&mut embedding);
+
+        let norm: f64 = embedding
+            .iter()
+            .map(|x| (*x as f64).powi(2))
+            .sum::<f64>()
+            .sqrt();
+        assert!((norm - 1.0).abs() < 1e-6);
+        assert!((crate::drift::cosine_similarity(&embedding, &embedding) - 1.0).abs() < 1e-6);
+        assert!((crate::drift::dot_product(&embedding, &embedding) - 1.0).abs() < 1e-6);
+
+        let mut zero = vec![0.0; 3];
+        normalize(&mut zero);
+        assert_eq!(zero, vec![0.0; 3]);
+    }
+
     #[test]
     fn test_mock_embedding() {
         let provider = MockEmbedding::new(384);
@@ -332,4 +573,76 @@ mod tests {
         let embedding3 = provider.embed_sync("Goodbye, world!").unwrap();
         assert_ne!(embedding, embedding3);
     }
+
+    #[test]
+    fn test_build_embedding_provider_follows_config() {
+        let mut config = RepoConfig::default();
+        let provider = build_embedding_provider(&config);
+        assert_eq!(provider.model(), "nomic-embed-text");
+
+        let config_toml = "[embedding]\nprovider = \"openai\"\napi_key = \"sk-test\"\n";
+        config.embedding = toml::from_str::<RepoConfig>(config_toml).unwrap().embedding;
+        assert_eq!(config.embedding.provider, EmbeddingBackend::OpenAI);
+        let provider = build_embedding_provider(&config);
+        assert_eq!(provider.model(), "text-embedding-3-small");
+        assert_eq!(provider.dimension(), 1536);
+
+        config.embedding.model = Some("text-embedding-3-large".to_string());
+        config.embedding.dimension = Some(3072);
+        let provider = build_embedding_provider(&config);
+        assert_eq!(provider.model(), "text-embedding-3-large");
+        assert_eq!(provider.dimension(), 3072);
+    }
+
+    #[test]
+    fn test_embed_in_groups_preserves_order() {
+        let texts: Vec<String> = (0..10).map(|i| i.to_string()).collect();
+        let rt = tokio::runtime::Runtime::new().unwrap();
+
+        // Earlier texts take longer, so later groups finish first
+        let embeddings = rt
+            .block_on(embed_in_groups(&texts, 3, 4, |text| async move {
+                let n: u64 = text.parse().unwrap();
+                tokio::time::sleep(std::time::Duration::from_millis(20 - 2 * n)).await;
+                Ok(vec![n as f32])
+            }))
+            .unwrap();
+
+        let expected: Vec<Vec<f32>> = (0..10).map(|i| vec![i as f32]).collect();
+        assert_eq!(embeddings, expected);
+
+        let failed = rt.block_on(embed_in_groups(&texts, 3, 4, |text| async move {
+            if text == "7" {
+                anyhow::bail!("boom");
+            }
+            Ok(vec![0.0])
+        }));
+        assert!(failed.is_err());
+    }
+
+    #[test]
+    fn test_embedding_mismatch_detection() {
+        // Nothing stored yet, or the same model
+        assert_eq!(EmbeddingMismatch::detect(None, "nomic", 768), None);
+        assert_eq!(
+            EmbeddingMismatch::detect(Some(("nomic".to_string(), 768)), "nomic", 768),
+            None
+        );
+        // Rows from before the model was recorded only compare dimensions
+        assert_eq!(
+            EmbeddingMismatch::detect(Some(("unknown".to_string(), 768)), "nomic", 768),
+            None
+        );
+
+        let switched =
+            EmbeddingMismatch::detect(Some(("minilm".to_string(), 384)), "openai", 1536).unwrap();
+        assert_eq!(switched.stored_dim, 384);
+        assert_eq!(switched.dim, 1536);
+
+        // Same dimension but a different model is still incomparable
+        assert!(EmbeddingMismatch::detect(Some(("a".to_string(), 768)), "b", 768).is_some());
+        assert!(
+            EmbeddingMismatch::detect(Some(("unknown".to_string(), 384)), "nomic", 768).is_some()
+        );
+    }
 }
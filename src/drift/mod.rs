@@ -5,18 +5,41 @@
 //! - Applying hard drift rules (API changes, removed functions)
 //! - Applying soft drift rules (behavioral changes)
 
+mod baseline;
+mod cli_help;
+mod coverage;
 mod detector;
+mod diff;
 mod embedding;
 mod rules;
+mod scan;
+#[cfg(test)]
+mod test_support;
 
-pub use detector::DriftDetector;
-pub use embedding::{EmbeddingProvider, LocalEmbedding};
-pub use rules::{DriftRule, HardDriftRules, SoftDriftRules};
+pub use baseline::Baseline;
+pub use cli_help::CliHelp;
+pub use coverage::{DocCoverage, FileCoverage};
+pub use detector::{DriftConfig, DriftDetector};
+pub use diff::{diff_range, DriftDiff};
+pub use embedding::{
+    build_embedding_provider, normalize, EmbeddingBackend, EmbeddingMismatch, EmbeddingProvider,
+    LocalEmbedding, OpenAIEmbedding,
+};
+pub(crate) use rules::doc_relates_to;
+pub use rules::{
+    ConfigKeyRule, ConflictingDocsRule, DefaultValueChangeRule, DocLinkRule, DriftRule,
+    HardDriftRules, SoftDriftRules, DEFAULT_DIFF_CONTEXT_LINES,
+};
+pub use scan::{
+    backfill_embeddings, scan_repository, scan_repository_with_progress, BackfillReport,
+    ScanOptions, ScanPhase, ScanProgress, ScanReport, ScanStats,
+};
 
+use crate::repo::CommitInfo;
 use serde::{Deserialize, Serialize};
 
 /// Severity level of a drift event
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum DriftSeverity {
     /// Critical: Public API changed without doc update
     Critical,
@@ -84,6 +107,27 @@ pub struct DriftEvent {
     pub suggested_fix: Option<String>,
     /// Current status
     pub status: DriftStatus,
+    /// File where the drift should be looked at first
+    ///
+    /// This is the first related doc section when one is known, since that
+    /// is what a fix edits; otherwise it is the changed code.
+    #[serde(default)]
+    pub primary_file: Option<String>,
+    /// 1-indexed line in `primary_file`
+    #[serde(default)]
+    pub primary_line: Option<usize>,
+    /// Categories of the drift, e.g. `signature_change` or `broken_link`
+    ///
+    /// Events from a [`DriftRule`] are tagged with the rule's name.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Commit that last changed the drifted code, when it is committed
+    #[serde(default)]
+    pub introduced_by: Option<CommitInfo>,
+    /// Description as the rule reported it, kept when `description` is
+    /// rewritten (e.g. by the LLM) so the identity stays the same
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detected_description: Option<String>,
 }
 
 impl DriftEvent {
@@ -104,6 +148,11 @@ impl DriftEvent {
             related_doc_chunks: Vec::new(),
             suggested_fix: None,
             status: DriftStatus::Pending,
+            primary_file: None,
+            primary_line: None,
+            tags: Vec::new(),
+            introduced_by: None,
+            detected_description: None,
         }
     }
 
@@ -119,11 +168,93 @@ impl DriftEvent {
         self
     }
 
+    /// Add a tag, unless the event already has it
+    pub fn with_tag(mut self, tag: &str) -> Self {
+        if !self.has_tag(tag) {
+            self.tags.push(tag.to_string());
+        }
+        self
+    }
+
+    /// Whether the event carries `tag`
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
+    /// Set the primary file and line of the event
+    pub fn with_location(mut self, file_path: &str, line: usize) -> Self {
+        self.primary_file = Some(file_path.to_string());
+        self.primary_line = Some(line);
+        self
+    }
+
+    /// Primary location formatted as `file:line`, if known
+    pub fn location(&self) -> Option<String> {
+        let file = self.primary_file.as_ref()?;
+        Some(match self.primary_line {
+            Some(line) => format!("{}:{}", file, line),
+            None => file.clone(),
+        })
+    }
+
     /// Set the suggested fix
     pub fn with_suggested_fix(mut self, fix: &str) -> Self {
         self.suggested_fix = Some(fix.to_string());
         self
     }
+
+    /// Replace the description without changing the event's identity
+    pub fn rewrite_description(&mut self, description: String) {
+        let detected = std::mem::replace(&mut self.description, description);
+        self.detected_description.get_or_insert(detected);
+    }
+
+    /// Deterministic identity of the underlying drift
+    ///
+    /// Unlike `id`, this is stable across scans: the same severity, related
+    /// chunks, and detected description always hash to the same value. A
+    /// description rewritten after detection does not count.
+    pub fn identity(&self) -> String {
+        let mut code_chunks = self.related_code_chunks.clone();
+        code_chunks.sort();
+        let mut doc_chunks = self.related_doc_chunks.clone();
+        doc_chunks.sort();
+
+        crate::extract::content_hash(&format!(
+            "{:?}\n{}\n{}\n{}",
+            self.severity,
+            code_chunks.join(","),
+            doc_chunks.join(","),
+            self.detected_description
+                .as_deref()
+                .unwrap_or(&self.description)
+        ))
+    }
+
+    /// Output order: most severe first, then most confident, then by ID
+    pub fn cmp_priority(&self, other: &Self) -> std::cmp::Ordering {
+        self.severity
+            .cmp(&other.severity)
+            .then(
+                other
+                    .confidence
+                    .partial_cmp(&self.confidence)
+                    .unwrap_or(std::cmp::Ordering::Equal),
+            )
+            .then_with(|| self.id.cmp(&other.id))
+    }
+}
+
+/// The first 8 characters of an event ID, for display
+///
+/// IDs shorter than that (e.g. from a hand-edited import) are returned whole.
+pub fn short_id(id: &str) -> &str {
+    id.get(..8).unwrap_or(id)
+}
+
+/// Sort events into a stable output order (see [`DriftEvent::cmp_priority`])
+pub fn sort_events(events: &mut [DriftEvent]) {
+    events.sort_by(DriftEvent::cmp_priority);
 }
 
 /// Result of comparing two chunks
@@ -150,17 +281,95 @@ impl SimilarityResult {
     }
 }
 
-/// Compute cosine similarity between two vectors
-pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f64 {
+/// How embedding vectors are compared
+///
+/// Every metric yields a score where higher means more similar, so the
+/// same `similarity_threshold` comparison works for each; the threshold
+/// itself usually needs retuning when the metric changes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SimilarityMetric {
+    /// Cosine of the angle between the vectors (-1.0 - 1.0)
+    #[default]
+    Cosine,
+    /// Raw dot product, equal to cosine for normalized vectors but cheaper
+    Dot,
+    /// `1 / (1 + d)` for Euclidean distance `d` (0.0 - 1.0)
+    Euclidean,
+}
+
+impl std::fmt::Display for SimilarityMetric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SimilarityMetric::Cosine => write!(f, "cosine"),
+            SimilarityMetric::Dot => write!(f, "dot"),
+            SimilarityMetric::Euclidean => write!(f, "euclidean"),
+        }
+    }
+}
+
+impl std::str::FromStr for SimilarityMetric {
+    type Err = crate::error::DocSentinelError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "cosine" => Ok(SimilarityMetric::Cosine),
+            "dot" => Ok(SimilarityMetric::Dot),
+            "euclidean" => Ok(SimilarityMetric::Euclidean),
+            _ => Err(crate::error::DocSentinelError::Config(format!(
+                "Unknown similarity metric: {} (expected cosine, dot, or euclidean)",
+                s
+            ))),
+        }
+    }
+}
+
+/// Compare two vectors with `metric`
+///
+/// Vectors of different lengths, or empty ones, score 0.0.
+pub fn similarity(a: &[f32], b: &[f32], metric: SimilarityMetric) -> f64 {
+    match metric {
+        SimilarityMetric::Cosine => cosine_similarity(a, b),
+        SimilarityMetric::Dot => dot_product(a, b),
+        SimilarityMetric::Euclidean => euclidean_similarity(a, b),
+    }
+}
+
+/// Compute the dot product of two vectors
+pub fn dot_product(a: &[f32], b: &[f32]) -> f64 {
     if a.len() != b.len() || a.is_empty() {
         return 0.0;
     }
 
-    let dot_product: f64 = a
-        .iter()
+    a.iter()
         .zip(b.iter())
         .map(|(x, y)| (*x as f64) * (*y as f64))
-        .sum();
+        .sum()
+}
+
+/// Compute `1 / (1 + distance)` from the Euclidean distance of two vectors
+pub fn euclidean_similarity(a: &[f32], b: &[f32]) -> f64 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+
+    let distance = a
+        .iter()
+        .zip(b.iter())
+        .map(|(x, y)| (*x as f64 - *y as f64).powi(2))
+        .sum::<f64>()
+        .sqrt();
+
+    1.0 / (1.0 + distance)
+}
+
+/// Compute cosine similarity between two vectors
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f64 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+
+    let dot_product = dot_product(a, b);
     let norm_a: f64 = a.iter().map(|x| (*x as f64).powi(2)).sum::<f64>().sqrt();
     let norm_b: f64 = b.iter().map(|x| (*x as f64).powi(2)).sum::<f64>().sqrt();
 
@@ -175,6 +384,13 @@ pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f64 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_short_id() {
+        assert_eq!(short_id("0b7c2a1e-44f1-4d0e-9d7a-5f3e1c2b9a10"), "0b7c2a1e");
+        assert_eq!(short_id("a1"), "a1");
+        assert_eq!(short_id("ééééé"), "éééé");
+    }
+
     #[test]
     fn test_cosine_similarity() {
         let a = vec![1.0, 0.0, 0.0];
@@ -188,6 +404,35 @@ mod tests {
         assert!((cosine_similarity(&a, &d) - (-1.0)).abs() < 0.001);
     }
 
+    #[test]
+    fn test_similarity_metrics() {
+        let a = vec![1.0, 2.0, 2.0];
+        let b = vec![2.0, 0.0, 1.0];
+
+        // |a| = 3, |b| = sqrt(5), a.b = 4, |a - b| = sqrt(1 + 4 + 1)
+        let cosine = similarity(&a, &b, SimilarityMetric::Cosine);
+        assert!((cosine - 4.0 / (3.0 * 5f64.sqrt())).abs() < 1e-9);
+        assert!((similarity(&a, &b, SimilarityMetric::Dot) - 4.0).abs() < 1e-9);
+        let euclidean = similarity(&a, &b, SimilarityMetric::Euclidean);
+        assert!((euclidean - 1.0 / (1.0 + 6f64.sqrt())).abs() < 1e-9);
+
+        // Identical vectors are as close as Euclidean similarity gets
+        assert!((similarity(&a, &a, SimilarityMetric::Euclidean) - 1.0).abs() < 1e-9);
+
+        for metric in [
+            SimilarityMetric::Cosine,
+            SimilarityMetric::Dot,
+            SimilarityMetric::Euclidean,
+        ] {
+            assert_eq!(similarity(&a, &[1.0], metric), 0.0);
+            assert_eq!(
+                metric.to_string().parse::<SimilarityMetric>().unwrap(),
+                metric
+            );
+        }
+        assert!("manhattan".parse::<SimilarityMetric>().is_err());
+    }
+
     #[test]
     fn test_drift_event_creation() {
         let event = DriftEvent::new(
@@ -203,4 +448,27 @@ mod tests {
         assert_eq!(event.related_code_chunks.len(), 1);
         assert_eq!(event.related_doc_chunks.len(), 1);
     }
+
+    #[test]
+    fn test_identity_survives_rewritten_description() {
+        let mut event = DriftEvent::new(
+            DriftSeverity::High,
+            "Documented signature out of date: add",
+            "",
+            0.9,
+        )
+        .with_code_chunk("src/lib.rs::add")
+        .with_doc_chunk("README.md#Usage");
+        let identity = event.identity();
+
+        event.rewrite_description("The README still shows two parameters".to_string());
+        event.rewrite_description("add() takes three numbers now".to_string());
+
+        assert_eq!(event.description, "add() takes three numbers now");
+        assert_eq!(
+            event.detected_description.as_deref(),
+            Some("Documented signature out of date: add")
+        );
+        assert_eq!(event.identity(), identity);
+    }
 }
@@ -4,9 +4,64 @@
 //! Soft rules: Possible drift (behavioral changes, comment changes)
 
 use super::{DriftEvent, DriftSeverity};
-use crate::extract::{CodeChunk, DocChunk};
+use crate::extract::doc::extract_code_blocks;
+use crate::extract::{
+    heading_anchor, mentions_config_key, CodeChunk, ConfigKey, DocChunk, Language,
+};
+use std::collections::{HashMap, HashSet};
 
 /// Trait for drift detection rules
+///
+/// Implement this to add project-specific checks, then register the rule
+/// with [`HardDriftRules::push_rule`] or [`SoftDriftRules::push_rule`] and
+/// pass the set to a [`DriftDetector`](super::DriftDetector).
+///
+/// # Example
+///
+/// ```
+/// use docsentinel::drift::{DriftDetector, DriftEvent, DriftRule, DriftSeverity, HardDriftRules};
+/// use docsentinel::{CodeChunk, DocChunk};
+///
+/// /// Flags public functions whose names still contain `todo`
+/// struct TodoNameRule;
+///
+/// impl DriftRule for TodoNameRule {
+///     fn name(&self) -> &str {
+///         "todo_name"
+///     }
+///
+///     fn check_code_change(
+///         &self,
+///         _old_chunk: Option<&CodeChunk>,
+///         new_chunk: Option<&CodeChunk>,
+///         _related_docs: &[&DocChunk],
+///     ) -> Option<DriftEvent> {
+///         let chunk = new_chunk.filter(|c| c.is_public && c.symbol_name.contains("todo"))?;
+///         Some(
+///             DriftEvent::new(
+///                 DriftSeverity::Low,
+///                 &format!("Placeholder name in public API: {}", chunk.symbol_name),
+///                 "Public symbols should not be named `todo`",
+///                 1.0,
+///             )
+///             .with_code_chunk(&chunk.id),
+///         )
+///     }
+///
+///     fn check_doc_change(
+///         &self,
+///         _old_chunk: Option<&DocChunk>,
+///         _new_chunk: Option<&DocChunk>,
+///         _related_code: &[&CodeChunk],
+///     ) -> Option<DriftEvent> {
+///         None
+///     }
+/// }
+///
+/// let mut rules = HardDriftRules::new();
+/// rules.push_rule(TodoNameRule);
+/// let detector = DriftDetector::new().with_hard_rules(rules);
+/// ```
 pub trait DriftRule: Send + Sync {
     /// Rule name
     fn name(&self) -> &str;
@@ -34,6 +89,24 @@ pub struct HardDriftRules {
 }
 
 impl HardDriftRules {
+    /// Create a set of hard drift rules from `rules`, without the defaults
+    ///
+    /// Pass an empty list and add rules with [`push_rule`](Self::push_rule)
+    /// to run only custom rules.
+    pub fn with_rules(rules: Vec<Box<dyn DriftRule>>) -> Self {
+        Self { rules }
+    }
+
+    /// Add a rule, checked after the ones already registered
+    pub fn push_rule(&mut self, rule: impl DriftRule + 'static) {
+        self.rules.push(Box::new(rule));
+    }
+
+    /// Names of the registered rules, in the order they are checked
+    pub fn rule_names(&self) -> Vec<&str> {
+        self.rules.iter().map(|rule| rule.name()).collect()
+    }
+
     /// Create default hard drift rules
     pub fn new() -> Self {
         Self {
@@ -41,7 +114,14 @@ impl HardDriftRules {
                 Box::new(SignatureChangeRule),
                 Box::new(RemovedFunctionRule),
                 Box::new(ParameterChangeRule),
+                Box::new(ExampleArityRule),
                 Box::new(ReturnTypeChangeRule),
+                Box::new(UndocumentedPublicApiRule),
+                Box::new(VisibilityChangeRule),
+                Box::new(DocSignatureMismatchRule),
+                Box::new(DeprecationRule),
+                Box::new(DefaultValueChangeRule),
+                Box::new(DocParamTagRule),
             ],
         }
     }
@@ -55,7 +135,10 @@ impl HardDriftRules {
     ) -> Vec<DriftEvent> {
         self.rules
             .iter()
-            .filter_map(|rule| rule.check_code_change(old_chunk, new_chunk, related_docs))
+            .filter_map(|rule| {
+                rule.check_code_change(old_chunk, new_chunk, related_docs)
+                    .map(|event| event.with_tag(rule.name()))
+            })
             .collect()
     }
 
@@ -68,7 +151,10 @@ impl HardDriftRules {
     ) -> Vec<DriftEvent> {
         self.rules
             .iter()
-            .filter_map(|rule| rule.check_doc_change(old_chunk, new_chunk, related_code))
+            .filter_map(|rule| {
+                rule.check_doc_change(old_chunk, new_chunk, related_code)
+                    .map(|event| event.with_tag(rule.name()))
+            })
             .collect()
     }
 }
@@ -85,13 +171,50 @@ pub struct SoftDriftRules {
 }
 
 impl SoftDriftRules {
+    /// Create a set of soft drift rules from `rules`, without the defaults
+    ///
+    /// Pass an empty list and add rules with [`push_rule`](Self::push_rule)
+    /// to run only custom rules.
+    pub fn with_rules(rules: Vec<Box<dyn DriftRule>>) -> Self {
+        Self { rules }
+    }
+
+    /// Add a rule, checked after the ones already registered
+    pub fn push_rule(&mut self, rule: impl DriftRule + 'static) {
+        self.rules.push(Box::new(rule));
+    }
+
+    /// Names of the registered rules, in the order they are checked
+    pub fn rule_names(&self) -> Vec<&str> {
+        self.rules.iter().map(|rule| rule.name()).collect()
+    }
+
     /// Create default soft drift rules
     pub fn new() -> Self {
+        Self::with_diff_context(DEFAULT_DIFF_CONTEXT_LINES)
+    }
+
+    /// Create default soft drift rules whose evidence diffs keep
+    /// `context_lines` unchanged lines around each change
+    pub fn with_diff_context(context_lines: usize) -> Self {
         Self {
-            rules: vec![Box::new(DocCommentChangeRule), Box::new(BehaviorChangeRule)],
+            rules: vec![
+                Box::new(DocCommentChangeRule { context_lines }),
+                Box::new(BehaviorChangeRule { context_lines }),
+                Box::new(DecoratorChangeRule),
+            ],
         }
     }
 
+    /// Soft rules that `scan` applies to changed symbols
+    ///
+    /// Only decorator changes are reported there. Doc comment and
+    /// implementation changes fire on most edits, so they are left to
+    /// [`DriftDetector`](super::DriftDetector) and `diff`.
+    pub fn for_scan() -> Self {
+        Self::with_rules(vec![Box::new(DecoratorChangeRule)])
+    }
+
     /// Check all rules against a code change
     pub fn check_code_change(
         &self,
@@ -101,7 +224,10 @@ impl SoftDriftRules {
     ) -> Vec<DriftEvent> {
         self.rules
             .iter()
-            .filter_map(|rule| rule.check_code_change(old_chunk, new_chunk, related_docs))
+            .filter_map(|rule| {
+                rule.check_code_change(old_chunk, new_chunk, related_docs)
+                    .map(|event| event.with_tag(rule.name()))
+            })
             .collect()
     }
 }
@@ -112,6 +238,45 @@ impl Default for SoftDriftRules {
     }
 }
 
+/// Unchanged lines kept around each change in evidence diffs
+pub const DEFAULT_DIFF_CONTEXT_LINES: usize = 3;
+
+/// Diff lines kept in evidence before the rest is cut
+const MAX_EVIDENCE_DIFF_LINES: usize = 40;
+
+/// Unified diff of `old` and `new` for event evidence
+///
+/// Only hunks are included, each with `context_lines` unchanged lines around
+/// its changes. Diffs over [`MAX_EVIDENCE_DIFF_LINES`] lines are cut with a
+/// note of how many lines were left out.
+pub(crate) fn evidence_diff(old: &str, new: &str, context_lines: usize) -> String {
+    let diff = similar::TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(context_lines)
+        .missing_newline_hint(false)
+        .to_string();
+    let lines: Vec<&str> = diff.lines().collect();
+    if lines.len() <= MAX_EVIDENCE_DIFF_LINES {
+        return lines.join("\n");
+    }
+    format!(
+        "{}\n... ({} more diff lines)",
+        lines[..MAX_EVIDENCE_DIFF_LINES].join("\n"),
+        lines.len() - MAX_EVIDENCE_DIFF_LINES
+    )
+}
+
+/// Attach related docs to an event and point its location at the first one
+fn with_related_docs(mut event: DriftEvent, related_docs: &[&DocChunk]) -> DriftEvent {
+    for doc in related_docs {
+        event = event.with_doc_chunk(&doc.id);
+    }
+    if let Some(doc) = related_docs.first() {
+        event = event.with_location(&doc.file_path, doc.start_line);
+    }
+    event
+}
+
 // ==================== Hard Rules ====================
 
 /// Detects when a function signature changes without doc update
@@ -136,11 +301,12 @@ impl DriftRule for SignatureChangeRule {
             return None;
         }
 
-        // Check if signature changed
+        // Check if signature changed, ignoring formatting
         let old_sig = old.signature.as_ref()?;
         let new_sig = new.signature.as_ref()?;
 
-        if old_sig == new_sig {
+        if normalize_signature(old_sig, old.language) == normalize_signature(new_sig, new.language)
+        {
             return None;
         }
 
@@ -150,19 +316,16 @@ impl DriftRule for SignatureChangeRule {
         if has_related_docs {
             let evidence = format!("Signature changed from:\n  {}\nto:\n  {}", old_sig, new_sig);
 
-            let mut event = DriftEvent::new(
+            let event = DriftEvent::new(
                 DriftSeverity::High,
                 &format!("Public API signature changed: {}", new.symbol_name),
                 &evidence,
                 0.95,
             )
-            .with_code_chunk(&new.id);
-
-            for doc in related_docs {
-                event = event.with_doc_chunk(&doc.id);
-            }
+            .with_code_chunk(&new.id)
+            .with_location(&new.file_path, new.start_line);
 
-            return Some(event);
+            return Some(with_related_docs(event, related_docs));
         }
 
         None
@@ -213,22 +376,150 @@ impl DriftRule for RemovedFunctionRule {
                 old.symbol_name
             );
 
-            let mut event = DriftEvent::new(
+            let event = DriftEvent::new(
                 DriftSeverity::Critical,
                 &format!("Documented function removed: {}", old.symbol_name),
                 &evidence,
                 1.0,
             )
-            .with_code_chunk(&old.id);
+            .with_code_chunk(&old.id)
+            .with_location(&old.file_path, old.start_line);
+
+            return Some(with_related_docs(event, related_docs));
+        }
+
+        None
+    }
 
-            for doc in related_docs {
-                event = event.with_doc_chunk(&doc.id);
+    fn check_doc_change(
+        &self,
+        _old_chunk: Option<&DocChunk>,
+        _new_chunk: Option<&DocChunk>,
+        _related_code: &[&CodeChunk],
+    ) -> Option<DriftEvent> {
+        None
+    }
+}
+
+/// Body similarity at which a removed and an added symbol count as a rename
+const RENAME_SIMILARITY: f64 = 0.9;
+
+/// Pair symbols removed from a file with the symbols they were renamed to
+///
+/// A removed and an added symbol pair up when they are in the same file, of
+/// the same kind, and their content is nearly identical once each symbol's
+/// own name is ignored. Each symbol is used in at most one pair, best matches
+/// first.
+pub(crate) fn pair_renames<'a>(
+    removed: &[&'a CodeChunk],
+    added: &[&'a CodeChunk],
+) -> Vec<(&'a CodeChunk, &'a CodeChunk)> {
+    let mut candidates: Vec<(f64, usize, usize)> = Vec::new();
+    for (i, old) in removed.iter().enumerate() {
+        for (j, new) in added.iter().enumerate() {
+            if old.file_path != new.file_path
+                || old.symbol_type != new.symbol_type
+                || old.language != new.language
+            {
+                continue;
             }
+            let score = rename_similarity(old, new);
+            if score >= RENAME_SIMILARITY {
+                candidates.push((score, i, j));
+            }
+        }
+    }
+    candidates.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)).then(a.2.cmp(&b.2)));
+
+    let mut used_old = HashSet::new();
+    let mut used_new = HashSet::new();
+    let mut pairs = Vec::new();
+    for (_, i, j) in candidates {
+        if !used_old.contains(&i) && !used_new.contains(&j) {
+            used_old.insert(i);
+            used_new.insert(j);
+            pairs.push((removed[i], added[j]));
+        }
+    }
+    pairs
+}
 
-            return Some(event);
+/// Similarity (0.0 - 1.0) of two symbols' content with their names masked
+fn rename_similarity(old: &CodeChunk, new: &CodeChunk) -> f64 {
+    let old_tokens = name_masked_tokens(&old.content, short_name(&old.symbol_name));
+    let new_tokens = name_masked_tokens(&new.content, short_name(&new.symbol_name));
+    similar::TextDiff::from_slices(&old_tokens, &new_tokens).ratio() as f64
+}
+
+/// Identifier and punctuation tokens of `content`, with `name` replaced
+fn name_masked_tokens<'a>(content: &'a str, name: &str) -> Vec<&'a str> {
+    let mut tokens = Vec::new();
+    let mut word_start = None;
+    for (i, c) in content.char_indices() {
+        let is_word = c.is_alphanumeric() || c == '_';
+        if is_word {
+            word_start.get_or_insert(i);
+            continue;
+        }
+        if let Some(start) = word_start.take() {
+            tokens.push(&content[start..i]);
+        }
+        if !c.is_whitespace() {
+            tokens.push(&content[i..i + c.len_utf8()]);
         }
+    }
+    if let Some(start) = word_start {
+        tokens.push(&content[start..]);
+    }
+    tokens
+        .into_iter()
+        .map(|token| if token == name { "\u{0}" } else { token })
+        .collect()
+}
 
-        None
+/// Detects a documented symbol that was renamed
+///
+/// Checked on the removed and added symbols paired by [`pair_renames`], so
+/// the old chunk's name differs from the new one's.
+pub(crate) struct RenamedSymbolRule;
+
+impl DriftRule for RenamedSymbolRule {
+    fn name(&self) -> &str {
+        "renamed_symbol"
+    }
+
+    fn check_code_change(
+        &self,
+        old_chunk: Option<&CodeChunk>,
+        new_chunk: Option<&CodeChunk>,
+        related_docs: &[&DocChunk],
+    ) -> Option<DriftEvent> {
+        let (old, new) = (old_chunk?, new_chunk?);
+        if old.symbol_name == new.symbol_name || !old.is_public || related_docs.is_empty() {
+            return None;
+        }
+
+        let evidence = format!(
+            "'{}' in {} was renamed to '{}' but the documentation still uses the old name",
+            old.symbol_name, old.file_path, new.symbol_name
+        );
+        let event = DriftEvent::new(
+            DriftSeverity::High,
+            &format!(
+                "Documented symbol renamed: {} -> {}",
+                old.symbol_name, new.symbol_name
+            ),
+            &evidence,
+            rename_similarity(old, new),
+        )
+        .with_code_chunk(&new.id)
+        .with_suggested_fix(&format!(
+            "Replace `{}` with `{}` in the documentation",
+            short_name(&old.symbol_name),
+            short_name(&new.symbol_name)
+        ));
+
+        Some(with_related_docs(event, related_docs))
     }
 
     fn check_doc_change(
@@ -266,8 +557,8 @@ impl DriftRule for ParameterChangeRule {
         let new_sig = new.signature.as_ref()?;
 
         // Extract parameters from signatures
-        let old_params = extract_parameters(old_sig);
-        let new_params = extract_parameters(new_sig);
+        let old_params = extract_parameters(&normalize_signature(old_sig, old.language));
+        let new_params = extract_parameters(&normalize_signature(new_sig, new.language));
 
         if old_params == new_params {
             return None;
@@ -300,22 +591,116 @@ impl DriftRule for ParameterChangeRule {
                 evidence_parts.push(format!("Removed parameters: {}", removed.join(", ")));
             }
 
-            let mut event = DriftEvent::new(
+            let event = DriftEvent::new(
                 DriftSeverity::High,
                 &format!("Parameters changed for: {}", new.symbol_name),
                 &evidence_parts.join("\n"),
                 0.9,
             )
-            .with_code_chunk(&new.id);
+            .with_code_chunk(&new.id)
+            .with_location(&new.file_path, new.start_line);
+
+            return Some(with_related_docs(event, related_docs));
+        }
+
+        None
+    }
+
+    fn check_doc_change(
+        &self,
+        _old_chunk: Option<&DocChunk>,
+        _new_chunk: Option<&DocChunk>,
+        _related_code: &[&CodeChunk],
+    ) -> Option<DriftEvent> {
+        None
+    }
+}
+
+/// Detects doc examples that call a changed function with the wrong number
+/// of arguments
+///
+/// Only fenced blocks in the function's language (or with no language) are
+/// read. Methods are matched as `.name(...)` or `Type::name(...)` calls, and
+/// free functions as bare or path-qualified calls, so same-named functions
+/// elsewhere in an example are left alone.
+struct ExampleArityRule;
+
+impl DriftRule for ExampleArityRule {
+    fn name(&self) -> &str {
+        "example_arity"
+    }
+
+    fn check_code_change(
+        &self,
+        old_chunk: Option<&CodeChunk>,
+        new_chunk: Option<&CodeChunk>,
+        related_docs: &[&DocChunk],
+    ) -> Option<DriftEvent> {
+        let old = old_chunk?;
+        let new = new_chunk?;
+
+        if !new.is_public || related_docs.is_empty() {
+            return None;
+        }
+
+        let old_sig = old.signature.as_ref()?;
+        let new_sig = new.signature.as_ref()?;
+        if normalize_signature(old_sig, old.language) == normalize_signature(new_sig, new.language)
+        {
+            return None;
+        }
+
+        let arity = Arity::of(new_sig, new.language)?;
+        let (owner, name) = split_symbol_name(&new.symbol_name, new.language);
+
+        let mut mismatches = Vec::new();
+        let mut mismatched_docs = Vec::new();
+        for doc in related_docs {
+            let mut found = false;
+            for block in extract_code_blocks(&doc.content) {
+                if !block_is_language(block.language.as_deref(), new.language) {
+                    continue;
+                }
+                for call in example_calls(&block.content, name) {
+                    let Some(expected) = arity.for_call(&call.form, owner, new.language) else {
+                        continue;
+                    };
+                    if expected.accepts(call.args) {
+                        continue;
+                    }
 
-            for doc in related_docs {
-                event = event.with_doc_chunk(&doc.id);
+                    let line = doc.start_line + block.line - 1 + call.line_offset;
+                    mismatches.push(format!(
+                        "  {}:{} ({}): {} passes {}",
+                        doc.file_path, line, doc.heading, call.text, call.args
+                    ));
+                    found = true;
+                }
+            }
+            if found {
+                mismatched_docs.push(*doc);
             }
+        }
 
-            return Some(event);
+        if mismatches.is_empty() {
+            return None;
         }
 
-        None
+        let evidence = format!(
+            "{} takes {}\nExamples with a different argument count:\n{}",
+            collapse_whitespace(new_sig),
+            arity,
+            mismatches.join("\n")
+        );
+        let event = DriftEvent::new(
+            DriftSeverity::High,
+            &format!("Example calls out of date: {}", new.symbol_name),
+            &evidence,
+            0.85,
+        )
+        .with_code_chunk(&new.id);
+
+        Some(with_related_docs(event, &mismatched_docs))
     }
 
     fn check_doc_change(
@@ -352,13 +737,16 @@ impl DriftRule for ReturnTypeChangeRule {
         let old_sig = old.signature.as_ref()?;
         let new_sig = new.signature.as_ref()?;
 
-        let old_return = extract_return_type(old_sig);
-        let new_return = extract_return_type(new_sig);
-
-        if old_return == new_return {
+        if extract_return_type(&normalize_signature(old_sig, old.language))
+            == extract_return_type(&normalize_signature(new_sig, new.language))
+        {
             return None;
         }
 
+        // Report the return types as written
+        let old_return = extract_return_type(old_sig);
+        let new_return = extract_return_type(new_sig);
+
         let has_related_docs = !related_docs.is_empty();
 
         if has_related_docs {
@@ -368,19 +756,16 @@ impl DriftRule for ReturnTypeChangeRule {
                 new_return.as_deref().unwrap_or("none")
             );
 
-            let mut event = DriftEvent::new(
+            let event = DriftEvent::new(
                 DriftSeverity::High,
                 &format!("Return type changed for: {}", new.symbol_name),
                 &evidence,
                 0.9,
             )
-            .with_code_chunk(&new.id);
-
-            for doc in related_docs {
-                event = event.with_doc_chunk(&doc.id);
-            }
+            .with_code_chunk(&new.id)
+            .with_location(&new.file_path, new.start_line);
 
-            return Some(event);
+            return Some(with_related_docs(event, related_docs));
         }
 
         None
@@ -396,14 +781,12 @@ impl DriftRule for ReturnTypeChangeRule {
     }
 }
 
-// ==================== Soft Rules ====================
-
-/// Detects when doc comments change significantly
-struct DocCommentChangeRule;
+/// Detects newly added public symbols that have no doc comment
+struct UndocumentedPublicApiRule;
 
-impl DriftRule for DocCommentChangeRule {
+impl DriftRule for UndocumentedPublicApiRule {
     fn name(&self) -> &str {
-        "doc_comment_change"
+        "undocumented_public_api"
     }
 
     fn check_code_change(
@@ -412,52 +795,41 @@ impl DriftRule for DocCommentChangeRule {
         new_chunk: Option<&CodeChunk>,
         related_docs: &[&DocChunk],
     ) -> Option<DriftEvent> {
-        let old = old_chunk?;
         let new = new_chunk?;
 
-        if !new.is_public {
+        // Only newly added symbols
+        if old_chunk.is_some() {
             return None;
         }
 
-        let old_doc = old.doc_comment.as_ref()?;
-        let new_doc = new.doc_comment.as_ref()?;
-
-        if old_doc == new_doc {
+        if !new.is_public {
             return None;
         }
 
-        // Check if the change is significant (more than just whitespace)
-        let old_normalized: String = old_doc.split_whitespace().collect();
-        let new_normalized: String = new_doc.split_whitespace().collect();
+        let has_doc_comment = new
+            .doc_comment
+            .as_ref()
+            .is_some_and(|doc| !doc.trim().is_empty());
 
-        if old_normalized == new_normalized {
+        if has_doc_comment {
             return None;
         }
 
-        let has_related_docs = !related_docs.is_empty();
-
-        if has_related_docs {
-            let evidence = format!(
-                "Doc comment changed for '{}'. External documentation may need update.",
-                new.symbol_name
-            );
-
-            let mut event = DriftEvent::new(
-                DriftSeverity::Medium,
-                &format!("Doc comment changed: {}", new.symbol_name),
-                &evidence,
-                0.7,
-            )
-            .with_code_chunk(&new.id);
-
-            for doc in related_docs {
-                event = event.with_doc_chunk(&doc.id);
-            }
-
-            return Some(event);
-        }
-
-        None
+        let evidence = format!(
+            "New public {} '{}' in {} has no doc comment",
+            new.symbol_type, new.symbol_name, new.file_path
+        );
+
+        let event = DriftEvent::new(
+            DriftSeverity::Medium,
+            &format!("Undocumented new public API: {}", new.symbol_name),
+            &evidence,
+            0.9,
+        )
+        .with_code_chunk(&new.id)
+        .with_location(&new.file_path, new.start_line);
+
+        Some(with_related_docs(event, related_docs))
     }
 
     fn check_doc_change(
@@ -470,12 +842,12 @@ impl DriftRule for DocCommentChangeRule {
     }
 }
 
-/// Detects potential behavioral changes from code modifications
-struct BehaviorChangeRule;
+/// Detects documented symbols whose visibility changed
+struct VisibilityChangeRule;
 
-impl DriftRule for BehaviorChangeRule {
+impl DriftRule for VisibilityChangeRule {
     fn name(&self) -> &str {
-        "behavior_change"
+        "visibility_change"
     }
 
     fn check_code_change(
@@ -487,49 +859,37 @@ impl DriftRule for BehaviorChangeRule {
         let old = old_chunk?;
         let new = new_chunk?;
 
-        if !new.is_public {
+        if old.is_public == new.is_public || related_docs.is_empty() {
             return None;
         }
 
-        // Signature didn't change but content did
-        if old.signature == new.signature && old.content != new.content {
-            // Look for behavioral indicators
-            let behavior_keywords = [
-                "default", "error", "panic", "return", "throw", "raise", "assert", "expect",
-                "unwrap", "if", "else", "match",
-            ];
-
-            let old_has_keyword = behavior_keywords.iter().any(|k| old.content.contains(k));
-            let new_has_keyword = behavior_keywords.iter().any(|k| new.content.contains(k));
-
-            // If behavioral keywords changed, might indicate behavior change
-            if old_has_keyword != new_has_keyword {
-                let has_related_docs = !related_docs.is_empty();
-
-                if has_related_docs {
-                    let evidence = format!(
-                        "Implementation of '{}' changed. Behavior may have changed.",
-                        new.symbol_name
-                    );
-
-                    let mut event = DriftEvent::new(
-                        DriftSeverity::Low,
-                        &format!("Potential behavior change: {}", new.symbol_name),
-                        &evidence,
-                        0.5,
-                    )
-                    .with_code_chunk(&new.id);
+        let visibility = |is_public: bool| if is_public { "public" } else { "private" };
+        let evidence = format!(
+            "Visibility changed from {} to {}",
+            visibility(old.is_public),
+            visibility(new.is_public)
+        );
 
-                    for doc in related_docs {
-                        event = event.with_doc_chunk(&doc.id);
-                    }
+        // Hiding a documented symbol breaks users; exposing one is only a doc gap
+        let (severity, description, confidence) = if old.is_public {
+            (
+                DriftSeverity::High,
+                format!("Documented public symbol made private: {}", new.symbol_name),
+                0.95,
+            )
+        } else {
+            (
+                DriftSeverity::Low,
+                format!("Private symbol made public: {}", new.symbol_name),
+                0.8,
+            )
+        };
 
-                    return Some(event);
-                }
-            }
-        }
+        let event = DriftEvent::new(severity, &description, &evidence, confidence)
+            .with_code_chunk(&new.id)
+            .with_location(&new.file_path, new.start_line);
 
-        None
+        Some(with_related_docs(event, related_docs))
     }
 
     fn check_doc_change(
@@ -542,65 +902,1668 @@ impl DriftRule for BehaviorChangeRule {
     }
 }
 
-// ==================== Helper Functions ====================
+/// Detects doc sections that show a signature no longer matching the code
+///
+/// This is the inverse direction of the signature rules above: the docs were
+/// edited (or written) with a signature that the current code does not have.
+struct DocSignatureMismatchRule;
 
-/// Extract parameter names from a function signature
-fn extract_parameters(signature: &str) -> Vec<String> {
-    // Find content between parentheses
-    let start = signature.find('(').unwrap_or(0);
-    let end = signature.rfind(')').unwrap_or(signature.len());
+impl DriftRule for DocSignatureMismatchRule {
+    fn name(&self) -> &str {
+        "doc_signature_mismatch"
+    }
 
-    if start >= end {
-        return Vec::new();
+    fn check_code_change(
+        &self,
+        _old_chunk: Option<&CodeChunk>,
+        _new_chunk: Option<&CodeChunk>,
+        _related_docs: &[&DocChunk],
+    ) -> Option<DriftEvent> {
+        None
     }
 
-    let params_str = &signature[start + 1..end];
+    fn check_doc_change(
+        &self,
+        _old_chunk: Option<&DocChunk>,
+        new_chunk: Option<&DocChunk>,
+        related_code: &[&CodeChunk],
+    ) -> Option<DriftEvent> {
+        let doc = new_chunk?;
+        let mut mismatches: Vec<(DocumentedSignature, Vec<&CodeChunk>)> = Vec::new();
 
-    params_str
-        .split(',')
-        .map(|p| p.trim())
-        .filter(|p| !p.is_empty())
-        .map(|p| {
-            // Extract just the parameter name (before : or =)
-            p.split(':')
-                .next()
-                .unwrap_or(p)
-                .split('=')
-                .next()
-                .unwrap_or(p)
-                .trim()
-                .to_string()
-        })
-        .collect()
-}
+        for documented in doc_signatures(doc) {
+            if mismatches.iter().any(|(d, _)| d.name == documented.name) {
+                continue;
+            }
 
-/// Extract return type from a function signature
-fn extract_return_type(signature: &str) -> Option<String> {
-    // Look for -> in the signature
-    if let Some(arrow_pos) = signature.find("->") {
-        let return_part = &signature[arrow_pos + 2..];
-        // Clean up the return type
-        let return_type = return_part.trim().trim_end_matches('{').trim().to_string();
-        if !return_type.is_empty() {
-            return Some(return_type);
+            let candidates: Vec<&CodeChunk> = related_code
+                .iter()
+                .filter(|code| {
+                    code.signature.is_some() && short_name(&code.symbol_name) == documented.name
+                })
+                .copied()
+                .collect();
+
+            // Several symbols can share a name (e.g. `new`); the docs are only
+            // wrong if they match none of them
+            let matches_any = candidates
+                .iter()
+                .filter_map(|code| code.signature.as_deref())
+                .filter_map(parse_signature)
+                .any(|actual| documented.matches(&actual));
+            if !candidates.is_empty() && !matches_any {
+                mismatches.push((documented, candidates));
+            }
         }
-    }
-    None
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        if mismatches.is_empty() {
+            return None;
+        }
 
-    #[test]
-    fn test_extract_parameters() {
-        let sig = "fn hello(name: &str, age: u32) -> String";
-        let params = extract_parameters(sig);
-        assert_eq!(params, vec!["name", "age"]);
+        let names: Vec<&str> = mismatches.iter().map(|(d, _)| d.name.as_str()).collect();
+        let evidence = mismatches
+            .iter()
+            .map(|(documented, candidates)| {
+                let actual: Vec<String> = candidates
+                    .iter()
+                    .filter_map(|code| code.signature.as_deref())
+                    .map(|sig| format!("  {}", collapse_whitespace(sig)))
+                    .collect();
+                format!(
+                    "Docs show:\n  {}\nCode has:\n{}",
+                    documented.text,
+                    actual.join("\n")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        let mut event = DriftEvent::new(
+            DriftSeverity::High,
+            &format!("Documented signature out of date: {}", names.join(", ")),
+            &evidence,
+            0.9,
+        )
+        .with_doc_chunk(&doc.id)
+        .with_location(&doc.file_path, doc.start_line);
+        for (_, candidates) in &mismatches {
+            for code in candidates {
+                event = event.with_code_chunk(&code.id);
+            }
+        }
 
-        let sig2 = "def greet(name, greeting='Hello')";
-        let params2 = extract_parameters(sig2);
-        assert_eq!(params2, vec!["name", "greeting"]);
+        Some(event)
+    }
+}
+
+/// Detects public symbols newly marked `#[deprecated]` whose docs still
+/// present them as current
+struct DeprecationRule;
+
+impl DriftRule for DeprecationRule {
+    fn name(&self) -> &str {
+        "deprecation"
+    }
+
+    fn check_code_change(
+        &self,
+        old_chunk: Option<&CodeChunk>,
+        new_chunk: Option<&CodeChunk>,
+        related_docs: &[&DocChunk],
+    ) -> Option<DriftEvent> {
+        let old = old_chunk?;
+        let new = new_chunk?;
+
+        if !new.is_public || !new.is_deprecated() || old.is_deprecated() {
+            return None;
+        }
+
+        // Sections that already mention the deprecation are up to date
+        let stale_docs: Vec<&DocChunk> = related_docs
+            .iter()
+            .copied()
+            .filter(|doc| !doc.content.to_lowercase().contains("deprecated"))
+            .collect();
+        if stale_docs.is_empty() {
+            return None;
+        }
+
+        let attribute = new
+            .attributes
+            .iter()
+            .find(|attr| attr.starts_with("deprecated"))
+            .map(String::as_str)
+            .unwrap_or("deprecated");
+        let evidence = format!(
+            "Symbol gained #[{}] but {} related doc section(s) do not mention the deprecation",
+            attribute,
+            stale_docs.len()
+        );
+
+        let event = DriftEvent::new(
+            DriftSeverity::Medium,
+            &format!(
+                "Deprecated symbol still documented as current: {}",
+                new.symbol_name
+            ),
+            &evidence,
+            0.85,
+        )
+        .with_code_chunk(&new.id)
+        .with_location(&new.file_path, new.start_line);
+
+        Some(with_related_docs(event, &stale_docs))
+    }
+
+    fn check_doc_change(
+        &self,
+        _old_chunk: Option<&DocChunk>,
+        _new_chunk: Option<&DocChunk>,
+        _related_code: &[&CodeChunk],
+    ) -> Option<DriftEvent> {
+        None
+    }
+}
+
+/// Detects JSDoc-style `@param` tags in a doc comment that no longer match
+/// the signature's parameters
+///
+/// Only doc comments with at least one `@param` (or `@arg`) tag are checked.
+/// Receivers (`self`, `cls`) are not expected to be documented, and tags
+/// for properties of a parameter (`@param opts.timeout`) are skipped.
+struct DocParamTagRule;
+
+impl DriftRule for DocParamTagRule {
+    fn name(&self) -> &str {
+        "doc_param_tag"
+    }
+
+    fn check_code_change(
+        &self,
+        _old_chunk: Option<&CodeChunk>,
+        new_chunk: Option<&CodeChunk>,
+        _related_docs: &[&DocChunk],
+    ) -> Option<DriftEvent> {
+        let new = new_chunk?;
+        let documented = param_tag_names(new.doc_comment.as_deref()?);
+        if documented.is_empty() {
+            return None;
+        }
+
+        let signature = normalize_signature(new.signature.as_ref()?, new.language);
+        let actual: Vec<String> = extract_parameters(&signature)
+            .iter()
+            .map(|param| bare_parameter_name(param))
+            .filter(|name| !name.is_empty() && !matches!(name.as_str(), "self" | "cls"))
+            .collect();
+
+        let extra: Vec<&String> = documented.iter().filter(|n| !actual.contains(n)).collect();
+        let missing: Vec<&String> = actual.iter().filter(|n| !documented.contains(n)).collect();
+        if extra.is_empty() && missing.is_empty() {
+            return None;
+        }
+
+        let mut evidence = Vec::new();
+        if let ([old], [new]) = (extra.as_slice(), missing.as_slice()) {
+            evidence.push(format!("@param `{}` appears renamed to `{}`", old, new));
+        } else {
+            if !extra.is_empty() {
+                evidence.push(format!(
+                    "Documented but not in the signature: {}",
+                    join_names(&extra)
+                ));
+            }
+            if !missing.is_empty() {
+                evidence.push(format!("Missing @param for: {}", join_names(&missing)));
+            }
+        }
+
+        Some(
+            DriftEvent::new(
+                DriftSeverity::High,
+                &format!("@param tags out of date: {}", new.symbol_name),
+                &evidence.join("\n"),
+                0.9,
+            )
+            .with_code_chunk(&new.id)
+            .with_location(&new.file_path, new.start_line),
+        )
+    }
+
+    fn check_doc_change(
+        &self,
+        _old_chunk: Option<&DocChunk>,
+        _new_chunk: Option<&DocChunk>,
+        _related_code: &[&CodeChunk],
+    ) -> Option<DriftEvent> {
+        None
+    }
+}
+
+/// Parameter names documented with `@param` (or `@arg` / `@argument`) tags,
+/// in order
+///
+/// Handles an optional `{type}`, optional `[name=default]` brackets, and
+/// `...rest` parameters. Tags naming a property (`opts.timeout`) are left out.
+fn param_tag_names(doc_comment: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    for line in doc_comment.lines() {
+        let line = line.trim().trim_start_matches('*').trim_start();
+        let Some(rest) = ["@param", "@argument", "@arg"]
+            .iter()
+            .find_map(|tag| line.strip_prefix(tag))
+            .filter(|rest| rest.starts_with(char::is_whitespace))
+        else {
+            continue;
+        };
+
+        let mut rest = rest.trim_start();
+        if rest.starts_with('{') {
+            let mut depth = 0;
+            let end = rest.char_indices().find_map(|(i, c)| {
+                match c {
+                    '{' => depth += 1,
+                    '}' => depth -= 1,
+                    _ => {}
+                }
+                (depth == 0).then_some(i + 1)
+            });
+            let Some(end) = end else { continue };
+            rest = rest[end..].trim_start();
+        }
+
+        let token = rest.split_whitespace().next().unwrap_or("");
+        let name = token
+            .trim_start_matches('[')
+            .split(['=', ']'])
+            .next()
+            .unwrap_or("")
+            .trim_end_matches(':')
+            .trim_start_matches("...");
+        if !name.is_empty() && !name.contains('.') && !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+/// A parameter as written in a signature, without `mut`, references, or
+/// `*` / `...` spread markers
+fn bare_parameter_name(param: &str) -> String {
+    param
+        .trim_start_matches(['&', '*'])
+        .trim_start_matches("...")
+        .trim_start_matches("mut ")
+        .trim()
+        .to_string()
+}
+
+/// `a, b` with each name in backticks
+fn join_names(names: &[&String]) -> String {
+    names
+        .iter()
+        .map(|name| format!("`{}`", name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// ==================== Soft Rules ====================
+
+/// Detects when doc comments change significantly
+struct DocCommentChangeRule {
+    /// Unchanged lines kept around each change in the evidence diff
+    context_lines: usize,
+}
+
+impl DriftRule for DocCommentChangeRule {
+    fn name(&self) -> &str {
+        "doc_comment_change"
+    }
+
+    fn check_code_change(
+        &self,
+        old_chunk: Option<&CodeChunk>,
+        new_chunk: Option<&CodeChunk>,
+        related_docs: &[&DocChunk],
+    ) -> Option<DriftEvent> {
+        let old = old_chunk?;
+        let new = new_chunk?;
+
+        if !new.is_public {
+            return None;
+        }
+
+        let old_doc = old.doc_comment.as_ref()?;
+        let new_doc = new.doc_comment.as_ref()?;
+
+        if old_doc == new_doc {
+            return None;
+        }
+
+        // Check if the change is significant (more than just whitespace)
+        let old_normalized: String = old_doc.split_whitespace().collect();
+        let new_normalized: String = new_doc.split_whitespace().collect();
+
+        if old_normalized == new_normalized {
+            return None;
+        }
+
+        let has_related_docs = !related_docs.is_empty();
+
+        if has_related_docs {
+            let evidence = format!(
+                "Doc comment changed for '{}'. External documentation may need update.\n{}",
+                new.symbol_name,
+                evidence_diff(old_doc, new_doc, self.context_lines)
+            );
+
+            let event = DriftEvent::new(
+                DriftSeverity::Medium,
+                &format!("Doc comment changed: {}", new.symbol_name),
+                &evidence,
+                0.7,
+            )
+            .with_code_chunk(&new.id)
+            .with_location(&new.file_path, new.start_line);
+
+            return Some(with_related_docs(event, related_docs));
+        }
+
+        None
+    }
+
+    fn check_doc_change(
+        &self,
+        _old_chunk: Option<&DocChunk>,
+        _new_chunk: Option<&DocChunk>,
+        _related_code: &[&CodeChunk],
+    ) -> Option<DriftEvent> {
+        None
+    }
+}
+
+/// Detects potential behavioral changes from code modifications
+struct BehaviorChangeRule {
+    /// Unchanged lines kept around each change in the evidence diff
+    context_lines: usize,
+}
+
+impl DriftRule for BehaviorChangeRule {
+    fn name(&self) -> &str {
+        "behavior_change"
+    }
+
+    fn check_code_change(
+        &self,
+        old_chunk: Option<&CodeChunk>,
+        new_chunk: Option<&CodeChunk>,
+        related_docs: &[&DocChunk],
+    ) -> Option<DriftEvent> {
+        let old = old_chunk?;
+        let new = new_chunk?;
+
+        if !new.is_public {
+            return None;
+        }
+
+        // Signature didn't change but content did
+        if old.signature == new.signature && old.content != new.content {
+            // Look for behavioral indicators
+            let behavior_keywords = [
+                "default", "error", "panic", "return", "throw", "raise", "assert", "expect",
+                "unwrap", "if", "else", "match",
+            ];
+
+            let old_has_keyword = behavior_keywords.iter().any(|k| old.content.contains(k));
+            let new_has_keyword = behavior_keywords.iter().any(|k| new.content.contains(k));
+
+            // If behavioral keywords changed, might indicate behavior change
+            if old_has_keyword != new_has_keyword {
+                let has_related_docs = !related_docs.is_empty();
+
+                if has_related_docs {
+                    let evidence = format!(
+                        "Implementation of '{}' changed. Behavior may have changed.\n{}",
+                        new.symbol_name,
+                        evidence_diff(&old.content, &new.content, self.context_lines)
+                    );
+
+                    let event = DriftEvent::new(
+                        DriftSeverity::Low,
+                        &format!("Potential behavior change: {}", new.symbol_name),
+                        &evidence,
+                        0.5,
+                    )
+                    .with_code_chunk(&new.id)
+                    .with_location(&new.file_path, new.start_line);
+
+                    return Some(with_related_docs(event, related_docs));
+                }
+            }
+        }
+
+        None
+    }
+
+    fn check_doc_change(
+        &self,
+        _old_chunk: Option<&DocChunk>,
+        _new_chunk: Option<&DocChunk>,
+        _related_code: &[&CodeChunk],
+    ) -> Option<DriftEvent> {
+        None
+    }
+}
+
+/// Detects when a symbol's decorators change (e.g. gaining `@property`)
+struct DecoratorChangeRule;
+
+impl DriftRule for DecoratorChangeRule {
+    fn name(&self) -> &str {
+        "decorator_change"
+    }
+
+    fn check_code_change(
+        &self,
+        old_chunk: Option<&CodeChunk>,
+        new_chunk: Option<&CodeChunk>,
+        related_docs: &[&DocChunk],
+    ) -> Option<DriftEvent> {
+        let old = old_chunk?;
+        let new = new_chunk?;
+
+        if !new.is_public {
+            return None;
+        }
+
+        let added: Vec<&String> = new
+            .decorators
+            .iter()
+            .filter(|d| !old.decorators.contains(d))
+            .collect();
+        let removed: Vec<&String> = old
+            .decorators
+            .iter()
+            .filter(|d| !new.decorators.contains(d))
+            .collect();
+
+        if added.is_empty() && removed.is_empty() {
+            return None;
+        }
+
+        let mut evidence_parts = Vec::new();
+        if !added.is_empty() {
+            let added: Vec<String> = added.iter().map(|d| format!("@{}", d)).collect();
+            evidence_parts.push(format!("Added decorators: {}", added.join(", ")));
+        }
+        if !removed.is_empty() {
+            let removed: Vec<String> = removed.iter().map(|d| format!("@{}", d)).collect();
+            evidence_parts.push(format!("Removed decorators: {}", removed.join(", ")));
+        }
+
+        let event = DriftEvent::new(
+            DriftSeverity::Medium,
+            &format!("Decorators changed: {}", new.symbol_name),
+            &evidence_parts.join("\n"),
+            0.8,
+        )
+        .with_code_chunk(&new.id)
+        .with_location(&new.file_path, new.start_line);
+
+        Some(with_related_docs(event, related_docs))
+    }
+
+    fn check_doc_change(
+        &self,
+        _old_chunk: Option<&DocChunk>,
+        _new_chunk: Option<&DocChunk>,
+        _related_code: &[&CodeChunk],
+    ) -> Option<DriftEvent> {
+        None
+    }
+}
+
+// ==================== Config Rules ====================
+
+/// Detects documented config keys that were removed or renamed
+///
+/// Config files are not chunked like code, so this rule compares the key
+/// sets of the old and new versions of a file directly.
+pub struct ConfigKeyRule;
+
+impl ConfigKeyRule {
+    /// Check a config file change against the docs that may reference it
+    pub fn check_config_change(
+        &self,
+        old_keys: &[ConfigKey],
+        new_keys: &[ConfigKey],
+        docs: &[&DocChunk],
+    ) -> Vec<DriftEvent> {
+        let is_missing =
+            |key: &ConfigKey, keys: &[ConfigKey]| !keys.iter().any(|k| k.key == key.key);
+        let removed: Vec<&ConfigKey> = old_keys
+            .iter()
+            .filter(|k| is_missing(k, new_keys))
+            .collect();
+        let added: Vec<&ConfigKey> = new_keys
+            .iter()
+            .filter(|k| is_missing(k, old_keys))
+            .collect();
+
+        let mut events = Vec::new();
+        for key in &removed {
+            let related_docs: Vec<&DocChunk> = docs
+                .iter()
+                .filter(|doc| mentions_config_key(&doc.content, key))
+                .copied()
+                .collect();
+            if related_docs.is_empty() {
+                continue;
+            }
+
+            // A lone removal next to a lone addition under the same parent is
+            // most likely a rename
+            let siblings_removed = removed
+                .iter()
+                .filter(|k| k.parent() == key.parent())
+                .count();
+            let siblings_added: Vec<&&ConfigKey> = added
+                .iter()
+                .filter(|k| k.parent() == key.parent())
+                .collect();
+            let renamed_to = match (siblings_removed, siblings_added.as_slice()) {
+                (1, [new_key]) => Some(new_key),
+                _ => None,
+            };
+
+            let mut evidence = format!(
+                "Key `{}` was defined at {}:{} and is no longer present",
+                key.key, key.file_path, key.line
+            );
+            let (description, confidence) = match renamed_to {
+                Some(new_key) => {
+                    evidence.push_str(&format!(
+                        "\n`{}` was added at {}:{}",
+                        new_key.key, new_key.file_path, new_key.line
+                    ));
+                    (
+                        format!(
+                            "Documented config key renamed: {} -> {}",
+                            key.key, new_key.key
+                        ),
+                        0.8,
+                    )
+                }
+                None => (format!("Documented config key removed: {}", key.key), 0.9),
+            };
+
+            let event = DriftEvent::new(DriftSeverity::High, &description, &evidence, confidence)
+                .with_tag("config_key")
+                .with_location(&key.file_path, key.line);
+            events.push(with_related_docs(event, &related_docs));
+        }
+
+        events
+    }
+}
+
+// ==================== Default Value Rules ====================
+
+/// Detects docs that still cite a default value that changed
+///
+/// Python parameters are compared by their `param=value` defaults, and config
+/// keys by the scalar value in the config file. A related doc section is
+/// reported when a line that names the parameter (or says "default") still
+/// contains the old value.
+pub struct DefaultValueChangeRule;
+
+impl DefaultValueChangeRule {
+    /// Check a config file change against the docs that may cite its values
+    pub fn check_config_change(
+        &self,
+        old_keys: &[ConfigKey],
+        new_keys: &[ConfigKey],
+        docs: &[&DocChunk],
+    ) -> Vec<DriftEvent> {
+        let mut events = Vec::new();
+        for new_key in new_keys {
+            let Some(old_key) = old_keys.iter().find(|k| k.key == new_key.key) else {
+                continue;
+            };
+            let (Some(old_value), Some(new_value)) = (&old_key.value, &new_key.value) else {
+                continue;
+            };
+            if old_value == new_value {
+                continue;
+            }
+
+            let citing: Vec<(&DocChunk, &str)> = docs
+                .iter()
+                .filter(|doc| mentions_config_key(&doc.content, new_key))
+                .filter_map(|doc| {
+                    cited_default(&doc.content, new_key.name(), old_value).map(|line| (*doc, line))
+                })
+                .collect();
+            if citing.is_empty() {
+                continue;
+            }
+
+            let event = DriftEvent::new(
+                DriftSeverity::Medium,
+                &format!(
+                    "Documented value changed for config key {}: {} -> {}",
+                    new_key.key, old_value, new_value
+                ),
+                &default_evidence(
+                    &format!(
+                        "`{}` changed from {} to {} at {}:{}",
+                        new_key.key, old_value, new_value, new_key.file_path, new_key.line
+                    ),
+                    &citing,
+                ),
+                0.8,
+            )
+            .with_tag("default_value");
+            let related: Vec<&DocChunk> = citing.iter().map(|(doc, _)| *doc).collect();
+            events.push(with_related_docs(event, &related));
+        }
+        events
+    }
+}
+
+impl DriftRule for DefaultValueChangeRule {
+    fn name(&self) -> &str {
+        "default_value"
+    }
+
+    fn check_code_change(
+        &self,
+        old_chunk: Option<&CodeChunk>,
+        new_chunk: Option<&CodeChunk>,
+        related_docs: &[&DocChunk],
+    ) -> Option<DriftEvent> {
+        let old = old_chunk?;
+        let new = new_chunk?;
+        if !new.is_public || new.language != Language::Python {
+            return None;
+        }
+
+        let old_defaults = python_defaults(old.signature.as_deref()?);
+        let new_defaults = python_defaults(new.signature.as_deref()?);
+
+        let mut changes = Vec::new();
+        let mut citing: Vec<(&DocChunk, &str)> = Vec::new();
+        for (param, new_value) in &new_defaults {
+            let Some((_, old_value)) = old_defaults.iter().find(|(p, _)| p == param) else {
+                continue;
+            };
+            if old_value == new_value {
+                continue;
+            }
+            let cited: Vec<_> = related_docs
+                .iter()
+                .filter_map(|doc| cited_default(&doc.content, param, old_value).map(|l| (*doc, l)))
+                .collect();
+            if cited.is_empty() {
+                continue;
+            }
+            changes.push(format!("{}: {} -> {}", param, old_value, new_value));
+            for (doc, line) in cited {
+                if !citing.iter().any(|(d, _)| d.id == doc.id) {
+                    citing.push((doc, line));
+                }
+            }
+        }
+        if changes.is_empty() {
+            return None;
+        }
+
+        let event = DriftEvent::new(
+            DriftSeverity::Medium,
+            &format!(
+                "Default value changed for {}: {}",
+                new.symbol_name,
+                changes.join(", ")
+            ),
+            &default_evidence(
+                &format!("Changed defaults in {}: {}", new.id, changes.join(", ")),
+                &citing,
+            ),
+            0.8,
+        )
+        .with_code_chunk(&new.id);
+        let related: Vec<&DocChunk> = citing.iter().map(|(doc, _)| *doc).collect();
+        Some(with_related_docs(event, &related))
+    }
+
+    fn check_doc_change(
+        &self,
+        _old_chunk: Option<&DocChunk>,
+        _new_chunk: Option<&DocChunk>,
+        _related_code: &[&CodeChunk],
+    ) -> Option<DriftEvent> {
+        None
+    }
+}
+
+/// Evidence listing the doc lines that still cite an old default
+fn default_evidence(change: &str, citing: &[(&DocChunk, &str)]) -> String {
+    let mut evidence = change.to_string();
+    for (doc, line) in citing {
+        evidence.push_str(&format!(
+            "\n{} ({}) still says: {}",
+            doc.file_path,
+            doc.heading,
+            line.trim()
+        ));
+    }
+    evidence
+}
+
+/// Python parameters with a default, as `(name, value)` pairs
+///
+/// Values keep their source text, except that string quotes are removed so
+/// `"info"` matches docs that write `info`.
+fn python_defaults(signature: &str) -> Vec<(String, String)> {
+    let Some(params) = parameter_list(signature) else {
+        return Vec::new();
+    };
+    split_top_level(params)
+        .into_iter()
+        .filter_map(|param| {
+            let (name, value) = param.split_once('=')?;
+            let name = name.split(':').next()?.trim().trim_start_matches('*');
+            Some((name.to_string(), unquote(value.trim()).to_string()))
+        })
+        .filter(|(name, value)| !name.is_empty() && !value.is_empty())
+        .collect()
+}
+
+/// A literal without its surrounding string quotes
+fn unquote(value: &str) -> &str {
+    ['"', '\'']
+        .iter()
+        .find_map(|q| value.strip_prefix(*q)?.strip_suffix(*q))
+        .unwrap_or(value)
+}
+
+/// The first line of `text` that cites `value` as the default of `name`
+///
+/// The line must name the parameter or mention a default, and contain the
+/// value as a whole token, so `5` does not match `50` or `5.5`.
+fn cited_default<'a>(text: &'a str, name: &str, value: &str) -> Option<&'a str> {
+    text.lines().find(|line| {
+        let about = line.contains(name) || line.to_lowercase().contains("default");
+        about && contains_token(line, value)
+    })
+}
+
+/// Whether `value` appears in `line` with no identifier or digit characters
+/// directly around it
+fn contains_token(line: &str, value: &str) -> bool {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    line.match_indices(value).any(|(start, _)| {
+        let before = line[..start].chars().next_back();
+        let mut after = line[start + value.len()..].chars();
+        let next = after.next();
+        let decimal = next == Some('.') && after.next().is_some_and(|c| c.is_ascii_digit());
+        !before.is_some_and(|c| is_word(c) || c == '.') && !next.is_some_and(is_word) && !decimal
+    })
+}
+
+// ==================== Doc Link Rules ====================
+
+/// Detects Markdown links to doc files or headings that no longer exist
+///
+/// Only links to other docs (`.md`, `.mdx`, `.markdown`, `.rst`) and heading
+/// anchors are checked. Links to source files, images, or directories are
+/// not tracked as doc chunks, so they are skipped.
+pub struct DocLinkRule;
+
+impl DocLinkRule {
+    /// Check the links in `docs` against the known doc sections
+    ///
+    /// `known_docs` should hold every current doc section in the repository,
+    /// including those in `docs`.
+    pub fn check_links(&self, docs: &[&DocChunk], known_docs: &[&DocChunk]) -> Vec<DriftEvent> {
+        let anchors = doc_anchors(known_docs);
+
+        let mut events = Vec::new();
+        for doc in docs {
+            for link in &doc.links {
+                let Some(file) = link.target_file(&doc.file_path) else {
+                    continue;
+                };
+                if !is_doc_path(&file) {
+                    continue;
+                }
+
+                let problem = match (anchors.get(file.as_str()), link.anchor()) {
+                    (None, _) => format!("`{}` is not a known doc file", file),
+                    (Some(file_anchors), Some(fragment))
+                        if !file_anchors.contains(&fragment.to_lowercase()) =>
+                    {
+                        format!("no heading in `{}` has the anchor `#{}`", file, fragment)
+                    }
+                    _ => continue,
+                };
+
+                let event = DriftEvent::new(
+                    DriftSeverity::Medium,
+                    &format!("Broken doc link: [{}]({})", link.text, link.target),
+                    &format!(
+                        "Link at {}:{} points to a missing target: {}",
+                        doc.file_path, link.line, problem
+                    ),
+                    0.9,
+                )
+                .with_tag("broken_link");
+                events.push(
+                    with_related_docs(event, &[doc]).with_location(&doc.file_path, link.line),
+                );
+            }
+        }
+
+        events
+    }
+}
+
+/// Heading anchors of each known doc file, keyed by file path
+///
+/// Section anchors come from the chunk IDs, which the extractor numbers the
+/// way GitHub does (`usage`, `usage-1`).
+fn doc_anchors<'a>(docs: &[&'a DocChunk]) -> HashMap<&'a str, HashSet<String>> {
+    let mut by_file: HashMap<&str, HashSet<String>> = HashMap::new();
+    for doc in docs {
+        let anchors = by_file.entry(doc.file_path.as_str()).or_default();
+        // A file without headings is stored as one section named after the
+        // file
+        if doc.heading_path == [doc.file_path.clone()] {
+            continue;
+        }
+        anchors.insert(doc.anchor().to_string());
+        // Parent headings of short sections that were not extracted
+        anchors.extend(doc.heading_path.iter().map(|h| heading_anchor(h)));
+    }
+    by_file
+}
+
+/// Whether a path has a documentation extension that the doc extractor reads
+fn is_doc_path(path: &str) -> bool {
+    std::path::Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| {
+            ["md", "mdx", "markdown", "rst"]
+                .iter()
+                .any(|ext| e.eq_ignore_ascii_case(ext))
+        })
+}
+
+// ==================== Conflicting Doc Rules ====================
+
+/// Detects doc sections that show different signatures for the same symbol
+///
+/// When the README and an API page both show `fn foo`, updating only one of
+/// them leaves the docs contradicting each other, whichever one the code
+/// currently matches.
+pub struct ConflictingDocsRule;
+
+impl ConflictingDocsRule {
+    /// Compare the signatures that `related_docs` show for `code`
+    ///
+    /// Each section contributes the first signature it shows with the
+    /// symbol's name. Two signatures disagree when their parameters differ,
+    /// or when both show a return type and those differ. Callers should skip
+    /// names shared by several symbols (like `new`), since each section may
+    /// then describe a different one.
+    pub fn check_symbol(&self, code: &CodeChunk, related_docs: &[&DocChunk]) -> Option<DriftEvent> {
+        let name = short_name(&code.symbol_name);
+        let shown: Vec<(&DocChunk, DocumentedSignature)> = related_docs
+            .iter()
+            .filter_map(|doc| {
+                doc_signatures(doc)
+                    .into_iter()
+                    .find(|sig| sig.name == name)
+                    .map(|sig| (*doc, sig))
+            })
+            .collect();
+
+        let agree = |a: &DocumentedSignature, b: &DocumentedSignature| {
+            a.params == b.params
+                && match (&a.return_type, &b.return_type) {
+                    (Some(a), Some(b)) => a == b,
+                    _ => true,
+                }
+        };
+        let (_, first) = shown.first()?;
+        if shown.iter().all(|(_, sig)| agree(first, sig)) {
+            return None;
+        }
+
+        let locations: Vec<String> = shown
+            .iter()
+            .map(|(doc, sig)| {
+                format!(
+                    "  {}:{} ({}): {}",
+                    doc.file_path, doc.start_line, doc.heading, sig.text
+                )
+            })
+            .collect();
+        let mut evidence = format!(
+            "Sections show different signatures for `{}`:\n{}",
+            name,
+            locations.join("\n")
+        );
+        if let Some(ref signature) = code.signature {
+            evidence.push_str(&format!(
+                "\nCode has:\n  {}",
+                collapse_whitespace(signature)
+            ));
+        }
+
+        let docs: Vec<&DocChunk> = shown.iter().map(|(doc, _)| *doc).collect();
+        let event = DriftEvent::new(
+            DriftSeverity::Medium,
+            &format!("Conflicting documented signatures: {}", name),
+            &evidence,
+            0.85,
+        )
+        .with_tag("conflicting_docs")
+        .with_code_chunk(&code.id);
+
+        Some(with_related_docs(event, &docs))
+    }
+}
+
+// ==================== Helper Functions ====================
+
+/// A function signature shown in documentation
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct DocumentedSignature {
+    /// Function name
+    pub name: String,
+    /// Normalized parameter list (without parentheses)
+    pub params: String,
+    /// Normalized return type, if the docs show one
+    pub return_type: Option<String>,
+    /// The signature as written (whitespace collapsed)
+    pub text: String,
+}
+
+impl DocumentedSignature {
+    /// Whether this documented signature agrees with an actual one
+    ///
+    /// Parameters must match; the return type is only compared when the docs
+    /// show one, since examples often leave it out.
+    fn matches(&self, actual: &DocumentedSignature) -> bool {
+        self.name == actual.name
+            && self.params == actual.params
+            && match (&self.return_type, &actual.return_type) {
+                (Some(documented), Some(actual)) => documented == actual,
+                (Some(_), None) => false,
+                (None, _) => true,
+            }
+    }
+}
+
+/// Find single-line `fn`/`def` signatures in documentation text
+///
+/// Only text starting at a `fn` or `def` keyword is considered, so calls like
+/// `connect(5)` in examples are not mistaken for signatures.
+pub(crate) fn documented_signatures(text: &str) -> Vec<DocumentedSignature> {
+    let mut signatures = Vec::new();
+
+    for line in text.lines() {
+        for keyword in ["fn ", "def "] {
+            let mut search_from = 0;
+            while let Some(pos) = line[search_from..].find(keyword) {
+                let start = search_from + pos;
+                search_from = start + keyword.len();
+
+                let at_word_start = line[..start]
+                    .chars()
+                    .next_back()
+                    .is_none_or(|c| !c.is_alphanumeric() && c != '_');
+                if !at_word_start {
+                    continue;
+                }
+
+                // Inline code ends at the closing backtick
+                let snippet = line[start..].split('`').next().unwrap_or("");
+                if let Some(signature) = parse_signature(snippet) {
+                    signatures.push(signature);
+                }
+            }
+        }
+    }
+
+    signatures
+}
+
+/// Find the signatures a doc section shows as code, in document order
+///
+/// Only fenced blocks tagged as Rust or Python (or untagged) and inline code
+/// spans are searched, so prose and examples in other languages, such as
+/// shell sessions, are not mistaken for signatures. reStructuredText
+/// sections are searched as plain text.
+pub(crate) fn doc_signatures(doc: &DocChunk) -> Vec<DocumentedSignature> {
+    if doc.is_rst() {
+        return documented_signatures(&doc.content);
+    }
+
+    let mut found: Vec<(usize, DocumentedSignature)> = Vec::new();
+    for block in doc.fenced_blocks() {
+        let tag = block.language.as_deref();
+        if block_is_language(tag, Language::Rust) || block_is_language(tag, Language::Python) {
+            found.extend(
+                documented_signatures(&block.content)
+                    .into_iter()
+                    .map(|signature| (block.line, signature)),
+            );
+        }
+    }
+    for span in doc.inline_code_spans() {
+        found.extend(
+            documented_signatures(&span.code)
+                .into_iter()
+                .map(|signature| (span.line, signature)),
+        );
+    }
+    found.sort_by_key(|(line, _)| *line);
+    found.into_iter().map(|(_, signature)| signature).collect()
+}
+
+/// Names of the functions whose signatures a doc section shows
+pub(crate) fn documented_signature_names(doc: &DocChunk) -> Vec<String> {
+    doc_signatures(doc)
+        .into_iter()
+        .map(|signature| signature.name)
+        .collect()
+}
+
+/// Parse a `fn`/`def` signature into its name, parameters, and return type
+fn parse_signature(signature: &str) -> Option<DocumentedSignature> {
+    let signature = collapse_whitespace(signature);
+    let start = signature
+        .find("fn ")
+        .filter(|&i| i == 0 || signature[..i].ends_with(' '))
+        .map(|i| i + 3)
+        .or_else(|| signature.find("def ").map(|i| i + 4))?;
+    let rest = &signature[start..];
+
+    let name_end = rest
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(rest.len());
+    let name = &rest[..name_end];
+    if name.is_empty() {
+        return None;
+    }
+
+    // Skip generics, then take the balanced parameter list
+    let open = rest[name_end..].find('(')? + name_end;
+    let mut depth = 0;
+    let mut close = None;
+    for (i, c) in rest[open..].char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    close = Some(open + i);
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+    let close = close?;
+    let params = normalize_params(&rest[open + 1..close]);
+
+    let after = rest[close + 1..].trim();
+    let return_type = after.strip_prefix("->").map(|ret| {
+        let ret = ret.split(" where ").next().unwrap_or(ret);
+        let ret = ret.split('{').next().unwrap_or(ret);
+        normalize_params(ret.trim().trim_end_matches([':', ';']))
+    });
+
+    // Drop a trailing body or terminator from the displayed text
+    let text_end = rest[close..].find('{').map_or(rest.len(), |i| close + i);
+    let text = format!("{}{}", &signature[..start], &rest[..text_end]);
+
+    Some(DocumentedSignature {
+        name: name.to_string(),
+        params,
+        return_type: return_type.filter(|r| !r.is_empty()),
+        text: text.trim().trim_end_matches([':', ';']).trim().to_string(),
+    })
+}
+
+/// Normalize a parameter list so formatting differences don't count
+///
+/// Whitespace is kept only between two word characters (`mut x`, `impl
+/// Trait`), so `name: &str` and `name:&str` compare equal.
+fn normalize_params(params: &str) -> String {
+    params
+        .split(',')
+        .map(join_tokens)
+        .filter(|p| !p.is_empty())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Normalize a code signature so formatting-only edits compare equal
+///
+/// Line breaks and runs of whitespace collapse, whitespace is kept only
+/// between two word characters (so `& str` becomes `&str`), and trailing
+/// commas before a closing bracket are dropped. Rust signatures also lose
+/// the trailing comma of a `where` clause; Python ones a trailing `:`.
+pub(crate) fn normalize_signature(signature: &str, language: Language) -> String {
+    let joined = join_tokens(signature);
+
+    let mut normalized = String::with_capacity(joined.len());
+    let mut chars = joined.chars().peekable();
+    while let Some(c) = chars.next() {
+        let closes_next = chars
+            .peek()
+            .is_some_and(|next| matches!(next, ')' | ']' | '>'));
+        if c == ',' && closes_next {
+            continue;
+        }
+        normalized.push(c);
+    }
+
+    match language {
+        Language::Rust => normalized.trim_end_matches(',').to_string(),
+        Language::Python => normalized.trim_end_matches(':').to_string(),
+    }
+}
+
+/// Join whitespace-separated tokens, keeping a space only between two word
+/// characters
+fn join_tokens(text: &str) -> String {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+
+    let mut joined = String::new();
+    for token in text.split_whitespace() {
+        if joined.ends_with(is_word) && token.starts_with(is_word) {
+            joined.push(' ');
+        }
+        joined.push_str(token);
+    }
+    joined
+}
+
+/// Collapse runs of whitespace (including newlines) into single spaces
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Symbol name without its `Type::` prefix
+pub(crate) fn short_name(symbol_name: &str) -> &str {
+    symbol_name.rsplit("::").next().unwrap_or(symbol_name)
+}
+
+/// Short names shared by more than one of the given symbols
+pub(crate) fn ambiguous_names<'a>(
+    code: impl IntoIterator<Item = &'a CodeChunk>,
+) -> HashSet<&'a str> {
+    let mut seen = HashSet::new();
+    code.into_iter()
+        .map(|chunk| short_name(&chunk.symbol_name))
+        .filter(|name| !seen.insert(*name))
+        .collect()
+}
+
+/// Whether a doc section links to a symbol or shows its signature
+pub(crate) fn doc_relates_to(doc: &DocChunk, code: &CodeChunk) -> bool {
+    doc.links_to(code)
+        || documented_signature_names(doc)
+            .iter()
+            .any(|name| name == short_name(&code.symbol_name))
+}
+
+/// Extract parameter names from a function signature
+fn extract_parameters(signature: &str) -> Vec<String> {
+    let Some(params_str) = parameter_list(signature) else {
+        return Vec::new();
+    };
+
+    split_top_level(params_str)
+        .into_iter()
+        .map(|p| {
+            // Extract just the parameter name (before : or =)
+            p.split(':')
+                .next()
+                .unwrap_or(p)
+                .split('=')
+                .next()
+                .unwrap_or(p)
+                .trim()
+                .to_string()
+        })
+        .collect()
+}
+
+/// The text between a signature's parameter parentheses
+fn parameter_list(signature: &str) -> Option<&str> {
+    let open = signature.find('(')?;
+    let close = matching_paren(signature, open)?;
+    Some(&signature[open + 1..close])
+}
+
+/// Byte index of the `)` closing the `(` at `open`
+///
+/// Brackets inside string and character literals are skipped.
+fn matching_paren(text: &str, open: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in text[open..].char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' => quote = Some('"'),
+            '\'' if opens_literal(text, open + i) => quote = Some('\''),
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Whether the `'` at byte `index` opens a character or string literal
+///
+/// Quotes right after `&`, `<` or `+` start Rust lifetimes (`&'a str`);
+/// others open a literal if it is closed on the same line.
+fn opens_literal(text: &str, index: usize) -> bool {
+    if matches!(text[..index].chars().next_back(), Some('&' | '<' | '+')) {
+        return false;
+    }
+    let rest = &text[index + 1..];
+    rest.split('\n')
+        .next()
+        .is_some_and(|line| line.contains('\''))
+}
+
+/// Split a parameter or argument list at commas outside brackets and
+/// literals, dropping empty entries (e.g. after a trailing comma)
+fn split_top_level(list: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut start = 0;
+    let mut quote = None;
+    let mut escaped = false;
+    let mut prev = ' ';
+    for (i, c) in list.char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            prev = c;
+            continue;
+        }
+        match c {
+            '"' => quote = Some('"'),
+            '\'' if opens_literal(list, i) => quote = Some('\''),
+            '(' | '[' | '{' | '<' => depth += 1,
+            // `->` in `impl Fn(u8) -> u8` is not a closing bracket
+            '>' if prev == '-' => {}
+            ')' | ']' | '}' | '>' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(list[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+        prev = c;
+    }
+    parts.push(list[start..].trim());
+    parts.retain(|p| !p.is_empty());
+    parts
+}
+
+/// How many arguments a function accepts
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Arity {
+    /// Arguments without a default
+    min: usize,
+    /// Most arguments accepted, or `None` for variadic functions
+    max: Option<usize>,
+    /// Whether the function takes `self` (or `cls`)
+    receiver: bool,
+}
+
+impl Arity {
+    /// Arity of a signature, not counting its receiver
+    fn of(signature: &str, language: Language) -> Option<Self> {
+        let mut arity = Arity {
+            min: 0,
+            max: Some(0),
+            receiver: false,
+        };
+        for (index, param) in split_top_level(parameter_list(signature)?)
+            .into_iter()
+            .enumerate()
+        {
+            let name = param.split([':', '=']).next().unwrap_or(param).trim();
+            let is_receiver = match language {
+                Language::Rust => {
+                    name.trim_start_matches(['&', ' '])
+                        .trim_start_matches("mut ")
+                        == "self"
+                        || name.starts_with("&'") && name.ends_with("self")
+                }
+                Language::Python => index == 0 && (name == "self" || name == "cls"),
+            };
+            if is_receiver {
+                arity.receiver = true;
+                continue;
+            }
+            match name {
+                // Positional-only and keyword-only markers
+                "/" | "*" => {}
+                _ if name.starts_with('*') => arity.max = None,
+                _ => {
+                    arity.max = arity.max.map(|max| max + 1);
+                    if !param.contains('=') {
+                        arity.min += 1;
+                    }
+                }
+            }
+        }
+        Some(arity)
+    }
+
+    /// Arity expected of a call written as `form`, or `None` when such a
+    /// call cannot reach a function owned by `owner`
+    fn for_call(self, form: &CallForm, owner: Option<&str>, language: Language) -> Option<Self> {
+        match (form, owner) {
+            (CallForm::Bare, None) => Some(self),
+            (CallForm::Method, Some(_)) if self.receiver => Some(self),
+            (CallForm::Path(_), None) if language == Language::Rust => Some(self),
+            (CallForm::Path(qualifier), Some(owner))
+                if language == Language::Rust && qualifier == owner =>
+            {
+                if self.receiver {
+                    // `Type::method(value, ...)` passes the receiver itself
+                    Some(Arity {
+                        min: self.min + 1,
+                        max: self.max.map(|max| max + 1),
+                        receiver: false,
+                    })
+                } else {
+                    Some(self)
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn accepts(&self, args: usize) -> bool {
+        args >= self.min && self.max.is_none_or(|max| args <= max)
+    }
+}
+
+impl std::fmt::Display for Arity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.max {
+            Some(max) if max == self.min => write!(f, "{} argument(s)", max),
+            Some(max) => write!(f, "{} to {} arguments", self.min, max),
+            None => write!(f, "at least {} argument(s)", self.min),
+        }
+    }
+}
+
+/// How an example reaches the function it calls
+#[derive(Debug, Clone, PartialEq)]
+enum CallForm {
+    /// `name(...)`
+    Bare,
+    /// `qualifier::name(...)`
+    Path(String),
+    /// `value.name(...)`
+    Method,
+}
+
+/// A call found in an example code block
+#[derive(Debug, Clone, PartialEq)]
+struct ExampleCall {
+    form: CallForm,
+    /// Number of arguments passed
+    args: usize,
+    /// The call as written, whitespace collapsed
+    text: String,
+    /// Line of the call within the block (0-based)
+    line_offset: usize,
+}
+
+/// Calls to `name` in example code, skipping its definition and calls that
+/// spread arguments (`*args`, `..`) whose count is unknown
+fn example_calls(code: &str, name: &str) -> Vec<ExampleCall> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let mut calls = Vec::new();
+
+    for (start, _) in code.match_indices(name) {
+        let open = start + name.len();
+        if !code[open..].starts_with('(') {
+            continue;
+        }
+        let before = &code[..start];
+        if before.ends_with(is_word) {
+            continue;
+        }
+        let before_trimmed = before.trim_end();
+        if before_trimmed.ends_with("fn") || before_trimmed.ends_with("def") {
+            continue;
+        }
+
+        let (form, prefix) = if let Some(path) = before.strip_suffix("::") {
+            let qualifier_start = path.rfind(|c: char| !is_word(c)).map_or(0, |i| i + 1);
+            let qualifier = &path[qualifier_start..];
+            (
+                CallForm::Path(qualifier.to_string()),
+                format!("{}::", qualifier),
+            )
+        } else if before.ends_with('.') {
+            (CallForm::Method, ".".to_string())
+        } else {
+            (CallForm::Bare, String::new())
+        };
+
+        let Some(close) = matching_paren(code, open) else {
+            continue;
+        };
+        let args = split_top_level(&code[open + 1..close]);
+        if args
+            .iter()
+            .any(|arg| arg.starts_with('*') || arg.starts_with(".."))
+        {
+            continue;
+        }
+
+        calls.push(ExampleCall {
+            form,
+            args: args.len(),
+            text: format!("{}{}", prefix, collapse_whitespace(&code[start..=close])),
+            line_offset: before.matches('\n').count(),
+        });
+    }
+
+    calls
+}
+
+/// Split a symbol name into its owning type (if any) and short name
+///
+/// Rust methods are named `Type::method`, Python ones `Class.method`.
+fn split_symbol_name(symbol_name: &str, language: Language) -> (Option<&str>, &str) {
+    let separator = match language {
+        Language::Rust => "::",
+        Language::Python => ".",
+    };
+    match symbol_name.rsplit_once(separator) {
+        Some((owner, name)) => (Some(owner), name),
+        None => (None, symbol_name),
+    }
+}
+
+/// Whether a fenced block's language tag fits code in `language`
+///
+/// Untagged blocks are assumed to be in the project's language. Tags such
+/// as `rust,no_run` are matched on their first word.
+fn block_is_language(tag: Option<&str>, language: Language) -> bool {
+    let Some(tag) = tag else {
+        return true;
+    };
+    let tag = tag.split([',', ' ']).next().unwrap_or(tag).to_lowercase();
+    match language {
+        Language::Rust => matches!(tag.as_str(), "rust" | "rs"),
+        Language::Python => matches!(tag.as_str(), "python" | "py" | "python3" | "pycon"),
+    }
+}
+
+/// Extract return type from a function signature
+fn extract_return_type(signature: &str) -> Option<String> {
+    // Look for -> in the signature
+    if let Some(arrow_pos) = signature.find("->") {
+        let return_part = &signature[arrow_pos + 2..];
+        // Clean up the return type
+        let return_type = return_part.trim().trim_end_matches('{').trim().to_string();
+        if !return_type.is_empty() {
+            return Some(return_type);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extract::code::{Language, SymbolType};
+
+    fn create_public_fn_chunk(doc_comment: Option<&str>) -> CodeChunk {
+        let mut chunk = CodeChunk::new(
+            "src/lib.rs",
+            "new_api",
+            SymbolType::Function,
+            "pub fn new_api() {}",
+            Language::Rust,
+            1,
+            1,
+        );
+        chunk.is_public = true;
+        chunk.doc_comment = doc_comment.map(String::from);
+        chunk
+    }
+
+    #[test]
+    fn test_pair_renames_matches_similar_bodies() {
+        let chunk = |name: &str, body: &str| {
+            let mut chunk = CodeChunk::new(
+                "src/lib.rs",
+                name,
+                SymbolType::Function,
+                &format!(
+                    "/// Sums the values\npub fn {}(values: &[i64]) -> i64 {{\n    {}\n}}",
+                    name, body
+                ),
+                Language::Rust,
+                1,
+                3,
+            );
+            chunk.is_public = true;
+            chunk
+        };
+        let total = chunk("total", "values.iter().sum()");
+        let sum_all = chunk("sum_all", "values.iter().sum()");
+        let parse = chunk("parse", "values.len() as i64 * 2 + 1");
+        let mut elsewhere = sum_all.clone();
+        elsewhere.file_path = "src/other.rs".to_string();
+
+        let pairs = pair_renames(&[&total], &[&parse, &sum_all]);
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].1.symbol_name, "sum_all");
+
+        assert!(pair_renames(&[&total], &[&parse]).is_empty());
+        assert!(pair_renames(&[&total], &[&elsewhere]).is_empty());
+
+        let doc = DocChunk::new(
+            "README.md",
+            vec!["Totals".to_string()],
+            "Totals",
+            crate::extract::HeadingLevel::H2,
+            "Call `total(&values)`.",
+            1,
+            2,
+        );
+        let event = RenamedSymbolRule
+            .check_code_change(Some(&total), Some(&sum_all), &[&doc])
+            .unwrap();
+        assert_eq!(event.severity, DriftSeverity::High);
+        assert_eq!(
+            event.description,
+            "Documented symbol renamed: total -> sum_all"
+        );
+        assert_eq!(event.related_code_chunks, vec![sum_all.id.clone()]);
+        assert_eq!(event.related_doc_chunks, vec![doc.id.clone()]);
+        assert!(RenamedSymbolRule
+            .check_code_change(Some(&total), Some(&sum_all), &[])
+            .is_none());
+    }
+
+    #[test]
+    fn test_extract_parameters() {
+        let sig = "fn hello(name: &str, age: u32) -> String";
+        let params = extract_parameters(sig);
+        assert_eq!(params, vec!["name", "age"]);
+
+        let sig2 = "def greet(name, greeting='Hello')";
+        let params2 = extract_parameters(sig2);
+        assert_eq!(params2, vec!["name", "greeting"]);
+    }
+
+    #[test]
+    fn test_normalize_signature() {
+        assert_eq!(
+            normalize_signature(
+                "pub fn connect(\n    host: & str,\n    port: u16,\n) -> Result< Conn >",
+                Language::Rust
+            ),
+            normalize_signature(
+                "pub fn connect(host: &str, port: u16) -> Result<Conn>",
+                Language::Rust
+            )
+        );
+        assert_eq!(
+            normalize_signature("pub fn f<T>(x: T) where T: Clone,", Language::Rust),
+            "pub fn f<T>(x:T)where T:Clone"
+        );
+        assert_eq!(
+            normalize_signature("def greet(name, greeting = 'Hi',):", Language::Python),
+            "def greet(name,greeting='Hi')"
+        );
+        assert_ne!(
+            normalize_signature("fn f(a: &str)", Language::Rust),
+            normalize_signature("fn f(a: &mut str)", Language::Rust)
+        );
     }
 
     #[test]
@@ -611,4 +2574,671 @@ mod tests {
         let sig2 = "fn void_func()";
         assert_eq!(extract_return_type(sig2), None);
     }
+
+    #[test]
+    fn test_undocumented_new_public_api() {
+        let chunk = create_public_fn_chunk(None);
+        let event = UndocumentedPublicApiRule
+            .check_code_change(None, Some(&chunk), &[])
+            .unwrap();
+
+        assert_eq!(event.severity, DriftSeverity::Medium);
+        assert!(event.description.contains("new_api"));
+        assert_eq!(event.related_code_chunks, vec![chunk.id.clone()]);
+        assert_eq!(event.location().as_deref(), Some("src/lib.rs:1"));
+
+        // Existing symbols are not "new"
+        assert!(UndocumentedPublicApiRule
+            .check_code_change(Some(&chunk), Some(&chunk), &[])
+            .is_none());
+    }
+
+    #[test]
+    fn test_documented_new_public_api() {
+        let chunk = create_public_fn_chunk(Some("Does something new"));
+        assert!(UndocumentedPublicApiRule
+            .check_code_change(None, Some(&chunk), &[])
+            .is_none());
+    }
+
+    #[test]
+    fn test_signature_change_points_at_doc() {
+        use crate::extract::doc::HeadingLevel;
+
+        let mut old = create_public_fn_chunk(None);
+        old.signature = Some("pub fn new_api()".to_string());
+        let mut new = old.clone();
+        new.signature = Some("pub fn new_api(flag: bool)".to_string());
+        let doc = DocChunk::new(
+            "docs/api.md",
+            vec!["API".to_string()],
+            "API",
+            HeadingLevel::H2,
+            "Call new_api() to begin.",
+            12,
+            14,
+        );
+
+        let event = SignatureChangeRule
+            .check_code_change(Some(&old), Some(&new), &[&doc])
+            .unwrap();
+        assert_eq!(event.related_doc_chunks, vec![doc.id.clone()]);
+        assert_eq!(event.primary_file.as_deref(), Some("docs/api.md"));
+        assert_eq!(event.primary_line, Some(12));
+    }
+
+    #[test]
+    fn test_reformatted_signature_is_not_drift() {
+        let mut old = create_public_fn_chunk(None);
+        old.signature = Some("pub fn new_api(name: &str, retries: u32) -> Vec<u8>".to_string());
+        let mut reformatted = old.clone();
+        reformatted.signature = Some(
+            "pub fn new_api(\n    name: & str,\n    retries: u32,\n) -> Vec< u8 >".to_string(),
+        );
+        let doc = create_usage_doc();
+
+        let rules = HardDriftRules::new();
+        assert!(rules
+            .check_code_change(Some(&old), Some(&reformatted), &[&doc])
+            .is_empty());
+
+        let mut retyped = old.clone();
+        retyped.signature = Some("pub fn new_api(name: &str, retries: u64) -> Vec<u8>".to_string());
+        let events = rules.check_code_change(Some(&old), Some(&retyped), &[&doc]);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].tags, vec!["signature_change"]);
+        assert!(events[0]
+            .evidence
+            .contains("to:\n  pub fn new_api(name: &str, retries: u64) -> Vec<u8>"));
+    }
+
+    #[test]
+    fn test_example_call_with_wrong_arity() {
+        use crate::extract::doc::HeadingLevel;
+
+        let mut old = create_public_fn_chunk(None);
+        old.signature = Some("pub fn new_api(a: i32)".to_string());
+        let mut new = old.clone();
+        new.signature = Some("pub fn new_api(a: i32, b: &str)".to_string());
+        let doc = DocChunk::new(
+            "README.md",
+            vec!["Usage".to_string()],
+            "Usage",
+            HeadingLevel::H2,
+            "Call it like this:\n\n```rust\nlet x = 1;\nnew_api(x);\n```\n\n\
+             Or with a label:\n\n```rust\nnew_api(1, \"a, b\");\n```\n",
+            10,
+            24,
+        );
+
+        let event = ExampleArityRule
+            .check_code_change(Some(&old), Some(&new), &[&doc])
+            .unwrap();
+        assert_eq!(event.severity, DriftSeverity::High);
+        assert_eq!(event.description, "Example calls out of date: new_api");
+        assert!(event.evidence.contains("takes 2 argument(s)"));
+        assert!(event
+            .evidence
+            .contains("README.md:14 (Usage): new_api(x) passes 1"));
+        assert!(!event.evidence.contains("new_api(1,"));
+        assert_eq!(event.related_doc_chunks, vec![doc.id.clone()]);
+
+        // Every example already passes the new argument
+        let updated = DocChunk::new(
+            "README.md",
+            vec!["Usage".to_string()],
+            "Usage",
+            HeadingLevel::H2,
+            "```rust\nnew_api(1, \"x\");\n```\n",
+            10,
+            14,
+        );
+        assert!(ExampleArityRule
+            .check_code_change(Some(&old), Some(&new), &[&updated])
+            .is_none());
+    }
+
+    #[test]
+    fn test_example_calls_match_methods_by_form() {
+        assert_eq!(
+            Arity::of("pub fn push(&mut self, item: T)", Language::Rust),
+            Some(Arity {
+                min: 1,
+                max: Some(1),
+                receiver: true
+            })
+        );
+        assert_eq!(
+            Arity::of(
+                "def greet(self, name, greeting='Hi', *rest)",
+                Language::Python
+            ),
+            Some(Arity {
+                min: 1,
+                max: None,
+                receiver: true
+            })
+        );
+
+        let calls = example_calls(
+            "fn push(x: u8) {}\nlist.push(1, 2);\nList::push(&mut list, 3);\npush(4);",
+            "push",
+        );
+        let forms: Vec<_> = calls
+            .iter()
+            .map(|c| (&c.form, c.args, c.line_offset))
+            .collect();
+        assert_eq!(
+            forms,
+            vec![
+                (&CallForm::Method, 2, 1),
+                (&CallForm::Path("List".to_string()), 2, 2),
+                (&CallForm::Bare, 1, 3),
+            ]
+        );
+
+        let arity = Arity::of("pub fn push(&mut self, item: T)", Language::Rust).unwrap();
+        let expected: Vec<_> = calls
+            .iter()
+            .map(|c| {
+                arity
+                    .for_call(&c.form, Some("List"), Language::Rust)
+                    .map(|a| a.accepts(c.args))
+            })
+            .collect();
+        assert_eq!(expected, vec![Some(false), Some(true), None]);
+    }
+
+    fn create_usage_doc() -> DocChunk {
+        DocChunk::new(
+            "README.md",
+            vec!["Usage".to_string()],
+            "Usage",
+            crate::extract::doc::HeadingLevel::H2,
+            "Call new_api() to begin.",
+            3,
+            5,
+        )
+    }
+
+    #[test]
+    fn test_behavior_change_evidence_includes_diff() {
+        let mut old = create_public_fn_chunk(None);
+        old.content = "pub fn new_api() {\n    let a = 1;\n    let b = 2;\n    let c = 3;\n    let d = 4;\n    start(a);\n}\n".to_string();
+        let mut new = old.clone();
+        new.content = old.content.replace("start(a);", "start(a).unwrap();");
+        let doc = create_usage_doc();
+
+        let rule = BehaviorChangeRule { context_lines: 1 };
+        let event = rule
+            .check_code_change(Some(&old), Some(&new), &[&doc])
+            .unwrap();
+        let lines: Vec<&str> = event.evidence.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "Implementation of 'new_api' changed. Behavior may have changed.",
+                "@@ -5,3 +5,3 @@",
+                "     let d = 4;",
+                "-    start(a);",
+                "+    start(a).unwrap();",
+                " }",
+            ]
+        );
+
+        // Long diffs are cut
+        let old: String = (0..100).map(|i| format!("{}\n", i)).collect();
+        let diff = evidence_diff(&old, "", 3);
+        assert_eq!(diff.lines().count(), MAX_EVIDENCE_DIFF_LINES + 1);
+        assert!(diff.ends_with("... (61 more diff lines)"));
+    }
+
+    #[test]
+    fn test_visibility_made_private() {
+        let old = create_public_fn_chunk(Some("Does something"));
+        let mut new = old.clone();
+        new.is_public = false;
+        let doc = create_usage_doc();
+
+        let event = VisibilityChangeRule
+            .check_code_change(Some(&old), Some(&new), &[&doc])
+            .unwrap();
+        assert_eq!(event.severity, DriftSeverity::High);
+        assert!(event.evidence.contains("from public to private"));
+        assert_eq!(event.related_doc_chunks, vec![doc.id.clone()]);
+
+        // Undocumented symbols are not drift
+        assert!(VisibilityChangeRule
+            .check_code_change(Some(&old), Some(&new), &[])
+            .is_none());
+    }
+
+    #[test]
+    fn test_visibility_made_public() {
+        let mut old = create_public_fn_chunk(Some("Does something"));
+        old.is_public = false;
+        let new = create_public_fn_chunk(Some("Does something"));
+        let doc = create_usage_doc();
+
+        let event = VisibilityChangeRule
+            .check_code_change(Some(&old), Some(&new), &[&doc])
+            .unwrap();
+        assert_eq!(event.severity, DriftSeverity::Low);
+        assert!(event.evidence.contains("from private to public"));
+
+        assert!(VisibilityChangeRule
+            .check_code_change(Some(&new), Some(&new), &[&doc])
+            .is_none());
+    }
+
+    #[test]
+    fn test_decorator_change() {
+        let old = create_public_fn_chunk(Some("Balance"));
+        let mut new = old.clone();
+        new.decorators = vec!["property".to_string()];
+
+        let event = DecoratorChangeRule
+            .check_code_change(Some(&old), Some(&new), &[])
+            .unwrap();
+        assert_eq!(event.severity, DriftSeverity::Medium);
+        assert!(event.evidence.contains("Added decorators: @property"));
+
+        assert!(DecoratorChangeRule
+            .check_code_change(Some(&new), Some(&new), &[])
+            .is_none());
+    }
+
+    #[test]
+    fn test_newly_deprecated_symbol() {
+        let old = create_public_fn_chunk(Some("Does something"));
+        let new = old
+            .clone()
+            .with_attributes(vec!["deprecated(note = \"use other_api\")".to_string()]);
+        let doc = create_usage_doc();
+
+        let event = DeprecationRule
+            .check_code_change(Some(&old), Some(&new), &[&doc])
+            .unwrap();
+        assert_eq!(event.severity, DriftSeverity::Medium);
+        assert!(event
+            .evidence
+            .contains("#[deprecated(note = \"use other_api\")]"));
+        assert_eq!(event.related_doc_chunks, vec![doc.id.clone()]);
+
+        // Already deprecated, or docs that say so, are not drift
+        assert!(DeprecationRule
+            .check_code_change(Some(&new), Some(&new), &[&doc])
+            .is_none());
+        let mut updated = doc.clone();
+        updated.content = "new_api() is deprecated; use other_api().".to_string();
+        assert!(DeprecationRule
+            .check_code_change(Some(&old), Some(&new), &[&updated])
+            .is_none());
+    }
+
+    #[test]
+    fn test_param_tag_names() {
+        let comment = "Open a connection.\n\
+             * @param {string} host - Server name\n\
+             * @param {{retries: number}} [options={}] Settings\n\
+             * @param options.retries Property, not a parameter\n\
+             * @arg ...rest Extra values\n\
+             @param port: epydoc style\n\
+             * @returns {Conn} The connection\n\
+             * @parameter not a param tag";
+        assert_eq!(
+            param_tag_names(comment),
+            vec!["host", "options", "rest", "port"]
+        );
+    }
+
+    #[test]
+    fn test_param_tags_out_of_date() {
+        let mut chunk = CodeChunk::new(
+            "client.py",
+            "Client.connect",
+            SymbolType::Method,
+            "def connect(self, host, port, *extra): ...",
+            Language::Python,
+            1,
+            1,
+        );
+        chunk.signature = Some("def connect(self, host, port, *extra):".to_string());
+        chunk.doc_comment = Some(
+            "Open a connection.\n\n@param {str} host Server\n@param {int} prt Port\n@param extra More"
+                .to_string(),
+        );
+
+        let event = DocParamTagRule
+            .check_code_change(None, Some(&chunk), &[])
+            .unwrap();
+        assert_eq!(event.severity, DriftSeverity::High);
+        assert_eq!(event.evidence, "@param `prt` appears renamed to `port`");
+        assert_eq!(event.related_code_chunks, vec![chunk.id.clone()]);
+
+        chunk.doc_comment = Some("@param host Server\n@param timeout Seconds".to_string());
+        let event = DocParamTagRule
+            .check_code_change(None, Some(&chunk), &[])
+            .unwrap();
+        assert_eq!(
+            event.evidence,
+            "Documented but not in the signature: `timeout`\nMissing @param for: `port`, `extra`"
+        );
+
+        // Matching tags, or a comment without tags, are not drift
+        chunk.doc_comment = Some("@param host\n@param port\n@param extra".to_string());
+        assert!(DocParamTagRule
+            .check_code_change(None, Some(&chunk), &[])
+            .is_none());
+        chunk.doc_comment = Some("Open a connection.".to_string());
+        assert!(DocParamTagRule
+            .check_code_change(None, Some(&chunk), &[])
+            .is_none());
+    }
+
+    #[test]
+    fn test_config_key_removed_and_renamed() {
+        let key = |name: &str, line: usize| ConfigKey {
+            file_path: "config.toml".to_string(),
+            key: name.to_string(),
+            line,
+            value: None,
+        };
+        let doc = DocChunk::new(
+            "README.md",
+            vec!["Configuration".to_string()],
+            "Configuration",
+            crate::extract::doc::HeadingLevel::H2,
+            "Set `llm.model` and `retries` in config.toml.",
+            10,
+            14,
+        );
+        let old = vec![key("llm", 1), key("llm.model", 2), key("retries", 4)];
+
+        // `llm.model` renamed, `retries` removed
+        let new = vec![key("llm", 1), key("llm.model_name", 2)];
+        let events = ConfigKeyRule.check_config_change(&old, &new, &[&doc]);
+        assert_eq!(events.len(), 2);
+        assert_eq!(
+            events[0].description,
+            "Documented config key renamed: llm.model -> llm.model_name"
+        );
+        assert_eq!(
+            events[1].description,
+            "Documented config key removed: retries"
+        );
+        assert_eq!(events[1].location().as_deref(), Some("README.md:10"));
+        assert_eq!(events[1].related_doc_chunks, vec![doc.id.clone()]);
+
+        // Undocumented keys are not reported
+        let old = vec![key("timeout", 3)];
+        assert!(ConfigKeyRule
+            .check_config_change(&old, &[], &[&doc])
+            .is_empty());
+    }
+
+    #[test]
+    fn test_python_default_change_with_stale_docs() {
+        let python_fn = |signature: &str| {
+            let mut chunk = CodeChunk::new(
+                "client.py",
+                "connect",
+                SymbolType::Function,
+                &format!("{}\n    pass", signature),
+                Language::Python,
+                1,
+                2,
+            );
+            chunk.is_public = true;
+            chunk.signature = Some(signature.to_string());
+            chunk
+        };
+        let old = python_fn("def connect(host, timeout=5, level=\"info\"):");
+        let new = python_fn("def connect(host, timeout=30, level=\"info\"):");
+        let doc = DocChunk::new(
+            "README.md",
+            vec!["Connecting".to_string()],
+            "Connecting",
+            crate::extract::doc::HeadingLevel::H2,
+            "Call `connect(host)` to open a session.\n`timeout` defaults to 5 seconds.",
+            7,
+            9,
+        );
+
+        let event = DefaultValueChangeRule
+            .check_code_change(Some(&old), Some(&new), &[&doc])
+            .unwrap();
+        assert_eq!(event.severity, DriftSeverity::Medium);
+        assert_eq!(
+            event.description,
+            "Default value changed for connect: timeout: 5 -> 30"
+        );
+        assert!(event
+            .evidence
+            .contains("README.md (Connecting) still says: `timeout` defaults to 5 seconds."));
+        assert_eq!(event.related_code_chunks, vec![new.id.clone()]);
+        assert_eq!(event.related_doc_chunks, vec![doc.id.clone()]);
+        assert_eq!(event.location().as_deref(), Some("README.md:7"));
+
+        // Docs that already cite the new value, or a different number, are fine
+        let mut updated = doc.clone();
+        updated.content = "`timeout` defaults to 30 seconds, or 50 with retries.".to_string();
+        assert!(DefaultValueChangeRule
+            .check_code_change(Some(&old), Some(&new), &[&updated])
+            .is_none());
+        // Without related docs there is nothing to report
+        assert!(DefaultValueChangeRule
+            .check_code_change(Some(&old), Some(&new), &[])
+            .is_none());
+    }
+
+    #[test]
+    fn test_python_defaults_and_value_tokens() {
+        assert_eq!(
+            python_defaults("def f(self, a, b: int = 5, *args, sep='=', **kw):"),
+            vec![
+                ("b".to_string(), "5".to_string()),
+                ("sep".to_string(), "=".to_string())
+            ]
+        );
+        assert!(contains_token("defaults to 5.", "5"));
+        assert!(!contains_token("defaults to 50", "5"));
+        assert!(!contains_token("defaults to 5.5", "5"));
+        assert!(!contains_token("version 1.5", "5"));
+        assert!(contains_token("level (default: \"info\")", "info"));
+    }
+
+    #[test]
+    fn test_config_default_change_with_stale_docs() {
+        let key = |name: &str, value: &str| ConfigKey {
+            file_path: "config.toml".to_string(),
+            key: name.to_string(),
+            line: 2,
+            value: Some(value.to_string()),
+        };
+        let doc = DocChunk::new(
+            "README.md",
+            vec!["Configuration".to_string()],
+            "Configuration",
+            crate::extract::doc::HeadingLevel::H2,
+            "`retries` controls reconnects and defaults to 3.",
+            10,
+            12,
+        );
+
+        let events = DefaultValueChangeRule.check_config_change(
+            &[key("retries", "3"), key("timeout", "5")],
+            &[key("retries", "5"), key("timeout", "5")],
+            &[&doc],
+        );
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            events[0].description,
+            "Documented value changed for config key retries: 3 -> 5"
+        );
+        assert_eq!(events[0].severity, DriftSeverity::Medium);
+        assert_eq!(events[0].related_doc_chunks, vec![doc.id.clone()]);
+    }
+
+    #[test]
+    fn test_conflicting_documented_signatures() {
+        let mut code = create_public_fn_chunk(None);
+        code.signature = Some("pub fn new_api(name: &str) -> String".to_string());
+        let section = |file: &str, content: &str| {
+            DocChunk::new(
+                file,
+                vec!["API".to_string()],
+                "API",
+                crate::extract::doc::HeadingLevel::H2,
+                content,
+                3,
+                8,
+            )
+        };
+        let readme = section("README.md", "Call `fn new_api(name: &str) -> String`.");
+        let api = section(
+            "docs/api.md",
+            "```rust\npub fn new_api(name: &str, retries: u32) -> String\n```",
+        );
+
+        let event = ConflictingDocsRule
+            .check_symbol(&code, &[&readme, &api])
+            .unwrap();
+        assert_eq!(event.severity, DriftSeverity::Medium);
+        assert_eq!(
+            event.description,
+            "Conflicting documented signatures: new_api"
+        );
+        assert!(event
+            .evidence
+            .contains("README.md:3 (API): fn new_api(name: &str) -> String"));
+        assert!(event
+            .evidence
+            .contains("docs/api.md:3 (API): fn new_api(name: &str, retries: u32) -> String"));
+        assert_eq!(
+            event.related_doc_chunks,
+            vec![readme.id.clone(), api.id.clone()]
+        );
+        assert_eq!(event.related_code_chunks, vec![code.id.clone()]);
+
+        // Agreeing sections, one leaving out the return type, are fine
+        let short = section("docs/api.md", "Use `fn new_api(name:&str)` to start.");
+        assert!(ConflictingDocsRule
+            .check_symbol(&code, &[&readme, &short])
+            .is_none());
+        assert!(ConflictingDocsRule.check_symbol(&code, &[&api]).is_none());
+    }
+
+    #[test]
+    fn test_broken_doc_links() {
+        let section = |file: &str, heading: &str, content: &str, line: usize| {
+            DocChunk::new(
+                file,
+                vec![heading.to_string()],
+                heading,
+                crate::extract::doc::HeadingLevel::H2,
+                content,
+                line,
+                line + 2,
+            )
+        };
+        let usage = section("README.md", "Usage", "## Usage\n\nRun it.", 1);
+        let setup = section(
+            "docs/guide.md",
+            "Setup",
+            "## Setup\n\nSee [usage](../README.md#usage) and [options](#options).",
+            1,
+        );
+        let known = [&usage, &setup];
+
+        let events = DocLinkRule.check_links(&[&setup], &known);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].severity, DriftSeverity::Medium);
+        assert_eq!(
+            events[0].description,
+            "Broken doc link: [options](#options)"
+        );
+        assert!(events[0]
+            .evidence
+            .contains("no heading in `docs/guide.md` has the anchor `#options`"));
+        assert_eq!(events[0].location().as_deref(), Some("docs/guide.md:3"));
+
+        // A renamed file breaks the cross-file link
+        let moved = section("USAGE.md", "Usage", "## Usage\n\nRun it.", 1);
+        let events = DocLinkRule.check_links(&[&setup], &[&moved, &setup]);
+        assert_eq!(events.len(), 2);
+        assert!(events[0]
+            .evidence
+            .contains("`README.md` is not a known doc file"));
+    }
+
+    #[test]
+    fn test_documented_signatures() {
+        let text = "Call `fn connect(host: &str,  port: u16) -> Result<Conn>` first.\n\
+                    ```python\ndef send(self, data) -> int:\n    ...\n```\n\
+                    Then run connect(\"db\", 5) and define(x).";
+        let signatures = documented_signatures(text);
+
+        assert_eq!(signatures.len(), 2);
+        assert_eq!(signatures[0].name, "connect");
+        assert_eq!(signatures[0].params, "host:&str, port:u16");
+        assert_eq!(signatures[0].return_type.as_deref(), Some("Result<Conn>"));
+        assert_eq!(signatures[1].name, "send");
+        assert_eq!(signatures[1].return_type.as_deref(), Some("int"));
+    }
+
+    #[test]
+    fn test_doc_signature_mismatch() {
+        let mut code = create_public_fn_chunk(None);
+        code.signature = Some("pub fn new_api(name: &str) -> String".to_string());
+        let doc_with = |content: &str| {
+            DocChunk::new(
+                "README.md",
+                vec!["Usage".to_string()],
+                "Usage",
+                crate::extract::doc::HeadingLevel::H2,
+                content,
+                3,
+                8,
+            )
+        };
+
+        // Docs hardcode an out-of-date signature
+        let stale = doc_with("```rust\npub fn new_api(name: &str, retries: u32) -> String\n```");
+        let event = DocSignatureMismatchRule
+            .check_doc_change(None, Some(&stale), &[&code])
+            .unwrap();
+        assert_eq!(event.severity, DriftSeverity::High);
+        assert_eq!(
+            event.description,
+            "Documented signature out of date: new_api"
+        );
+        assert!(event.evidence.contains("retries: u32"));
+        assert_eq!(event.related_code_chunks, vec![code.id.clone()]);
+        assert_eq!(event.location().as_deref(), Some("README.md:3"));
+
+        // Matching signatures, with or without the return type, are fine
+        for content in [
+            "Use `fn new_api(name: &str) -> String`.",
+            "Use `fn new_api(name:&str)` to start.",
+        ] {
+            assert!(DocSignatureMismatchRule
+                .check_doc_change(None, Some(&doc_with(content)), &[&code])
+                .is_none());
+        }
+
+        // Only code is searched: not prose, and not blocks of other languages
+        for content in [
+            "The old fn new_api(name: &str, retries: u32) is gone.",
+            "```console\n$ grep 'fn new_api(name: &str, retries: u32)' src\n```",
+        ] {
+            assert!(DocSignatureMismatchRule
+                .check_doc_change(None, Some(&doc_with(content)), &[&code])
+                .is_none());
+        }
+        let table = doc_with("| API |\n|-----|\n| `fn new_api(name: &str, retries: u32)` |");
+        assert!(DocSignatureMismatchRule
+            .check_doc_change(None, Some(&table), &[&code])
+            .is_some());
+    }
 }
@@ -0,0 +1,1927 @@
+//! Repository scanning
+//!
+//! Runs the full scan pipeline without printing anything:
+//! - Collects changed files from git (and optionally the working tree)
+//! - Extracts code and doc chunks and generates embeddings
+//! - Applies drift rules and stores the resulting events
+//! - Optionally checks documented config keys in changed TOML/YAML files
+//!
+//! Callers that want progress or cancellation pass a [`ScanProgress`]. A
+//! cancelled scan stops embedding, stores what it has, and leaves the last
+//! scanned commit alone so the next scan picks up where it stopped.
+
+use super::diff::{extract_code_at, extract_doc_files_at};
+use super::rules::{ambiguous_names, doc_relates_to, documented_signature_names, short_name};
+use super::{
+    build_embedding_provider, normalize, short_id, sort_events, ConfigKeyRule, ConflictingDocsRule,
+    DefaultValueChangeRule, DocLinkRule, DriftConfig, DriftEvent, DriftSeverity, EmbeddingMismatch,
+    EmbeddingProvider, HardDriftRules, SoftDriftRules,
+};
+use crate::error::DocSentinelError;
+use crate::extract::{
+    extract_config_keys, CodeChunk, CodeExtractor, ConfigKey, DocChunk, DocExtractor,
+    EmbeddingTextMode,
+};
+use crate::llm::{AnalysisRequest, DriftAnalyzer, LlmClient};
+use crate::repo::{path_matches_pattern, ChangedFile, CommitInfo, Repository};
+use crate::storage::Database;
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Options controlling what a scan covers
+#[derive(Debug, Clone)]
+pub struct ScanOptions {
+    /// Scan every file rather than only changes since the last scan
+    pub full: bool,
+    /// Commit range to scan (e.g. `HEAD~5..HEAD`); overrides `full`
+    pub range: Option<String>,
+    /// Revision a full scan compares against; defaults to the merge-base of
+    /// HEAD with the default branch
+    pub base: Option<String>,
+    /// Include uncommitted changes in the working tree
+    pub uncommitted: bool,
+    /// Generate embeddings when an endpoint is configured
+    pub embeddings: bool,
+    /// Refine detected events with the configured LLM
+    pub with_llm: bool,
+    /// Discard stored embeddings and re-embed every file with the configured
+    /// model (implies `full`)
+    pub reembed: bool,
+    /// Only scan files matching one of these patterns (every file when empty)
+    pub include: Vec<String>,
+    /// Skip files matching any of these patterns
+    pub exclude: Vec<String>,
+}
+
+impl ScanOptions {
+    /// Whether `path` passes the `include` and `exclude` filters
+    ///
+    /// Patterns use the same gitignore-style syntax as `ignore_patterns`, and
+    /// apply on top of them.
+    pub fn includes_path(&self, path: &str) -> bool {
+        let matches = |pattern: &String| path_matches_pattern(pattern, path);
+        (self.include.is_empty() || self.include.iter().any(matches))
+            && !self.exclude.iter().any(matches)
+    }
+
+    /// Whether the scan is limited to part of the repository
+    fn is_filtered(&self) -> bool {
+        !self.include.is_empty() || !self.exclude.is_empty()
+    }
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            full: false,
+            range: None,
+            base: None,
+            uncommitted: false,
+            embeddings: true,
+            with_llm: false,
+            reembed: false,
+            include: Vec::new(),
+            exclude: Vec::new(),
+        }
+    }
+}
+
+/// Stages of a scan reported to [`ScanProgress`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanPhase {
+    /// Reading and parsing changed files (units: files)
+    Extracting,
+    /// Generating embeddings (units: chunks)
+    Embedding,
+    /// Writing chunks to the database (units: chunks)
+    Storing,
+}
+
+impl std::fmt::Display for ScanPhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScanPhase::Extracting => write!(f, "Extracting"),
+            ScanPhase::Embedding => write!(f, "Embedding"),
+            ScanPhase::Storing => write!(f, "Storing"),
+        }
+    }
+}
+
+/// Receives progress updates from a running scan and can ask it to stop
+///
+/// All methods have no-op defaults; `()` reports nothing and never cancels.
+pub trait ScanProgress: Send + Sync {
+    /// A phase started with `total` units of work
+    fn start_phase(&self, _phase: ScanPhase, _total: usize) {}
+
+    /// `count` more units of the current phase finished
+    fn advance(&self, _count: usize) {}
+
+    /// Whether the scan should stop at the next safe point
+    fn cancelled(&self) -> bool {
+        false
+    }
+}
+
+impl ScanProgress for () {}
+
+/// Chunks written per transaction, so an interrupted scan keeps its progress
+const CHUNKS_PER_TRANSACTION: usize = 200;
+
+/// Chunks embedded between cancellation checks
+const CHUNKS_PER_EMBEDDING_STEP: usize = 64;
+
+/// Summary counts for a scan
+#[derive(Debug, Clone, Default)]
+pub struct ScanStats {
+    /// Commit the scan started from (`None` for a full scan)
+    pub from_commit: Option<String>,
+    /// Commit the scan ran up to
+    pub to_commit: String,
+    /// Revision a full scan compared symbols against, if any
+    pub base_commit: Option<String>,
+    /// Number of changed code files
+    pub code_files: usize,
+    /// Number of changed doc files
+    pub doc_files: usize,
+    /// Number of changed config files checked for documented keys
+    pub config_files: usize,
+    /// Number of uncommitted files considered
+    pub uncommitted_files: usize,
+    /// Number of code chunks in modified files left untouched by the diff,
+    /// whose stored data was kept as is
+    pub unchanged_chunks: usize,
+    /// Whether embeddings were generated for the extracted chunks
+    pub embedded: bool,
+    /// Number of events the LLM refined successfully
+    pub llm_refined: usize,
+    /// Whether the scan was cancelled before embedding finished
+    pub interrupted: bool,
+}
+
+/// Result of a scan
+#[derive(Debug, Clone, Default)]
+pub struct ScanReport {
+    /// Drift events detected and stored by this scan
+    pub events: Vec<DriftEvent>,
+    /// Code chunks extracted from changed files
+    pub code_chunks: Vec<CodeChunk>,
+    /// Doc chunks extracted from changed files
+    pub doc_chunks: Vec<DocChunk>,
+    /// Summary counts
+    pub stats: ScanStats,
+    /// Non-fatal problems (unparseable files, unreachable embedding service)
+    pub warnings: Vec<String>,
+    /// Set when stored embeddings came from a different model than the one
+    /// configured; re-scan with `reembed` to replace them
+    pub embedding_mismatch: Option<EmbeddingMismatch>,
+}
+
+/// Scan a repository for drift and store the results
+///
+/// The repository must already be initialized. Chunks and events are written
+/// to its database and the last scanned commit is updated.
+pub fn scan_repository(repo: &Repository, options: &ScanOptions) -> Result<ScanReport> {
+    scan_repository_with_progress(repo, options, &())
+}
+
+/// Scan a repository, reporting progress and checking for cancellation
+///
+/// When `progress` reports a cancellation, chunks not yet embedded are stored
+/// without embeddings, LLM refinement is skipped, and the last scanned commit
+/// is not updated. Unchanged chunks reuse their stored embeddings, so the
+/// next scan only embeds what is missing.
+pub fn scan_repository_with_progress(
+    repo: &Repository,
+    options: &ScanOptions,
+    progress: &dyn ScanProgress,
+) -> Result<ScanReport> {
+    let sentinel_dir = repo.sentinel_dir();
+
+    if !sentinel_dir.exists() {
+        return Err(DocSentinelError::NotInitialized.into());
+    }
+
+    let db_path = sentinel_dir.join("docsentinel.db");
+    let mut db = Database::open(&db_path)?;
+    if repo.config().embedding_endpoint().is_some() {
+        db = db.with_embedding_model(repo.config().embedding_model());
+    }
+    let mut report = ScanReport::default();
+
+    // Check the LLM up front so a stopped server or missing model fails
+    // before extraction rather than after it
+    let llm_client = if options.with_llm {
+        let client = LlmClient::from_repo_config(repo.config())?;
+        tokio::runtime::Runtime::new()?.block_on(client.ensure_ready())?;
+        Some(client)
+    } else {
+        None
+    };
+
+    if options.reembed {
+        db.clear_embeddings()?;
+    }
+
+    // Determine what to scan
+    let mut base_commit = None;
+    let (from_commit, to_commit) = if let Some(ref range_str) = options.range {
+        // Parse range like "HEAD~5..HEAD"
+        let parts: Vec<&str> = range_str.split("..").collect();
+        if parts.len() == 2 {
+            (Some(parts[0].to_string()), parts[1].to_string())
+        } else {
+            (None, range_str.to_string())
+        }
+    } else if options.full || options.reembed {
+        let head = repo.head_commit()?;
+        base_commit = full_scan_base(repo, options.base.as_deref(), &head)?;
+        (None, head)
+    } else {
+        let last_scan = db.get_last_scan_commit()?;
+        (last_scan, repo.head_commit()?)
+    };
+
+    // Get changed files from commits
+    let mut changes = repo.changes_between(from_commit.as_deref(), &to_commit)?;
+
+    // Include uncommitted changes if requested
+    let mut uncommitted_files = Vec::new();
+    if options.uncommitted {
+        let uncommitted_changes = repo.uncommitted_changes()?;
+        report.stats.uncommitted_files = uncommitted_changes.len();
+        uncommitted_files = uncommitted_changes.clone();
+        // Merge uncommitted changes, avoiding duplicates
+        for uc in uncommitted_changes {
+            if !changes.iter().any(|c| c.path == uc.path) {
+                changes.push(uc);
+            }
+        }
+    }
+
+    if options.is_filtered() {
+        changes.retain(|c| options.includes_path(&c.path.to_string_lossy()));
+        uncommitted_files.retain(|c| options.includes_path(&c.path.to_string_lossy()));
+    }
+
+    // Renamed files keep their stored chunks under the new path, so the
+    // contents are compared and embeddings reused as for a modified file
+    for change in &changes {
+        if let Some(ref old_path) = change.old_path {
+            db.rename_file(&old_path.to_string_lossy(), &change.path.to_string_lossy())?;
+        }
+    }
+
+    let code_changes: Vec<_> = changes.iter().filter(|c| c.is_code()).collect();
+    let doc_changes: Vec<_> = changes.iter().filter(|c| c.is_documentation()).collect();
+
+    // A full scan extracts every file as new, so the base revision stands in
+    // for the stored chunks when looking for before/after pairs
+    let doc_extractor = DocExtractor::from_repo_config(repo.config());
+    let baseline = match base_commit {
+        Some(ref base) => Some(Baseline::extract(
+            repo,
+            &doc_extractor,
+            base,
+            &to_commit,
+            &uncommitted_files,
+            &mut report.warnings,
+        )?),
+        None => None,
+    };
+
+    report.stats.from_commit = from_commit;
+    report.stats.to_commit = to_commit;
+    report.stats.base_commit = base_commit;
+    report.stats.code_files = code_changes.len();
+    report.stats.doc_files = doc_changes.len();
+
+    progress.start_phase(
+        ScanPhase::Extracting,
+        code_changes.len() + doc_changes.len(),
+    );
+
+    // Read code files, sorted by path so chunk order is stable across runs
+    let mut code_files = Vec::new();
+    for change in &code_changes {
+        let content = skip_unreadable(repo.read_file_current(&change.path), &mut report.warnings)?;
+        if let Some(content) = content {
+            code_files.push((change.path.clone(), content));
+        }
+    }
+    code_files.sort_by(|a, b| a.0.cmp(&b.0));
+
+    // Extract code chunks in parallel
+    let mut all_code_chunks = Vec::new();
+
+    for ((path, _), result) in code_files
+        .iter()
+        .zip(CodeExtractor::extract_files(&code_files))
+    {
+        match result {
+            Ok(chunks) => all_code_chunks.extend(chunks),
+            Err(e) => {
+                report
+                    .warnings
+                    .push(format!("Failed to extract {:?}: {}", path, e));
+            }
+        }
+    }
+    progress.advance(code_changes.len());
+
+    // Symbols outside the diff hunks of a modified file keep their stored
+    // content and embedding, so they are not re-embedded or re-checked
+    let unchanged_chunks = if options.reembed {
+        Vec::new()
+    } else {
+        split_unchanged_chunks(&db, &code_changes, &mut all_code_chunks)?
+    };
+    report.stats.unchanged_chunks = unchanged_chunks.len();
+
+    // Extract doc chunks
+    let mut all_doc_chunks = Vec::new();
+
+    for change in &doc_changes {
+        let content = skip_unreadable(repo.read_file_current(&change.path), &mut report.warnings)?;
+        if let Some(content) = content {
+            match doc_extractor.extract_file(&change.path, &content) {
+                Ok(chunks) => all_doc_chunks.extend(chunks),
+                Err(e) => {
+                    report
+                        .warnings
+                        .push(format!("Failed to extract {:?}: {}", change.path, e));
+                }
+            }
+        }
+        progress.advance(1);
+    }
+
+    // Generate embeddings
+    if options.embeddings && repo.config().embedding_endpoint().is_some() {
+        let provider = build_embedding_provider(repo.config());
+        if !options.reembed {
+            reuse_stored_embeddings(&db, &mut all_code_chunks, &mut all_doc_chunks)?;
+        }
+        let rt = tokio::runtime::Runtime::new()?;
+        match rt.block_on(embed_chunks(
+            provider.as_ref(),
+            repo.config().llm.embedding_text,
+            &mut all_code_chunks,
+            &mut all_doc_chunks,
+            progress,
+        )) {
+            Ok(false) => {
+                report.stats.interrupted = true;
+            }
+            Ok(true) => {
+                report.stats.embedded = true;
+                let dim = all_code_chunks
+                    .iter()
+                    .filter_map(|c| c.embedding.as_ref())
+                    .chain(all_doc_chunks.iter().filter_map(|c| c.embedding.as_ref()))
+                    .map(|e| e.len())
+                    .next();
+                if let Some(dim) = dim {
+                    report.embedding_mismatch = EmbeddingMismatch::detect(
+                        db.embedding_model_in_use()?,
+                        provider.model(),
+                        dim,
+                    );
+                }
+                if let Some(ref mismatch) = report.embedding_mismatch {
+                    report.warnings.push(format!(
+                        "{}; similarity against older chunks is unreliable until they are re-embedded",
+                        mismatch
+                    ));
+                }
+            }
+            Err(e) => {
+                report
+                    .warnings
+                    .push(format!("Failed to generate embeddings: {}", e));
+            }
+        }
+        if repo.config().normalize_embeddings {
+            let embeddings = all_code_chunks
+                .iter_mut()
+                .filter_map(|c| c.embedding.as_mut())
+                .chain(
+                    all_doc_chunks
+                        .iter_mut()
+                        .filter_map(|c| c.embedding.as_mut()),
+                );
+            for embedding in embeddings {
+                normalize(embedding);
+            }
+        }
+    }
+
+    // Apply drift rules against the previously stored chunks, or for files
+    // changed since the base of a full scan, their versions there. On a first
+    // scan there is nothing else to compare against, so every symbol would
+    // look new.
+    let drift_config = DriftConfig::from_repo_config(repo.config());
+    let mut rule_events = Vec::new();
+    let scanned_before = db.get_last_scan_commit()?.is_some();
+    if baseline.is_some() || scanned_before {
+        let hard_rules = HardDriftRules::new();
+        let soft_rules = SoftDriftRules::for_scan();
+        let linked_docs = linked_doc_chunks(&db, &all_doc_chunks)?;
+        for chunk in &all_code_chunks {
+            let old_chunk = match baseline {
+                Some(ref baseline) if baseline.changed(&chunk.file_path) => {
+                    baseline.code.get(&chunk.id).cloned()
+                }
+                _ if scanned_before => db.get_code_chunk(&chunk.id)?,
+                _ => continue,
+            };
+            if old_chunk.as_ref().is_some_and(|old| old.hash == chunk.hash) {
+                continue;
+            }
+            let related_docs: Vec<&DocChunk> = linked_docs
+                .iter()
+                .filter(|doc| doc.links_to(chunk))
+                .collect();
+            if drift_config.use_hard_rules {
+                rule_events.extend(hard_rules.check_code_change(
+                    old_chunk.as_ref(),
+                    Some(chunk),
+                    &related_docs,
+                ));
+            }
+            if drift_config.use_soft_rules {
+                rule_events.extend(soft_rules.check_code_change(
+                    old_chunk.as_ref(),
+                    Some(chunk),
+                    &related_docs,
+                ));
+            }
+        }
+
+        // Edited docs are checked against the current code, including symbols
+        // from files that did not change in this scan
+        if drift_config.use_hard_rules && !all_doc_chunks.is_empty() {
+            let scanned_ids: HashSet<&str> =
+                all_code_chunks.iter().map(|c| c.id.as_str()).collect();
+            let stored_code: Vec<CodeChunk> = db
+                .get_all_code_chunks()?
+                .into_iter()
+                .filter(|c| !scanned_ids.contains(c.id.as_str()))
+                .collect();
+
+            for doc in &all_doc_chunks {
+                let old_doc = match baseline {
+                    Some(ref baseline) if baseline.changed(&doc.file_path) => {
+                        baseline.docs.get(&doc.id).cloned()
+                    }
+                    _ if scanned_before => db.get_doc_chunk(&doc.id)?,
+                    _ => continue,
+                };
+                if old_doc.as_ref().is_some_and(|old| old.hash == doc.hash) {
+                    continue;
+                }
+                let documented = documented_signature_names(doc);
+                let related_code: Vec<&CodeChunk> = all_code_chunks
+                    .iter()
+                    .chain(&stored_code)
+                    .filter(|code| {
+                        doc.links_to(code)
+                            || documented
+                                .iter()
+                                .any(|name| name == short_name(&code.symbol_name))
+                    })
+                    .collect();
+                rule_events.extend(hard_rules.check_doc_change(
+                    old_doc.as_ref(),
+                    Some(doc),
+                    &related_code,
+                ));
+            }
+        }
+    }
+
+    // Commit in groups so an interrupted process keeps what was written
+    progress.start_phase(
+        ScanPhase::Storing,
+        all_code_chunks.len() + all_doc_chunks.len(),
+    );
+    for group in all_code_chunks.chunks(CHUNKS_PER_TRANSACTION) {
+        db.upsert_code_chunks(group)?;
+        progress.advance(group.len());
+    }
+    db.with_transaction(|db| {
+        unchanged_chunks
+            .iter()
+            .try_for_each(|c| db.update_code_chunk_lines(&c.id, c.start_line, c.end_line))
+    })?;
+    for group in all_doc_chunks.chunks(CHUNKS_PER_TRANSACTION) {
+        db.upsert_doc_chunks(group)?;
+        progress.advance(group.len());
+    }
+
+    // Check links in edited docs, and links elsewhere that point into an
+    // edited, moved, or deleted doc, along with signatures that edited docs
+    // show differently from other docs. Stored sections of changed files may
+    // include headings that were since removed, so those files are known
+    // only by what this scan extracted.
+    let mut doc_events = Vec::new();
+    if drift_config.use_hard_rules && !doc_changes.is_empty() {
+        // A renamed doc's old path counts too, so links to it are rechecked
+        let changed_docs: HashSet<String> = doc_changes
+            .iter()
+            .flat_map(|c| [c.path.as_path(), c.old_path()])
+            .map(|path| path.to_string_lossy().to_string())
+            .collect();
+        let stored_docs = db.get_all_doc_chunks()?;
+        let known_docs: Vec<&DocChunk> = stored_docs
+            .iter()
+            .filter(|doc| !changed_docs.contains(&doc.file_path))
+            .chain(&all_doc_chunks)
+            .collect();
+        let linking_docs: Vec<&DocChunk> = known_docs
+            .iter()
+            .copied()
+            .filter(|doc| {
+                changed_docs.contains(&doc.file_path)
+                    || doc.links.iter().any(|link| {
+                        link.target_file(&doc.file_path)
+                            .is_some_and(|file| changed_docs.contains(&file))
+                    })
+            })
+            .collect();
+        doc_events = DocLinkRule.check_links(&linking_docs, &known_docs);
+
+        // Symbols whose signature an edited section shows differently from
+        // another section. Names shared by several symbols are ambiguous.
+        let edited_names: HashSet<String> = all_doc_chunks
+            .iter()
+            .flat_map(documented_signature_names)
+            .collect();
+        if !edited_names.is_empty() {
+            let public_code = db.get_public_code_chunks()?;
+            let ambiguous = ambiguous_names(&public_code);
+            for chunk in &public_code {
+                let name = short_name(&chunk.symbol_name);
+                if ambiguous.contains(name) || !edited_names.contains(name) {
+                    continue;
+                }
+                let related: Vec<&DocChunk> = known_docs
+                    .iter()
+                    .copied()
+                    .filter(|doc| doc_relates_to(doc, chunk))
+                    .collect();
+                if related
+                    .iter()
+                    .any(|doc| changed_docs.contains(&doc.file_path))
+                {
+                    doc_events.extend(ConflictingDocsRule.check_symbol(chunk, &related));
+                }
+            }
+        }
+    }
+
+    // Compare documented config keys against the previous version of each
+    // changed config file. Without a previous commit there is nothing to
+    // compare against.
+    let mut config_events = Vec::new();
+    if let (true, Some(from)) = (
+        repo.config().drift.check_config_keys,
+        report.stats.from_commit.as_deref(),
+    ) {
+        let config_changes: Vec<_> = changes.iter().filter(|c| c.is_config()).collect();
+        report.stats.config_files = config_changes.len();
+
+        let docs = db.get_all_doc_chunks()?;
+        let docs: Vec<&DocChunk> = docs.iter().collect();
+        for change in &config_changes {
+            let old_content = skip_unreadable(
+                repo.read_file_at_commit(&change.path, from),
+                &mut report.warnings,
+            )?;
+            let new_content =
+                skip_unreadable(repo.read_file_current(&change.path), &mut report.warnings)?;
+            match (
+                config_keys(&change.path, old_content.as_deref()),
+                config_keys(&change.path, new_content.as_deref()),
+            ) {
+                (Ok(old_keys), Ok(new_keys)) => {
+                    config_events
+                        .extend(ConfigKeyRule.check_config_change(&old_keys, &new_keys, &docs));
+                    config_events.extend(
+                        DefaultValueChangeRule.check_config_change(&old_keys, &new_keys, &docs),
+                    );
+                }
+                (Err(e), _) | (_, Err(e)) => {
+                    report
+                        .warnings
+                        .push(format!("Failed to extract {:?}: {}", change.path, e));
+                }
+            }
+        }
+    }
+
+    let mut events = Vec::new();
+
+    // Check for code changes without corresponding doc changes
+    if !code_changes.is_empty() && doc_changes.is_empty() {
+        for code_change in &code_changes {
+            // Check if this is a public API file
+            let chunks: Vec<_> = all_code_chunks
+                .iter()
+                .filter(|c| c.file_path == code_change.path.to_string_lossy())
+                .filter(|c| c.is_public)
+                .collect();
+
+            if !chunks.is_empty() {
+                let mut event = DriftEvent::new(
+                    DriftSeverity::Medium,
+                    &format!(
+                        "Code changed in {:?} but no documentation was updated",
+                        code_change.path
+                    ),
+                    &format!(
+                        "{} public symbols modified: {}",
+                        chunks.len(),
+                        chunks
+                            .iter()
+                            .map(|c| c.symbol_name.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                    0.7,
+                )
+                .with_tag("docs_not_updated");
+                for chunk in &chunks {
+                    event = event.with_code_chunk(&chunk.id);
+                }
+                event = event.with_location(&chunks[0].file_path, chunks[0].start_line);
+                events.push(event);
+            }
+        }
+    }
+
+    events.extend(rule_events);
+    events.extend(config_events);
+    events.extend(doc_events);
+
+    // Force configured severities before the floor sees them
+    repo.config().apply_severity_overrides(&mut events);
+
+    // Drop events below the configured severity and confidence floors
+    let drift_settings = &repo.config().drift;
+    events.retain(|event| {
+        event.severity <= drift_settings.min_severity
+            && event.confidence >= drift_settings.min_confidence
+    });
+
+    // Drop events suppressed by permanent ignore rules
+    events.retain(|event| !repo.config().is_event_ignored(event));
+
+    // Attribute drift in code this scan extracted to the commit that last
+    // changed that code
+    let scanned_code: HashMap<&str, &CodeChunk> =
+        all_code_chunks.iter().map(|c| (c.id.as_str(), c)).collect();
+    let mut blamed: HashMap<&str, Option<CommitInfo>> = HashMap::new();
+    for event in &mut events {
+        let Some(chunk) = event
+            .related_code_chunks
+            .iter()
+            .find_map(|id| scanned_code.get(id.as_str()))
+        else {
+            continue;
+        };
+        if !blamed.contains_key(chunk.id.as_str()) {
+            let path = Path::new(&chunk.file_path);
+            let commit = match repo.last_commit_for_lines(path, chunk.start_line, chunk.end_line) {
+                Ok(commit) => commit,
+                Err(e) => {
+                    report
+                        .warnings
+                        .push(format!("Failed to blame {:?}: {}", path, e));
+                    None
+                }
+            };
+            blamed.insert(chunk.id.as_str(), commit);
+        }
+        event.introduced_by = blamed[chunk.id.as_str()].clone();
+    }
+
+    // Refine events with LLM analysis
+    if let Some(client) = llm_client.filter(|_| !events.is_empty() && !report.stats.interrupted) {
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(refine_events_with_llm(
+            client,
+            &db,
+            &mut events,
+            &mut report,
+        ))?;
+    }
+
+    // Store drift events
+    db.with_transaction(|db| -> Result<()> {
+        for event in &mut events {
+            event.id = db.insert_drift_event(event)?;
+        }
+        Ok(())
+    })?;
+
+    let retention_days = repo.config().history_retention_days;
+    if retention_days > 0 {
+        db.prune_chunk_history(retention_days)?;
+    }
+
+    // Update last scan commit. An interrupted scan leaves it so the same
+    // range is scanned again and the remaining chunks get embedded, and a
+    // filtered scan leaves it so the files it skipped are not missed.
+    if report.stats.interrupted {
+        report.warnings.push(
+            "Scan interrupted; extracted chunks were saved, run the scan again to finish"
+                .to_string(),
+        );
+    } else if !options.is_filtered() {
+        db.set_last_scan_commit(&report.stats.to_commit)?;
+    }
+
+    sort_events(&mut events);
+    report.events = events;
+    report.code_chunks = all_code_chunks;
+    report.doc_chunks = all_doc_chunks;
+    Ok(report)
+}
+
+/// Treat a file over `max_file_bytes` or with non-UTF-8 content as absent,
+/// recording a warning
+pub(super) fn skip_unreadable(
+    read: crate::error::Result<Option<String>>,
+    warnings: &mut Vec<String>,
+) -> Result<Option<String>> {
+    match read {
+        Err(e @ (DocSentinelError::FileTooLarge { .. } | DocSentinelError::InvalidUtf8 { .. })) => {
+            warnings.push(e.to_string());
+            Ok(None)
+        }
+        read => Ok(read?),
+    }
+}
+
+/// Keys of a config file version (empty when the file does not exist)
+fn config_keys(path: &Path, content: Option<&str>) -> Result<Vec<ConfigKey>> {
+    match content {
+        Some(content) => Ok(extract_config_keys(path, content)?),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Revision a full scan compares against
+///
+/// An explicit `base` is used as given. Otherwise this is the merge-base of
+/// `head` with the default branch, or `None` when there is no default branch.
+fn full_scan_base(repo: &Repository, base: Option<&str>, head: &str) -> Result<Option<String>> {
+    if let Some(base) = base {
+        return Ok(Some(base.to_string()));
+    }
+    match repo.default_branch()? {
+        Some(branch) => Ok(Some(repo.merge_base(&branch, head)?)),
+        None => Ok(None),
+    }
+}
+
+/// Chunks as they were at the base revision of a full scan
+struct Baseline {
+    /// Files that differ between the base and what is being scanned
+    changed_files: HashSet<String>,
+    /// Code chunks of the changed files at the base, by ID
+    code: HashMap<String, CodeChunk>,
+    /// Doc chunks of the changed files at the base, by ID
+    docs: HashMap<String, DocChunk>,
+}
+
+impl Baseline {
+    /// Extract the files changed in `base..head`, plus `uncommitted` ones, as
+    /// they were at `base`
+    fn extract(
+        repo: &Repository,
+        doc_extractor: &DocExtractor,
+        base: &str,
+        head: &str,
+        uncommitted: &[ChangedFile],
+        warnings: &mut Vec<String>,
+    ) -> Result<Self> {
+        let mut changes = repo.changes_between(Some(base), head)?;
+        // Uncommitted edits differ from the base even where head does not
+        for change in uncommitted {
+            if !changes.iter().any(|c| c.path == change.path) {
+                changes.push(change.clone());
+            }
+        }
+        let code_paths: Vec<PathBuf> = changes
+            .iter()
+            .filter(|c| c.is_code())
+            .map(|c| c.path.clone())
+            .collect();
+        let doc_paths: Vec<PathBuf> = changes
+            .iter()
+            .filter(|c| c.is_documentation())
+            .map(|c| c.path.clone())
+            .collect();
+
+        let code = extract_code_at(repo, &code_paths, base, warnings)?;
+        let docs = extract_doc_files_at(repo, doc_extractor, &doc_paths, base, warnings)?;
+        Ok(Self {
+            changed_files: code_paths
+                .iter()
+                .chain(&doc_paths)
+                .map(|p| p.to_string_lossy().to_string())
+                .collect(),
+            code: code.into_iter().map(|c| (c.id.clone(), c)).collect(),
+            docs: docs.into_iter().map(|d| (d.id.clone(), d)).collect(),
+        })
+    }
+
+    /// Whether `file_path` differs from the base
+    fn changed(&self, file_path: &str) -> bool {
+        self.changed_files.contains(file_path)
+    }
+}
+
+/// Doc sections with explicit symbol annotations
+///
+/// Sections extracted in this scan replace their stored versions, so newly
+/// added or edited annotations take effect immediately.
+fn linked_doc_chunks(db: &Database, scanned: &[DocChunk]) -> Result<Vec<DocChunk>> {
+    let mut docs: Vec<DocChunk> = db
+        .get_all_doc_chunks()?
+        .into_iter()
+        .filter(|doc| !scanned.iter().any(|s| s.id == doc.id))
+        .collect();
+    docs.extend(scanned.iter().cloned());
+    docs.retain(|doc| !doc.linked_symbols.is_empty());
+    Ok(docs)
+}
+
+/// Move chunks the diff did not touch out of `chunks`
+///
+/// A chunk is left alone when its file has known changed lines, none of them
+/// fall within the chunk, and its stored hash still matches. The hash check
+/// catches edits the committed diff does not cover, such as uncommitted ones.
+fn split_unchanged_chunks(
+    db: &Database,
+    changes: &[&ChangedFile],
+    chunks: &mut Vec<CodeChunk>,
+) -> Result<Vec<CodeChunk>> {
+    let mut stored_hashes = HashMap::new();
+    for change in changes {
+        if change.changed_lines.is_some() {
+            let file_path = change.path.to_string_lossy().to_string();
+            let hashes = db.get_code_chunk_hashes_for_file(&file_path)?;
+            stored_hashes.insert(file_path, (*change, hashes));
+        }
+    }
+    if stored_hashes.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let (unchanged, changed) = std::mem::take(chunks).into_iter().partition(|chunk| {
+        stored_hashes
+            .get(&chunk.file_path)
+            .is_some_and(|(change, hashes)| {
+                !change.touches_lines(chunk.start_line, chunk.end_line)
+                    && hashes.get(&chunk.id) == Some(&chunk.hash)
+            })
+    });
+    *chunks = changed;
+    Ok(unchanged)
+}
+
+/// Copy stored embeddings onto chunks whose content has not changed
+fn reuse_stored_embeddings(
+    db: &Database,
+    code_chunks: &mut [CodeChunk],
+    doc_chunks: &mut [DocChunk],
+) -> Result<()> {
+    for chunk in code_chunks.iter_mut() {
+        if let Some(stored) = db.get_code_chunk(&chunk.id)? {
+            if stored.hash == chunk.hash {
+                chunk.embedding = stored.embedding;
+            }
+        }
+    }
+    for chunk in doc_chunks.iter_mut() {
+        if let Some(stored) = db.get_doc_chunk(&chunk.id)? {
+            if stored.hash == chunk.hash {
+                chunk.embedding = stored.embedding;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Generate embeddings in place for chunks that do not have one yet
+///
+/// `mode` picks which parts of each chunk are embedded.
+/// Returns `false` when `progress` cancelled the scan before every chunk was
+/// embedded; chunks finished so far keep their embeddings.
+async fn embed_chunks(
+    provider: &dyn EmbeddingProvider,
+    mode: EmbeddingTextMode,
+    code_chunks: &mut [CodeChunk],
+    doc_chunks: &mut [DocChunk],
+    progress: &dyn ScanProgress,
+) -> Result<bool> {
+    let mut code_pending: Vec<&mut CodeChunk> = code_chunks
+        .iter_mut()
+        .filter(|c| c.embedding.is_none())
+        .collect();
+    let mut doc_pending: Vec<&mut DocChunk> = doc_chunks
+        .iter_mut()
+        .filter(|c| c.embedding.is_none())
+        .collect();
+    progress.start_phase(ScanPhase::Embedding, code_pending.len() + doc_pending.len());
+
+    for group in code_pending.chunks_mut(CHUNKS_PER_EMBEDDING_STEP) {
+        if progress.cancelled() {
+            return Ok(false);
+        }
+        let texts: Vec<String> = group.iter().map(|c| c.embedding_text_for(mode)).collect();
+        let embeddings = provider.embed_batch(&texts).await?;
+        for (chunk, embedding) in group.iter_mut().zip(embeddings) {
+            chunk.embedding = Some(embedding);
+        }
+        progress.advance(group.len());
+    }
+
+    for group in doc_pending.chunks_mut(CHUNKS_PER_EMBEDDING_STEP) {
+        if progress.cancelled() {
+            return Ok(false);
+        }
+        let texts: Vec<String> = group.iter().map(|c| c.embedding_text_for(mode)).collect();
+        let embeddings = provider.embed_batch(&texts).await?;
+        for (chunk, embedding) in group.iter_mut().zip(embeddings) {
+            chunk.embedding = Some(embedding);
+        }
+        progress.advance(group.len());
+    }
+
+    Ok(true)
+}
+
+/// Result of [`backfill_embeddings`]
+#[derive(Debug, Clone, Default)]
+pub struct BackfillReport {
+    /// Code chunks that were given an embedding
+    pub code_chunks: usize,
+    /// Doc chunks that were given an embedding
+    pub doc_chunks: usize,
+    /// Chunks still without an embedding because the run was cancelled
+    pub remaining: usize,
+    /// Set when the other stored embeddings came from a different model than
+    /// the one configured
+    pub embedding_mismatch: Option<EmbeddingMismatch>,
+}
+
+/// Embed the stored chunks that have no embedding yet
+///
+/// For repositories first scanned without embeddings. Chunks are taken from
+/// the database as they are, so no files are read and git is not touched.
+/// When `progress` reports a cancellation, the chunks embedded so far are
+/// stored and the rest are left for the next run.
+pub fn backfill_embeddings(
+    repo: &Repository,
+    progress: &dyn ScanProgress,
+) -> Result<BackfillReport> {
+    let sentinel_dir = repo.sentinel_dir();
+    if !sentinel_dir.exists() {
+        return Err(DocSentinelError::NotInitialized.into());
+    }
+    if repo.config().embedding_endpoint().is_none() {
+        anyhow::bail!("No embedding endpoint configured");
+    }
+
+    let db = Database::open(sentinel_dir.join("docsentinel.db"))?
+        .with_embedding_model(repo.config().embedding_model());
+    let provider = build_embedding_provider(repo.config());
+    backfill_with_provider(
+        &db,
+        provider.as_ref(),
+        repo.config().llm.embedding_text,
+        repo.config().normalize_embeddings,
+        progress,
+    )
+}
+
+/// Embed and store the chunks in `db` lacking an embedding using `provider`
+///
+/// Chunks embedded before a provider error are still stored.
+fn backfill_with_provider(
+    db: &Database,
+    provider: &dyn EmbeddingProvider,
+    mode: EmbeddingTextMode,
+    normalize_embeddings: bool,
+    progress: &dyn ScanProgress,
+) -> Result<BackfillReport> {
+    let (mut code_chunks, mut doc_chunks) = db.get_chunks_without_embeddings()?;
+    let pending = code_chunks.len() + doc_chunks.len();
+
+    let embedded = tokio::runtime::Runtime::new()?.block_on(embed_chunks(
+        provider,
+        mode,
+        &mut code_chunks,
+        &mut doc_chunks,
+        progress,
+    ));
+    code_chunks.retain(|c| c.embedding.is_some());
+    doc_chunks.retain(|c| c.embedding.is_some());
+
+    let mut report = BackfillReport {
+        code_chunks: code_chunks.len(),
+        doc_chunks: doc_chunks.len(),
+        remaining: pending - code_chunks.len() - doc_chunks.len(),
+        embedding_mismatch: None,
+    };
+    let dim = code_chunks
+        .iter()
+        .filter_map(|c| c.embedding.as_ref())
+        .chain(doc_chunks.iter().filter_map(|c| c.embedding.as_ref()))
+        .map(|e| e.len())
+        .next();
+    if let Some(dim) = dim {
+        report.embedding_mismatch =
+            EmbeddingMismatch::detect(db.embedding_model_in_use()?, provider.model(), dim);
+    }
+
+    if normalize_embeddings {
+        let embeddings = code_chunks
+            .iter_mut()
+            .filter_map(|c| c.embedding.as_mut())
+            .chain(doc_chunks.iter_mut().filter_map(|c| c.embedding.as_mut()));
+        for embedding in embeddings {
+            normalize(embedding);
+        }
+    }
+
+    progress.start_phase(ScanPhase::Storing, code_chunks.len() + doc_chunks.len());
+    for group in code_chunks.chunks(CHUNKS_PER_TRANSACTION) {
+        db.upsert_code_chunks(group)?;
+        progress.advance(group.len());
+    }
+    for group in doc_chunks.chunks(CHUNKS_PER_TRANSACTION) {
+        db.upsert_doc_chunks(group)?;
+        progress.advance(group.len());
+    }
+
+    embedded?;
+    Ok(report)
+}
+
+/// Refine event descriptions, evidence, and fixes with the LLM
+///
+/// Events without a related doc chunk are left untouched. Up to
+/// `llm.concurrency` events are analyzed at once. Successful refinements are
+/// counted in the report's stats, and each failed analysis adds a warning.
+async fn refine_events_with_llm(
+    client: LlmClient,
+    db: &Database,
+    events: &mut [DriftEvent],
+    report: &mut ScanReport,
+) -> Result<()> {
+    let analyzer = DriftAnalyzer::new(client);
+
+    let mut indices = Vec::new();
+    let mut requests = Vec::new();
+    for (index, event) in events.iter().enumerate() {
+        let doc_chunk = match event.related_doc_chunks.first() {
+            Some(doc_id) => match db.get_doc_chunk(doc_id)? {
+                Some(chunk) => chunk,
+                None => continue,
+            },
+            None => continue,
+        };
+
+        let new_code = match event.related_code_chunks.first() {
+            Some(code_id) => db.get_code_chunk(code_id)?,
+            None => None,
+        };
+
+        indices.push(index);
+        requests.push(AnalysisRequest::new(
+            event.clone(),
+            None,
+            new_code,
+            doc_chunk,
+        ));
+    }
+
+    let results = analyzer.analyze_each(requests).await;
+    for (index, result) in indices.into_iter().zip(results) {
+        let event = &mut events[index];
+        match result {
+            Ok(result) => {
+                report.stats.llm_refined += 1;
+                if !result.summary.is_empty() {
+                    event.rewrite_description(result.summary);
+                }
+                if !result.reason.is_empty() {
+                    event.evidence = format!("{}\n\n{}", event.evidence, result.reason);
+                }
+                if result.suggested_fix.is_some() {
+                    event.suggested_fix = result.suggested_fix;
+                }
+            }
+            Err(e) => {
+                report.warnings.push(format!(
+                    "LLM analysis failed for {}: {}",
+                    event_subject(event),
+                    e
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// What an event is about, for messages: its first related code chunk
+/// (`file::symbol`), else its location, else its short ID
+fn event_subject(event: &DriftEvent) -> String {
+    event
+        .related_code_chunks
+        .first()
+        .cloned()
+        .or_else(|| event.location())
+        .unwrap_or_else(|| short_id(&event.id).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drift::test_support::commit_files;
+
+    fn no_embeddings() -> ScanOptions {
+        ScanOptions {
+            embeddings: false,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_event_subject() {
+        let event = DriftEvent::new(DriftSeverity::High, "d", "e", 0.9);
+        assert_eq!(event_subject(&event), short_id(&event.id));
+
+        let event = event.with_location("README.md", 4);
+        assert_eq!(event_subject(&event), "README.md:4");
+
+        let event = event.with_code_chunk("src/lib.rs::add");
+        assert_eq!(event_subject(&event), "src/lib.rs::add");
+    }
+
+    #[test]
+    fn test_scan_repository_returns_report() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = git2::Repository::init(dir.path()).unwrap();
+        commit_files(
+            &git,
+            &[
+                (
+                    "src/lib.rs",
+                    "/// Adds two numbers\npub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n",
+                ),
+                (
+                    "README.md",
+                    "# Demo\n\n## Usage\n\nCall `add(1, 2)` to add numbers.\n",
+                ),
+            ],
+            "initial",
+        );
+
+        let repo = Repository::open(dir.path()).unwrap();
+        assert!(scan_repository(&repo, &ScanOptions::default()).is_err());
+
+        repo.init_sentinel_dir().unwrap();
+        let options = ScanOptions {
+            full: true,
+            ..no_embeddings()
+        };
+        let report = scan_repository(&repo, &options).unwrap();
+
+        assert!(report.stats.from_commit.is_none());
+        assert!(!report.stats.to_commit.is_empty());
+        assert_eq!(report.stats.code_files, 1);
+        assert_eq!(report.stats.doc_files, 1);
+        assert!(!report.stats.embedded);
+        assert!(report.code_chunks.iter().any(|c| c.symbol_name == "add"));
+        assert!(!report.doc_chunks.is_empty());
+    }
+
+    #[test]
+    fn test_include_and_exclude_filter_scanned_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = git2::Repository::init(dir.path()).unwrap();
+        commit_files(
+            &git,
+            &[
+                ("src/lib.rs", "pub fn root() {}\n"),
+                ("src/api/mod.rs", "pub fn api_mod() {}\n"),
+                ("src/api/users.rs", "pub fn list_users() {}\n"),
+                ("tests/smoke.rs", "pub fn smoke() {}\n"),
+                ("README.md", "# Demo\n\n## Usage\n\nCall `root()` first.\n"),
+            ],
+            "initial",
+        );
+        let repo = Repository::open(dir.path()).unwrap();
+        repo.init_sentinel_dir().unwrap();
+        let symbols = |report: &ScanReport| {
+            let mut names: Vec<String> = report
+                .code_chunks
+                .iter()
+                .map(|c| c.symbol_name.clone())
+                .collect();
+            names.sort();
+            names
+        };
+
+        let options = ScanOptions {
+            full: true,
+            include: vec!["src/**".to_string()],
+            ..no_embeddings()
+        };
+        let report = scan_repository(&repo, &options).unwrap();
+        assert_eq!(symbols(&report), vec!["api_mod", "list_users", "root"]);
+        assert_eq!(report.stats.doc_files, 0);
+
+        let options = ScanOptions {
+            full: true,
+            include: vec!["src/**".to_string()],
+            exclude: vec!["**/mod.rs".to_string()],
+            ..no_embeddings()
+        };
+        let report = scan_repository(&repo, &options).unwrap();
+        assert_eq!(symbols(&report), vec!["list_users", "root"]);
+
+        // A filtered scan does not record the commit, so the next scan still
+        // sees the skipped files
+        let db = Database::open(repo.sentinel_dir().join("docsentinel.db")).unwrap();
+        assert!(db.get_last_scan_commit().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_scan_flags_removed_config_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = git2::Repository::init(dir.path()).unwrap();
+        commit_files(
+            &git,
+            &[
+                ("app.toml", "[server]\nport = 8080\nretries = 3\n"),
+                (
+                    "README.md",
+                    "# Demo\n\n## Configuration\n\nSet `server.retries` in app.toml.\n",
+                ),
+            ],
+            "initial",
+        );
+
+        let repo = Repository::open(dir.path()).unwrap();
+        repo.init_sentinel_dir().unwrap();
+        let mut config = repo.config().clone();
+        config.drift.check_config_keys = true;
+        config.save(dir.path()).unwrap();
+        let repo = Repository::open(dir.path()).unwrap();
+
+        scan_repository(&repo, &no_embeddings()).unwrap();
+
+        commit_files(
+            &git,
+            &[("app.toml", "[server]\nport = 8080\n")],
+            "drop retries",
+        );
+        let report = scan_repository(&repo, &no_embeddings()).unwrap();
+
+        assert_eq!(report.stats.config_files, 1);
+        let event = report
+            .events
+            .iter()
+            .find(|e| e.description == "Documented config key removed: server.retries")
+            .unwrap();
+        assert_eq!(event.location().as_deref(), Some("README.md:3"));
+    }
+
+    #[test]
+    fn test_unchanged_function_in_modified_file_is_not_reprocessed() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = git2::Repository::init(dir.path()).unwrap();
+        commit_files(
+            &git,
+            &[(
+                "src/lib.rs",
+                "pub fn first() -> i32 {\n    1\n}\n\npub fn second() -> i32 {\n    2\n}\n",
+            )],
+            "initial",
+        );
+
+        let repo = Repository::open(dir.path()).unwrap();
+        repo.init_sentinel_dir().unwrap();
+        scan_repository(&repo, &no_embeddings()).unwrap();
+
+        // Give the untouched symbol an embedding a re-upsert would drop
+        let db = Database::open(repo.sentinel_dir().join("docsentinel.db")).unwrap();
+        let mut second = db
+            .get_code_chunks_for_file("src/lib.rs")
+            .unwrap()
+            .into_iter()
+            .find(|c| c.symbol_name == "second")
+            .unwrap();
+        assert_eq!(second.start_line, 5);
+        second.embedding = Some(vec![0.5, 0.5]);
+        db.upsert_code_chunk(&second).unwrap();
+
+        commit_files(
+            &git,
+            &[(
+                "src/lib.rs",
+                "pub fn first() -> i32 {\n    let one = 1;\n    one\n}\n\n\
+                 pub fn second() -> i32 {\n    2\n}\n",
+            )],
+            "grow first",
+        );
+        let report = scan_repository(&repo, &no_embeddings()).unwrap();
+
+        assert_eq!(report.stats.unchanged_chunks, 1);
+        let processed: Vec<&str> = report
+            .code_chunks
+            .iter()
+            .map(|c| c.symbol_name.as_str())
+            .collect();
+        assert_eq!(processed, vec!["first"]);
+
+        let stored = db.get_code_chunk(&second.id).unwrap().unwrap();
+        assert_eq!(stored.embedding, Some(vec![0.5, 0.5]));
+        assert_eq!((stored.start_line, stored.end_line), (6, 8));
+    }
+
+    #[test]
+    fn test_renamed_file_keeps_chunk_embeddings() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = git2::Repository::init(dir.path()).unwrap();
+        let source = "pub fn first() -> i32 {\n    1\n}\n\npub fn second() -> i32 {\n    2\n}\n";
+        commit_files(&git, &[("src/old.rs", source)], "initial");
+
+        let repo = Repository::open(dir.path()).unwrap();
+        repo.init_sentinel_dir().unwrap();
+        scan_repository(&repo, &no_embeddings()).unwrap();
+
+        let db = Database::open(repo.sentinel_dir().join("docsentinel.db")).unwrap();
+        for mut chunk in db.get_code_chunks_for_file("src/old.rs").unwrap() {
+            chunk.embedding = Some(vec![0.5, 0.5]);
+            db.upsert_code_chunk(&chunk).unwrap();
+        }
+
+        // Move the file with git, as `git mv` would
+        let mut index = git.index().unwrap();
+        index.remove_path(Path::new("src/old.rs")).unwrap();
+        std::fs::remove_file(dir.path().join("src/old.rs")).unwrap();
+        commit_files(&git, &[("src/new.rs", source)], "rename");
+
+        let changes = repo.changes_between(Some("HEAD~1"), "HEAD").unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].kind, crate::repo::ChangeKind::Renamed);
+        assert_eq!(changes[0].old_path(), Path::new("src/old.rs"));
+
+        scan_repository(&repo, &no_embeddings()).unwrap();
+
+        assert!(db
+            .get_code_chunks_for_file("src/old.rs")
+            .unwrap()
+            .is_empty());
+        let moved = db.get_code_chunks_for_file("src/new.rs").unwrap();
+        assert_eq!(moved.len(), 2);
+        for chunk in moved {
+            assert!(chunk.id.starts_with("src/new.rs::"));
+            assert_eq!(chunk.embedding, Some(vec![0.5, 0.5]));
+        }
+    }
+
+    #[test]
+    fn test_scan_flags_doc_with_stale_signature() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = git2::Repository::init(dir.path()).unwrap();
+        commit_files(
+            &git,
+            &[
+                (
+                    "src/lib.rs",
+                    "pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n",
+                ),
+                ("README.md", "# Demo\n\n## Usage\n\nAdds numbers.\n"),
+            ],
+            "initial",
+        );
+
+        let repo = Repository::open(dir.path()).unwrap();
+        repo.init_sentinel_dir().unwrap();
+        scan_repository(&repo, &no_embeddings()).unwrap();
+
+        // Only the docs change, and they show the wrong parameter list
+        commit_files(
+            &git,
+            &[(
+                "README.md",
+                "# Demo\n\n## Usage\n\n```rust\npub fn add(a: i32) -> i32\n```\n",
+            )],
+            "document add",
+        );
+        let report = scan_repository(&repo, &no_embeddings()).unwrap();
+
+        let event = report
+            .events
+            .iter()
+            .find(|e| e.description == "Documented signature out of date: add")
+            .unwrap();
+        assert_eq!(event.severity, DriftSeverity::High);
+        assert_eq!(
+            event.related_code_chunks,
+            vec!["src/lib.rs::add".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_full_scan_compares_against_default_branch() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = git2::Repository::init(dir.path()).unwrap();
+        commit_files(
+            &git,
+            &[
+                (
+                    "src/lib.rs",
+                    "pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n",
+                ),
+                (
+                    "README.md",
+                    "# Demo\n\n## Usage\n\n<!-- docsentinel: src/lib.rs::add -->\n\
+                     Call `add(1, 2)`.\n",
+                ),
+            ],
+            "initial",
+        );
+
+        // Work on a feature branch, leaving the default branch at the base
+        let base_commit = git.head().unwrap().peel_to_commit().unwrap();
+        let base = base_commit.id().to_string();
+        git.branch("feature", &base_commit, false).unwrap();
+        git.set_head("refs/heads/feature").unwrap();
+        commit_files(
+            &git,
+            &[("src/lib.rs", "pub fn add(a: i32) -> i32 {\n    a\n}\n")],
+            "drop b",
+        );
+
+        let repo = Repository::open(dir.path()).unwrap();
+        assert_eq!(repo.default_branch().unwrap().as_deref(), Some("master"));
+        repo.init_sentinel_dir().unwrap();
+        let options = ScanOptions {
+            full: true,
+            ..no_embeddings()
+        };
+        let report = scan_repository(&repo, &options).unwrap();
+
+        assert_eq!(report.stats.base_commit.as_deref(), Some(base.as_str()));
+        // The first scan still sees add's old signature at the base
+        assert!(report
+            .events
+            .iter()
+            .any(|e| e.evidence.starts_with("Signature changed from:")
+                && e.related_code_chunks == vec!["src/lib.rs::add".to_string()]));
+    }
+
+    #[test]
+    fn test_repeated_scans_render_identical_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = git2::Repository::init(dir.path()).unwrap();
+        commit_files(
+            &git,
+            &[
+                (
+                    "src/lib.rs",
+                    "pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n\n\
+                     pub fn sub(a: i32, b: i32) -> i32 {\n    a - b\n}\n\n\
+                     pub fn neg(a: i32) -> i32 {\n    -a\n}\n",
+                ),
+                (
+                    "README.md",
+                    "# Demo\n\n## Add\n\n<!-- docsentinel: src/lib.rs::add -->\n\
+                     Call `add(1, 2)`.\n\n## Sub\n\n<!-- docsentinel: src/lib.rs::sub -->\n\
+                     Call `sub(2, 1)`.\n",
+                ),
+            ],
+            "initial",
+        );
+
+        let base_commit = git.head().unwrap().peel_to_commit().unwrap();
+        git.branch("feature", &base_commit, false).unwrap();
+        git.set_head("refs/heads/feature").unwrap();
+        commit_files(
+            &git,
+            &[(
+                "src/lib.rs",
+                "pub fn add(a: i32) -> i32 {\n    a\n}\n\n\
+                 pub fn sub(a: i32) -> i32 {\n    a\n}\n\n\
+                 fn neg(a: i32) -> i32 {\n    -a\n}\n",
+            )],
+            "change api",
+        );
+
+        let repo = Repository::open(dir.path()).unwrap();
+        repo.init_sentinel_dir().unwrap();
+        let options = ScanOptions {
+            full: true,
+            ..no_embeddings()
+        };
+        let first = scan_repository(&repo, &options).unwrap();
+        let second = scan_repository(&repo, &options).unwrap();
+
+        assert!(first.events.len() > 1);
+        assert!(first
+            .events
+            .windows(2)
+            .all(|pair| pair[0].cmp_priority(&pair[1]).is_le()));
+        assert_eq!(
+            crate::cli::render_events_json(&first.events, false).unwrap(),
+            crate::cli::render_events_json(&second.events, false).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_severity_override_escalates_matching_event() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = git2::Repository::init(dir.path()).unwrap();
+        commit_files(
+            &git,
+            &[
+                (
+                    "src/lib.rs",
+                    "pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n",
+                ),
+                ("README.md", "# Demo\n\n## Usage\n\nAdds numbers.\n"),
+            ],
+            "initial",
+        );
+
+        let repo = Repository::open(dir.path()).unwrap();
+        repo.init_sentinel_dir().unwrap();
+        scan_repository(&repo, &no_embeddings()).unwrap();
+
+        let mut config = repo.config().clone();
+        config
+            .severity_overrides
+            .insert("README.md".to_string(), DriftSeverity::Critical);
+        config.save(dir.path()).unwrap();
+        let repo = Repository::open(dir.path()).unwrap();
+
+        commit_files(
+            &git,
+            &[(
+                "README.md",
+                "# Demo\n\n## Usage\n\n```rust\npub fn add(a: i32) -> i32\n```\n",
+            )],
+            "document add",
+        );
+        let report = scan_repository(&repo, &no_embeddings()).unwrap();
+
+        let event = report
+            .events
+            .iter()
+            .find(|e| e.description == "Documented signature out of date: add")
+            .unwrap();
+        assert_eq!(event.severity, DriftSeverity::Critical);
+    }
+
+    /// Scan a Python method before and after it becomes a `@property`
+    /// whose doc comment and body also change, returning the event tags
+    fn decorator_change_tags(use_soft_rules: bool) -> Vec<String> {
+        let dir = tempfile::tempdir().unwrap();
+        let git = git2::Repository::init(dir.path()).unwrap();
+        commit_files(
+            &git,
+            &[
+                (
+                    "account.py",
+                    "class Account:\n    def balance(self):\n        \"\"\"Balance in dollars\"\"\"\n        return self.dollars\n",
+                ),
+                (
+                    "README.md",
+                    "# Demo\n\n## Balance\n\n<!-- docsentinel: Account.balance -->\nCall `balance()`.\n",
+                ),
+            ],
+            "initial",
+        );
+
+        let repo = Repository::open(dir.path()).unwrap();
+        repo.init_sentinel_dir().unwrap();
+        let mut config = repo.config().clone();
+        config.drift.use_soft_rules = use_soft_rules;
+        config.save(dir.path()).unwrap();
+        let repo = Repository::open(dir.path()).unwrap();
+        scan_repository(&repo, &no_embeddings()).unwrap();
+
+        commit_files(
+            &git,
+            &[(
+                "account.py",
+                "class Account:\n    @property\n    def balance(self):\n        \"\"\"Balance in cents\"\"\"\n        return self.cents\n",
+            )],
+            "make balance a property",
+        );
+        let report = scan_repository(&repo, &no_embeddings()).unwrap();
+        report.events.into_iter().flat_map(|e| e.tags).collect()
+    }
+
+    #[test]
+    fn test_scan_applies_only_the_decorator_soft_rule() {
+        let tags = decorator_change_tags(true);
+        assert!(tags.iter().any(|t| t == "decorator_change"), "{:?}", tags);
+        assert!(
+            !tags.iter().any(|t| t == "doc_comment_change"),
+            "{:?}",
+            tags
+        );
+        assert!(!tags.iter().any(|t| t == "behavior_change"), "{:?}", tags);
+    }
+
+    #[test]
+    fn test_use_soft_rules_false_suppresses_soft_rule_events() {
+        let tags = decorator_change_tags(false);
+        assert!(!tags.iter().any(|t| t == "decorator_change"), "{:?}", tags);
+    }
+
+    #[test]
+    fn test_permanently_ignored_symbol_is_not_flagged() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = git2::Repository::init(dir.path()).unwrap();
+        commit_files(
+            &git,
+            &[
+                (
+                    "src/lib.rs",
+                    "pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n",
+                ),
+                ("README.md", "# Demo\n\n## Usage\n\nCall `add(1, 2)`.\n"),
+            ],
+            "initial",
+        );
+
+        let repo = Repository::open(dir.path()).unwrap();
+        repo.init_sentinel_dir().unwrap();
+        scan_repository(&repo, &no_embeddings()).unwrap();
+
+        commit_files(
+            &git,
+            &[("src/lib.rs", "pub fn add(a: i32) -> i32 {\n    a\n}\n")],
+            "drop b",
+        );
+        let report = scan_repository(&repo, &no_embeddings()).unwrap();
+        let event = report
+            .events
+            .iter()
+            .find(|e| {
+                e.related_code_chunks
+                    .contains(&"src/lib.rs::add".to_string())
+            })
+            .unwrap();
+
+        let mut config = repo.config().clone();
+        assert_eq!(config.ignore_event(event), vec!["symbol add".to_string()]);
+        config.save(dir.path()).unwrap();
+        let repo = Repository::open(dir.path()).unwrap();
+
+        commit_files(
+            &git,
+            &[("src/lib.rs", "pub fn add(a: i64) -> i64 {\n    a\n}\n")],
+            "widen a",
+        );
+        let report = scan_repository(&repo, &no_embeddings()).unwrap();
+        assert!(report.events.iter().all(|e| !e
+            .related_code_chunks
+            .contains(&"src/lib.rs::add".to_string())));
+    }
+
+    #[test]
+    fn test_scan_skips_oversized_and_binary_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = git2::Repository::init(dir.path()).unwrap();
+        let generated = format!("pub fn generated() {{}}\n{}", "// filler\n".repeat(100));
+        commit_files(
+            &git,
+            &[
+                (
+                    "src/lib.rs",
+                    "pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n",
+                ),
+                ("src/generated.rs", &generated),
+                ("src/blob.rs", "pub fn blob() {}\n\0\0\0"),
+            ],
+            "initial",
+        );
+
+        let repo = Repository::open(dir.path()).unwrap();
+        repo.init_sentinel_dir().unwrap();
+        let mut config = repo.config().clone();
+        config.max_file_bytes = 500;
+        config.save(dir.path()).unwrap();
+        let repo = Repository::open(dir.path()).unwrap();
+
+        let report = scan_repository(&repo, &no_embeddings()).unwrap();
+        let symbols: Vec<&str> = report
+            .code_chunks
+            .iter()
+            .map(|c| c.symbol_name.as_str())
+            .collect();
+        assert_eq!(symbols, vec!["add"]);
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].contains("src/generated.rs"));
+        assert!(report.warnings[0].contains("max_file_bytes"));
+    }
+
+    /// Returns a constant vector for every text
+    struct StubEmbedding;
+
+    #[async_trait::async_trait]
+    impl EmbeddingProvider for StubEmbedding {
+        async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+            Ok(texts.iter().map(|_| vec![1.0, 0.0]).collect())
+        }
+
+        fn dimension(&self) -> usize {
+            2
+        }
+
+        fn model(&self) -> &str {
+            "stub"
+        }
+    }
+
+    /// Cancels once `limit` units of work have finished
+    struct CancelAfter {
+        done: std::sync::atomic::AtomicUsize,
+        limit: usize,
+    }
+
+    impl ScanProgress for CancelAfter {
+        fn advance(&self, count: usize) {
+            self.done
+                .fetch_add(count, std::sync::atomic::Ordering::SeqCst);
+        }
+
+        fn cancelled(&self) -> bool {
+            self.done.load(std::sync::atomic::Ordering::SeqCst) >= self.limit
+        }
+    }
+
+    #[test]
+    fn test_scan_skips_files_that_are_not_utf8() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = git2::Repository::init(dir.path()).unwrap();
+        commit_files(
+            &git,
+            &[
+                (
+                    "src/lib.rs",
+                    "pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n",
+                ),
+                ("README.md", "# Demo\n\n## Usage\n\nCall `add(1, 2)`.\n"),
+                ("CHANGELOG.md", "# Changes\n"),
+            ],
+            "initial",
+        );
+        // A Latin-1 changelog in the working tree
+        std::fs::write(
+            dir.path().join("CHANGELOG.md"),
+            b"# Changes\n\n## 1.0\n\nCaf\xe9 release notes.\n",
+        )
+        .unwrap();
+
+        let repo = Repository::open(dir.path()).unwrap();
+        repo.init_sentinel_dir().unwrap();
+        let report = scan_repository(&repo, &no_embeddings()).unwrap();
+
+        assert!(report.doc_chunks.iter().any(|c| c.file_path == "README.md"));
+        assert!(report
+            .doc_chunks
+            .iter()
+            .all(|c| c.file_path != "CHANGELOG.md"));
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].contains("CHANGELOG.md"));
+        assert!(report.warnings[0].contains("not valid UTF-8"));
+    }
+
+    #[test]
+    fn test_embedding_stops_when_cancelled() {
+        use crate::extract::code::{Language, SymbolType};
+
+        let mut code: Vec<CodeChunk> = (0..CHUNKS_PER_EMBEDDING_STEP * 2)
+            .map(|i| {
+                CodeChunk::new(
+                    "src/lib.rs",
+                    &format!("f{}", i),
+                    SymbolType::Function,
+                    &format!("fn f{}() {{}}", i),
+                    Language::Rust,
+                    i + 1,
+                    i + 1,
+                )
+            })
+            .collect();
+        let progress = CancelAfter {
+            done: Default::default(),
+            limit: 1,
+        };
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let completed = rt
+            .block_on(embed_chunks(
+                &StubEmbedding,
+                EmbeddingTextMode::Full,
+                &mut code,
+                &mut [],
+                &progress,
+            ))
+            .unwrap();
+        assert!(!completed);
+        let embedded = code.iter().filter(|c| c.embedding.is_some()).count();
+        assert_eq!(embedded, CHUNKS_PER_EMBEDDING_STEP);
+
+        // Resuming only embeds the chunks that are still missing
+        let completed = rt
+            .block_on(embed_chunks(
+                &StubEmbedding,
+                EmbeddingTextMode::Full,
+                &mut code,
+                &mut [],
+                &(),
+            ))
+            .unwrap();
+        assert!(completed);
+        assert!(code.iter().all(|c| c.embedding.is_some()));
+    }
+
+    #[test]
+    fn test_backfill_embeds_every_chunk_without_one() {
+        use crate::drift::embedding::MockEmbedding;
+
+        let dir = tempfile::tempdir().unwrap();
+        let git = git2::Repository::init(dir.path()).unwrap();
+        commit_files(
+            &git,
+            &[
+                (
+                    "src/lib.rs",
+                    "/// Adds\npub fn add(a: i32) -> i32 {\n    a\n}\n\npub struct Point;\n",
+                ),
+                ("README.md", "# Demo\n\n## Usage\n\nCall `add(1)`.\n"),
+            ],
+            "initial",
+        );
+        let repo = Repository::open(dir.path()).unwrap();
+        repo.init_sentinel_dir().unwrap();
+        let options = ScanOptions {
+            full: true,
+            ..no_embeddings()
+        };
+        scan_repository(&repo, &options).unwrap();
+
+        let db = Database::open(repo.sentinel_dir().join("docsentinel.db"))
+            .unwrap()
+            .with_embedding_model("mock");
+        let (code, docs) = db.get_chunks_without_embeddings().unwrap();
+        assert!(!code.is_empty() && !docs.is_empty());
+
+        let report = backfill_with_provider(
+            &db,
+            &MockEmbedding::new(8),
+            EmbeddingTextMode::Full,
+            true,
+            &(),
+        )
+        .unwrap();
+
+        assert_eq!(report.code_chunks, code.len());
+        assert_eq!(report.doc_chunks, docs.len());
+        assert_eq!(report.remaining, 0);
+        assert!(report.embedding_mismatch.is_none());
+        let (code, docs) = db.get_chunks_without_embeddings().unwrap();
+        assert!(code.is_empty() && docs.is_empty());
+        assert_eq!(
+            db.embedding_model_in_use().unwrap(),
+            Some(("mock".to_string(), 8))
+        );
+        let stored = db.get_all_code_chunks_with_embeddings().unwrap();
+        assert_eq!(stored.len(), report.code_chunks);
+        let norm: f32 = stored[0]
+            .embedding
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|x| x * x)
+            .sum();
+        assert!((norm - 1.0).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,30 @@
+//! Helpers shared by the drift tests
+
+use std::path::Path;
+
+/// Write files into the working tree and commit them on top of HEAD
+///
+/// Returns the new commit's hash.
+pub(crate) fn commit_files(
+    git: &git2::Repository,
+    files: &[(&str, &str)],
+    message: &str,
+) -> String {
+    let root = git.workdir().unwrap().to_path_buf();
+    let mut index = git.index().unwrap();
+    for (path, content) in files {
+        let full_path = root.join(path);
+        std::fs::create_dir_all(full_path.parent().unwrap()).unwrap();
+        std::fs::write(&full_path, content).unwrap();
+        index.add_path(Path::new(path)).unwrap();
+    }
+    index.write().unwrap();
+
+    let tree = git.find_tree(index.write_tree().unwrap()).unwrap();
+    let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+    let parent = git.head().ok().and_then(|h| h.peel_to_commit().ok());
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    git.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+        .unwrap()
+        .to_string()
+}
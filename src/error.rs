@@ -0,0 +1,116 @@
+//! Error types for DocSentinel
+//!
+//! Library modules return [`DocSentinelError`] so callers can match on
+//! specific failures. The CLI converts these into `anyhow::Error` at its
+//! boundary; callers holding an `anyhow::Error` can recover the variant with
+//! `downcast_ref::<DocSentinelError>()`.
+
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Result type used by the library modules
+pub type Result<T, E = DocSentinelError> = std::result::Result<T, E>;
+
+/// Errors produced by DocSentinel
+#[derive(Debug, Error)]
+pub enum DocSentinelError {
+    /// The `.docsentinel` directory does not exist
+    #[error("DocSentinel not initialized. Run 'docsentinel init' first.")]
+    NotInitialized,
+
+    /// SQLite failure (missing, locked, or corrupt database)
+    #[error("Database error: {0}")]
+    Database(#[from] rusqlite::Error),
+
+    /// Git failure
+    #[error("Git error: {0}")]
+    Git(#[from] git2::Error),
+
+    /// A revision could not be resolved to a commit
+    #[error("Failed to parse revision: {revision}")]
+    InvalidRevision {
+        revision: String,
+        #[source]
+        source: git2::Error,
+    },
+
+    /// The repository has no working directory
+    #[error("Repository has no working directory (bare repo?)")]
+    BareRepository,
+
+    /// A path lies outside the repository root
+    #[error("Path is not within repository: {0:?}")]
+    OutsideRepository(PathBuf),
+
+    /// Filesystem failure
+    #[error("Failed to access {path:?}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// Invalid configuration file or value
+    #[error("Invalid configuration: {0}")]
+    Config(String),
+
+    /// A stored record could not be decoded
+    #[error("Corrupt stored data: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    /// A tree-sitter grammar could not be loaded
+    #[error("Failed to load parser grammar: {0}")]
+    Grammar(#[from] tree_sitter::LanguageError),
+
+    /// A file exceeds the configured `max_file_bytes`
+    #[error("Skipped {path:?}: {size} bytes exceeds max_file_bytes ({limit})")]
+    FileTooLarge {
+        path: PathBuf,
+        size: u64,
+        limit: u64,
+    },
+
+    /// A text file's content is not valid UTF-8
+    #[error("Skipped {path:?}: content is not valid UTF-8")]
+    InvalidUtf8 { path: PathBuf },
+
+    /// A file could not be extracted into chunks
+    #[error("Failed to extract {path}: {message}")]
+    Extract { path: String, message: String },
+
+    /// An exported baseline cannot be loaded into this database
+    #[error("Cannot import: {0}")]
+    Import(String),
+
+    /// The LLM endpoint failed or returned an unusable response
+    #[error("LLM error: {message}")]
+    Llm { message: String },
+}
+
+impl DocSentinelError {
+    /// Build a [`DocSentinelError::Io`] for the given path
+    pub fn io(path: impl Into<PathBuf>, source: std::io::Error) -> Self {
+        DocSentinelError::Io {
+            path: path.into(),
+            source,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_survives_anyhow_boundary() {
+        let err: anyhow::Error = DocSentinelError::NotInitialized.into();
+        assert!(matches!(
+            err.downcast_ref::<DocSentinelError>(),
+            Some(DocSentinelError::NotInitialized)
+        ));
+        assert_eq!(
+            err.to_string(),
+            "DocSentinel not initialized. Run 'docsentinel init' first."
+        );
+    }
+}
@@ -6,10 +6,11 @@
 //! - Structs / classes
 //! - Doc comments
 
-use super::{content_hash, Chunk};
-use anyhow::{Context, Result};
+use super::{content_hash, Chunk, EmbeddingTextMode};
+use crate::error::{DocSentinelError, Result};
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Supported programming languages
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -82,6 +83,12 @@ pub struct CodeChunk {
     pub signature: Option<String>,
     /// Whether this is a public symbol
     pub is_public: bool,
+    /// Decorators applied to the symbol, without the leading `@` (Python)
+    #[serde(default)]
+    pub decorators: Vec<String>,
+    /// `deprecated`, `cfg`, and `cfg_attr` attributes, without `#[...]` (Rust)
+    #[serde(default)]
+    pub attributes: Vec<String>,
     /// Embedding vector (populated later)
     #[serde(skip)]
     pub embedding: Option<Vec<f32>>,
@@ -132,10 +139,33 @@ impl CodeChunk {
             doc_comment: None,
             signature: None,
             is_public: false,
+            decorators: Vec::new(),
+            attributes: Vec::new(),
             embedding: None,
         }
     }
 
+    /// Set the symbol's attributes
+    ///
+    /// Rust attributes sit outside the item's node, so they are folded into
+    /// the hash here to make adding or removing one a change.
+    pub fn with_attributes(mut self, attributes: Vec<String>) -> Self {
+        if !attributes.is_empty() {
+            self.hash = content_hash(&format!("{}\n{}", attributes.join("\n"), self.content));
+        }
+        self.attributes = attributes;
+        self
+    }
+
+    /// Whether the symbol carries a `#[deprecated]` attribute
+    pub fn is_deprecated(&self) -> bool {
+        self.attributes.iter().any(|attr| {
+            attr == "deprecated"
+                || attr.starts_with("deprecated(")
+                || attr.starts_with("deprecated ")
+        })
+    }
+
     /// Get a summary suitable for embedding
     pub fn embedding_text(&self) -> String {
         let mut parts = Vec::new();
@@ -150,6 +180,23 @@ impl CodeChunk {
             parts.push(format!("Signature: {}", sig));
         }
 
+        // Include decorators if present
+        if !self.decorators.is_empty() {
+            let decorators: Vec<String> =
+                self.decorators.iter().map(|d| format!("@{}", d)).collect();
+            parts.push(format!("Decorators: {}", decorators.join(", ")));
+        }
+
+        // Include attributes if present
+        if !self.attributes.is_empty() {
+            let attributes: Vec<String> = self
+                .attributes
+                .iter()
+                .map(|a| format!("#[{}]", a))
+                .collect();
+            parts.push(format!("Attributes: {}", attributes.join(", ")));
+        }
+
         // Include symbol info
         parts.push(format!(
             "{} {} in {}",
@@ -158,6 +205,26 @@ impl CodeChunk {
 
         parts.join("\n")
     }
+
+    /// Get the text to embed for the given mode
+    ///
+    /// [`EmbeddingTextMode::Full`] is the same as [`Self::embedding_text`].
+    pub fn embedding_text_for(&self, mode: EmbeddingTextMode) -> String {
+        let signature = || match self.signature {
+            Some(ref sig) => sig.clone(),
+            None => format!("{} {}", self.symbol_type, self.symbol_name),
+        };
+
+        match mode {
+            EmbeddingTextMode::Full => self.embedding_text(),
+            EmbeddingTextMode::SignatureOnly => signature(),
+            EmbeddingTextMode::DocCommentOnly => match self.doc_comment.as_deref() {
+                Some(doc) if !doc.trim().is_empty() => doc.to_string(),
+                _ => signature(),
+            },
+            EmbeddingTextMode::Content => self.content.clone(),
+        }
+    }
 }
 
 /// Type of code symbol
@@ -191,6 +258,30 @@ impl std::fmt::Display for SymbolType {
     }
 }
 
+impl std::str::FromStr for SymbolType {
+    type Err = DocSentinelError;
+
+    /// Parse the names printed by `Display` (case-insensitive)
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "function" => Ok(SymbolType::Function),
+            "method" => Ok(SymbolType::Method),
+            "struct" => Ok(SymbolType::Struct),
+            "class" => Ok(SymbolType::Class),
+            "enum" => Ok(SymbolType::Enum),
+            "trait" => Ok(SymbolType::Trait),
+            "impl" => Ok(SymbolType::Impl),
+            "module" => Ok(SymbolType::Module),
+            "constant" => Ok(SymbolType::Constant),
+            _ => Err(DocSentinelError::Config(format!(
+                "Unknown symbol kind: {} (expected function, method, struct, class, enum, \
+                 trait, impl, module, or constant)",
+                s
+            ))),
+        }
+    }
+}
+
 /// Extracts code chunks from source files
 pub struct CodeExtractor {
     rust_parser: tree_sitter::Parser,
@@ -201,14 +292,10 @@ impl CodeExtractor {
     /// Create a new code extractor
     pub fn new() -> Result<Self> {
         let mut rust_parser = tree_sitter::Parser::new();
-        rust_parser
-            .set_language(&Language::Rust.tree_sitter_language())
-            .context("Failed to set Rust language")?;
+        rust_parser.set_language(&Language::Rust.tree_sitter_language())?;
 
         let mut python_parser = tree_sitter::Parser::new();
-        python_parser
-            .set_language(&Language::Python.tree_sitter_language())
-            .context("Failed to set Python language")?;
+        python_parser.set_language(&Language::Python.tree_sitter_language())?;
 
         Ok(Self {
             rust_parser,
@@ -220,25 +307,77 @@ impl CodeExtractor {
     pub fn extract_file(&mut self, path: &Path, content: &str) -> Result<Vec<CodeChunk>> {
         let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
 
-        let language = Language::from_extension(extension)
-            .ok_or_else(|| anyhow::anyhow!("Unsupported language: {}", extension))?;
+        let language =
+            Language::from_extension(extension).ok_or_else(|| DocSentinelError::Extract {
+                path: path.to_string_lossy().to_string(),
+                message: format!("Unsupported language: {}", extension),
+            })?;
+
+        self.extract_source(path, content, language)
+    }
 
+    /// Extract chunks from source in a known language
+    ///
+    /// `path` only names the chunks, so it need not exist or carry the
+    /// language's extension (e.g. an unsaved editor buffer).
+    pub fn extract_source(
+        &mut self,
+        path: &Path,
+        content: &str,
+        language: Language,
+    ) -> Result<Vec<CodeChunk>> {
         match language {
             Language::Rust => self.extract_rust(path, content),
             Language::Python => self.extract_python(path, content),
         }
     }
 
+    /// Extract chunks from many files in parallel
+    ///
+    /// tree-sitter parsers are not `Sync`, so each rayon worker owns its own
+    /// extractor. Results are returned in the same order as `files`.
+    pub fn extract_files(files: &[(PathBuf, String)]) -> Vec<Result<Vec<CodeChunk>>> {
+        files
+            .par_iter()
+            .map_init(CodeExtractor::new, |extractor, (path, content)| {
+                extractor
+                    .as_mut()
+                    .map_err(|e| DocSentinelError::Extract {
+                        path: path.to_string_lossy().to_string(),
+                        message: e.to_string(),
+                    })?
+                    .extract_file(path, content)
+            })
+            .collect()
+    }
+
     /// Extract chunks from Rust code
     fn extract_rust(&mut self, path: &Path, content: &str) -> Result<Vec<CodeChunk>> {
         let tree = self
             .rust_parser
             .parse(content, None)
-            .ok_or_else(|| anyhow::anyhow!("Failed to parse Rust file"))?;
+            .ok_or_else(|| parse_failed(path, "Rust"))?;
 
         let mut chunks = Vec::new();
         let file_path = path.to_string_lossy().to_string();
 
+        // Module-level docs (`//!`) at the top of the file
+        if let Some((doc, end_row)) = rust_inner_doc(content) {
+            let lines: Vec<&str> = content.lines().take(end_row + 1).collect();
+            let mut chunk = CodeChunk::new(
+                &file_path,
+                &rust_module_name(path),
+                SymbolType::Module,
+                &lines.join("\n"),
+                Language::Rust,
+                1,
+                end_row + 1,
+            );
+            chunk.is_public = true;
+            chunk.doc_comment = Some(doc);
+            chunks.push(chunk);
+        }
+
         self.walk_rust_tree(tree.root_node(), content, &file_path, &mut chunks);
 
         Ok(chunks)
@@ -275,6 +414,11 @@ impl CodeExtractor {
                     chunks.push(chunk);
                 }
             }
+            "mod_item" => {
+                if let Some(chunk) = self.extract_rust_module(node, source, file_path) {
+                    chunks.push(chunk);
+                }
+            }
             "impl_item" => {
                 // Extract methods from impl blocks
                 self.extract_rust_impl_methods(node, source, file_path, chunks);
@@ -311,7 +455,8 @@ impl CodeExtractor {
             Language::Rust,
             start_line,
             end_line,
-        );
+        )
+        .with_attributes(self.extract_rust_attributes(node, source));
 
         // Check for pub visibility
         chunk.is_public = self.has_rust_visibility(node, source);
@@ -325,6 +470,47 @@ impl CodeExtractor {
         Some(chunk)
     }
 
+    /// Extract a documented inline Rust module
+    ///
+    /// Outer (`///`) and inner (`//!`) docs are joined. Undocumented modules
+    /// and `mod name;` declarations, whose docs live in their own file, are
+    /// skipped.
+    fn extract_rust_module(
+        &self,
+        node: tree_sitter::Node,
+        source: &str,
+        file_path: &str,
+    ) -> Option<CodeChunk> {
+        let body = node.child_by_field_name("body")?;
+        let name_node = node.child_by_field_name("name")?;
+        let name = name_node.utf8_text(source.as_bytes()).ok()?;
+
+        let body_text = body.utf8_text(source.as_bytes()).ok()?;
+        let inner = rust_inner_doc(body_text.strip_prefix('{')?).map(|(doc, _)| doc);
+        let outer = self.extract_rust_doc_comment(node, source);
+        let doc = match (outer, inner) {
+            (Some(outer), Some(inner)) => format!("{}\n\n{}", outer, inner),
+            (outer, inner) => outer.or(inner)?,
+        };
+
+        let content = node.utf8_text(source.as_bytes()).ok()?;
+        let mut chunk = CodeChunk::new(
+            file_path,
+            name,
+            SymbolType::Module,
+            content,
+            Language::Rust,
+            node.start_position().row + 1,
+            node.end_position().row + 1,
+        )
+        .with_attributes(self.extract_rust_attributes(node, source));
+
+        chunk.is_public = self.has_rust_visibility(node, source);
+        chunk.doc_comment = Some(doc);
+
+        Some(chunk)
+    }
+
     /// Extract a Rust struct
     fn extract_rust_struct(
         &self,
@@ -347,7 +533,8 @@ impl CodeExtractor {
             Language::Rust,
             start_line,
             end_line,
-        );
+        )
+        .with_attributes(self.extract_rust_attributes(node, source));
 
         chunk.is_public = self.has_rust_visibility(node, source);
         chunk.doc_comment = self.extract_rust_doc_comment(node, source);
@@ -377,7 +564,8 @@ impl CodeExtractor {
             Language::Rust,
             start_line,
             end_line,
-        );
+        )
+        .with_attributes(self.extract_rust_attributes(node, source));
 
         chunk.is_public = self.has_rust_visibility(node, source);
         chunk.doc_comment = self.extract_rust_doc_comment(node, source);
@@ -407,7 +595,8 @@ impl CodeExtractor {
             Language::Rust,
             start_line,
             end_line,
-        );
+        )
+        .with_attributes(self.extract_rust_attributes(node, source));
 
         chunk.is_public = self.has_rust_visibility(node, source);
         chunk.doc_comment = self.extract_rust_doc_comment(node, source);
@@ -450,7 +639,8 @@ impl CodeExtractor {
                                     Language::Rust,
                                     start_line,
                                     end_line,
-                                );
+                                )
+                                .with_attributes(self.extract_rust_attributes(item, source));
 
                                 chunk.is_public = self.has_rust_visibility(item, source);
                                 chunk.doc_comment = self.extract_rust_doc_comment(item, source);
@@ -479,22 +669,110 @@ impl CodeExtractor {
         false
     }
 
+    /// Attribute nodes directly above a Rust item, nearest first
+    ///
+    /// Doc comments may sit between attributes, so they are stepped over.
+    fn rust_attribute_nodes<'a>(&self, node: tree_sitter::Node<'a>) -> Vec<tree_sitter::Node<'a>> {
+        let mut attributes = Vec::new();
+        let mut sibling = node.prev_sibling();
+        while let Some(prev) = sibling {
+            match prev.kind() {
+                "attribute_item" => attributes.push(prev),
+                "line_comment" | "block_comment" => {}
+                _ => break,
+            }
+            sibling = prev.prev_sibling();
+        }
+        attributes
+    }
+
+    /// Extract the `deprecated`/`cfg` attributes that apply to a Rust item
+    ///
+    /// `cfg` attributes on enclosing modules and impl blocks are inherited,
+    /// since they gate every item inside.
+    fn extract_rust_attributes(&self, node: tree_sitter::Node, source: &str) -> Vec<String> {
+        let attribute_text = |attr: tree_sitter::Node| -> Option<String> {
+            let text = attr.utf8_text(source.as_bytes()).ok()?;
+            let inner = text.trim().strip_prefix("#[")?.strip_suffix(']')?;
+            Some(inner.split_whitespace().collect::<Vec<_>>().join(" "))
+        };
+        let path = |attr: &str| -> String {
+            attr.split(|c: char| c == '(' || c == '=' || c.is_whitespace())
+                .next()
+                .unwrap_or_default()
+                .to_string()
+        };
+
+        let mut attributes: Vec<String> = self
+            .rust_attribute_nodes(node)
+            .into_iter()
+            .rev()
+            .filter_map(attribute_text)
+            .filter(|attr| matches!(path(attr).as_str(), "deprecated" | "cfg" | "cfg_attr"))
+            .collect();
+
+        let mut ancestor = node.parent();
+        while let Some(parent) = ancestor {
+            if matches!(parent.kind(), "mod_item" | "impl_item" | "trait_item") {
+                for attr in self.rust_attribute_nodes(parent).into_iter().rev() {
+                    if let Some(attr) = attribute_text(attr) {
+                        if path(&attr) == "cfg" && !attributes.contains(&attr) {
+                            attributes.push(attr);
+                        }
+                    }
+                }
+            }
+            ancestor = parent.parent();
+        }
+
+        attributes
+    }
+
     /// Extract doc comment for a Rust node
+    ///
+    /// Collects `///` lines and `/** */` blocks directly above the item.
     fn extract_rust_doc_comment(&self, node: tree_sitter::Node, source: &str) -> Option<String> {
-        // Look for preceding line comments starting with ///
+        // Look for preceding line comments starting with ///, stepping over
+        // attributes such as #[inline] that sit between the docs and the item
         let start_byte = node.start_byte();
         let prefix = &source[..start_byte];
+        let attribute_rows: Vec<(usize, usize)> = self
+            .rust_attribute_nodes(node)
+            .iter()
+            .map(|attr| (attr.start_position().row, attr.end_position().row))
+            .collect();
+        let in_attribute = |row: usize| {
+            attribute_rows
+                .iter()
+                .any(|&(start, end)| start <= row && row <= end)
+        };
 
         let mut doc_lines = Vec::new();
-        for line in prefix.lines().rev() {
-            let trimmed = line.trim();
-            if trimmed.starts_with("///") {
-                doc_lines.push(trimmed.trim_start_matches("///").trim());
+        let lines: Vec<&str> = prefix.lines().collect();
+        let mut row = lines.len();
+        while row > 0 {
+            row -= 1;
+            let trimmed = lines[row].trim();
+            if in_attribute(row) {
+                continue;
+            } else if trimmed.starts_with("///") {
+                doc_lines.push(trimmed.trim_start_matches("///").trim().to_string());
             } else if trimmed.starts_with("//!") {
                 // Module-level doc, skip
                 break;
             } else if trimmed.is_empty() {
                 continue;
+            } else if trimmed.ends_with("*/") {
+                // A `/** ... */` block; plain `/* */` comments end the docs
+                let Some(start) = (0..=row).rev().find(|&r| lines[r].trim().starts_with("/*"))
+                else {
+                    break;
+                };
+                let Some(block) = rust_block_doc(&lines[start..=row], "/**") else {
+                    break;
+                };
+                doc_lines.extend(block.into_iter().rev());
+                row = start;
             } else {
                 break;
             }
@@ -534,7 +812,7 @@ impl CodeExtractor {
         let tree = self
             .python_parser
             .parse(content, None)
-            .ok_or_else(|| anyhow::anyhow!("Failed to parse Python file"))?;
+            .ok_or_else(|| parse_failed(path, "Python"))?;
 
         let mut chunks = Vec::new();
         let file_path = path.to_string_lossy().to_string();
@@ -601,9 +879,15 @@ impl CodeExtractor {
         let name_node = node.child_by_field_name("name")?;
         let name = name_node.utf8_text(source.as_bytes()).ok()?;
 
-        let content = node.utf8_text(source.as_bytes()).ok()?;
-        let start_line = node.start_position().row + 1;
-        let end_line = node.end_position().row + 1;
+        // Include decorators in the chunk so adding or removing one is a change
+        let decorated = node
+            .parent()
+            .filter(|parent| parent.kind() == "decorated_definition");
+        let outer = decorated.unwrap_or(node);
+
+        let content = outer.utf8_text(source.as_bytes()).ok()?;
+        let start_line = outer.start_position().row + 1;
+        let end_line = outer.end_position().row + 1;
 
         let (full_name, symbol_type) = if let Some(class) = class_name {
             (format!("{}.{}", class, name), SymbolType::Method)
@@ -630,9 +914,23 @@ impl CodeExtractor {
         // Extract signature
         chunk.signature = self.extract_python_signature(node, source);
 
+        if let Some(decorated) = decorated {
+            chunk.decorators = self.extract_python_decorators(decorated, source);
+        }
+
         Some(chunk)
     }
 
+    /// Extract decorator expressions (without `@`) from a decorated definition
+    fn extract_python_decorators(&self, node: tree_sitter::Node, source: &str) -> Vec<String> {
+        let mut cursor = node.walk();
+        node.children(&mut cursor)
+            .filter(|child| child.kind() == "decorator")
+            .filter_map(|child| child.utf8_text(source.as_bytes()).ok())
+            .map(|text| text.trim().trim_start_matches('@').trim().to_string())
+            .collect()
+    }
+
     /// Extract a Python class
     fn extract_python_class(
         &self,
@@ -721,6 +1019,88 @@ impl Default for CodeExtractor {
     }
 }
 
+/// Error for a file tree-sitter could not parse
+/// Lines of a Rust block doc comment opened with `opener` (`/**` or `/*!`)
+///
+/// `lines` run from the line opening the block to the one closing it. The
+/// delimiters and leading `*` of each line are stripped, along with blank
+/// lines at either end. Returns `None` for plain `/* */` comments.
+fn rust_block_doc(lines: &[&str], opener: &str) -> Option<Vec<String>> {
+    let text = lines.join("\n");
+    let text = text.trim();
+    let body = text.strip_prefix(opener)?.strip_suffix("*/")?;
+    // `/***` and `/**/` are ordinary comments
+    if body.starts_with('*') || (body.is_empty() && opener == "/**") {
+        return None;
+    }
+
+    let mut doc: Vec<String> = body
+        .lines()
+        .map(|line| {
+            let line = line.trim();
+            line.strip_prefix('*').unwrap_or(line).trim().to_string()
+        })
+        .collect();
+    while doc.first().is_some_and(|l| l.is_empty()) {
+        doc.remove(0);
+    }
+    while doc.last().is_some_and(|l| l.is_empty()) {
+        doc.pop();
+    }
+    Some(doc)
+}
+
+/// Inner doc comments (`//!` or `/*! */`) leading a Rust file or module body
+///
+/// Blank lines and `#![...]` attributes before and between them are skipped.
+/// Returns the docs and the row of their last line.
+fn rust_inner_doc(source: &str) -> Option<(String, usize)> {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut doc = Vec::new();
+    let mut end_row = None;
+    let mut row = 0;
+    while row < lines.len() {
+        let trimmed = lines[row].trim();
+        if let Some(line) = trimmed.strip_prefix("//!") {
+            doc.push(line.trim().to_string());
+            end_row = Some(row);
+        } else if trimmed.starts_with("/*!") {
+            let end = (row..lines.len()).find(|&r| lines[r].trim_end().ends_with("*/"))?;
+            doc.extend(rust_block_doc(&lines[row..=end], "/*!")?);
+            end_row = Some(end);
+            row = end;
+        } else if !(trimmed.is_empty() || trimmed.starts_with("#!") || trimmed.starts_with("#![")) {
+            break;
+        }
+        row += 1;
+    }
+
+    let doc = doc.join("\n");
+    let doc = doc.trim();
+    (!doc.is_empty()).then(|| (doc.to_string(), end_row.unwrap_or_default()))
+}
+
+/// Module name of a Rust file: the file stem, or the directory for `mod.rs`
+fn rust_module_name(path: &Path) -> String {
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("module");
+    if stem == "mod" {
+        if let Some(dir) = path.parent().and_then(|p| p.file_name()) {
+            return dir.to_string_lossy().to_string();
+        }
+    }
+    stem.to_string()
+}
+
+fn parse_failed(path: &Path, language: &str) -> DocSentinelError {
+    DocSentinelError::Extract {
+        path: path.to_string_lossy().to_string(),
+        message: format!("Failed to parse {} file", language),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -745,6 +1125,306 @@ pub fn hello_world(name: &str) -> String {
         assert!(chunks[0].doc_comment.is_some());
     }
 
+    #[test]
+    fn test_symbol_type_from_str_round_trips() {
+        for symbol_type in [
+            SymbolType::Function,
+            SymbolType::Struct,
+            SymbolType::Constant,
+        ] {
+            assert_eq!(
+                symbol_type.to_string().parse::<SymbolType>().unwrap(),
+                symbol_type
+            );
+        }
+        assert_eq!("Trait".parse::<SymbolType>().unwrap(), SymbolType::Trait);
+        assert!("widget".parse::<SymbolType>().is_err());
+    }
+
+    #[test]
+    fn test_embedding_text_modes() {
+        let mut extractor = CodeExtractor::new().unwrap();
+        let code = "/// Greets someone\npub fn greet(name: &str) -> String {\n    name.to_string()\n}\n\npub fn bare() {}\n";
+        let chunks = extractor
+            .extract_file(Path::new("src/lib.rs"), code)
+            .unwrap();
+        let (greet, bare) = (&chunks[0], &chunks[1]);
+
+        assert_eq!(
+            greet.embedding_text_for(EmbeddingTextMode::Full),
+            greet.embedding_text()
+        );
+        assert_eq!(
+            greet.embedding_text_for(EmbeddingTextMode::SignatureOnly),
+            greet.signature.clone().unwrap()
+        );
+        assert_eq!(
+            greet.embedding_text_for(EmbeddingTextMode::DocCommentOnly),
+            "Greets someone"
+        );
+        assert_eq!(
+            greet.embedding_text_for(EmbeddingTextMode::Content),
+            greet.content
+        );
+
+        // Undocumented symbols fall back to the signature
+        assert_eq!(
+            bare.embedding_text_for(EmbeddingTextMode::DocCommentOnly),
+            bare.embedding_text_for(EmbeddingTextMode::SignatureOnly)
+        );
+    }
+
+    #[test]
+    fn test_extract_rust_doc_comment_above_attributes() {
+        let mut extractor = CodeExtractor::new().unwrap();
+        let code = r#"
+/// Adds two numbers
+#[inline]
+#[deprecated(
+    since = "0.2.0",
+    note = "use checked_add"
+)]
+pub fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+#[cfg(feature = "extra")]
+mod extra {
+    /// Subtracts two numbers
+    #[must_use]
+    pub fn sub(a: i32, b: i32) -> i32 {
+        a - b
+    }
+}
+"#;
+
+        let chunks = extractor.extract_file(Path::new("math.rs"), code).unwrap();
+
+        let add = chunks.iter().find(|c| c.symbol_name == "add").unwrap();
+        assert_eq!(add.doc_comment.as_deref(), Some("Adds two numbers"));
+        assert_eq!(
+            add.attributes,
+            vec![r#"deprecated( since = "0.2.0", note = "use checked_add" )"#]
+        );
+        assert!(add.is_deprecated());
+
+        let sub = chunks.iter().find(|c| c.symbol_name == "sub").unwrap();
+        assert_eq!(sub.doc_comment.as_deref(), Some("Subtracts two numbers"));
+        assert_eq!(sub.attributes, vec![r#"cfg(feature = "extra")"#]);
+        assert!(!sub.is_deprecated());
+    }
+
+    #[test]
+    fn test_extract_rust_block_doc_comment() {
+        let mut extractor = CodeExtractor::new().unwrap();
+        let code = r#"
+/**
+ * Parses a config file.
+ *
+ * Returns the parsed settings.
+ */
+#[must_use]
+pub fn parse(path: &str) -> Config {
+    todo!()
+}
+
+/** Formats a value */
+pub fn format(value: i32) -> String {
+    value.to_string()
+}
+
+/* Not documentation */
+pub fn plain() {}
+"#;
+
+        let chunks = extractor
+            .extract_file(Path::new("config.rs"), code)
+            .unwrap();
+        let doc = |name: &str| {
+            chunks
+                .iter()
+                .find(|c| c.symbol_name == name)
+                .unwrap()
+                .doc_comment
+                .clone()
+        };
+
+        assert_eq!(
+            doc("parse").as_deref(),
+            Some("Parses a config file.\n\nReturns the parsed settings.")
+        );
+        assert_eq!(doc("format").as_deref(), Some("Formats a value"));
+        assert_eq!(doc("plain"), None);
+    }
+
+    #[test]
+    fn test_extract_rust_module_docs() {
+        let mut extractor = CodeExtractor::new().unwrap();
+        let code = r#"//! Storage layer
+//!
+//! Persists chunks in SQLite.
+#![allow(dead_code)]
+
+/// Opens the database
+pub fn open() {}
+
+/// Schema helpers
+pub mod schema {
+    //! Table definitions
+    pub fn create() {}
+}
+
+mod quiet {
+    pub fn hush() {}
+}
+"#;
+
+        let chunks = extractor
+            .extract_file(Path::new("src/storage/mod.rs"), code)
+            .unwrap();
+        let modules: Vec<&CodeChunk> = chunks
+            .iter()
+            .filter(|c| c.symbol_type == SymbolType::Module)
+            .collect();
+
+        assert_eq!(modules.len(), 2);
+        assert_eq!(modules[0].symbol_name, "storage");
+        assert_eq!(
+            modules[0].doc_comment.as_deref(),
+            Some("Storage layer\n\nPersists chunks in SQLite.")
+        );
+        assert_eq!((modules[0].start_line, modules[0].end_line), (1, 3));
+        assert_eq!(modules[1].symbol_name, "schema");
+        assert_eq!(
+            modules[1].doc_comment.as_deref(),
+            Some("Schema helpers\n\nTable definitions")
+        );
+
+        let open = chunks.iter().find(|c| c.symbol_name == "open").unwrap();
+        assert_eq!(open.doc_comment.as_deref(), Some("Opens the database"));
+        assert!(chunks.iter().any(|c| c.symbol_name == "hush"));
+    }
+
+    #[test]
+    fn test_extract_files_preserves_order() {
+        let files: Vec<(PathBuf, String)> = (0..20)
+            .map(|i| {
+                (
+                    PathBuf::from(format!("file_{:02}.rs", i)),
+                    format!("pub fn func_{}() {{}}", i),
+                )
+            })
+            .chain(std::iter::once((
+                PathBuf::from("notes.txt"),
+                "not code".to_string(),
+            )))
+            .collect();
+
+        let results = CodeExtractor::extract_files(&files);
+
+        assert_eq!(results.len(), files.len());
+        for (i, result) in results.iter().take(20).enumerate() {
+            let chunks = result.as_ref().unwrap();
+            assert_eq!(chunks[0].symbol_name, format!("func_{}", i));
+        }
+        assert!(results[20].is_err());
+    }
+
+    /// Compare serial and parallel extraction of 400 generated Rust files
+    ///
+    /// Run with `cargo test --release -- --ignored --nocapture
+    /// extraction_timing`; set `RAYON_NUM_THREADS` to vary the pool size.
+    #[test]
+    #[ignore]
+    fn test_extraction_timing() {
+        let files: Vec<(PathBuf, String)> = (0..400)
+            .map(|i| {
+                let content: String = (0..40)
+                    .map(|j| {
+                        format!(
+                            "/// Adds {j} to the input\npub fn f{i}_{j}(x: u64, y: &str) -> u64 {{\n    \
+                             let n = y.len() as u64;\n    x + n + {j}\n}}\n\n"
+                        )
+                    })
+                    .collect();
+                (PathBuf::from(format!("src/m{:03}.rs", i)), content)
+            })
+            .collect();
+
+        let started = std::time::Instant::now();
+        let mut extractor = CodeExtractor::new().unwrap();
+        let serial: Vec<Vec<CodeChunk>> = files
+            .iter()
+            .map(|(path, content)| extractor.extract_file(path, content).unwrap())
+            .collect();
+        let serial_time = started.elapsed();
+
+        let started = std::time::Instant::now();
+        let parallel: Vec<Vec<CodeChunk>> = CodeExtractor::extract_files(&files)
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+        let parallel_time = started.elapsed();
+
+        let ids = |chunks: &[Vec<CodeChunk>]| -> Vec<String> {
+            chunks.iter().flatten().map(|c| c.id.clone()).collect()
+        };
+        assert_eq!(ids(&serial), ids(&parallel));
+        println!(
+            "{} chunks: serial {:?}, parallel {:?} on {} thread(s)",
+            ids(&parallel).len(),
+            serial_time,
+            parallel_time,
+            rayon::current_num_threads()
+        );
+    }
+
+    #[test]
+    fn test_extract_python_decorators() {
+        let mut extractor = CodeExtractor::new().unwrap();
+        let code = r#"
+class Account:
+    @property
+    def balance(self):
+        return self._balance
+
+    @staticmethod
+    @deprecated("use open_account")
+    def create():
+        pass
+
+    def close(self):
+        pass
+"#;
+
+        let chunks = extractor
+            .extract_file(Path::new("account.py"), code)
+            .unwrap();
+
+        let balance = chunks
+            .iter()
+            .find(|c| c.symbol_name == "Account.balance")
+            .unwrap();
+        assert_eq!(balance.decorators, vec!["property"]);
+        assert!(balance.content.starts_with("@property"));
+        assert!(balance.embedding_text().contains("Decorators: @property"));
+
+        let create = chunks
+            .iter()
+            .find(|c| c.symbol_name == "Account.create")
+            .unwrap();
+        assert_eq!(
+            create.decorators,
+            vec!["staticmethod", "deprecated(\"use open_account\")"]
+        );
+
+        let close = chunks
+            .iter()
+            .find(|c| c.symbol_name == "Account.close")
+            .unwrap();
+        assert!(close.decorators.is_empty());
+    }
+
     #[test]
     fn test_extract_python_function() {
         let mut extractor = CodeExtractor::new().unwrap();
@@ -0,0 +1,391 @@
+//! Config key extraction
+//!
+//! Extracts dotted key paths from configuration files:
+//! - TOML (tables, nested tables, arrays of tables)
+//! - YAML (block mappings, including mappings inside sequences)
+
+use crate::error::{DocSentinelError, Result};
+use std::path::Path;
+
+/// Supported configuration formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConfigFormat {
+    Toml,
+    Yaml,
+}
+
+impl ConfigFormat {
+    /// Detect format from file extension
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_lowercase().as_str() {
+            "toml" => Some(ConfigFormat::Toml),
+            "yaml" | "yml" => Some(ConfigFormat::Yaml),
+            _ => None,
+        }
+    }
+}
+
+/// A key defined in a configuration file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigKey {
+    /// File path relative to repository root
+    pub file_path: String,
+    /// Dotted key path (e.g. `llm.model`)
+    pub key: String,
+    /// 1-indexed line where the key is defined
+    pub line: usize,
+    /// Scalar value as written, without quotes; `None` for tables and lists
+    pub value: Option<String>,
+}
+
+impl ConfigKey {
+    /// Last segment of the key path
+    pub fn name(&self) -> &str {
+        self.key.rsplit('.').next().unwrap_or(&self.key)
+    }
+
+    /// Key path without its last segment (empty for top-level keys)
+    pub fn parent(&self) -> &str {
+        self.key.rsplit_once('.').map(|(p, _)| p).unwrap_or("")
+    }
+}
+
+/// Extract config keys from a file, based on its extension
+///
+/// Returns an empty list for unsupported formats.
+pub fn extract_config_keys(path: &Path, content: &str) -> Result<Vec<ConfigKey>> {
+    let format = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .and_then(ConfigFormat::from_extension);
+    let file_path = path.to_string_lossy();
+
+    match format {
+        Some(ConfigFormat::Toml) => extract_toml_keys(&file_path, content),
+        Some(ConfigFormat::Yaml) => Ok(extract_yaml_keys(&file_path, content)),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Extract keys from a TOML document
+fn extract_toml_keys(file_path: &str, content: &str) -> Result<Vec<ConfigKey>> {
+    let table: toml::Table =
+        content
+            .parse()
+            .map_err(|e: toml::de::Error| DocSentinelError::Extract {
+                path: file_path.to_string(),
+                message: format!("Failed to parse TOML: {}", e),
+            })?;
+
+    let mut paths = Vec::new();
+    collect_toml_paths(&table, "", &mut paths);
+
+    Ok(paths
+        .into_iter()
+        .map(|(key, value)| {
+            let line = find_key_line(content, &key);
+            ConfigKey {
+                file_path: file_path.to_string(),
+                key,
+                line,
+                value,
+            }
+        })
+        .collect())
+}
+
+/// Recursively collect dotted paths, with their scalar values, from a TOML
+/// table
+fn collect_toml_paths(
+    table: &toml::Table,
+    prefix: &str,
+    paths: &mut Vec<(String, Option<String>)>,
+) {
+    for (name, value) in table {
+        let path = if prefix.is_empty() {
+            name.clone()
+        } else {
+            format!("{}.{}", prefix, name)
+        };
+        if !paths.iter().any(|(p, _)| *p == path) {
+            let scalar = match value {
+                toml::Value::String(s) => Some(s.clone()),
+                toml::Value::Table(_) | toml::Value::Array(_) => None,
+                other => Some(other.to_string()),
+            };
+            paths.push((path.clone(), scalar));
+        }
+
+        match value {
+            toml::Value::Table(child) => collect_toml_paths(child, &path, paths),
+            toml::Value::Array(items) => {
+                // Arrays of tables share one key path for all entries
+                for item in items {
+                    if let toml::Value::Table(child) = item {
+                        collect_toml_paths(child, &path, paths);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Best-effort line lookup for a TOML key
+///
+/// Looks for a `[table]` header matching the full path first, then for the
+/// first assignment of the key's last segment. Falls back to line 1.
+fn find_key_line(content: &str, key: &str) -> usize {
+    let name = key.rsplit('.').next().unwrap_or(key);
+
+    for (i, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        let header = trimmed.trim_start_matches('[').trim_end_matches(']');
+        if trimmed.starts_with('[') && header.trim() == key {
+            return i + 1;
+        }
+    }
+
+    for (i, line) in content.lines().enumerate() {
+        let trimmed = line.trim().trim_start_matches(['"', '\'']);
+        if let Some(rest) = trimmed.strip_prefix(name) {
+            let rest = rest.trim_start_matches(['"', '\'']).trim_start();
+            if rest.starts_with('=') {
+                return i + 1;
+            }
+        }
+    }
+
+    1
+}
+
+/// Extract keys from a YAML document
+///
+/// This is an indentation-based scan rather than a full YAML parser: flow
+/// mappings and anchors are not expanded, and block scalars are skipped.
+fn extract_yaml_keys(file_path: &str, content: &str) -> Vec<ConfigKey> {
+    let mut keys: Vec<ConfigKey> = Vec::new();
+    // (indent, key path) of the enclosing mappings
+    let mut stack: Vec<(usize, String)> = Vec::new();
+    // Indent of a block scalar (`|` / `>`) whose body is being skipped
+    let mut block_indent: Option<usize> = None;
+
+    for (i, raw) in content.lines().enumerate() {
+        let line = raw.trim_end();
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if trimmed == "---" || trimmed == "..." {
+            stack.clear();
+            block_indent = None;
+            continue;
+        }
+
+        let mut indent = line.len() - trimmed.len();
+        if let Some(scalar_indent) = block_indent {
+            if indent > scalar_indent {
+                continue;
+            }
+            block_indent = None;
+        }
+
+        // Sequence items: `- key: value` opens a mapping at the item's indent
+        let mut entry = trimmed;
+        while let Some(rest) = entry.strip_prefix("- ") {
+            indent += 2;
+            entry = rest.trim_start();
+        }
+
+        let Some((name, value)) = split_yaml_key(entry) else {
+            continue;
+        };
+
+        while stack.last().is_some_and(|(level, _)| *level >= indent) {
+            stack.pop();
+        }
+        let path = match stack.last() {
+            Some((_, parent)) => format!("{}.{}", parent, name),
+            None => name.to_string(),
+        };
+
+        let value = value.trim();
+        if !keys.iter().any(|k| k.key == path) {
+            keys.push(ConfigKey {
+                file_path: file_path.to_string(),
+                key: path.clone(),
+                line: i + 1,
+                value: yaml_scalar(value),
+            });
+        }
+
+        if value.starts_with('|') || value.starts_with('>') {
+            block_indent = Some(indent);
+        }
+        stack.push((indent, path));
+    }
+
+    keys
+}
+
+/// The scalar written after a YAML key, without quotes or a trailing comment
+///
+/// Nested mappings, block scalars, flow collections and aliases have no
+/// single value and return `None`.
+fn yaml_scalar(value: &str) -> Option<String> {
+    let value = match value.find(" #") {
+        Some(comment) if !value.starts_with(['"', '\'']) => value[..comment].trim_end(),
+        _ => value,
+    };
+    if value.is_empty() || value.starts_with(['|', '>', '{', '[', '&', '*']) {
+        return None;
+    }
+    let unquoted = ['"', '\'']
+        .iter()
+        .find_map(|q| value.strip_prefix(*q)?.strip_suffix(*q))
+        .unwrap_or(value);
+    Some(unquoted.to_string())
+}
+
+/// Split a YAML mapping entry into key and value
+fn split_yaml_key(entry: &str) -> Option<(&str, &str)> {
+    let (name, value) = if let Some(rest) = entry.strip_prefix('"') {
+        let end = rest.find('"')?;
+        (&rest[..end], rest[end + 1..].strip_prefix(':')?)
+    } else if let Some(rest) = entry.strip_prefix('\'') {
+        let end = rest.find('\'')?;
+        (&rest[..end], rest[end + 1..].strip_prefix(':')?)
+    } else {
+        let colon = entry
+            .find(": ")
+            .or_else(|| entry.strip_suffix(':').map(|s| s.len()))?;
+        (entry[..colon].trim_end(), &entry[colon + 1..])
+    };
+
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '/'));
+    valid.then_some((name, value))
+}
+
+/// Check whether a documentation section references a config key
+///
+/// A key counts as referenced when its full dotted path appears in the text,
+/// when its name appears as inline code, or when a line (e.g. in a config
+/// example) assigns it with `name =` or `name:`.
+pub fn mentions_config_key(text: &str, key: &ConfigKey) -> bool {
+    let name = key.name();
+
+    if key.key.contains('.') && text.contains(&key.key) {
+        return true;
+    }
+    if text.contains(&format!("`{}`", name)) {
+        return true;
+    }
+
+    text.lines().any(|line| {
+        let trimmed = line.trim_start().trim_start_matches("- ");
+        trimmed
+            .strip_prefix(name)
+            .map(|rest| {
+                let rest = rest.trim_start();
+                rest.starts_with('=') || rest.starts_with(':')
+            })
+            .unwrap_or(false)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_toml_keys() {
+        let content = "top_k = 5\n\n[llm]\nmodel = \"x\"\n\n[drift.rules]\nstrict = true\n\n[[bin]]\nname = \"a\"\n";
+        let keys = extract_config_keys(Path::new("config.toml"), content).unwrap();
+        let paths: Vec<&str> = keys.iter().map(|k| k.key.as_str()).collect();
+
+        for expected in [
+            "top_k",
+            "llm",
+            "llm.model",
+            "drift",
+            "drift.rules",
+            "drift.rules.strict",
+            "bin",
+            "bin.name",
+        ] {
+            assert!(paths.contains(&expected), "missing {}", expected);
+        }
+
+        let model = keys.iter().find(|k| k.key == "llm.model").unwrap();
+        assert_eq!(model.line, 4);
+        assert_eq!(model.name(), "model");
+        assert_eq!(model.parent(), "llm");
+        assert_eq!(model.value.as_deref(), Some("x"));
+        let top_k = keys.iter().find(|k| k.key == "top_k").unwrap();
+        assert_eq!(top_k.value.as_deref(), Some("5"));
+        let llm = keys.iter().find(|k| k.key == "llm").unwrap();
+        assert_eq!(llm.value, None);
+    }
+
+    #[test]
+    fn test_extract_yaml_keys() {
+        let content = "\
+# Service settings
+server:
+  host: localhost
+  port: 8080
+  banner: |
+    not: a key
+  routes:
+    - path: /api
+      handler: api
+logging:
+  level: info
+";
+        let keys = extract_config_keys(Path::new("app.yaml"), content).unwrap();
+        let paths: Vec<&str> = keys.iter().map(|k| k.key.as_str()).collect();
+
+        assert_eq!(
+            paths,
+            vec![
+                "server",
+                "server.host",
+                "server.port",
+                "server.banner",
+                "server.routes",
+                "server.routes.path",
+                "server.routes.handler",
+                "logging",
+                "logging.level",
+            ]
+        );
+        assert_eq!(keys[2].line, 4);
+
+        let values: Vec<Option<&str>> = keys.iter().map(|k| k.value.as_deref()).collect();
+        assert_eq!(values[0], None);
+        assert_eq!(values[2], Some("8080"));
+        assert_eq!(values[3], None);
+        assert_eq!(values[8], Some("info"));
+    }
+
+    #[test]
+    fn test_mentions_config_key() {
+        let key = ConfigKey {
+            file_path: "config.toml".to_string(),
+            key: "llm.model".to_string(),
+            line: 4,
+            value: Some("x".to_string()),
+        };
+
+        assert!(mentions_config_key("Set `llm.model` to pick a model", &key));
+        assert!(mentions_config_key(
+            "```toml\n[llm]\nmodel = \"x\"\n```",
+            &key
+        ));
+        assert!(mentions_config_key("The `model` key selects it", &key));
+        assert!(!mentions_config_key("Pick a good model for the job", &key));
+    }
+}
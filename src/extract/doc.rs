@@ -3,11 +3,15 @@
 //! Extracts semantically meaningful sections from documentation files:
 //! - Splits documents by heading hierarchy
 //! - Each section becomes a DocChunk
+//!
+//! Markdown is parsed with pulldown-cmark; reStructuredText (`.rst`) headings
+//! are detected from their underline/overline adornments.
 
-use super::{content_hash, Chunk};
-use anyhow::Result;
-use pulldown_cmark::{Event, HeadingLevel as CmarkHeadingLevel, Parser, Tag, TagEnd};
+use super::{content_hash, Chunk, EmbeddingTextMode};
+use crate::error::{DocSentinelError, Result};
+use pulldown_cmark::{Event, HeadingLevel as CmarkHeadingLevel, Options, Parser, Tag, TagEnd};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
 /// Heading level in documentation
@@ -40,10 +44,32 @@ impl std::fmt::Display for HeadingLevel {
     }
 }
 
+impl std::str::FromStr for HeadingLevel {
+    type Err = DocSentinelError;
+
+    /// Parse `H1`..`H6` (case-insensitive) or a bare `1`..`6`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = s.strip_prefix(['H', 'h']).unwrap_or(s);
+        match digits {
+            "1" => Ok(HeadingLevel::H1),
+            "2" => Ok(HeadingLevel::H2),
+            "3" => Ok(HeadingLevel::H3),
+            "4" => Ok(HeadingLevel::H4),
+            "5" => Ok(HeadingLevel::H5),
+            "6" => Ok(HeadingLevel::H6),
+            _ => Err(DocSentinelError::Config(format!(
+                "Unknown heading level: {} (expected H1 to H6)",
+                s
+            ))),
+        }
+    }
+}
+
 /// A semantic unit extracted from documentation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DocChunk {
-    /// Unique identifier (file path + heading path)
+    /// Unique identifier: the file path and the heading's GitHub-style
+    /// anchor, e.g. `README.md#getting-started`
     pub id: String,
     /// File path relative to repository root
     pub file_path: String,
@@ -64,6 +90,69 @@ pub struct DocChunk {
     /// Embedding vector (populated later)
     #[serde(skip)]
     pub embedding: Option<Vec<f32>>,
+    /// Symbols this section explicitly documents, from
+    /// `<!-- docsentinel: path::symbol -->` annotations
+    #[serde(default)]
+    pub linked_symbols: Vec<String>,
+    /// Relative links to other docs or headings, e.g. `[setup](guide.md#setup)`
+    #[serde(default)]
+    pub links: Vec<DocLink>,
+    /// Top-level `key: value` pairs from the file's YAML front matter,
+    /// shared by every chunk of the file
+    #[serde(default)]
+    pub frontmatter: HashMap<String, String>,
+}
+
+/// A Markdown link from a doc section to another file or heading
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DocLink {
+    /// Link text as rendered
+    pub text: String,
+    /// Link destination as written (`guide.md#setup`, `#usage`)
+    pub target: String,
+    /// Line number of the link in the file
+    pub line: usize,
+}
+
+impl DocLink {
+    /// File the link points to, resolved against the linking file's path
+    ///
+    /// A link with no path (`#usage`) points to the linking file itself and
+    /// a leading `/` is relative to the repository root. Returns `None` for
+    /// paths that escape the repository.
+    pub fn target_file(&self, from_file: &str) -> Option<String> {
+        let path = self.target.split(['#', '?']).next().unwrap_or_default();
+        if path.is_empty() {
+            return Some(from_file.to_string());
+        }
+
+        let mut parts: Vec<&str> = match path.strip_prefix('/') {
+            Some(_) => Vec::new(),
+            None => {
+                let mut dir: Vec<&str> = from_file.split('/').collect();
+                dir.pop();
+                dir
+            }
+        };
+        for part in path.split('/') {
+            match part {
+                "" | "." => {}
+                ".." => {
+                    parts.pop()?;
+                }
+                part => parts.push(part),
+            }
+        }
+        Some(parts.join("/"))
+    }
+
+    /// Heading anchor the link points to, without the `#`
+    pub fn anchor(&self) -> Option<&str> {
+        self.target
+            .split_once('#')
+            .map(|(_, anchor)| anchor)
+            .filter(|anchor| !anchor.is_empty())
+    }
 }
 
 impl Chunk for DocChunk {
@@ -95,8 +184,7 @@ impl DocChunk {
         start_line: usize,
         end_line: usize,
     ) -> Self {
-        let path_str = heading_path.join(" > ");
-        let id = format!("{}#{}", file_path, path_str);
+        let id = format!("{}#{}", file_path, heading_anchor(heading));
         let hash = content_hash(content);
 
         Self {
@@ -110,6 +198,9 @@ impl DocChunk {
             start_line,
             end_line,
             embedding: None,
+            linked_symbols: parse_doc_annotations(content),
+            links: parse_doc_links(content, start_line),
+            frontmatter: HashMap::new(),
         }
     }
 
@@ -122,16 +213,238 @@ impl DocChunk {
         )
     }
 
+    /// Get the text to embed for the given mode
+    ///
+    /// Only [`EmbeddingTextMode::Full`] adds the heading path and file; the
+    /// other modes embed the section content alone.
+    pub fn embedding_text_for(&self, mode: EmbeddingTextMode) -> String {
+        match mode {
+            EmbeddingTextMode::Full => self.embedding_text(),
+            EmbeddingTextMode::SignatureOnly
+            | EmbeddingTextMode::DocCommentOnly
+            | EmbeddingTextMode::Content => self.content.clone(),
+        }
+    }
+
+    /// Which part of a split section this is (1-based), or `None` for a
+    /// whole section
+    pub fn part(&self) -> Option<usize> {
+        self.id
+            .strip_suffix(')')?
+            .rsplit_once(" (part ")?
+            .1
+            .parse()
+            .ok()
+    }
+
+    /// The heading anchor part of the ID, without `#` or a part suffix
+    pub fn anchor(&self) -> &str {
+        let anchor = self
+            .id
+            .strip_prefix(self.file_path.as_str())
+            .and_then(|rest| rest.strip_prefix('#'))
+            .unwrap_or_default();
+        match self.part() {
+            Some(part) => anchor
+                .strip_suffix(&format!(" (part {})", part))
+                .unwrap_or(anchor),
+            None => anchor,
+        }
+    }
+
     /// Get the full heading path as a string
     pub fn full_path(&self) -> String {
         self.heading_path.join(" > ")
     }
+
+    /// Whether this section is explicitly linked to a code chunk
+    ///
+    /// Annotations may name the chunk by its full ID (`src/lib.rs::foo`) or
+    /// by bare symbol name (`foo`).
+    pub fn links_to(&self, chunk: &super::CodeChunk) -> bool {
+        self.linked_symbols
+            .iter()
+            .any(|symbol| *symbol == chunk.id || *symbol == chunk.symbol_name)
+    }
+
+    /// Whether the section comes from a reStructuredText file
+    ///
+    /// Its content is not Markdown, so it has no inline code spans or fenced
+    /// blocks.
+    pub fn is_rst(&self) -> bool {
+        is_rst_path(Path::new(&self.file_path))
+    }
+
+    /// Inline code spans in the section's Markdown, outside code blocks
+    pub fn inline_code_spans(&self) -> Vec<InlineCode> {
+        if self.is_rst() {
+            return Vec::new();
+        }
+        inline_code_spans(&self.content)
+    }
+
+    /// Fenced code blocks in the section's Markdown
+    ///
+    /// Indented code blocks are left out, since indentation is also used to
+    /// continue list items.
+    pub fn fenced_blocks(&self) -> Vec<CodeBlock> {
+        if self.is_rst() {
+            return Vec::new();
+        }
+        extract_code_blocks(&self.content)
+            .into_iter()
+            .filter(|block| block.fenced)
+            .collect()
+    }
+}
+
+/// Whether `path` names a reStructuredText file
+fn is_rst_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("rst"))
 }
 
+/// Parse `<!-- docsentinel: path::symbol -->` annotations from doc content
+///
+/// Several symbols may be listed in one annotation, separated by commas.
+pub fn parse_doc_annotations(content: &str) -> Vec<String> {
+    const PREFIX: &str = "<!--";
+    const SUFFIX: &str = "-->";
+
+    let mut symbols = Vec::new();
+    let mut rest = content;
+
+    while let Some(start) = rest.find(PREFIX) {
+        let after = &rest[start + PREFIX.len()..];
+        let Some(end) = after.find(SUFFIX) else {
+            break;
+        };
+
+        if let Some(body) = after[..end].trim().strip_prefix("docsentinel:") {
+            for symbol in body.split(',') {
+                let symbol = symbol.trim();
+                if !symbol.is_empty() && !symbols.iter().any(|s| s == symbol) {
+                    symbols.push(symbol.to_string());
+                }
+            }
+        }
+
+        rest = &after[end + SUFFIX.len()..];
+    }
+
+    symbols
+}
+
+/// Parse relative Markdown links from doc content
+///
+/// Links with a URL scheme (`https:`, `mailto:`) are skipped since they point
+/// outside the repository. `start_line` is the line of the first line of
+/// `content`, so link lines are file line numbers.
+pub fn parse_doc_links(content: &str, start_line: usize) -> Vec<DocLink> {
+    let mut links = Vec::new();
+    let mut current: Option<(String, String, usize)> = None;
+
+    for (event, range) in Parser::new(content).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                let line = start_line + content[..range.start].matches('\n').count();
+                current = Some((dest_url.to_string(), String::new(), line));
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some((_, link_text, _)) = current.as_mut() {
+                    link_text.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::Link) => {
+                if let Some((target, text, line)) = current.take() {
+                    if !target.is_empty() && !has_url_scheme(&target) {
+                        links.push(DocLink { text, target, line });
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    links
+}
+
+/// Whether a link target starts with a URL scheme such as `https:`
+fn has_url_scheme(target: &str) -> bool {
+    target.split_once(':').is_some_and(|(scheme, _)| {
+        scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    })
+}
+
+/// GitHub-style anchor for a heading: lowercased, punctuation dropped, and
+/// spaces replaced with hyphens
+///
+/// Repeated headings in one file get `-1`, `-2`, ... suffixes on GitHub; use
+/// [`HeadingAnchors`] to number a file's headings in order.
+pub fn heading_anchor(heading: &str) -> String {
+    heading
+        .trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Assigns GitHub-style anchors to the headings of one file, in order
+///
+/// A heading whose anchor is already taken gets the first free `-1`, `-2`,
+/// ... suffix, so every anchor in the file is unique.
+#[derive(Debug, Default)]
+pub struct HeadingAnchors {
+    taken: HashSet<String>,
+    counts: HashMap<String, usize>,
+}
+
+impl HeadingAnchors {
+    /// Start numbering a new file
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Anchor for the next heading in the file
+    pub fn assign(&mut self, heading: &str) -> String {
+        let base = heading_anchor(heading);
+        let count = self.counts.entry(base.clone()).or_default();
+        let mut anchor = base.clone();
+        while self.taken.contains(&anchor) {
+            *count += 1;
+            anchor = format!("{}-{}", base, count);
+        }
+        self.taken.insert(anchor.clone());
+        anchor
+    }
+}
+
+/// Heading given to the prose before a file's first heading
+const PREAMBLE_HEADING: &str = "Preamble";
+
 /// Extracts doc chunks from Markdown files
 pub struct DocExtractor {
     /// Minimum section length to extract (in characters)
     min_section_length: usize,
+    /// Sections longer than this many lines are split into parts (0 disables
+    /// splitting)
+    max_chunk_lines: usize,
+    /// Lines shared by consecutive parts of a split section
+    chunk_overlap_lines: usize,
+    /// Shallowest heading level extracted as a section
+    min_level: HeadingLevel,
+    /// Deepest heading level extracted as a section; deeper sections are
+    /// folded into their nearest extracted ancestor
+    max_level: HeadingLevel,
 }
 
 impl DocExtractor {
@@ -139,56 +452,209 @@ impl DocExtractor {
     pub fn new() -> Self {
         Self {
             min_section_length: 10,
+            max_chunk_lines: 0,
+            chunk_overlap_lines: 0,
+            min_level: HeadingLevel::H1,
+            max_level: HeadingLevel::H4,
         }
     }
 
+    /// Create a doc extractor using a repository's chunk size and heading
+    /// level settings
+    pub fn from_repo_config(config: &crate::repo::RepoConfig) -> Self {
+        Self::new()
+            .with_max_chunk_lines(config.max_doc_chunk_lines, config.doc_chunk_overlap_lines)
+            .with_heading_levels(config.doc_min_level, config.doc_max_level)
+    }
+
+    /// Only extract sections with headings from `min` to `max` (inclusive)
+    ///
+    /// Sections deeper than `max` are folded into their nearest extracted
+    /// ancestor instead of becoming chunks of their own; those with no such
+    /// ancestor are kept. Sections shallower than `min` are not extracted.
+    pub fn with_heading_levels(mut self, min: HeadingLevel, max: HeadingLevel) -> Self {
+        self.min_level = min;
+        self.max_level = max.max(min);
+        self
+    }
+
     /// Set minimum section length
     pub fn with_min_length(mut self, length: usize) -> Self {
         self.min_section_length = length;
         self
     }
 
+    /// Split sections longer than `max_lines` into parts that share
+    /// `overlap_lines` lines with the part before them
+    ///
+    /// Parts keep the section's heading path; their IDs get a `(part N)`
+    /// suffix. A `max_lines` of 0 disables splitting, and the overlap is
+    /// capped below `max_lines` so every part makes progress.
+    pub fn with_max_chunk_lines(mut self, max_lines: usize, overlap_lines: usize) -> Self {
+        self.max_chunk_lines = max_lines;
+        self.chunk_overlap_lines = overlap_lines.min(max_lines.saturating_sub(1));
+        self
+    }
+
     /// Extract chunks from a Markdown file
+    ///
+    /// Leading YAML front matter in Markdown files is left out of every
+    /// chunk's content; its keys are attached to each chunk instead.
     pub fn extract_file(&self, path: &Path, content: &str) -> Result<Vec<DocChunk>> {
         let file_path = path.to_string_lossy().to_string();
-        let lines: Vec<&str> = content.lines().collect();
 
         let mut chunks = Vec::new();
-        let mut sections = self.parse_sections(content);
+        let is_rst = is_rst_path(path);
+        // RST overlines look like front matter fences, so only Markdown has it
+        let (frontmatter, body) = if is_rst {
+            (HashMap::new(), content.to_string())
+        } else {
+            strip_frontmatter(content)
+        };
+        let content = body.as_str();
+        let lines: Vec<&str> = content.lines().collect();
 
-        // Convert sections to chunks
+        let mut sections = if is_rst {
+            self.parse_sections_rst(content)
+        } else {
+            self.parse_sections(content)
+        };
+
+        // Anchors count every heading, including sections too short to keep
+        let mut anchors = HeadingAnchors::new();
+        let first_heading_line = sections.first().map(|s| s.start_line);
+        let preamble = first_heading_line
+            .and_then(|line| self.preamble_section(&lines, line))
+            .map(|mut preamble| {
+                preamble.anchor = anchors.assign(&preamble.heading);
+                preamble
+            });
+        for section in &mut sections {
+            section.anchor = anchors.assign(&section.heading);
+        }
+        let mut sections = self.fold_heading_levels(sections, &lines);
+        // The preamble sits outside the heading hierarchy, so it is never
+        // folded or dropped by the level range
+        if let Some(preamble) = preamble {
+            sections.insert(0, preamble);
+        }
+
+        // Convert sections to chunks, splitting oversized ones
         for section in sections.drain(..) {
             if section.content.len() >= self.min_section_length {
-                let chunk = DocChunk::new(
-                    &file_path,
-                    section.heading_path,
-                    &section.heading,
-                    section.level,
-                    &section.content,
-                    section.start_line,
-                    section.end_line,
-                );
-                chunks.push(chunk);
+                for part in self.split_section(section, &lines) {
+                    chunks.push(part.into_chunk(&file_path));
+                }
             }
         }
 
-        // If no sections found, treat the whole file as one chunk
+        // If no sections found, treat the whole file as one section
         if chunks.is_empty() && !content.trim().is_empty() {
-            let chunk = DocChunk::new(
-                &file_path,
-                vec![file_path.clone()],
-                &file_path,
-                HeadingLevel::H1,
-                content,
-                1,
-                lines.len(),
-            );
-            chunks.push(chunk);
+            let whole = Section {
+                anchor: anchors.assign(&file_path),
+                heading_path: vec![file_path.clone()],
+                heading: file_path.clone(),
+                level: HeadingLevel::H1,
+                content: content.to_string(),
+                start_line: 1,
+                end_line: lines.len(),
+                part: None,
+            };
+            for part in self.split_section(whole, &lines) {
+                chunks.push(part.into_chunk(&file_path));
+            }
+        }
+
+        if !frontmatter.is_empty() {
+            for chunk in &mut chunks {
+                chunk.frontmatter = frontmatter.clone();
+            }
         }
 
         Ok(chunks)
     }
 
+    /// Build a section from the prose before the first heading, if any
+    ///
+    /// Leading and trailing blank lines are left out so the section's line
+    /// range covers only its text.
+    fn preamble_section(&self, lines: &[&str], first_heading_line: usize) -> Option<Section> {
+        let before = &lines[..first_heading_line.saturating_sub(1).min(lines.len())];
+        let first = before.iter().position(|line| !line.trim().is_empty())?;
+        let last = before.iter().rposition(|line| !line.trim().is_empty())?;
+        let (start_line, end_line) = (first + 1, last + 1);
+
+        Some(Section {
+            anchor: String::new(),
+            heading_path: vec![PREAMBLE_HEADING.to_string()],
+            heading: PREAMBLE_HEADING.to_string(),
+            level: HeadingLevel::H1,
+            content: self.extract_section_content(lines, start_line, end_line),
+            start_line,
+            end_line,
+            part: None,
+        })
+    }
+
+    /// Apply the heading level range to parsed sections, in file order
+    ///
+    /// A section deeper than `max_level` belongs to the last kept section
+    /// before it, since every section in between is deeper still; that
+    /// section is extended to cover it.
+    fn fold_heading_levels(&self, sections: Vec<Section>, lines: &[&str]) -> Vec<Section> {
+        let mut kept: Vec<Section> = Vec::new();
+        // Index in `kept` of the section deep sections fold into
+        let mut ancestor: Option<usize> = None;
+        for section in sections {
+            if section.level < self.min_level {
+                ancestor = None;
+                continue;
+            }
+            match ancestor {
+                Some(index) if section.level > self.max_level => {
+                    let parent = &mut kept[index];
+                    parent.end_line = section.end_line;
+                    parent.content =
+                        self.extract_section_content(lines, parent.start_line, parent.end_line);
+                }
+                _ => {
+                    if section.level <= self.max_level {
+                        ancestor = Some(kept.len());
+                    }
+                    kept.push(section);
+                }
+            }
+        }
+        kept
+    }
+
+    /// Split a section longer than `max_chunk_lines` into overlapping parts
+    fn split_section(&self, section: Section, lines: &[&str]) -> Vec<Section> {
+        let line_count = section.end_line.saturating_sub(section.start_line) + 1;
+        if self.max_chunk_lines == 0 || line_count <= self.max_chunk_lines {
+            return vec![section];
+        }
+
+        let step = self.max_chunk_lines - self.chunk_overlap_lines;
+        let mut parts = Vec::new();
+        let mut start = section.start_line;
+        loop {
+            let end = (start + self.max_chunk_lines - 1).min(section.end_line);
+            parts.push(Section {
+                content: self.extract_section_content(lines, start, end),
+                start_line: start,
+                end_line: end,
+                part: Some(parts.len() + 1),
+                ..section.clone()
+            });
+            if end == section.end_line {
+                break;
+            }
+            start += step;
+        }
+        parts
+    }
+
     /// Parse sections from Markdown content
     fn parse_sections(&self, content: &str) -> Vec<Section> {
         let mut sections = Vec::new();
@@ -242,12 +708,14 @@ impl DocExtractor {
                     let heading_path: Vec<String> =
                         current_path.iter().map(|(_, h)| h.clone()).collect();
                     current_section = Some(Section {
+                        anchor: String::new(),
                         heading_path,
                         heading: heading_text.clone(),
                         level: heading_level,
                         content: String::new(),
                         start_line: heading_line,
                         end_line: heading_line,
+                        part: None,
                     });
                 }
                 Event::Text(text) if in_heading => {
@@ -271,6 +739,93 @@ impl DocExtractor {
         sections
     }
 
+    /// Parse sections from reStructuredText content
+    ///
+    /// RST has no fixed heading syntax: levels are assigned in the order that
+    /// adornment styles (underline character, with or without overline) first
+    /// appear in the document.
+    fn parse_sections_rst(&self, content: &str) -> Vec<Section> {
+        let lines: Vec<&str> = content.lines().collect();
+
+        // (start line, level, heading text), 1-indexed lines
+        let mut headings: Vec<(usize, HeadingLevel, String)> = Vec::new();
+        let mut styles: Vec<(char, bool)> = Vec::new();
+
+        let mut i = 0;
+        while i < lines.len() {
+            let preceded_by_blank = i == 0 || lines[i - 1].trim().is_empty();
+
+            // Overline, title, underline
+            if let Some(c) = rst_adornment_char(lines[i]) {
+                if preceded_by_blank && i + 2 < lines.len() {
+                    let title = lines[i + 1].trim();
+                    if !title.is_empty()
+                        && rst_adornment_char(lines[i + 1]).is_none()
+                        && rst_adornment_char(lines[i + 2]) == Some(c)
+                        && lines[i + 2].trim_end().chars().count() >= title.chars().count()
+                    {
+                        let level = rst_heading_level(&mut styles, (c, true));
+                        headings.push((i + 1, level, title.to_string()));
+                        i += 3;
+                        continue;
+                    }
+                }
+            }
+
+            // Title, underline
+            let title = lines[i].trim_end();
+            if preceded_by_blank
+                && !title.is_empty()
+                && !title.starts_with(char::is_whitespace)
+                && rst_adornment_char(title).is_none()
+                && i + 1 < lines.len()
+            {
+                if let Some(c) = rst_adornment_char(lines[i + 1]) {
+                    if lines[i + 1].trim_end().chars().count() >= title.chars().count() {
+                        let level = rst_heading_level(&mut styles, (c, false));
+                        headings.push((i + 1, level, title.to_string()));
+                        i += 2;
+                        continue;
+                    }
+                }
+            }
+
+            i += 1;
+        }
+
+        let mut sections = Vec::new();
+        let mut current_path: Vec<(HeadingLevel, String)> = Vec::new();
+
+        for (index, (start_line, level, heading)) in headings.iter().enumerate() {
+            while let Some((parent_level, _)) = current_path.last() {
+                if parent_level >= level {
+                    current_path.pop();
+                } else {
+                    break;
+                }
+            }
+            current_path.push((*level, heading.clone()));
+
+            let end_line = headings
+                .get(index + 1)
+                .map(|(next_start, _, _)| next_start.saturating_sub(1).max(*start_line))
+                .unwrap_or(lines.len());
+
+            sections.push(Section {
+                anchor: String::new(),
+                heading_path: current_path.iter().map(|(_, h)| h.clone()).collect(),
+                heading: heading.clone(),
+                level: *level,
+                content: self.extract_section_content(&lines, *start_line, end_line),
+                start_line: *start_line,
+                end_line,
+                part: None,
+            });
+        }
+
+        sections
+    }
+
     /// Find the line number for a heading
     fn find_heading_line(&self, lines: &[&str], heading: &str, start_from: usize) -> usize {
         for (i, line) in lines.iter().enumerate().skip(start_from) {
@@ -304,30 +859,140 @@ impl Default for DocExtractor {
     }
 }
 
+/// Split leading YAML front matter from Markdown content
+///
+/// Returns the front matter's top-level `key: value` pairs and the content
+/// with the front matter lines blanked, so line numbers in the rest of the
+/// file are unchanged. Nested values and list items are skipped. Content
+/// without a closed `---` block is returned as is.
+fn strip_frontmatter(content: &str) -> (HashMap<String, String>, String) {
+    let lines: Vec<&str> = content.lines().collect();
+    if lines.first().map(|line| line.trim_end()) != Some("---") {
+        return (HashMap::new(), content.to_string());
+    }
+    let Some(close) = lines
+        .iter()
+        .skip(1)
+        .position(|line| matches!(line.trim_end(), "---" | "..."))
+        .map(|i| i + 1)
+    else {
+        return (HashMap::new(), content.to_string());
+    };
+
+    let mut fields = HashMap::new();
+    for line in &lines[1..close] {
+        if line.starts_with(char::is_whitespace) || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if key.trim().is_empty() || value.is_empty() {
+            continue;
+        }
+        let value = ['"', '\'']
+            .iter()
+            .find_map(|q| value.strip_prefix(*q).and_then(|v| v.strip_suffix(*q)))
+            .unwrap_or(value);
+        fields.insert(key.trim().to_string(), value.to_string());
+    }
+
+    let body = std::iter::repeat_n("", close + 1)
+        .chain(lines[close + 1..].iter().copied())
+        .collect::<Vec<_>>()
+        .join("\n");
+    (fields, body)
+}
+
+/// Return the adornment character if `line` is an RST heading adornment
+fn rst_adornment_char(line: &str) -> Option<char> {
+    let line = line.trim_end();
+    let first = line.chars().next()?;
+
+    if first.is_ascii_punctuation() && line.chars().all(|c| c == first) {
+        Some(first)
+    } else {
+        None
+    }
+}
+
+/// Map an RST adornment style to a heading level, registering new styles
+fn rst_heading_level(styles: &mut Vec<(char, bool)>, style: (char, bool)) -> HeadingLevel {
+    let depth = match styles.iter().position(|s| *s == style) {
+        Some(depth) => depth,
+        None => {
+            styles.push(style);
+            styles.len() - 1
+        }
+    };
+
+    match depth {
+        0 => HeadingLevel::H1,
+        1 => HeadingLevel::H2,
+        2 => HeadingLevel::H3,
+        3 => HeadingLevel::H4,
+        4 => HeadingLevel::H5,
+        _ => HeadingLevel::H6,
+    }
+}
+
 /// Internal representation of a section during parsing
+#[derive(Clone)]
 struct Section {
+    /// Unique anchor of the heading within its file
+    anchor: String,
     heading_path: Vec<String>,
     heading: String,
     level: HeadingLevel,
     content: String,
     start_line: usize,
     end_line: usize,
+    /// Part number when the section was split
+    part: Option<usize>,
+}
+
+impl Section {
+    fn into_chunk(self, file_path: &str) -> DocChunk {
+        let mut chunk = DocChunk::new(
+            file_path,
+            self.heading_path,
+            &self.heading,
+            self.level,
+            &self.content,
+            self.start_line,
+            self.end_line,
+        );
+        chunk.id = format!("{}#{}", file_path, self.anchor);
+        if let Some(part) = self.part {
+            chunk.id = format!("{} (part {})", chunk.id, part);
+        }
+        chunk
+    }
 }
 
 /// Extract code blocks from Markdown content
 pub fn extract_code_blocks(content: &str) -> Vec<CodeBlock> {
     let mut blocks = Vec::new();
-    let parser = Parser::new(content);
+    let parser = Parser::new(content).into_offset_iter();
 
     let mut in_code_block = false;
     let mut code_content = String::new();
     let mut code_lang = None;
+    let mut code_line = 1;
+    let mut fenced = false;
 
-    for event in parser {
+    for (event, range) in parser {
         match event {
             Event::Start(Tag::CodeBlock(kind)) => {
                 in_code_block = true;
                 code_content.clear();
+                fenced = matches!(kind, pulldown_cmark::CodeBlockKind::Fenced(_));
+                let block_line = content[..range.start].matches('\n').count() + 1;
+                code_line = match kind {
+                    pulldown_cmark::CodeBlockKind::Fenced(_) => block_line + 1,
+                    pulldown_cmark::CodeBlockKind::Indented => block_line,
+                };
                 code_lang = match kind {
                     pulldown_cmark::CodeBlockKind::Fenced(lang) => {
                         let lang_str = lang.to_string();
@@ -345,6 +1010,8 @@ pub fn extract_code_blocks(content: &str) -> Vec<CodeBlock> {
                 blocks.push(CodeBlock {
                     language: code_lang.take(),
                     content: code_content.clone(),
+                    line: code_line,
+                    fenced,
                 });
             }
             Event::Text(text) if in_code_block => {
@@ -364,11 +1031,127 @@ pub struct CodeBlock {
     pub language: Option<String>,
     /// Code content
     pub content: String,
+    /// Line of `content`'s first line within the Markdown text (1-based)
+    pub line: usize,
+    /// Whether the block is fenced (```` ``` ````) rather than indented
+    pub fenced: bool,
+}
+
+/// Where an inline code span appears in Markdown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpanContext {
+    /// Running text, such as a paragraph, heading, or block quote
+    Text,
+    /// An item of a bulleted or numbered list
+    ListItem,
+    /// A table cell
+    TableCell,
+}
+
+/// An inline code span found in documentation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlineCode {
+    /// Text between the backticks
+    pub code: String,
+    /// Line of the span within the Markdown text (1-based)
+    pub line: usize,
+    /// What the span is part of
+    pub context: SpanContext,
+}
+
+/// Extract inline code spans from Markdown content
+///
+/// Spans in a table inside a list item count as table cells.
+pub fn inline_code_spans(content: &str) -> Vec<InlineCode> {
+    let mut spans = Vec::new();
+    let mut list_depth = 0usize;
+    let mut in_cell = false;
+
+    for (event, range) in Parser::new_ext(content, Options::ENABLE_TABLES).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Item) => list_depth += 1,
+            Event::End(TagEnd::Item) => list_depth = list_depth.saturating_sub(1),
+            Event::Start(Tag::TableCell) => in_cell = true,
+            Event::End(TagEnd::TableCell) => in_cell = false,
+            Event::Code(code) => {
+                let context = if in_cell {
+                    SpanContext::TableCell
+                } else if list_depth > 0 {
+                    SpanContext::ListItem
+                } else {
+                    SpanContext::Text
+                };
+                spans.push(InlineCode {
+                    code: code.to_string(),
+                    line: content[..range.start].matches('\n').count() + 1,
+                    context,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    spans
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_inline_code_spans_and_fenced_blocks() {
+        let content = "## Usage\n\
+                       \n\
+                       Call `foo` to start.\n\
+                       \n\
+                       - Then `bar` it\n\
+                       \n\
+                       | Name | Kind |\n\
+                       |------|------|\n\
+                       | `baz` | fn |\n\
+                       \n\
+                       ```rust\n\
+                       foo(`not inline`);\n\
+                       ```\n\
+                       \n\
+                       \x20   indented();\n";
+        let doc = DocChunk::new(
+            "README.md",
+            vec!["Usage".to_string()],
+            "Usage",
+            HeadingLevel::H2,
+            content,
+            1,
+            15,
+        );
+
+        let spans = doc.inline_code_spans();
+        let summary: Vec<(&str, usize, SpanContext)> = spans
+            .iter()
+            .map(|s| (s.code.as_str(), s.line, s.context))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("foo", 3, SpanContext::Text),
+                ("bar", 5, SpanContext::ListItem),
+                ("baz", 9, SpanContext::TableCell),
+            ]
+        );
+
+        let blocks = doc.fenced_blocks();
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].language.as_deref(), Some("rust"));
+        assert_eq!(blocks[0].content, "foo(`not inline`);\n");
+        assert_eq!(blocks[0].line, 12);
+        assert_eq!(extract_code_blocks(content).len(), 2);
+
+        let mut rst = doc.clone();
+        rst.file_path = "docs/index.rst".to_string();
+        assert!(rst.inline_code_spans().is_empty());
+        assert!(rst.fenced_blocks().is_empty());
+    }
 
     #[test]
     fn test_extract_markdown_sections() {
@@ -403,6 +1186,30 @@ How to use the software.
         assert!(headings.contains(&"Usage"));
     }
 
+    #[test]
+    fn test_embedding_text_modes() {
+        let chunk = DocChunk::new(
+            "README.md",
+            vec!["Guide".to_string(), "Usage".to_string()],
+            "Usage",
+            HeadingLevel::H2,
+            "Call `greet` with a name.",
+            3,
+            5,
+        );
+
+        let full = chunk.embedding_text_for(EmbeddingTextMode::Full);
+        assert_eq!(full, chunk.embedding_text());
+        assert!(full.starts_with("Documentation section: Guide > Usage\nFile: README.md"));
+        for mode in [
+            EmbeddingTextMode::SignatureOnly,
+            EmbeddingTextMode::DocCommentOnly,
+            EmbeddingTextMode::Content,
+        ] {
+            assert_eq!(chunk.embedding_text_for(mode), "Call `greet` with a name.");
+        }
+    }
+
     #[test]
     fn test_extract_code_blocks() {
         let content = r#"
@@ -422,7 +1229,9 @@ print("Hello")
         let blocks = extract_code_blocks(content);
         assert_eq!(blocks.len(), 2);
         assert_eq!(blocks[0].language, Some("rust".to_string()));
+        assert_eq!(blocks[0].line, 5);
         assert_eq!(blocks[1].language, Some("python".to_string()));
+        assert_eq!(blocks[1].line, 11);
     }
 
     #[test]
@@ -448,4 +1257,369 @@ Content here.
         let gc = grandchild.unwrap();
         assert_eq!(gc.heading_path, vec!["Root", "Child", "Grandchild"]);
     }
+
+    #[test]
+    fn test_prose_before_first_heading_becomes_preamble() {
+        let extractor = DocExtractor::new();
+        let content = "
+Acme is a small library for parsing widgets.
+
+# Installation
+
+Add it to your manifest.
+";
+
+        let chunks = extractor
+            .extract_file(Path::new("README.md"), content)
+            .unwrap();
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].heading, "Preamble");
+        assert_eq!(chunks[0].heading_path, vec!["Preamble"]);
+        assert_eq!(chunks[0].id, "README.md#preamble");
+        assert_eq!(
+            chunks[0].content,
+            "Acme is a small library for parsing widgets."
+        );
+        assert_eq!((chunks[0].start_line, chunks[0].end_line), (2, 2));
+
+        assert_eq!(chunks[1].heading, "Installation");
+        assert_eq!((chunks[1].start_line, chunks[1].end_line), (4, 6));
+    }
+
+    #[test]
+    fn test_frontmatter_is_stripped_and_captured() {
+        let extractor = DocExtractor::new();
+        let content = "---
+title: \"Getting Started\"
+sidebar_position: 2
+tags:
+  - intro
+---
+
+# Setup
+
+Install the package.
+";
+
+        let chunks = extractor
+            .extract_file(Path::new("docs/start.mdx"), content)
+            .unwrap();
+
+        assert_eq!(chunks.len(), 1);
+        let chunk = &chunks[0];
+        assert_eq!(chunk.heading, "Setup");
+        assert!(!chunk.content.contains("title:"));
+        assert!(!chunk.content.contains("---"));
+        assert_eq!((chunk.start_line, chunk.end_line), (8, 10));
+        assert_eq!(
+            chunk.frontmatter.get("title").map(String::as_str),
+            Some("Getting Started")
+        );
+        assert_eq!(
+            chunk
+                .frontmatter
+                .get("sidebar_position")
+                .map(String::as_str),
+            Some("2")
+        );
+        assert!(!chunk.frontmatter.contains_key("tags"));
+    }
+
+    #[test]
+    fn test_unclosed_frontmatter_fence_is_content() {
+        let extractor = DocExtractor::new();
+        let content = "---\ntitle: Draft\n\n# Notes\n\nSome notes here.\n";
+
+        let chunks = extractor
+            .extract_file(Path::new("notes.md"), content)
+            .unwrap();
+
+        assert!(chunks.iter().all(|c| c.frontmatter.is_empty()));
+        assert!(chunks[0].content.contains("title: Draft"));
+    }
+
+    #[test]
+    fn test_duplicate_headings_get_numbered_anchors() {
+        let extractor = DocExtractor::new();
+        let content = r#"# Guide
+
+Introduction to the guide.
+
+## Example
+
+The first example section.
+
+## Example
+
+The second example section.
+
+## Example
+
+The third example section.
+"#;
+
+        let chunks = extractor
+            .extract_file(Path::new("docs/guide.md"), content)
+            .unwrap();
+
+        let ids: Vec<&str> = chunks.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "docs/guide.md#guide",
+                "docs/guide.md#example",
+                "docs/guide.md#example-1",
+                "docs/guide.md#example-2",
+            ]
+        );
+        // Heading paths stay readable and identical
+        assert_eq!(chunks[1].heading_path, chunks[3].heading_path);
+        assert_eq!(chunks[3].heading_path, vec!["Guide", "Example"]);
+        assert_eq!(chunks[3].anchor(), "example-2");
+    }
+
+    #[test]
+    fn test_special_characters_in_heading_anchors() {
+        let extractor = DocExtractor::new();
+        let content = r#"# Config > Options & #Flags
+
+Options that look like a heading path.
+
+## What's new in v2.0?
+
+Release notes for the new version.
+
+## Example 1
+
+A heading that already looks numbered.
+
+## Example
+
+Collides with the numbered one after dedupe.
+
+## Example
+
+Takes the next free suffix.
+"#;
+
+        let chunks = extractor
+            .extract_file(Path::new("README.md"), content)
+            .unwrap();
+
+        let anchors: Vec<&str> = chunks.iter().map(|c| c.anchor()).collect();
+        assert_eq!(
+            anchors,
+            vec![
+                "config--options--flags",
+                "whats-new-in-v20",
+                "example-1",
+                "example",
+                "example-2",
+            ]
+        );
+        assert_eq!(chunks[0].id, "README.md#config--options--flags");
+        assert_eq!(chunks[0].heading_path, vec!["Config > Options & #Flags"]);
+
+        let ids: HashSet<&str> = chunks.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids.len(), chunks.len());
+    }
+
+    #[test]
+    fn test_deep_headings_fold_into_included_ancestor() {
+        let content = r#"# Guide
+
+Introduction to the guide.
+
+## Install
+
+Run the installer.
+
+### From source
+
+Build it with cargo.
+
+#### Offline
+
+Vendor the dependencies first.
+
+## Usage
+
+Call the tool.
+"#;
+
+        let extractor = DocExtractor::new().with_heading_levels(HeadingLevel::H1, HeadingLevel::H2);
+        let chunks = extractor
+            .extract_file(Path::new("docs/guide.md"), content)
+            .unwrap();
+
+        let headings: Vec<&str> = chunks.iter().map(|c| c.heading.as_str()).collect();
+        assert_eq!(headings, vec!["Guide", "Install", "Usage"]);
+        let install = &chunks[1];
+        assert_eq!((install.start_line, install.end_line), (5, 16));
+        assert!(install.content.contains("Build it with cargo."));
+        assert!(install.content.contains("Vendor the dependencies first."));
+        assert_eq!(install.id, "docs/guide.md#install");
+        assert_eq!(chunks[2].start_line, 17);
+
+        // Shallower headings than the minimum are not extracted
+        let extractor = DocExtractor::new().with_heading_levels(HeadingLevel::H2, HeadingLevel::H2);
+        let chunks = extractor
+            .extract_file(Path::new("docs/guide.md"), content)
+            .unwrap();
+        let headings: Vec<&str> = chunks.iter().map(|c| c.heading.as_str()).collect();
+        assert_eq!(headings, vec!["Install", "Usage"]);
+
+        // The default range keeps H1-H4
+        let chunks = DocExtractor::new()
+            .extract_file(Path::new("docs/guide.md"), content)
+            .unwrap();
+        assert_eq!(chunks.len(), 5);
+        let chunks = DocExtractor::new()
+            .extract_file(
+                Path::new("docs/guide.md"),
+                "## A\n\nSome text.\n\n##### Tiny\n\nNote.\n",
+            )
+            .unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert!(chunks[0].content.ends_with("Note."));
+    }
+
+    #[test]
+    fn test_doc_annotations() {
+        let extractor = DocExtractor::new();
+        let content = r#"# API
+
+## Connecting
+
+<!-- docsentinel: src/client.rs::connect -->
+Call `connect` to open a session.
+
+## Misc
+
+<!-- a regular comment -->
+<!-- docsentinel: close, src/client.rs::Client -->
+Other helpers.
+"#;
+
+        let chunks = extractor
+            .extract_file(Path::new("docs/api.md"), content)
+            .unwrap();
+
+        let connecting = chunks.iter().find(|c| c.heading == "Connecting").unwrap();
+        assert_eq!(connecting.linked_symbols, vec!["src/client.rs::connect"]);
+
+        let misc = chunks.iter().find(|c| c.heading == "Misc").unwrap();
+        assert_eq!(misc.linked_symbols, vec!["close", "src/client.rs::Client"]);
+    }
+
+    #[test]
+    fn test_doc_links() {
+        let extractor = DocExtractor::new();
+        let content = r#"# Guide
+
+## Setup
+
+See [the `config` reference](../reference.md#config-file) and
+[usage](#usage), or the [website](https://example.com).
+"#;
+
+        let chunks = extractor
+            .extract_file(Path::new("docs/guide.md"), content)
+            .unwrap();
+        let setup = chunks.iter().find(|c| c.heading == "Setup").unwrap();
+
+        assert_eq!(setup.links.len(), 2);
+        assert_eq!(setup.links[0].text, "the config reference");
+        assert_eq!(setup.links[0].line, 5);
+        assert_eq!(
+            setup.links[0].target_file("docs/guide.md").as_deref(),
+            Some("reference.md")
+        );
+        assert_eq!(setup.links[0].anchor(), Some("config-file"));
+        assert_eq!(setup.links[1].line, 6);
+        assert_eq!(
+            setup.links[1].target_file("docs/guide.md").as_deref(),
+            Some("docs/guide.md")
+        );
+        assert_eq!(heading_anchor("Config File (v2)"), "config-file-v2");
+    }
+
+    #[test]
+    fn test_extract_rst_sections() {
+        let extractor = DocExtractor::new();
+        let content = "=======
+Project
+=======
+
+Introduction text for the project.
+
+Installation
+------------
+
+Run the installer.
+
+Usage
+-----
+
+Call the main entry point.
+";
+
+        let chunks = extractor
+            .extract_file(Path::new("docs/index.rst"), content)
+            .unwrap();
+
+        assert_eq!(chunks.len(), 3);
+
+        assert_eq!(chunks[0].heading, "Project");
+        assert_eq!(chunks[0].level, HeadingLevel::H1);
+        assert_eq!((chunks[0].start_line, chunks[0].end_line), (1, 6));
+
+        assert_eq!(chunks[1].heading, "Installation");
+        assert_eq!(chunks[1].level, HeadingLevel::H2);
+        assert_eq!(chunks[1].heading_path, vec!["Project", "Installation"]);
+        assert_eq!((chunks[1].start_line, chunks[1].end_line), (7, 11));
+        assert!(chunks[1].content.contains("Run the installer."));
+
+        assert_eq!(chunks[2].heading, "Usage");
+        assert_eq!(chunks[2].level, HeadingLevel::H2);
+        assert_eq!(chunks[2].heading_path, vec!["Project", "Usage"]);
+        assert_eq!(chunks[2].end_line, 15);
+    }
+
+    #[test]
+    fn test_long_section_is_split_into_overlapping_parts() {
+        let extractor = DocExtractor::new().with_max_chunk_lines(10, 3);
+        let mut content = String::from("# Guide\n\nShort intro paragraph.\n\n## Reference\n");
+        for i in 1..=20 {
+            content.push_str(&format!("Reference line {}\n", i));
+        }
+
+        let chunks = extractor
+            .extract_file(Path::new("docs/guide.md"), &content)
+            .unwrap();
+
+        // The intro stays whole; the 21-line Reference section becomes parts
+        assert_eq!(chunks[0].heading, "Guide");
+        assert_eq!(chunks[0].part(), None);
+        let parts: Vec<_> = chunks.iter().filter(|c| c.heading == "Reference").collect();
+        assert_eq!(parts.len(), 3);
+
+        let ranges: Vec<_> = parts.iter().map(|c| (c.start_line, c.end_line)).collect();
+        assert_eq!(ranges, vec![(5, 14), (12, 21), (19, 25)]);
+
+        for (i, part) in parts.iter().enumerate() {
+            assert_eq!(part.part(), Some(i + 1));
+            assert_eq!(part.heading_path, vec!["Guide", "Reference"]);
+            assert_eq!(part.id, format!("docs/guide.md#reference (part {})", i + 1));
+            assert_eq!(part.anchor(), "reference");
+        }
+        // Consecutive parts share the overlapping lines
+        assert!(parts[0].content.contains("Reference line 8"));
+        assert!(parts[1].content.contains("Reference line 8"));
+        assert!(parts[2].content.ends_with("Reference line 20"));
+
+        let ids: HashSet<_> = chunks.iter().map(|c| &c.id).collect();
+        assert_eq!(ids.len(), chunks.len());
+    }
 }
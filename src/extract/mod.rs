@@ -3,13 +3,21 @@
 //! This module handles extracting semantically meaningful units from:
 //! - Code files (using tree-sitter)
 //! - Documentation files (using Markdown parsing)
+//! - Config files (TOML/YAML keys)
 
 pub mod code;
+pub mod config;
 pub mod doc;
 
 pub use code::{CodeChunk, CodeExtractor, Language, SymbolType};
-pub use doc::{DocChunk, DocExtractor, HeadingLevel};
+pub use config::{extract_config_keys, mentions_config_key, ConfigFormat, ConfigKey};
+pub use doc::{
+    heading_anchor, CodeBlock, DocChunk, DocExtractor, DocLink, HeadingAnchors, HeadingLevel,
+    InlineCode, SpanContext,
+};
 
+use crate::error::DocSentinelError;
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
 /// Compute a stable hash for content
@@ -19,6 +27,55 @@ pub fn content_hash(content: &str) -> String {
     hex::encode(hasher.finalize())
 }
 
+/// Which parts of a chunk make up the text sent for embedding
+///
+/// Code-specialized models tend to match best on source text, prose models
+/// on doc comments. Doc sections have no signature or doc comment, so every
+/// mode other than `Full` embeds their content without the heading and file
+/// preamble.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbeddingTextMode {
+    /// Doc comment, signature, decorators, attributes, and symbol name
+    #[default]
+    Full,
+    /// Only the signature, or the symbol kind and name when there is none
+    SignatureOnly,
+    /// Only the doc comment; undocumented symbols fall back to `SignatureOnly`
+    DocCommentOnly,
+    /// The raw source text
+    Content,
+}
+
+impl std::fmt::Display for EmbeddingTextMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EmbeddingTextMode::Full => write!(f, "full"),
+            EmbeddingTextMode::SignatureOnly => write!(f, "signature_only"),
+            EmbeddingTextMode::DocCommentOnly => write!(f, "doc_comment_only"),
+            EmbeddingTextMode::Content => write!(f, "content"),
+        }
+    }
+}
+
+impl std::str::FromStr for EmbeddingTextMode {
+    type Err = DocSentinelError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "full" => Ok(EmbeddingTextMode::Full),
+            "signature_only" => Ok(EmbeddingTextMode::SignatureOnly),
+            "doc_comment_only" => Ok(EmbeddingTextMode::DocCommentOnly),
+            "content" => Ok(EmbeddingTextMode::Content),
+            _ => Err(DocSentinelError::Config(format!(
+                "Unknown embedding text mode: {} (expected full, signature_only, \
+                 doc_comment_only, or content)",
+                s
+            ))),
+        }
+    }
+}
+
 /// Common trait for all extractable chunks
 pub trait Chunk {
     /// Get the unique identifier for this chunk
@@ -5,6 +5,7 @@
 
 pub mod cli;
 pub mod drift;
+pub mod error;
 pub mod extract;
 pub mod llm;
 pub mod repo;
@@ -12,13 +13,15 @@ pub mod storage;
 pub mod tui;
 
 /// Re-export commonly used types
-pub use drift::{DriftDetector, DriftEvent, DriftSeverity};
+pub use drift::{
+    scan_repository, DriftDetector, DriftEvent, DriftSeverity, ScanOptions, ScanReport,
+};
 pub use extract::{CodeChunk, DocChunk};
 pub use repo::Repository;
 pub use storage::Database;
 
-/// Application-wide error type
-pub use anyhow::Result;
+/// Library error type
+pub use error::{DocSentinelError, Result};
 
 /// Version information
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
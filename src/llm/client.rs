@@ -23,8 +23,12 @@ pub struct LlmConfig {
     pub api_key: Option<String>,
     /// Maximum tokens for response
     pub max_tokens: usize,
+    /// Context window of the model in tokens
+    pub context_tokens: usize,
     /// Temperature for generation
     pub temperature: f32,
+    /// Maximum number of requests in flight at once
+    pub concurrency: usize,
 }
 
 impl Default for LlmConfig {
@@ -34,7 +38,9 @@ impl Default for LlmConfig {
             model: "llama2".to_string(),
             api_key: None,
             max_tokens: 2048,
+            context_tokens: 8192,
             temperature: 0.3,
+            concurrency: 4,
         }
     }
 }
@@ -73,17 +79,121 @@ impl LlmClient {
         })
     }
 
+    /// Create from a repository configuration
+    ///
+    /// Fails when no endpoint or model is configured.
+    pub fn from_repo_config(config: &crate::repo::RepoConfig) -> Result<Self> {
+        let (endpoint, model) = match (&config.llm.endpoint, &config.llm.model) {
+            (Some(endpoint), Some(model)) => (endpoint.clone(), model.clone()),
+            _ => {
+                anyhow::bail!(
+                    "LLM not configured. Set endpoint and model in .docsentinel/config.toml"
+                )
+            }
+        };
+
+        Ok(Self::new(LlmConfig {
+            endpoint,
+            model,
+            api_key: config.llm.api_key.clone(),
+            max_tokens: config.llm.max_tokens,
+            context_tokens: config.llm.context_tokens,
+            temperature: config.llm.temperature,
+            concurrency: config
+                .llm
+                .concurrency
+                .unwrap_or(LlmConfig::default().concurrency),
+        }))
+    }
+
+    /// Tokens a prompt may use, leaving room for the response
+    pub fn prompt_budget(&self) -> usize {
+        self.config
+            .context_tokens
+            .saturating_sub(self.config.max_tokens)
+    }
+
+    /// Maximum number of requests to have in flight at once
+    pub fn concurrency(&self) -> usize {
+        self.config.concurrency.max(1)
+    }
+
     /// Check if the LLM service is available
     pub async fn is_available(&self) -> bool {
-        let url = if self.config.endpoint.contains("11434") {
-            // Ollama
-            format!("{}/api/tags", self.config.endpoint)
+        self.list_models().await.is_ok()
+    }
+
+    /// List the models the endpoint serves
+    ///
+    /// Ollama names include their tag, e.g. `llama2:latest`.
+    pub async fn list_models(&self) -> Result<Vec<String>> {
+        if self.config.endpoint.contains("11434") {
+            let url = format!("{}/api/tags", self.config.endpoint);
+            let response = self
+                .client
+                .get(&url)
+                .send()
+                .await
+                .context("Failed to send request to Ollama")?;
+            if !response.status().is_success() {
+                anyhow::bail!("Ollama request failed: {}", response.status());
+            }
+            let tags: OllamaTagsResponse = response
+                .json()
+                .await
+                .context("Failed to parse Ollama model list")?;
+            Ok(tags.models.into_iter().map(|m| m.name).collect())
         } else {
-            // OpenAI-compatible
-            format!("{}/v1/models", self.config.endpoint)
+            let url = format!("{}/v1/models", self.config.endpoint);
+            let mut req_builder = self.client.get(&url);
+            if let Some(ref key) = self.config.api_key {
+                req_builder = req_builder.header("Authorization", format!("Bearer {}", key));
+            }
+            let response = req_builder
+                .send()
+                .await
+                .context("Failed to send request to OpenAI-compatible API")?;
+            if !response.status().is_success() {
+                anyhow::bail!("OpenAI request failed: {}", response.status());
+            }
+            let models: OpenAIModelsResponse = response
+                .json()
+                .await
+                .context("Failed to parse OpenAI model list")?;
+            Ok(models.data.into_iter().map(|m| m.id).collect())
+        }
+    }
+
+    /// Check that the endpoint is up and, for Ollama, that the model has
+    /// been pulled
+    ///
+    /// Meant to run before any slow work, so a stopped server fails with an
+    /// actionable message instead of an HTTP error partway through.
+    pub async fn ensure_ready(&self) -> Result<()> {
+        let is_ollama = self.config.endpoint.contains("11434");
+        let models = match self.list_models().await {
+            Ok(models) => models,
+            Err(e) if is_ollama => anyhow::bail!(
+                "LLM endpoint {} is not reachable ({}); is Ollama running? Start it with 'ollama serve'",
+                self.config.endpoint,
+                e.root_cause()
+            ),
+            Err(e) => anyhow::bail!(
+                "LLM endpoint {} is not reachable ({})",
+                self.config.endpoint,
+                e.root_cause()
+            ),
         };
 
-        self.client.get(&url).send().await.is_ok()
+        if is_ollama && !model_is_listed(&models, &self.config.model) {
+            anyhow::bail!(
+                "model '{}' not found; run 'ollama pull {}'",
+                self.config.model,
+                self.config.model
+            );
+        }
+
+        Ok(())
     }
 
     /// Generate a completion
@@ -95,6 +205,81 @@ impl LlmClient {
         }
     }
 
+    /// Generate a completion, invoking `on_token` as text arrives
+    ///
+    /// Ollama responses are streamed token by token. Other endpoints fall back
+    /// to a single non-streaming request and deliver the content in one call.
+    pub async fn complete_streaming(
+        &self,
+        prompt: &str,
+        mut on_token: impl FnMut(&str),
+    ) -> Result<LlmResponse> {
+        if !self.config.endpoint.contains("11434") {
+            let response = self.complete_openai(prompt).await?;
+            on_token(&response.content);
+            return Ok(response);
+        }
+
+        let url = format!("{}/api/generate", self.config.endpoint);
+
+        let request = OllamaGenerateRequest {
+            model: self.config.model.clone(),
+            prompt: prompt.to_string(),
+            stream: true,
+            options: OllamaOptions {
+                temperature: self.config.temperature,
+                num_predict: self.config.max_tokens as i32,
+            },
+        };
+
+        let mut response = self
+            .client
+            .post(&url)
+            .json(&request)
+            .send()
+            .await
+            .context("Failed to send request to Ollama")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!("Ollama request failed: {} - {}", status, body);
+        }
+
+        let mut content = String::new();
+        let mut tokens_used = None;
+        let mut buffer: Vec<u8> = Vec::new();
+
+        let mut handle_line = |line: &[u8]| -> Result<()> {
+            if let Some(chunk) = parse_ollama_stream_line(line)? {
+                on_token(&chunk.response);
+                content.push_str(&chunk.response);
+                if chunk.done {
+                    tokens_used = chunk.eval_count.map(|c| c as usize);
+                }
+            }
+            Ok(())
+        };
+
+        while let Some(bytes) = response
+            .chunk()
+            .await
+            .context("Failed to read Ollama response stream")?
+        {
+            buffer.extend_from_slice(&bytes);
+            while let Some(pos) = buffer.iter().position(|b| *b == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=pos).collect();
+                handle_line(&line)?;
+            }
+        }
+        handle_line(&buffer)?;
+
+        Ok(LlmResponse {
+            content,
+            tokens_used,
+        })
+    }
+
     /// Generate completion using Ollama API
     async fn complete_ollama(&self, prompt: &str) -> Result<LlmResponse> {
         let url = format!("{}/api/generate", self.config.endpoint);
@@ -212,6 +397,15 @@ impl LlmClient {
     }
 }
 
+/// Whether `model` is among Ollama's listed models
+///
+/// Ollama resolves an untagged name to its `latest` tag.
+fn model_is_listed(models: &[String], model: &str) -> bool {
+    models
+        .iter()
+        .any(|name| name == model || name.strip_suffix(":latest") == Some(model))
+}
+
 // Ollama API types
 
 #[derive(Debug, Serialize)]
@@ -234,6 +428,46 @@ struct OllamaGenerateResponse {
     eval_count: Option<i32>,
 }
 
+#[derive(Debug, Deserialize)]
+struct OllamaTagsResponse {
+    #[serde(default)]
+    models: Vec<OllamaModel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaModel {
+    name: String,
+}
+
+/// A single newline-delimited JSON object from a streaming Ollama response
+#[derive(Debug, Deserialize)]
+struct OllamaStreamChunk {
+    #[serde(default)]
+    response: String,
+    #[serde(default)]
+    done: bool,
+    eval_count: Option<i32>,
+    error: Option<String>,
+}
+
+/// Parse one line of a streaming Ollama response, skipping blank lines
+fn parse_ollama_stream_line(line: &[u8]) -> Result<Option<OllamaStreamChunk>> {
+    let line = String::from_utf8_lossy(line);
+    let line = line.trim();
+    if line.is_empty() {
+        return Ok(None);
+    }
+
+    let chunk: OllamaStreamChunk =
+        serde_json::from_str(line).context("Failed to parse Ollama stream chunk")?;
+
+    if let Some(error) = chunk.error {
+        anyhow::bail!("Ollama stream error: {}", error);
+    }
+
+    Ok(Some(chunk))
+}
+
 // OpenAI API types
 
 #[derive(Debug, Serialize)]
@@ -268,6 +502,17 @@ struct OpenAIUsage {
     total_tokens: i32,
 }
 
+#[derive(Debug, Deserialize)]
+struct OpenAIModelsResponse {
+    #[serde(default)]
+    data: Vec<OpenAIModel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIModel {
+    id: String,
+}
+
 /// Mock LLM client for testing
 ///
 /// Used in test environments to simulate LLM responses without requiring
@@ -330,6 +575,92 @@ mod tests {
         assert!(response.content.contains("success"));
     }
 
+    #[test]
+    fn test_parse_ollama_stream_line() {
+        assert!(parse_ollama_stream_line(b"  \n").unwrap().is_none());
+
+        let chunk = parse_ollama_stream_line(br#"{"response":"Hel","done":false}"#)
+            .unwrap()
+            .unwrap();
+        assert_eq!(chunk.response, "Hel");
+        assert!(!chunk.done);
+
+        let last = parse_ollama_stream_line(br#"{"response":"","done":true,"eval_count":7}"#)
+            .unwrap()
+            .unwrap();
+        assert!(last.done);
+        assert_eq!(last.eval_count, Some(7));
+
+        assert!(parse_ollama_stream_line(br#"{"error":"model not found"}"#).is_err());
+    }
+
+    #[test]
+    fn test_model_is_listed() {
+        let tags: OllamaTagsResponse = serde_json::from_str(
+            r#"{"models":[{"name":"llama2:latest","size":1},{"name":"codellama:7b"}]}"#,
+        )
+        .unwrap();
+        let models: Vec<String> = tags.models.into_iter().map(|m| m.name).collect();
+
+        assert!(model_is_listed(&models, "llama2"));
+        assert!(model_is_listed(&models, "llama2:latest"));
+        assert!(model_is_listed(&models, "codellama:7b"));
+        assert!(!model_is_listed(&models, "codellama"));
+        assert!(!model_is_listed(&models, "mistral"));
+    }
+
+    #[test]
+    fn test_ensure_ready_reports_unreachable_endpoint() {
+        // Nothing listens on the discard port, so the connection is refused
+        let client = LlmClient::new(LlmConfig {
+            endpoint: "http://127.0.0.1:9".to_string(),
+            ..Default::default()
+        });
+        let rt = tokio::runtime::Runtime::new().unwrap();
+
+        assert!(!rt.block_on(client.is_available()));
+        let error = rt.block_on(client.ensure_ready()).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("LLM endpoint http://127.0.0.1:9 is not reachable"));
+    }
+
+    #[test]
+    fn test_from_repo_config_uses_overrides() {
+        use crate::drift::build_embedding_provider;
+        use crate::repo::{ConfigOverrides, RepoConfig};
+
+        // Nothing configured: the overrides alone are enough
+        let overrides = ConfigOverrides {
+            llm_endpoint: Some("http://gpu-box:11434".to_string()),
+            llm_model: Some("qwen2.5-coder".to_string()),
+            embed_model: Some("mxbai-embed-large".to_string()),
+        };
+        assert!(LlmClient::from_repo_config(&RepoConfig::default()).is_err());
+        let config = RepoConfig::default().with_overrides(&overrides);
+
+        let client = LlmClient::from_repo_config(&config).unwrap();
+        assert_eq!(client.config.endpoint, "http://gpu-box:11434");
+        assert_eq!(client.config.model, "qwen2.5-coder");
+        assert_eq!(config.embedding_endpoint(), Some("http://gpu-box:11434"));
+        assert_eq!(
+            build_embedding_provider(&config).model(),
+            "mxbai-embed-large"
+        );
+
+        // Overrides win over configured values and leave the rest alone
+        let mut configured = RepoConfig::default();
+        configured.llm.endpoint = Some("http://localhost:11434".to_string());
+        configured.llm.model = Some("llama2".to_string());
+        let config = configured.with_overrides(&ConfigOverrides {
+            llm_model: Some("mistral".to_string()),
+            ..Default::default()
+        });
+        let client = LlmClient::from_repo_config(&config).unwrap();
+        assert_eq!(client.config.endpoint, "http://localhost:11434");
+        assert_eq!(client.config.model, "mistral");
+    }
+
     #[test]
     fn test_default_config() {
         let config = LlmConfig::default();
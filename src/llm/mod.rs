@@ -9,12 +9,18 @@ mod client;
 mod prompts;
 
 pub use client::{LlmClient, LlmConfig, LlmResponse};
-pub use prompts::{AnalysisPrompt, FixPrompt};
+pub use prompts::{
+    estimate_tokens, truncate_to_tokens, AnalysisPrompt, FixPrompt, TRUNCATION_MARKER,
+};
 
 use crate::drift::DriftEvent;
 use crate::extract::{CodeChunk, DocChunk};
 use anyhow::Result;
+use futures_util::stream::{self, StreamExt};
+use prompts::{code_content, render_within};
 use serde::{Deserialize, Serialize};
+use std::future::Future;
+use tokio::sync::Semaphore;
 
 /// Analysis result from LLM
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -40,6 +46,8 @@ pub struct AnalysisRequest {
     pub new_code: Option<CodeChunk>,
     /// Related documentation chunk
     pub doc_chunk: DocChunk,
+    /// Token budget the prompt must fit in (if limited)
+    pub token_budget: Option<usize>,
 }
 
 impl AnalysisRequest {
@@ -55,117 +63,163 @@ impl AnalysisRequest {
             old_code,
             new_code,
             doc_chunk,
+            token_budget: None,
         }
     }
 
+    /// Limit the prompt to `budget` tokens, truncating code and doc content
+    pub fn with_token_budget(mut self, budget: usize) -> Self {
+        self.token_budget = Some(budget);
+        self
+    }
+
     /// Generate the prompt for this request
     pub fn to_prompt(&self) -> String {
-        let mut prompt = String::new();
-
-        prompt.push_str("You are analyzing a potential documentation drift issue.\n\n");
-
-        // Add drift event info
-        prompt.push_str("## Drift Event\n");
-        prompt.push_str(&format!("Severity: {}\n", self.drift_event.severity));
-        prompt.push_str(&format!("Description: {}\n", self.drift_event.description));
-        prompt.push_str(&format!("Evidence: {}\n\n", self.drift_event.evidence));
-
-        // Add old code if available
-        if let Some(ref old) = self.old_code {
-            prompt.push_str("## Previous Code\n");
-            prompt.push_str(&format!("Symbol: {}\n", old.symbol_name));
-            if let Some(ref sig) = old.signature {
-                prompt.push_str(&format!("Signature: {}\n", sig));
-            }
-            if let Some(ref doc) = old.doc_comment {
-                prompt.push_str(&format!("Doc comment:\n{}\n", doc));
-            }
-            prompt.push_str(&format!("```\n{}\n```\n\n", old.content));
-        }
+        let parts = [
+            code_content(self.old_code.as_ref()),
+            code_content(self.new_code.as_ref()),
+            &self.doc_chunk.content,
+        ];
 
-        // Add new code if available
-        if let Some(ref new) = self.new_code {
-            prompt.push_str("## Current Code\n");
-            prompt.push_str(&format!("Symbol: {}\n", new.symbol_name));
-            if let Some(ref sig) = new.signature {
-                prompt.push_str(&format!("Signature: {}\n", sig));
-            }
-            if let Some(ref doc) = new.doc_comment {
-                prompt.push_str(&format!("Doc comment:\n{}\n", doc));
+        render_within(&parts, self.token_budget, |parts| {
+            let mut prompt = String::new();
+
+            prompt.push_str("You are analyzing a potential documentation drift issue.\n\n");
+
+            // Add drift event info
+            prompt.push_str("## Drift Event\n");
+            prompt.push_str(&format!("Severity: {}\n", self.drift_event.severity));
+            prompt.push_str(&format!("Description: {}\n", self.drift_event.description));
+            prompt.push_str(&format!("Evidence: {}\n\n", self.drift_event.evidence));
+
+            // Add old and new code if available
+            for (title, code, content) in [
+                ("Previous Code", &self.old_code, parts[0]),
+                ("Current Code", &self.new_code, parts[1]),
+            ] {
+                let Some(code) = code else {
+                    continue;
+                };
+                prompt.push_str(&format!("## {}\n", title));
+                prompt.push_str(&format!("Symbol: {}\n", code.symbol_name));
+                if let Some(ref sig) = code.signature {
+                    prompt.push_str(&format!("Signature: {}\n", sig));
+                }
+                if let Some(ref doc) = code.doc_comment {
+                    prompt.push_str(&format!("Doc comment:\n{}\n", doc));
+                }
+                prompt.push_str(&format!("```\n{}\n```\n\n", content));
             }
-            prompt.push_str(&format!("```\n{}\n```\n\n", new.content));
-        }
 
-        // Add documentation
-        prompt.push_str("## Current Documentation\n");
-        prompt.push_str(&format!("Section: {}\n", self.doc_chunk.full_path()));
-        prompt.push_str(&format!("Content:\n{}\n\n", self.doc_chunk.content));
-
-        // Add instructions
-        prompt.push_str("## Instructions\n");
-        prompt.push_str("Analyze this drift and respond with a JSON object containing:\n");
-        prompt.push_str("- summary: Brief summary of what changed\n");
-        prompt.push_str("- reason: Why the documentation is now incorrect\n");
-        prompt.push_str(
-            "- suggested_fix: The corrected documentation text (or null if no fix needed)\n",
-        );
-        prompt.push_str("- confidence: Your confidence in this analysis (0.0 to 1.0)\n\n");
-        prompt.push_str("Respond ONLY with valid JSON, no other text.\n");
+            // Add documentation
+            prompt.push_str("## Current Documentation\n");
+            prompt.push_str(&format!("Section: {}\n", self.doc_chunk.full_path()));
+            prompt.push_str(&format!("Content:\n{}\n\n", parts[2]));
+
+            // Add instructions
+            prompt.push_str("## Instructions\n");
+            prompt.push_str("Analyze this drift and respond with a JSON object containing:\n");
+            prompt.push_str("- summary: Brief summary of what changed\n");
+            prompt.push_str("- reason: Why the documentation is now incorrect\n");
+            prompt.push_str(
+                "- suggested_fix: The corrected documentation text (or null if no fix needed)\n",
+            );
+            prompt.push_str("- confidence: Your confidence in this analysis (0.0 to 1.0)\n\n");
+            prompt.push_str("Respond ONLY with valid JSON, no other text.\n");
 
-        prompt
+            prompt
+        })
     }
 }
 
 /// Analyzer that uses LLM for drift analysis
+///
+/// At most [`LlmClient::concurrency`] requests are in flight at once, across
+/// every caller sharing the analyzer.
 pub struct DriftAnalyzer {
     client: LlmClient,
+    permits: Semaphore,
 }
 
 impl DriftAnalyzer {
     /// Create a new drift analyzer
     pub fn new(client: LlmClient) -> Self {
-        Self { client }
+        let permits = Semaphore::new(client.concurrency());
+        Self { client, permits }
     }
 
     /// Analyze a drift event
     pub async fn analyze(&self, request: AnalysisRequest) -> Result<AnalysisResult> {
-        let prompt = request.to_prompt();
+        let _permit = self.permits.acquire().await?;
+        let prompt = request
+            .with_token_budget(self.client.prompt_budget())
+            .to_prompt();
         let response = self.client.complete(&prompt).await?;
 
-        // Parse the JSON response
-        let result: AnalysisResult = serde_json::from_str(&response.content)
-            .map_err(|e| anyhow::anyhow!("Failed to parse LLM response: {}", e))?;
+        parse_analysis(&response.content)
+    }
 
-        Ok(result)
+    /// Analyze drift events concurrently, returning each outcome in request
+    /// order
+    pub async fn analyze_each(
+        &self,
+        requests: Vec<AnalysisRequest>,
+    ) -> Vec<Result<AnalysisResult>> {
+        run_in_order(requests, self.client.concurrency(), |request| {
+            self.analyze(request)
+        })
+        .await
     }
 
     /// Analyze multiple drift events
+    ///
+    /// Results are in request order; a failed analysis yields a placeholder
+    /// result with zero confidence.
     pub async fn analyze_batch(
         &self,
         requests: Vec<AnalysisRequest>,
     ) -> Result<Vec<AnalysisResult>> {
-        let mut results = Vec::with_capacity(requests.len());
-
-        for request in requests {
-            match self.analyze(request).await {
-                Ok(result) => results.push(result),
-                Err(e) => {
-                    tracing::warn!("Failed to analyze drift event: {}", e);
-                    // Add a placeholder result
-                    results.push(AnalysisResult {
-                        summary: "Analysis failed".to_string(),
-                        reason: e.to_string(),
-                        suggested_fix: None,
-                        confidence: 0.0,
-                    });
-                }
-            }
-        }
+        Ok(self
+            .analyze_each(requests)
+            .await
+            .into_iter()
+            .map(|result| result.unwrap_or_else(failed_analysis))
+            .collect())
+    }
+}
+
+/// Parse the JSON an LLM returned for an analysis prompt
+fn parse_analysis(content: &str) -> Result<AnalysisResult> {
+    serde_json::from_str(content)
+        .map_err(|e| anyhow::anyhow!("Failed to parse LLM response: {}", e))
+}
 
-        Ok(results)
+/// Placeholder result for an analysis that failed
+fn failed_analysis(error: anyhow::Error) -> AnalysisResult {
+    tracing::warn!("Failed to analyze drift event: {}", error);
+    AnalysisResult {
+        summary: "Analysis failed".to_string(),
+        reason: error.to_string(),
+        suggested_fix: None,
+        confidence: 0.0,
     }
 }
 
+/// Run `analyze` over `items` with up to `concurrency` calls in flight
+///
+/// Calls may finish in any order, but the outputs match the order of `items`.
+async fn run_in_order<T, R, F, Fut>(items: Vec<T>, concurrency: usize, analyze: F) -> Vec<R>
+where
+    F: Fn(T) -> Fut,
+    Fut: Future<Output = R>,
+{
+    stream::iter(items)
+        .map(analyze)
+        .buffered(concurrency.max(1))
+        .collect()
+        .await
+}
+
 /// Generate a fix suggestion without LLM (rule-based)
 pub fn generate_simple_fix(
     _drift_event: &DriftEvent,
@@ -206,6 +260,7 @@ mod tests {
     use crate::drift::DriftSeverity;
     use crate::extract::code::{Language, SymbolType};
     use crate::extract::doc::HeadingLevel;
+    use crate::llm::client::MockLlmClient;
 
     #[test]
     fn test_analysis_request_prompt() {
@@ -243,4 +298,99 @@ mod tests {
         assert!(prompt.contains("my_func"));
         assert!(prompt.contains("JSON"));
     }
+
+    #[test]
+    fn test_concurrent_analysis_keeps_request_order() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let mut client = MockLlmClient::new();
+        client.add_response(
+            "Event 3",
+            r#"{"summary": "Event 3 analyzed", "reason": "", "suggested_fix": null, "confidence": 0.9}"#,
+        );
+        client.add_response("Event 5", "not json");
+
+        let doc = DocChunk::new("README.md", vec![], "Doc", HeadingLevel::H1, "", 1, 1);
+        let requests: Vec<AnalysisRequest> = (0..8)
+            .map(|i| {
+                let event = DriftEvent::new(DriftSeverity::Low, &format!("Event {}", i), "", 0.5);
+                AnalysisRequest::new(event, None, None, doc.clone())
+            })
+            .collect();
+
+        let in_flight = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        // Earlier requests take longer, so later ones finish first
+        let results: Vec<AnalysisResult> = rt
+            .block_on(run_in_order(requests, 3, |request| {
+                let (client, in_flight, peak) = (&client, &in_flight, &peak);
+                async move {
+                    let running = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(running, Ordering::SeqCst);
+                    let delay = 40 - 5 * request.drift_event.description[6..].parse::<u64>()?;
+                    tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    parse_analysis(&client.complete(&request.to_prompt())?.content)
+                }
+            }))
+            .into_iter()
+            .map(|result| result.unwrap_or_else(failed_analysis))
+            .collect();
+
+        assert_eq!(results.len(), 8);
+        assert_eq!(peak.load(Ordering::SeqCst), 3);
+        assert_eq!(results[3].summary, "Event 3 analyzed");
+        assert_eq!(results[5].summary, "Analysis failed");
+        assert_eq!(results[5].confidence, 0.0);
+        for i in [0, 1, 2, 4, 6, 7] {
+            assert_eq!(results[i].summary, "Mock analysis");
+        }
+    }
+
+    #[test]
+    fn test_oversized_prompt_is_truncated_to_budget() {
+        let event = DriftEvent::new(DriftSeverity::High, "Signature changed", "", 0.9);
+
+        let body: String = (0..2000)
+            .map(|i| format!("    let x{i} = {i};\n"))
+            .collect();
+        let mut code = CodeChunk::new(
+            "test.rs",
+            "my_func",
+            SymbolType::Function,
+            &format!("fn my_func(x: i32) {{\n{body}}}"),
+            Language::Rust,
+            1,
+            2002,
+        );
+        code.signature = Some("fn my_func(x: i32)".to_string());
+
+        let text: String = (0..2000)
+            .map(|i| format!("Line {i} of the guide.\n"))
+            .collect();
+        let doc = DocChunk::new(
+            "README.md",
+            vec!["API".to_string(), "my_func".to_string()],
+            "my_func",
+            HeadingLevel::H2,
+            &format!("## my_func\n{text}"),
+            1,
+            2001,
+        );
+
+        let request = AnalysisRequest::new(event, None, Some(code), doc);
+        let full = request.to_prompt();
+        assert!(estimate_tokens(&full) > 2000);
+        assert!(!full.contains(TRUNCATION_MARKER));
+
+        let prompt = request.with_token_budget(2000).to_prompt();
+        assert!(estimate_tokens(&prompt) <= 2000);
+        assert_eq!(prompt.matches(TRUNCATION_MARKER).count(), 2);
+        assert!(prompt.contains("Signature: fn my_func(x: i32)"));
+        assert!(prompt.contains("fn my_func(x: i32) {"));
+        assert!(prompt.contains("Section: API > my_func"));
+        assert!(prompt.contains("## my_func\n"));
+        assert!(prompt.contains("Respond ONLY with valid JSON"));
+    }
 }
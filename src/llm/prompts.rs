@@ -3,74 +3,158 @@
 use crate::drift::DriftEvent;
 use crate::extract::{CodeChunk, DocChunk};
 
+/// Marker left where prompt content was cut to fit the token budget
+pub const TRUNCATION_MARKER: &str = "[...truncated...]";
+
+/// Rough token count of `text`, at about four characters per token
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// Render a prompt whose variable-length `parts` (code, doc sections) are
+/// shortened to fit `budget` tokens
+///
+/// `render` builds the prompt from the parts. When the full prompt is over
+/// budget, the room left beside the fixed text (instructions, signatures,
+/// headings) is shared between the parts, smallest first, and each part that
+/// does not fit is cut with [`truncate_to_tokens`].
+pub(crate) fn render_within(
+    parts: &[&str],
+    budget: Option<usize>,
+    render: impl Fn(&[&str]) -> String,
+) -> String {
+    let prompt = render(parts);
+    let Some(budget) = budget.filter(|&b| estimate_tokens(&prompt) > b) else {
+        return prompt;
+    };
+
+    let fixed = estimate_tokens(&render(&vec![""; parts.len()]));
+    let sizes: Vec<usize> = parts.iter().map(|p| estimate_tokens(p)).collect();
+    let shares = share_budget(&sizes, budget.saturating_sub(fixed));
+    let truncated: Vec<String> = parts
+        .iter()
+        .zip(shares)
+        .map(|(part, share)| truncate_to_tokens(part, share))
+        .collect();
+    render(&truncated.iter().map(String::as_str).collect::<Vec<_>>())
+}
+
+/// Split `available` tokens between parts of the given sizes
+///
+/// Parts smaller than an even share keep their size and pass the rest on.
+fn share_budget(sizes: &[usize], available: usize) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by_key(|&i| sizes[i]);
+
+    let mut shares = vec![0; sizes.len()];
+    let mut left = available;
+    for (done, &i) in order.iter().enumerate() {
+        shares[i] = sizes[i].min(left / (sizes.len() - done));
+        left -= shares[i];
+    }
+    shares
+}
+
+/// Content of an optional code chunk, empty when absent
+pub(crate) fn code_content(code: Option<&CodeChunk>) -> &str {
+    code.map(|c| c.content.as_str()).unwrap_or_default()
+}
+
+/// Cut `text` to about `max_tokens`, keeping whole leading lines and ending
+/// with [`TRUNCATION_MARKER`]
+///
+/// The first line is always kept, since it holds a doc section's heading or
+/// the start of a code signature.
+pub fn truncate_to_tokens(text: &str, max_tokens: usize) -> String {
+    if estimate_tokens(text) <= max_tokens {
+        return text.to_string();
+    }
+
+    let max_chars = max_tokens.saturating_mul(4);
+    let mut lines = text.lines();
+    let mut kept = lines.next().unwrap_or_default().to_string();
+    for line in lines {
+        if kept.chars().count() + line.chars().count() + TRUNCATION_MARKER.len() + 2 > max_chars {
+            break;
+        }
+        kept.push('\n');
+        kept.push_str(line);
+    }
+    kept.push('\n');
+    kept.push_str(TRUNCATION_MARKER);
+    kept
+}
+
 /// Prompt for analyzing drift
 pub struct AnalysisPrompt;
 
 impl AnalysisPrompt {
     /// Generate a prompt for drift analysis
+    ///
+    /// With a `budget`, code and doc content are truncated so the prompt
+    /// stays within that many tokens (estimated).
     pub fn generate(
         drift_event: &DriftEvent,
         old_code: Option<&CodeChunk>,
         new_code: Option<&CodeChunk>,
         doc_chunk: &DocChunk,
+        budget: Option<usize>,
     ) -> String {
-        let mut prompt = String::new();
-
-        prompt.push_str(ANALYSIS_SYSTEM_PROMPT);
-        prompt.push('\n');
-
-        // Add context
-        prompt.push_str("## Context\n\n");
-        prompt.push_str(&format!("**Drift Type:** {}\n", drift_event.severity));
-        prompt.push_str(&format!("**Description:** {}\n", drift_event.description));
-        prompt.push_str(&format!("**Evidence:** {}\n\n", drift_event.evidence));
-
-        // Add code information
-        if let Some(old) = old_code {
-            prompt.push_str("### Previous Code\n\n");
-            prompt.push_str(&format!("**File:** `{}`\n", old.file_path));
-            prompt.push_str(&format!("**Symbol:** `{}`\n", old.symbol_name));
-            if let Some(ref sig) = old.signature {
-                prompt.push_str(&format!("**Signature:** `{}`\n", sig));
+        let parts = [
+            code_content(old_code),
+            code_content(new_code),
+            &doc_chunk.content,
+        ];
+
+        render_within(&parts, budget, |parts| {
+            let mut prompt = String::new();
+
+            prompt.push_str(ANALYSIS_SYSTEM_PROMPT);
+            prompt.push('\n');
+
+            // Add context
+            prompt.push_str("## Context\n\n");
+            prompt.push_str(&format!("**Drift Type:** {}\n", drift_event.severity));
+            prompt.push_str(&format!("**Description:** {}\n", drift_event.description));
+            prompt.push_str(&format!("**Evidence:** {}\n\n", drift_event.evidence));
+
+            // Add code information
+            for (title, code, content) in [
+                ("Previous Code", old_code, parts[0]),
+                ("Current Code", new_code, parts[1]),
+            ] {
+                let Some(code) = code else {
+                    continue;
+                };
+                prompt.push_str(&format!("### {}\n\n", title));
+                prompt.push_str(&format!("**File:** `{}`\n", code.file_path));
+                prompt.push_str(&format!("**Symbol:** `{}`\n", code.symbol_name));
+                if let Some(ref sig) = code.signature {
+                    prompt.push_str(&format!("**Signature:** `{}`\n", sig));
+                }
+                if let Some(ref doc) = code.doc_comment {
+                    prompt.push_str(&format!("**Doc Comment:**\n```\n{}\n```\n", doc));
+                }
+                prompt.push_str(&format!(
+                    "\n**Code:**\n```{}\n{}\n```\n\n",
+                    code.language, content
+                ));
             }
-            if let Some(ref doc) = old.doc_comment {
-                prompt.push_str(&format!("**Doc Comment:**\n```\n{}\n```\n", doc));
-            }
-            prompt.push_str(&format!(
-                "\n**Code:**\n```{}\n{}\n```\n\n",
-                old.language, old.content
-            ));
-        }
 
-        if let Some(new) = new_code {
-            prompt.push_str("### Current Code\n\n");
-            prompt.push_str(&format!("**File:** `{}`\n", new.file_path));
-            prompt.push_str(&format!("**Symbol:** `{}`\n", new.symbol_name));
-            if let Some(ref sig) = new.signature {
-                prompt.push_str(&format!("**Signature:** `{}`\n", sig));
-            }
-            if let Some(ref doc) = new.doc_comment {
-                prompt.push_str(&format!("**Doc Comment:**\n```\n{}\n```\n", doc));
-            }
+            // Add documentation
+            prompt.push_str("### Documentation Section\n\n");
+            prompt.push_str(&format!("**File:** `{}`\n", doc_chunk.file_path));
+            prompt.push_str(&format!("**Section:** {}\n", doc_chunk.full_path()));
             prompt.push_str(&format!(
-                "\n**Code:**\n```{}\n{}\n```\n\n",
-                new.language, new.content
+                "\n**Content:**\n```markdown\n{}\n```\n\n",
+                parts[2]
             ));
-        }
-
-        // Add documentation
-        prompt.push_str("### Documentation Section\n\n");
-        prompt.push_str(&format!("**File:** `{}`\n", doc_chunk.file_path));
-        prompt.push_str(&format!("**Section:** {}\n", doc_chunk.full_path()));
-        prompt.push_str(&format!(
-            "\n**Content:**\n```markdown\n{}\n```\n\n",
-            doc_chunk.content
-        ));
 
-        // Add instructions
-        prompt.push_str(ANALYSIS_INSTRUCTIONS);
+            // Add instructions
+            prompt.push_str(ANALYSIS_INSTRUCTIONS);
 
-        prompt
+            prompt
+        })
     }
 }
 
@@ -79,44 +163,52 @@ pub struct FixPrompt;
 
 impl FixPrompt {
     /// Generate a prompt for fix generation
+    ///
+    /// With a `budget`, code and doc content are truncated so the prompt
+    /// stays within that many tokens (estimated).
     pub fn generate(
         drift_event: &DriftEvent,
         new_code: &CodeChunk,
         doc_chunk: &DocChunk,
+        budget: Option<usize>,
     ) -> String {
-        let mut prompt = String::new();
+        let parts = [new_code.content.as_str(), doc_chunk.content.as_str()];
 
-        prompt.push_str(FIX_SYSTEM_PROMPT);
-        prompt.push('\n');
+        render_within(&parts, budget, |parts| {
+            let mut prompt = String::new();
 
-        // Add context
-        prompt.push_str("## Issue\n\n");
-        prompt.push_str(&format!("{}\n\n", drift_event.description));
-        prompt.push_str(&format!("**Evidence:** {}\n\n", drift_event.evidence));
+            prompt.push_str(FIX_SYSTEM_PROMPT);
+            prompt.push('\n');
 
-        // Add current code
-        prompt.push_str("## Current Code\n\n");
-        prompt.push_str(&format!("**Symbol:** `{}`\n", new_code.symbol_name));
-        if let Some(ref sig) = new_code.signature {
-            prompt.push_str(&format!("**Signature:** `{}`\n", sig));
-        }
-        if let Some(ref doc) = new_code.doc_comment {
-            prompt.push_str(&format!("**Doc Comment:**\n```\n{}\n```\n", doc));
-        }
-        prompt.push_str(&format!(
-            "\n```{}\n{}\n```\n\n",
-            new_code.language, new_code.content
-        ));
+            // Add context
+            prompt.push_str("## Issue\n\n");
+            prompt.push_str(&format!("{}\n\n", drift_event.description));
+            prompt.push_str(&format!("**Evidence:** {}\n\n", drift_event.evidence));
 
-        // Add documentation to fix
-        prompt.push_str("## Documentation to Update\n\n");
-        prompt.push_str(&format!("**Section:** {}\n\n", doc_chunk.full_path()));
-        prompt.push_str(&format!("```markdown\n{}\n```\n\n", doc_chunk.content));
+            // Add current code
+            prompt.push_str("## Current Code\n\n");
+            prompt.push_str(&format!("**Symbol:** `{}`\n", new_code.symbol_name));
+            if let Some(ref sig) = new_code.signature {
+                prompt.push_str(&format!("**Signature:** `{}`\n", sig));
+            }
+            if let Some(ref doc) = new_code.doc_comment {
+                prompt.push_str(&format!("**Doc Comment:**\n```\n{}\n```\n", doc));
+            }
+            prompt.push_str(&format!(
+                "\n```{}\n{}\n```\n\n",
+                new_code.language, parts[0]
+            ));
+
+            // Add documentation to fix
+            prompt.push_str("## Documentation to Update\n\n");
+            prompt.push_str(&format!("**Section:** {}\n\n", doc_chunk.full_path()));
+            prompt.push_str(&format!("```markdown\n{}\n```\n\n", parts[1]));
 
-        // Add instructions
-        prompt.push_str(FIX_INSTRUCTIONS);
+            // Add instructions
+            prompt.push_str(FIX_INSTRUCTIONS);
 
-        prompt
+            prompt
+        })
     }
 }
 
@@ -267,7 +359,7 @@ mod tests {
             15,
         );
 
-        let prompt = AnalysisPrompt::generate(&event, None, Some(&code), &doc);
+        let prompt = AnalysisPrompt::generate(&event, None, Some(&code), &doc, None);
 
         assert!(prompt.contains("Function signature changed"));
         assert!(prompt.contains("greet"));
@@ -303,9 +395,59 @@ mod tests {
             5,
         );
 
-        let prompt = FixPrompt::generate(&event, &code, &doc);
+        let prompt = FixPrompt::generate(&event, &code, &doc, None);
 
         assert!(prompt.contains("greet"));
         assert!(prompt.contains("updated_content"));
     }
+
+    #[test]
+    fn test_truncate_keeps_first_line() {
+        let text = "## Heading\none two three\nfour five six\n";
+        assert_eq!(truncate_to_tokens(text, 100), text);
+
+        let cut = truncate_to_tokens(text, 3);
+        assert_eq!(cut, format!("## Heading\n{}", TRUNCATION_MARKER));
+    }
+
+    #[test]
+    fn test_share_budget_gives_small_parts_their_size() {
+        assert_eq!(share_budget(&[10, 500, 1000], 400), vec![10, 195, 195]);
+        assert_eq!(share_budget(&[10, 20], 400), vec![10, 20]);
+        assert_eq!(share_budget(&[], 400), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_fix_prompt_within_budget() {
+        let event = DriftEvent::new(DriftSeverity::High, "Parameter added", "", 0.9);
+
+        let body: String = (0..1000).map(|i| format!("    step({i});\n")).collect();
+        let mut code = CodeChunk::new(
+            "src/lib.rs",
+            "greet",
+            SymbolType::Function,
+            &format!("pub fn greet(name: &str) {{\n{body}}}"),
+            Language::Rust,
+            1,
+            1002,
+        );
+        code.signature = Some("pub fn greet(name: &str)".to_string());
+
+        let doc = DocChunk::new(
+            "README.md",
+            vec!["API".to_string()],
+            "API",
+            HeadingLevel::H2,
+            "## API\nThe greet function.",
+            1,
+            2,
+        );
+
+        let prompt = FixPrompt::generate(&event, &code, &doc, Some(1000));
+        assert!(estimate_tokens(&prompt) <= 1000);
+        assert!(prompt.contains(TRUNCATION_MARKER));
+        assert!(prompt.contains("**Signature:** `pub fn greet(name: &str)`"));
+        // The short doc section fits and is left whole
+        assert!(prompt.contains("## API\nThe greet function."));
+    }
 }
@@ -3,17 +3,33 @@
 //! A local-first tool that detects when documentation no longer matches code,
 //! explains why, and optionally proposes fixes.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use docsentinel::cli::{
-    fix, generate, hooks, ignore, init, print_events_json, print_events_text, scan, status, Cli,
-    Commands, OutputFormat,
+    analyze_buffer, analyze_target, baseline, check_cli, clean, coverage, diff, doctor, embed,
+    export, fix, generate, hooks, ignore, import, init, interrupt_flag, print_coverage_text,
+    print_diff_json, print_diff_text, print_events_json, print_events_junit, print_events_markdown,
+    print_events_text, print_inventory_text, print_json, render_events_json, render_events_junit,
+    render_events_markdown, render_events_text, report, scan, stats, status, symbol_inventory,
+    unbaselined_events, Cli, Commands, GenerateOptions, OutputFormat, SeverityLevel, Style,
 };
-use std::path::Path;
+use docsentinel::drift::{DriftEvent, DriftSeverity, ScanOptions};
+use docsentinel::repo::ConfigOverrides;
+use docsentinel::DocSentinelError;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 
+/// Set in the environment of a watcher started by `watch --background`, so
+/// the re-run command watches instead of spawning another copy of itself
+const BACKGROUND_WATCH_ENV: &str = "DOCSENTINEL_BACKGROUND_WATCH";
+
 fn main() -> Result<()> {
     // Parse CLI arguments
     let cli = Cli::parse_args();
+    let style = cli.style();
 
     // Setup logging
     let filter = if cli.verbose {
@@ -23,169 +39,539 @@ fn main() -> Result<()> {
     };
 
     tracing_subscriber::registry()
-        .with(fmt::layer())
+        .with(
+            fmt::layer()
+                .with_writer(std::io::stderr)
+                .with_ansi(style.is_fancy()),
+        )
         .with(filter)
         .init();
 
     // Get repository path
     let repo_path = Path::new(&cli.path);
+    let scope = cli.scope.as_deref().map(Path::new);
+    let overrides = cli.config_overrides();
 
     // Execute command
     match cli.command {
         Commands::Init(args) => {
-            init(repo_path, args.force, args.quick)?;
+            init(repo_path, scope, args.force, args.quick, style)?;
 
             if !args.no_scan && !args.quick {
-                println!("Running initial scan...\n");
-                let events = scan(repo_path, true, None, false)?;
+                eprintln!("Running initial scan...\n");
+                let options = ScanOptions {
+                    full: true,
+                    ..Default::default()
+                };
+                let events = scan(repo_path, scope, &options, &overrides, style)?;
+                print_events(cli.format, cli.json_compact, style, &events)?;
+            }
+        }
 
-                match cli.format {
-                    OutputFormat::Json => print_events_json(&events)?,
-                    OutputFormat::Text => print_events_text(&events),
+        Commands::Scan(args) => {
+            let options = ScanOptions {
+                full: args.full,
+                range: args.range.clone(),
+                base: args.base.clone(),
+                uncommitted: args.uncommitted,
+                embeddings: !args.no_embeddings,
+                with_llm: args.with_llm,
+                reembed: args.reembed,
+                include: args.include.clone(),
+                exclude: args.exclude.clone(),
+            };
+            let events = scan(repo_path, scope, &options, &overrides, style)?;
+            match args.output.as_deref() {
+                Some(file) => {
+                    write_events(cli.format, cli.json_compact, &events, Path::new(file))?;
+                    eprintln!("{} Wrote results to {}", style.ok(), file);
                 }
+                None => print_events(cli.format, cli.json_compact, style, &events)?,
+            }
+            if args.update_baseline {
+                baseline(repo_path, scope, style)?;
+            }
+            if args.fail_on.is_some() {
+                let counted = unbaselined_events(repo_path, scope, &events)?;
+                if counted.len() < events.len() {
+                    eprintln!(
+                        "{} baselined drift event(s) not counted toward --fail-on",
+                        events.len() - counted.len()
+                    );
+                }
+                exit_on_severity(args.fail_on, &counted);
             }
         }
 
-        Commands::Scan(args) => {
-            let events = scan(
-                repo_path,
-                args.full,
-                args.range.as_deref(),
-                args.uncommitted,
-            )?;
+        Commands::Diff(args) => {
+            let diff = diff(repo_path, scope, &args.range)?;
 
             match cli.format {
-                OutputFormat::Json => print_events_json(&events)?,
-                OutputFormat::Text => print_events_text(&events),
+                OutputFormat::Json => print_diff_json(&diff, cli.json_compact)?,
+                OutputFormat::Text => print_diff_text(&diff),
+                OutputFormat::Markdown => print_events_markdown(&diff.introduced),
+                OutputFormat::Junit => print_events_junit(&diff.introduced),
+            }
+
+            if let Some(level) = args.fail_on {
+                let threshold: DriftSeverity = level.into();
+                if diff
+                    .introduced
+                    .iter()
+                    .any(|event| event.severity <= threshold)
+                {
+                    eprintln!("New drift at or above {} severity introduced", threshold);
+                    std::process::exit(1);
+                }
             }
         }
 
         Commands::Status(args) => {
-            status(repo_path, args.all, args.severity.as_deref())?;
+            status(
+                repo_path,
+                scope,
+                args.all,
+                args.severity.as_deref(),
+                args.tag.as_deref(),
+                args.detailed.then_some(args.context),
+                style,
+            )?;
         }
 
-        Commands::Tui(_args) => {
-            docsentinel::tui::run(repo_path)?;
+        Commands::Tui(args) => {
+            let view = args
+                .view
+                .as_deref()
+                .map(str::parse::<docsentinel::tui::View>)
+                .transpose()?;
+            docsentinel::tui::run(repo_path, scope, view, args.tag.as_deref(), overrides)?;
         }
 
         Commands::Fix(args) => {
-            fix(
-                repo_path,
-                &args.issue_id,
-                args.content.as_deref(),
-                args.commit,
-            )?;
+            fix(repo_path, scope, &args, style)?;
         }
 
         Commands::Ignore(args) => {
-            ignore(repo_path, &args.issue_id, args.reason.as_deref())?;
+            ignore(
+                repo_path,
+                scope,
+                &args.issue_id,
+                args.reason.as_deref(),
+                args.permanent,
+                style,
+            )?;
         }
 
         Commands::Hooks(args) => {
-            hooks(repo_path, args.install, args.uninstall)?;
+            hooks(
+                repo_path,
+                scope,
+                args.install,
+                args.uninstall,
+                args.pre_commit,
+                args.status,
+                style,
+            )?;
         }
 
         Commands::Watch(args) => {
-            run_watch(repo_path, args.debounce)?;
+            if args.background && std::env::var_os(BACKGROUND_WATCH_ENV).is_none() {
+                spawn_background_watch(repo_path, scope, style)?;
+            } else if args.once {
+                let events = watch_once(repo_path, scope, args.debounce, &overrides, style)?;
+                print_events(cli.format, cli.json_compact, style, &events)?;
+                exit_on_severity(args.fail_on, &events);
+            } else {
+                run_watch(repo_path, scope, args.debounce, &overrides, style)?;
+            }
         }
 
         Commands::Config(args) => {
-            handle_config(repo_path, &args)?;
+            handle_config(repo_path, scope, &args, style)?;
         }
 
-        Commands::Analyze(args) => {
-            analyze(repo_path, &args.target, args.docs, args.similarity)?;
-        }
+        Commands::Analyze(args) => match args.target.as_deref() {
+            _ if args.all => {
+                let symbols = symbol_inventory(repo_path, scope, args.docs)?;
+                match cli.format {
+                    OutputFormat::Json => print_json(&symbols, cli.json_compact)?,
+                    _ => print_inventory_text(&symbols, style),
+                }
+            }
+            Some(target) if !args.stdin => {
+                if cli.format == OutputFormat::Json {
+                    let report =
+                        analyze_target(repo_path, scope, target, args.docs || args.similarity)?;
+                    print_json(&report, cli.json_compact)?;
+                } else {
+                    analyze(repo_path, scope, target, args.docs, args.similarity, style)?;
+                }
+            }
+            target => {
+                let mut source = String::new();
+                std::io::stdin().read_to_string(&mut source)?;
+                let symbols = analyze_buffer(
+                    repo_path,
+                    scope,
+                    target,
+                    args.lang.map(Into::into),
+                    &source,
+                    args.docs,
+                )?;
+                print_json(&symbols, cli.json_compact)?;
+            }
+        },
 
         Commands::Generate(args) => {
-            generate(
-                repo_path,
-                args.readme,
-                args.docs,
-                args.output.as_deref(),
-                args.include_private,
-                args.with_llm,
-            )?;
+            let options = GenerateOptions {
+                readme: args.readme,
+                output: args.output,
+                output_dir: args.output_dir.map(PathBuf::from),
+                only: args.only,
+                sort: args.sort,
+                include_private: args.include_private,
+                with_llm: args.with_llm,
+            };
+            generate(repo_path, scope, &options, &overrides, style)?;
+        }
+
+        Commands::Stats(args) => {
+            stats(repo_path, scope, args.top)?;
+        }
+
+        Commands::CheckCli(args) => {
+            let events = check_cli(repo_path, scope, &args)?;
+            print_events(cli.format, cli.json_compact, style, &events)?;
+            exit_on_severity(args.fail_on, &events);
+        }
+
+        Commands::Coverage(args) => {
+            let coverage = coverage(repo_path, scope)?;
+
+            match cli.format {
+                OutputFormat::Json => print_json(&coverage, cli.json_compact)?,
+                _ => print_coverage_text(&coverage, args.detailed),
+            }
+
+            if let Some(threshold) = args.fail_under {
+                if coverage.percent() < threshold {
+                    eprintln!(
+                        "Documentation coverage {:.1}% is below {:.1}%",
+                        coverage.percent(),
+                        threshold
+                    );
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Commands::Report(args) => {
+            report(repo_path, scope, &args.output)?;
+        }
+
+        Commands::Clean(args) => {
+            clean(repo_path, scope, &args, style)?;
+        }
+
+        Commands::Embed(args) => {
+            embed(repo_path, scope, &args, &overrides, style)?;
+        }
+
+        Commands::Baseline => {
+            baseline(repo_path, scope, style)?;
+        }
+
+        Commands::Doctor => {
+            if !doctor(repo_path, scope, &overrides, style) {
+                std::process::exit(1);
+            }
+        }
+
+        Commands::Export(args) => {
+            export(repo_path, scope, Path::new(&args.output), style)?;
+        }
+
+        Commands::Import(args) => {
+            import(repo_path, scope, Path::new(&args.file), args.force, style)?;
         }
     }
 
     Ok(())
 }
 
-/// Run in watch mode
-fn run_watch(path: &Path, debounce_ms: u64) -> Result<()> {
-    use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
-    use std::sync::mpsc::channel;
-    use std::time::Duration;
+/// Print scan events in the selected output format
+fn print_events(
+    format: OutputFormat,
+    compact: bool,
+    style: Style,
+    events: &[DriftEvent],
+) -> Result<()> {
+    match format {
+        OutputFormat::Json => print_events_json(events, compact)?,
+        OutputFormat::Text => print_events_text(events, style),
+        OutputFormat::Markdown => print_events_markdown(events),
+        OutputFormat::Junit => print_events_junit(events),
+    }
+    Ok(())
+}
 
-    println!("Watching for changes in {:?}...", path);
-    println!("Press Ctrl+C to stop.\n");
+/// Write scan events to `file` in the selected output format
+///
+/// Text output is written without emoji or escape codes.
+fn write_events(
+    format: OutputFormat,
+    compact: bool,
+    events: &[DriftEvent],
+    file: &Path,
+) -> Result<()> {
+    let content = match format {
+        OutputFormat::Json => render_events_json(events, compact)? + "\n",
+        OutputFormat::Text => render_events_text(events, Style::Plain),
+        OutputFormat::Markdown => render_events_markdown(events),
+        OutputFormat::Junit => render_events_junit(events),
+    };
+    std::fs::write(file, content).with_context(|| format!("Failed to write to {}", file.display()))
+}
 
-    let (tx, rx) = channel();
+/// Exit with status 1 if any event is at least as severe as `fail_on`
+fn exit_on_severity(fail_on: Option<SeverityLevel>, events: &[DriftEvent]) {
+    if let Some(level) = fail_on {
+        let threshold: DriftSeverity = level.into();
+        if events.iter().any(|event| event.severity <= threshold) {
+            eprintln!("Drift at or above {} severity detected", threshold);
+            std::process::exit(1);
+        }
+    }
+}
 
-    let config = Config::default().with_poll_interval(Duration::from_millis(debounce_ms));
+/// How a wait for file changes ended
+enum WatchWait {
+    /// No relevant change arrived for the debounce interval
+    Settled,
+    /// Ctrl-C was pressed
+    Interrupted,
+    /// The file watcher stopped
+    Closed,
+}
+
+/// Start watching `path` recursively
+fn start_watcher(
+    path: &Path,
+    debounce: Duration,
+) -> Result<(
+    notify::RecommendedWatcher,
+    Receiver<notify::Result<notify::Event>>,
+)> {
+    use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
 
+    let (tx, rx) = channel();
+    let config = Config::default().with_poll_interval(debounce);
     let mut watcher = RecommendedWatcher::new(tx, config)?;
     watcher.watch(path, RecursiveMode::Recursive)?;
+    Ok((watcher, rx))
+}
 
-    let mut last_scan = std::time::Instant::now();
-    let debounce = Duration::from_millis(debounce_ms);
+/// Whether a file event touches a code or doc file
+///
+/// Files under `.git` and `.docsentinel` are skipped, so a scan writing its
+/// database does not trigger another scan.
+fn is_relevant_change(event: &notify::Event) -> bool {
+    event.paths.iter().any(|p| {
+        let internal = p
+            .components()
+            .any(|c| c.as_os_str() == ".git" || c.as_os_str() == ".docsentinel");
+        let ext = p.extension().and_then(|e| e.to_str()).unwrap_or("");
+        !internal && matches!(ext, "rs" | "py" | "md" | "mdx" | "rst")
+    })
+}
 
+/// Wait until no relevant change has arrived for `debounce`
+///
+/// With `after_change`, the quiet period only starts once a first relevant
+/// change arrives; otherwise it starts immediately.
+fn wait_for_quiet(
+    rx: &Receiver<notify::Result<notify::Event>>,
+    debounce: Duration,
+    after_change: bool,
+    interrupted: &AtomicBool,
+) -> WatchWait {
+    const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+    let mut last_change = (!after_change).then(Instant::now);
     loop {
-        match rx.recv() {
-            Ok(event) => {
-                if let Ok(event) = event {
-                    // Debounce
-                    if last_scan.elapsed() < debounce {
-                        continue;
-                    }
+        if interrupted.load(Ordering::SeqCst) {
+            return WatchWait::Interrupted;
+        }
+        if last_change.is_some_and(|at| at.elapsed() >= debounce) {
+            return WatchWait::Settled;
+        }
+        match rx.recv_timeout(POLL_INTERVAL) {
+            Ok(Ok(event)) if is_relevant_change(&event) => last_change = Some(Instant::now()),
+            Ok(Err(e)) => eprintln!("Watch error: {}", e),
+            Ok(Ok(_)) | Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return WatchWait::Closed,
+        }
+    }
+}
 
-                    // Check if it's a relevant file
-                    let dominated_paths: Vec<_> = event
-                        .paths
-                        .iter()
-                        .filter(|p| {
-                            let ext = p.extension().and_then(|e| e.to_str()).unwrap_or("");
-                            matches!(ext, "rs" | "py" | "md" | "mdx" | "rst")
-                        })
-                        .collect();
-
-                    if !dominated_paths.is_empty() {
-                        println!("\n📝 Changes detected, scanning...");
-
-                        match scan(path, false, None, true) {
-                            Ok(events) => {
-                                if events.is_empty() {
-                                    println!("✓ No drift detected");
-                                } else {
-                                    println!("⚠ {} drift event(s) detected", events.len());
-                                    print_events_text(&events);
-                                }
-                            }
-                            Err(e) => {
-                                eprintln!("Scan error: {}", e);
-                            }
-                        }
+/// Run in watch mode
+///
+/// Each burst of changes is scanned once it has been quiet for `debounce_ms`.
+/// Ctrl-C while waiting stops watching; during a scan it stops the scan,
+/// saving its progress, and then stops watching. A second Ctrl-C exits
+/// immediately.
+fn run_watch(
+    path: &Path,
+    scope: Option<&Path>,
+    debounce_ms: u64,
+    overrides: &ConfigOverrides,
+    style: Style,
+) -> Result<()> {
+    let debounce = Duration::from_millis(debounce_ms);
+    let (_watcher, rx) = start_watcher(path, debounce)?;
+    let interrupted = interrupt_flag();
 
-                        last_scan = std::time::Instant::now();
-                    }
+    println!("Watching for changes in {:?}...", path);
+    println!("Press Ctrl+C to stop.\n");
+
+    loop {
+        match wait_for_quiet(&rx, debounce, true, &interrupted) {
+            WatchWait::Settled => {}
+            WatchWait::Interrupted => break,
+            WatchWait::Closed => {
+                eprintln!("Watch error: file watcher stopped");
+                break;
+            }
+        }
+
+        println!("\n{}Changes detected, scanning...", style.icon("📝 "));
+        let options = ScanOptions {
+            uncommitted: true,
+            ..Default::default()
+        };
+        match scan(path, scope, &options, overrides, style) {
+            Ok(events) => {
+                if events.is_empty() {
+                    println!("{} No drift detected", style.ok());
+                } else {
+                    println!("{} {} drift event(s) detected", style.warn(), events.len());
+                    print_events_text(&events, style);
                 }
             }
             Err(e) => {
-                eprintln!("Watch error: {}", e);
-                break;
+                eprintln!("Scan error: {}", e);
             }
         }
+
+        if interrupted.load(Ordering::SeqCst) {
+            break;
+        }
     }
 
+    println!("\nStopped watching.");
     Ok(())
 }
 
-/// Handle config command
-fn handle_config(path: &Path, args: &docsentinel::cli::ConfigArgs) -> Result<()> {
+/// Wait for file changes to settle, then scan once
+///
+/// Changes already in progress push the scan back until none have arrived
+/// for `debounce_ms`; with nothing changing, the scan starts after one
+/// interval. Ctrl-C while waiting exits with status 130.
+fn watch_once(
+    path: &Path,
+    scope: Option<&Path>,
+    debounce_ms: u64,
+    overrides: &ConfigOverrides,
+    style: Style,
+) -> Result<Vec<DriftEvent>> {
+    let debounce = Duration::from_millis(debounce_ms);
+    let (_watcher, rx) = start_watcher(path, debounce)?;
+    let interrupted = interrupt_flag();
+
+    eprintln!("Waiting for changes to settle ({} ms)...", debounce_ms);
+    match wait_for_quiet(&rx, debounce, false, &interrupted) {
+        WatchWait::Settled => {}
+        WatchWait::Interrupted => {
+            eprintln!("Interrupted before scanning");
+            std::process::exit(130);
+        }
+        WatchWait::Closed => anyhow::bail!("File watcher stopped before changes settled"),
+    }
+
+    let options = ScanOptions {
+        uncommitted: true,
+        ..Default::default()
+    };
+    scan(path, scope, &options, overrides, style)
+}
+
+/// Re-run this watch command detached from the terminal
+///
+/// The child gets the same arguments and [`BACKGROUND_WATCH_ENV`], which
+/// makes it watch in the foreground of its own session. Output goes to `watch.log` in the `.docsentinel` directory. On Unix the
+/// watcher gets its own process group, so Ctrl-C in the terminal does not
+/// reach it; stop it with `kill <PID>`. The log is written in plain style.
+fn spawn_background_watch(path: &Path, scope: Option<&Path>, style: Style) -> Result<()> {
     use docsentinel::repo::Repository;
+    use std::process::{Command, Stdio};
+
+    let repo = Repository::open_scoped(path, scope)?;
+    let sentinel_dir = repo.sentinel_dir();
+    if !sentinel_dir.exists() {
+        return Err(DocSentinelError::NotInitialized.into());
+    }
 
-    let repo = Repository::open(path)?;
+    let log_path = sentinel_dir.join("watch.log");
+    let log = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_path)
+        .with_context(|| format!("Failed to open {}", log_path.display()))?;
+
+    let mut command = Command::new(std::env::current_exe()?);
+    command
+        .args(std::env::args_os().skip(1))
+        .env(BACKGROUND_WATCH_ENV, "1")
+        .env("NO_COLOR", "1")
+        .stdin(Stdio::null())
+        .stdout(log.try_clone()?)
+        .stderr(log);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const DETACHED_PROCESS: u32 = 0x0000_0008;
+        const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+        command.creation_flags(DETACHED_PROCESS | CREATE_NEW_PROCESS_GROUP);
+    }
+
+    let child = command
+        .spawn()
+        .context("Failed to start the background watcher")?;
+    println!(
+        "{} Watching in the background (PID {}), logging to {}",
+        style.ok(),
+        child.id(),
+        log_path.display()
+    );
+    Ok(())
+}
+
+/// Handle config command
+fn handle_config(
+    path: &Path,
+    scope: Option<&Path>,
+    args: &docsentinel::cli::ConfigArgs,
+    style: Style,
+) -> Result<()> {
+    use docsentinel::repo::{ConfigSource, RepoConfig, Repository, UserConfig};
+
+    let repo = Repository::open_scoped(path, scope)?;
     let config = repo.config();
 
     if args.show || (!args.reset && args.set.is_none() && args.get.is_none()) {
@@ -207,46 +593,106 @@ fn handle_config(path: &Path, args: &docsentinel::cli::ConfigArgs) -> Result<()>
             println!("  - {}", pattern);
         }
 
+        if !config.ignored_symbols.is_empty() {
+            println!("\nIgnored symbols:");
+            for symbol in &config.ignored_symbols {
+                println!("  - {}", symbol);
+            }
+        }
+
+        if !config.ignored_paths.is_empty() {
+            println!("\nIgnored paths:");
+            for pattern in &config.ignored_paths {
+                println!("  - {}", pattern);
+            }
+        }
+
         println!("\nLanguages: {:?}", config.languages);
         println!("Similarity threshold: {}", config.similarity_threshold);
+        println!("Similarity metric: {}", config.drift.similarity_metric);
         println!("Top K: {}", config.top_k);
-
-        if let Some(ref endpoint) = config.llm.endpoint {
-            println!("\nLLM endpoint: {}", endpoint);
-        }
-        if let Some(ref model) = config.llm.model {
-            println!("LLM model: {}", model);
+        println!("Max file bytes: {}", config.max_file_bytes);
+
+        // LLM settings may come from the user config or the environment
+        let merged = RepoConfig::load_merged(&repo.scope_dir())?;
+        let with_source = |value: Option<&str>, source: ConfigSource| match value {
+            Some(value) => format!("{} ({})", value, source),
+            None => "not set".to_string(),
+        };
+        println!(
+            "\nLLM endpoint: {}",
+            with_source(config.llm.endpoint.as_deref(), merged.endpoint_source)
+        );
+        println!(
+            "LLM model: {}",
+            with_source(config.llm.model.as_deref(), merged.model_source)
+        );
+        // Never echo the key itself
+        println!(
+            "LLM API key: {}",
+            with_source(
+                config.llm.api_key.as_ref().map(|_| "set"),
+                merged.api_key_source
+            )
+        );
+        if let Some(path) = UserConfig::path() {
+            println!("User config: {}", path.display());
         }
+        println!(
+            "Embedding provider: {} ({})",
+            config.embedding.provider,
+            config.embedding_model()
+        );
+        println!("Embedding text: {}", config.llm.embedding_text);
     }
 
     if let Some(ref key) = args.get {
-        match key.as_str() {
-            "similarity_threshold" => println!("{}", config.similarity_threshold),
-            "top_k" => println!("{}", config.top_k),
-            _ => println!("Unknown config key: {}", key),
-        }
+        println!("{}", config.get_value(key)?);
+    }
+
+    if let Some(ref assignment) = args.set {
+        let (key, value) = assignment
+            .split_once('=')
+            .map(|(k, v)| (k.trim(), v.trim()))
+            .filter(|(k, _)| !k.is_empty())
+            .ok_or_else(|| {
+                anyhow::anyhow!("Invalid --set value '{}', expected key=value", assignment)
+            })?;
+
+        // Only the repository's own settings are written back
+        let mut updated = repo.stored_config()?;
+        updated.set_value(key, value)?;
+        updated.save(&repo.scope_dir())?;
+        println!("{} Set {} = {}", style.ok(), key, value);
     }
 
     if args.reset {
-        let default_config = docsentinel::repo::RepoConfig::default();
-        default_config.save(repo.root())?;
-        println!("✓ Configuration reset to defaults");
+        let default_config = RepoConfig::default();
+        default_config.save(&repo.scope_dir())?;
+        println!("{} Configuration reset to defaults", style.ok());
     }
 
     Ok(())
 }
 
 /// Analyze a specific file or symbol
-fn analyze(path: &Path, target: &str, show_docs: bool, _show_similarity: bool) -> Result<()> {
+fn analyze(
+    path: &Path,
+    scope: Option<&Path>,
+    target: &str,
+    show_docs: bool,
+    show_similarity: bool,
+    style: Style,
+) -> Result<()> {
     use docsentinel::extract::{CodeExtractor, DocExtractor};
     use docsentinel::repo::Repository;
     use docsentinel::storage::Database;
 
-    let repo = Repository::open(path)?;
+    let repo = Repository::open_scoped(path, scope)?;
     let sentinel_dir = repo.sentinel_dir();
 
     if !sentinel_dir.exists() {
-        anyhow::bail!("DocSentinel not initialized. Run 'docsentinel init' first.");
+        return Err(DocSentinelError::NotInitialized.into());
     }
 
     let db_path = sentinel_dir.join("docsentinel.db");
@@ -269,6 +715,12 @@ fn analyze(path: &Path, target: &str, show_docs: bool, _show_similarity: bool) -
             println!("Code Analysis: {:?}", target_path);
             println!("================\n");
 
+            let similarities = if show_similarity {
+                docsentinel::cli::file_similarity(&repo, &db, target_path, 5)?
+            } else {
+                Vec::new()
+            };
+
             for chunk in &chunks {
                 println!("Symbol: {} ({})", chunk.symbol_name, chunk.symbol_type);
                 println!("  Lines: {}-{}", chunk.start_line, chunk.end_line);
@@ -279,8 +731,30 @@ fn analyze(path: &Path, target: &str, show_docs: bool, _show_similarity: bool) -
                 if let Some(ref doc) = chunk.doc_comment {
                     println!("  Doc: {}", doc.lines().next().unwrap_or(""));
                 }
+                let related = similarities
+                    .iter()
+                    .find(|s| s.symbol_name == chunk.symbol_name);
+                if let Some(related) = related {
+                    println!("  Similar docs:");
+                    for (doc, similarity) in &related.docs {
+                        println!(
+                            "    {} {} ({:.1}%) in {}",
+                            style.bullet(),
+                            doc.full_path(),
+                            similarity * 100.0,
+                            doc.file_path
+                        );
+                    }
+                }
                 println!();
             }
+
+            if show_similarity && similarities.is_empty() {
+                println!(
+                    "(No embeddings to compare; run 'docsentinel scan' with an embedding \
+                     endpoint configured to see similarity scores)"
+                );
+            }
         } else if matches!(ext, "md" | "mdx" | "rst") {
             let extractor = DocExtractor::new();
             let chunks = extractor.extract_file(target_path, &content)?;
@@ -307,40 +781,38 @@ fn analyze(path: &Path, target: &str, show_docs: bool, _show_similarity: bool) -
                 println!("\nRelated documentation:");
 
                 let doc_chunks = db.get_all_doc_chunks_with_embeddings()?;
+                let metric = repo.config().drift.similarity_metric;
 
-                if chunk.embedding.is_none() {
-                    println!("  (No embeddings available for this code chunk)");
-                } else if doc_chunks.is_empty() {
-                    println!("  (No document chunks with embeddings found)");
-                } else {
-                    let code_embedding = chunk.embedding.as_ref().unwrap();
-                    let mut similarities: Vec<_> = doc_chunks
-                        .into_iter()
-                        .filter_map(|doc| {
-                            if let Some(ref doc_emb) = doc.embedding {
-                                let similarity =
-                                    docsentinel::drift::cosine_similarity(code_embedding, doc_emb);
-                                Some((doc, similarity))
-                            } else {
-                                None
+                match chunk.embedding.as_ref() {
+                    None => println!("  (No embeddings available for this code chunk)"),
+                    Some(_) if doc_chunks.is_empty() => {
+                        println!("  (No document chunks with embeddings found)")
+                    }
+                    Some(code_embedding) => {
+                        let similarities = docsentinel::cli::most_similar_docs(
+                            &doc_chunks,
+                            code_embedding,
+                            metric,
+                            5,
+                        );
+
+                        for (doc, similarity) in &similarities {
+                            println!(
+                                "  {} {} ({:.1}%)",
+                                style.bullet(),
+                                doc.full_path(),
+                                *similarity * 100.0
+                            );
+                            println!("    File: {}", doc.file_path);
+                            if *similarity > 0.7 {
+                                println!("    {}", doc.content.lines().next().unwrap_or(""));
                             }
-                        })
-                        .collect();
-
-                    similarities
-                        .sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-
-                    for (doc, similarity) in similarities.iter().take(5) {
-                        println!("  • {} ({:.1}%)", doc.full_path(), *similarity * 100.0);
-                        println!("    File: {}", doc.file_path);
-                        if *similarity > 0.7 {
-                            println!("    {}", doc.content.lines().next().unwrap_or(""));
+                            println!();
                         }
-                        println!();
-                    }
 
-                    if similarities.is_empty() {
-                        println!("  (No similar documentation found)");
+                        if similarities.is_empty() {
+                            println!("  (No similar documentation found)");
+                        }
                     }
                 }
             }
@@ -351,3 +823,73 @@ fn analyze(path: &Path, target: &str, show_docs: bool, _show_similarity: bool) -
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{CreateKind, EventKind, ModifyKind};
+
+    fn change(path: &str) -> notify::Event {
+        notify::Event::new(EventKind::Modify(ModifyKind::Any)).add_path(PathBuf::from(path))
+    }
+
+    #[test]
+    fn test_is_relevant_change() {
+        assert!(is_relevant_change(&change("/repo/src/lib.rs")));
+        assert!(is_relevant_change(&change("/repo/docs/guide.mdx")));
+        assert!(is_relevant_change(
+            &notify::Event::new(EventKind::Create(CreateKind::File))
+                .add_path(PathBuf::from("/repo/Cargo.toml"))
+                .add_path(PathBuf::from("/repo/README.md"))
+        ));
+
+        assert!(!is_relevant_change(&change("/repo/Cargo.toml")));
+        assert!(!is_relevant_change(&change("/repo/.git/HEAD.rs")));
+        assert!(!is_relevant_change(&change("/repo/.docsentinel/notes.md")));
+        assert!(!is_relevant_change(&notify::Event::new(EventKind::Any)));
+    }
+
+    #[test]
+    fn test_wait_for_quiet_settles_after_debounce() {
+        let debounce = Duration::from_millis(50);
+        let not_interrupted = AtomicBool::new(false);
+        let (tx, rx) = channel();
+
+        // Without a change to wait for, the quiet period starts immediately
+        let started = Instant::now();
+        let wait = wait_for_quiet(&rx, debounce, false, &not_interrupted);
+        assert!(matches!(wait, WatchWait::Settled));
+        assert!(started.elapsed() >= debounce);
+
+        // Otherwise it starts at the first relevant change
+        tx.send(Ok(change("/repo/src/lib.rs"))).unwrap();
+        let wait = wait_for_quiet(&rx, debounce, true, &not_interrupted);
+        assert!(matches!(wait, WatchWait::Settled));
+    }
+
+    #[test]
+    fn test_wait_for_quiet_ignores_irrelevant_changes() {
+        let (tx, rx) = channel();
+        tx.send(Ok(change("/repo/.docsentinel/docsentinel.db")))
+            .unwrap();
+        tx.send(Err(notify::Error::generic("watch failed")))
+            .unwrap();
+        drop(tx);
+
+        // Nothing relevant arrived before the watcher closed
+        let wait = wait_for_quiet(
+            &rx,
+            Duration::from_millis(50),
+            true,
+            &AtomicBool::new(false),
+        );
+        assert!(matches!(wait, WatchWait::Closed));
+    }
+
+    #[test]
+    fn test_wait_for_quiet_stops_when_interrupted() {
+        let (_tx, rx) = channel();
+        let wait = wait_for_quiet(&rx, Duration::from_secs(60), true, &AtomicBool::new(true));
+        assert!(matches!(wait, WatchWait::Interrupted));
+    }
+}
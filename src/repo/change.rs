@@ -1,20 +1,39 @@
 //! Change tracking types for repository modifications
 
 use super::FileType;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Represents a changed file in the repository
 #[derive(Debug, Clone)]
 pub struct ChangedFile {
     /// Path to the file relative to repository root
+    ///
+    /// For a renamed file this is the new path.
     pub path: PathBuf,
+    /// Path the file was renamed from, for renamed files
+    pub old_path: Option<PathBuf>,
     /// Type of change
     pub kind: ChangeKind,
     /// Category of the file
     pub file_type: FileType,
+    /// Line ranges (1-based, inclusive) of the new version touched by the
+    /// change; `None` when unknown, e.g. for added or uncommitted files
+    pub changed_lines: Option<Vec<(usize, usize)>>,
 }
 
 impl ChangedFile {
+    /// Path the file had before the change
+    ///
+    /// Differs from [`ChangedFile::path`] only for renamed files.
+    pub fn old_path(&self) -> &Path {
+        self.old_path.as_deref().unwrap_or(&self.path)
+    }
+
+    /// Path the file has after the change
+    pub fn new_path(&self) -> &Path {
+        &self.path
+    }
+
     /// Check if this is a code file
     pub fn is_code(&self) -> bool {
         self.file_type == FileType::Code
@@ -24,6 +43,21 @@ impl ChangedFile {
     pub fn is_documentation(&self) -> bool {
         self.file_type == FileType::Documentation
     }
+
+    /// Check if this is a config file
+    pub fn is_config(&self) -> bool {
+        self.file_type == FileType::Config
+    }
+
+    /// Whether the change touches any line in `start..=end`
+    ///
+    /// Always true when the changed lines are unknown.
+    pub fn touches_lines(&self, start: usize, end: usize) -> bool {
+        match &self.changed_lines {
+            Some(ranges) => ranges.iter().any(|&(from, to)| from <= end && start <= to),
+            None => true,
+        }
+    }
 }
 
 /// Type of change made to a file
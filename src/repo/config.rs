@@ -1,8 +1,11 @@
 //! Repository configuration for DocSentinel
 
-use anyhow::{Context, Result};
+use crate::drift::{DriftEvent, DriftSeverity, EmbeddingBackend, SimilarityMetric};
+use crate::error::{DocSentinelError, Result};
+use crate::extract::{EmbeddingTextMode, HeadingLevel};
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 /// Configuration for a repository being analyzed
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,7 +18,10 @@ pub struct RepoConfig {
     #[serde(default = "default_code_patterns")]
     pub code_patterns: Vec<String>,
 
-    /// Patterns to ignore (glob patterns)
+    /// Patterns to ignore (gitignore-style, evaluated in order)
+    ///
+    /// A leading `!` re-includes paths matched by an earlier pattern; the last
+    /// matching pattern wins.
     #[serde(default = "default_ignore_patterns")]
     pub ignore_patterns: Vec<String>,
 
@@ -31,13 +37,73 @@ pub struct RepoConfig {
     #[serde(default = "default_top_k")]
     pub top_k: usize,
 
+    /// Scale embeddings to unit length before storing them, so cosine
+    /// similarity equals their dot product
+    #[serde(default)]
+    pub normalize_embeddings: bool,
+
+    /// Files larger than this many bytes are skipped by scans (0 disables
+    /// the limit)
+    #[serde(default = "default_max_file_bytes")]
+    pub max_file_bytes: u64,
+
+    /// Chunk history older than this many days is pruned by scans (0 keeps
+    /// it forever)
+    #[serde(default = "default_history_retention_days")]
+    pub history_retention_days: u32,
+
+    /// Doc sections longer than this many lines are split into overlapping
+    /// parts (0 keeps every section whole)
+    #[serde(default)]
+    pub max_doc_chunk_lines: usize,
+
+    /// Lines shared by consecutive parts of a split doc section
+    #[serde(default = "default_doc_chunk_overlap_lines")]
+    pub doc_chunk_overlap_lines: usize,
+
+    /// Shallowest heading level extracted as a doc section (`H1`..`H6`)
+    #[serde(default = "default_doc_min_level")]
+    pub doc_min_level: HeadingLevel,
+
+    /// Deepest heading level extracted as a doc section; content under
+    /// deeper headings is folded into the enclosing section
+    #[serde(default = "default_doc_max_level")]
+    pub doc_max_level: HeadingLevel,
+
     /// LLM endpoint configuration
     #[serde(default)]
     pub llm: LlmConfig,
+
+    /// Embedding provider configuration
+    #[serde(default)]
+    pub embedding: EmbeddingConfig,
+
+    /// Drift rule configuration
+    #[serde(default)]
+    pub drift: DriftSettings,
+
+    /// Symbols whose drift events are always suppressed
+    ///
+    /// Entries are symbol names as stored on code chunks (e.g. `parse` or
+    /// `Config::load`), or full chunk IDs (`src/lib.rs::parse`).
+    #[serde(default)]
+    pub ignored_symbols: Vec<String>,
+
+    /// Paths whose drift events are always suppressed (gitignore-style)
+    #[serde(default)]
+    pub ignored_paths: Vec<String>,
+
+    /// Severity forced onto drift events that touch a matching path or symbol
+    ///
+    /// Keys are gitignore-style patterns matched against an event's files,
+    /// or symbol names and chunk IDs as in `ignored_symbols`. When several
+    /// patterns match, the most severe value wins.
+    #[serde(default)]
+    pub severity_overrides: BTreeMap<String, DriftSeverity>,
 }
 
 /// LLM configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LlmConfig {
     /// API endpoint URL (e.g., http://localhost:11434/api for Ollama)
     pub endpoint: Option<String>,
@@ -52,9 +118,262 @@ pub struct LlmConfig {
     #[serde(default = "default_max_tokens")]
     pub max_tokens: usize,
 
+    /// Context window of the model in tokens; prompts are trimmed to leave
+    /// room for `max_tokens` of response
+    #[serde(default = "default_context_tokens")]
+    pub context_tokens: usize,
+
     /// Temperature for generation
     #[serde(default = "default_temperature")]
     pub temperature: f32,
+
+    /// Maximum number of analysis requests in flight at once (default 4)
+    pub concurrency: Option<usize>,
+
+    /// Number of texts embedded per request group
+    pub embedding_batch_size: Option<usize>,
+
+    /// Maximum number of embedding request groups in flight at once
+    pub embedding_concurrency: Option<usize>,
+
+    /// Which parts of each chunk are embedded (`full`, `signature_only`,
+    /// `doc_comment_only`, or `content`)
+    #[serde(default)]
+    pub embedding_text: EmbeddingTextMode,
+}
+
+impl Default for LlmConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: None,
+            model: None,
+            api_key: None,
+            max_tokens: default_max_tokens(),
+            context_tokens: default_context_tokens(),
+            temperature: default_temperature(),
+            concurrency: None,
+            embedding_batch_size: None,
+            embedding_concurrency: None,
+            embedding_text: EmbeddingTextMode::default(),
+        }
+    }
+}
+
+impl LlmConfig {
+    /// Tokens a prompt may use: the context window minus the response
+    pub fn prompt_budget(&self) -> usize {
+        self.context_tokens.saturating_sub(self.max_tokens)
+    }
+}
+
+/// Embedding provider configuration
+///
+/// Unset fields fall back to the `[llm]` endpoint and API key, then to the
+/// provider's defaults.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EmbeddingConfig {
+    /// Service generating embeddings (`ollama` or `openai`)
+    #[serde(default)]
+    pub provider: EmbeddingBackend,
+
+    /// API endpoint URL
+    pub endpoint: Option<String>,
+
+    /// Embedding model (defaults to `nomic-embed-text` for Ollama and
+    /// `text-embedding-3-small` for OpenAI)
+    pub model: Option<String>,
+
+    /// Embedding dimension, when it differs from the provider default
+    pub dimension: Option<usize>,
+
+    /// API key (if required)
+    pub api_key: Option<String>,
+}
+
+/// Environment variable that overrides the configured LLM API key
+pub const API_KEY_ENV: &str = "DOCSENTINEL_API_KEY";
+
+/// User-level configuration shared by every repository
+///
+/// Read from `~/.config/docsentinel/config.toml` (or under
+/// `$XDG_CONFIG_HOME`), so LLM credentials can stay out of the repository.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UserConfig {
+    /// LLM defaults for repositories that do not set their own
+    #[serde(default)]
+    pub llm: UserLlmConfig,
+}
+
+/// LLM settings a user config may supply
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UserLlmConfig {
+    /// API endpoint URL
+    pub endpoint: Option<String>,
+
+    /// Model name to use
+    pub model: Option<String>,
+
+    /// API key (if required)
+    pub api_key: Option<String>,
+}
+
+impl UserConfig {
+    /// Location of the user config file, if a home directory is known
+    pub fn path() -> Option<PathBuf> {
+        let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+        Some(config_dir.join("docsentinel").join("config.toml"))
+    }
+
+    /// Load the user config at `path`, or defaults when it does not exist
+    pub fn load_or_default(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path).map_err(|e| DocSentinelError::io(path, e))?;
+        toml::from_str(&content)
+            .map_err(|e| DocSentinelError::Config(format!("Failed to parse {:?}: {}", path, e)))
+    }
+}
+
+/// Where an effective configuration value came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// Not set anywhere
+    Default,
+    /// The user config file
+    User,
+    /// The repository's `.docsentinel/config.toml`
+    Repo,
+    /// The `DOCSENTINEL_API_KEY` environment variable
+    Env,
+}
+
+impl std::fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigSource::Default => write!(f, "default"),
+            ConfigSource::User => write!(f, "user config"),
+            ConfigSource::Repo => write!(f, "repo config"),
+            ConfigSource::Env => write!(f, "{}", API_KEY_ENV),
+        }
+    }
+}
+
+/// LLM and embedding settings given on the command line
+///
+/// These take precedence over the repository and user configs for a single
+/// run and are never saved.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigOverrides {
+    /// Replaces `llm.endpoint` (and the embedding endpoint when
+    /// `embedding.endpoint` is unset)
+    pub llm_endpoint: Option<String>,
+    /// Replaces `llm.model`
+    pub llm_model: Option<String>,
+    /// Replaces `embedding.model`
+    pub embed_model: Option<String>,
+}
+
+/// Repository configuration with user defaults and overrides applied
+#[derive(Debug, Clone)]
+pub struct MergedConfig {
+    /// The effective configuration
+    pub config: RepoConfig,
+    /// Source of `llm.endpoint`
+    pub endpoint_source: ConfigSource,
+    /// Source of `llm.model`
+    pub model_source: ConfigSource,
+    /// Source of `llm.api_key`
+    pub api_key_source: ConfigSource,
+}
+
+/// Drift detection settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DriftSettings {
+    /// Whether to apply hard rules (API changes, removed functions)
+    #[serde(default = "default_true")]
+    pub use_hard_rules: bool,
+
+    /// Whether to apply soft rules (behavioral changes, comment changes)
+    #[serde(default = "default_true")]
+    pub use_soft_rules: bool,
+
+    /// Similarity drop that counts as significant (0.0 - 1.0)
+    #[serde(default = "default_drop_threshold")]
+    pub drop_threshold: f64,
+
+    /// Events less severe than this are dropped before they are stored
+    #[serde(default = "default_min_severity")]
+    pub min_severity: DriftSeverity,
+
+    /// Events with a confidence below this (0.0 - 1.0) are dropped before
+    /// they are stored
+    #[serde(default = "default_min_confidence")]
+    pub min_confidence: f64,
+
+    /// Whether to flag documented TOML/YAML config keys that were removed or
+    /// renamed, or whose documented values changed (off by default since it
+    /// can be noisy)
+    #[serde(default)]
+    pub check_config_keys: bool,
+
+    /// How code and doc embeddings are compared (`cosine`, `dot`, or
+    /// `euclidean`)
+    #[serde(default)]
+    pub similarity_metric: SimilarityMetric,
+
+    /// Added to a doc section's similarity when its heading or content names
+    /// the symbol, so sections that mention it rank higher (0 turns it off)
+    #[serde(default = "default_lexical_weight")]
+    pub lexical_weight: f64,
+
+    /// Unchanged lines shown around each change in the diffs that rules
+    /// attach to their evidence
+    #[serde(default = "default_diff_context_lines")]
+    pub diff_context_lines: usize,
+}
+
+impl Default for DriftSettings {
+    fn default() -> Self {
+        Self {
+            use_hard_rules: true,
+            use_soft_rules: true,
+            drop_threshold: default_drop_threshold(),
+            min_severity: default_min_severity(),
+            min_confidence: default_min_confidence(),
+            check_config_keys: false,
+            similarity_metric: SimilarityMetric::default(),
+            lexical_weight: default_lexical_weight(),
+            diff_context_lines: default_diff_context_lines(),
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_drop_threshold() -> f64 {
+    0.2
+}
+
+fn default_lexical_weight() -> f64 {
+    0.15
+}
+
+fn default_diff_context_lines() -> usize {
+    crate::drift::DEFAULT_DIFF_CONTEXT_LINES
+}
+
+fn default_min_severity() -> DriftSeverity {
+    DriftSeverity::Low
+}
+
+fn default_min_confidence() -> f64 {
+    0.3
 }
 
 fn default_doc_patterns() -> Vec<String> {
@@ -102,10 +421,34 @@ fn default_top_k() -> usize {
     5
 }
 
+fn default_max_file_bytes() -> u64 {
+    1024 * 1024
+}
+
+fn default_history_retention_days() -> u32 {
+    90
+}
+
+fn default_doc_chunk_overlap_lines() -> usize {
+    10
+}
+
+fn default_doc_min_level() -> HeadingLevel {
+    HeadingLevel::H1
+}
+
+fn default_doc_max_level() -> HeadingLevel {
+    HeadingLevel::H4
+}
+
 fn default_max_tokens() -> usize {
     2048
 }
 
+fn default_context_tokens() -> usize {
+    8192
+}
+
 fn default_temperature() -> f32 {
     0.3
 }
@@ -119,7 +462,19 @@ impl Default for RepoConfig {
             languages: default_languages(),
             similarity_threshold: default_similarity_threshold(),
             top_k: default_top_k(),
+            normalize_embeddings: false,
+            max_file_bytes: default_max_file_bytes(),
+            history_retention_days: default_history_retention_days(),
+            max_doc_chunk_lines: 0,
+            doc_chunk_overlap_lines: default_doc_chunk_overlap_lines(),
+            doc_min_level: default_doc_min_level(),
+            doc_max_level: default_doc_max_level(),
             llm: LlmConfig::default(),
+            embedding: EmbeddingConfig::default(),
+            drift: DriftSettings::default(),
+            ignored_symbols: Vec::new(),
+            ignored_paths: Vec::new(),
+            severity_overrides: BTreeMap::new(),
         }
     }
 }
@@ -131,34 +486,379 @@ impl RepoConfig {
 
         if config_path.exists() {
             let content = std::fs::read_to_string(&config_path)
-                .with_context(|| format!("Failed to read config file: {:?}", config_path))?;
-            let config: RepoConfig = toml::from_str(&content)
-                .with_context(|| format!("Failed to parse config file: {:?}", config_path))?;
+                .map_err(|e| DocSentinelError::io(&config_path, e))?;
+            let config: RepoConfig = toml::from_str(&content).map_err(|e| {
+                DocSentinelError::Config(format!("Failed to parse {:?}: {}", config_path, e))
+            })?;
             Ok(config)
         } else {
             Ok(Self::default())
         }
     }
 
+    /// Load the repository configuration merged over the user config
+    ///
+    /// LLM endpoint, model, and API key fall back to the user config when
+    /// the repository leaves them unset, and `DOCSENTINEL_API_KEY` overrides
+    /// the API key from either file.
+    pub fn load_merged(repo_root: &Path) -> Result<MergedConfig> {
+        let user = match UserConfig::path() {
+            Some(path) => UserConfig::load_or_default(&path)?,
+            None => UserConfig::default(),
+        };
+        let env_api_key = std::env::var(API_KEY_ENV)
+            .ok()
+            .filter(|key| !key.is_empty());
+        Ok(Self::load_or_default(repo_root)?.merged(&user, env_api_key))
+    }
+
+    /// Fill LLM settings this config leaves unset from `user`
+    ///
+    /// `env_api_key`, when given, replaces the API key from either config.
+    pub fn merged(mut self, user: &UserConfig, env_api_key: Option<String>) -> MergedConfig {
+        fn fill(value: &mut Option<String>, fallback: &Option<String>) -> ConfigSource {
+            if value.is_some() {
+                ConfigSource::Repo
+            } else if fallback.is_some() {
+                value.clone_from(fallback);
+                ConfigSource::User
+            } else {
+                ConfigSource::Default
+            }
+        }
+
+        let endpoint_source = fill(&mut self.llm.endpoint, &user.llm.endpoint);
+        let model_source = fill(&mut self.llm.model, &user.llm.model);
+        let api_key_source = match env_api_key {
+            Some(key) => {
+                self.llm.api_key = Some(key);
+                ConfigSource::Env
+            }
+            None => fill(&mut self.llm.api_key, &user.llm.api_key),
+        };
+
+        MergedConfig {
+            config: self,
+            endpoint_source,
+            model_source,
+            api_key_source,
+        }
+    }
+
+    /// Apply command-line overrides on top of this configuration
+    pub fn with_overrides(mut self, overrides: &ConfigOverrides) -> Self {
+        if let Some(endpoint) = &overrides.llm_endpoint {
+            self.llm.endpoint = Some(endpoint.clone());
+        }
+        if let Some(model) = &overrides.llm_model {
+            self.llm.model = Some(model.clone());
+        }
+        if let Some(model) = &overrides.embed_model {
+            self.embedding.model = Some(model.clone());
+        }
+        self
+    }
+
     /// Save configuration to the repository
     pub fn save(&self, repo_root: &Path) -> Result<()> {
         let sentinel_dir = repo_root.join(".docsentinel");
-        std::fs::create_dir_all(&sentinel_dir)?;
+        std::fs::create_dir_all(&sentinel_dir)
+            .map_err(|e| DocSentinelError::io(&sentinel_dir, e))?;
 
         let config_path = sentinel_dir.join("config.toml");
-        let content = toml::to_string_pretty(self).context("Failed to serialize configuration")?;
+        let content = toml::to_string_pretty(self).map_err(|e| {
+            DocSentinelError::Config(format!("Failed to serialize configuration: {}", e))
+        })?;
+
+        std::fs::write(&config_path, content).map_err(|e| DocSentinelError::io(&config_path, e))?;
+
+        Ok(())
+    }
 
-        std::fs::write(&config_path, content)
-            .with_context(|| format!("Failed to write config file: {:?}", config_path))?;
+    /// Get a configuration value by key (e.g. `top_k`, `llm.model`)
+    pub fn get_value(&self, key: &str) -> Result<String> {
+        let value = match key {
+            "similarity_threshold" => self.similarity_threshold.to_string(),
+            "top_k" => self.top_k.to_string(),
+            "normalize_embeddings" => self.normalize_embeddings.to_string(),
+            "max_file_bytes" => self.max_file_bytes.to_string(),
+            "history_retention_days" => self.history_retention_days.to_string(),
+            "max_doc_chunk_lines" => self.max_doc_chunk_lines.to_string(),
+            "doc_chunk_overlap_lines" => self.doc_chunk_overlap_lines.to_string(),
+            "doc_min_level" => self.doc_min_level.to_string(),
+            "doc_max_level" => self.doc_max_level.to_string(),
+            "llm.endpoint" => self.llm.endpoint.clone().unwrap_or_default(),
+            "llm.model" => self.llm.model.clone().unwrap_or_default(),
+            "llm.temperature" => self.llm.temperature.to_string(),
+            "llm.context_tokens" => self.llm.context_tokens.to_string(),
+            "llm.embedding_text" => self.llm.embedding_text.to_string(),
+            "embedding.provider" => self.embedding.provider.to_string(),
+            "embedding.endpoint" => self.embedding.endpoint.clone().unwrap_or_default(),
+            "embedding.model" => self.embedding_model().to_string(),
+            "embedding.dimension" => self
+                .embedding
+                .dimension
+                .map(|d| d.to_string())
+                .unwrap_or_default(),
+            "drift.similarity_metric" => self.drift.similarity_metric.to_string(),
+            "drift.lexical_weight" => self.drift.lexical_weight.to_string(),
+            "drift.min_confidence" => self.drift.min_confidence.to_string(),
+            "drift.diff_context_lines" => self.drift.diff_context_lines.to_string(),
+            _ => return Err(unknown_key(key)),
+        };
+        Ok(value)
+    }
 
+    /// Set a configuration value by key, validating its range
+    pub fn set_value(&mut self, key: &str, value: &str) -> Result<()> {
+        match key {
+            "similarity_threshold" => {
+                let threshold: f32 = value
+                    .parse()
+                    .map_err(|_| invalid_value("number", key, value))?;
+                if !(0.0..=1.0).contains(&threshold) {
+                    return Err(DocSentinelError::Config(format!(
+                        "similarity_threshold must be between 0 and 1, got {}",
+                        value
+                    )));
+                }
+                self.similarity_threshold = threshold;
+            }
+            "top_k" => {
+                self.top_k = value
+                    .parse()
+                    .map_err(|_| invalid_value("integer", key, value))?;
+            }
+            "normalize_embeddings" => {
+                self.normalize_embeddings = value
+                    .parse()
+                    .map_err(|_| invalid_value("boolean", key, value))?;
+            }
+            "max_file_bytes" => {
+                self.max_file_bytes = value
+                    .parse()
+                    .map_err(|_| invalid_value("integer", key, value))?;
+            }
+            "history_retention_days" => {
+                self.history_retention_days = value
+                    .parse()
+                    .map_err(|_| invalid_value("integer", key, value))?;
+            }
+            "max_doc_chunk_lines" => {
+                self.max_doc_chunk_lines = value
+                    .parse()
+                    .map_err(|_| invalid_value("integer", key, value))?;
+            }
+            "doc_chunk_overlap_lines" => {
+                self.doc_chunk_overlap_lines = value
+                    .parse()
+                    .map_err(|_| invalid_value("integer", key, value))?;
+            }
+            "doc_min_level" => self.doc_min_level = value.parse()?,
+            "doc_max_level" => self.doc_max_level = value.parse()?,
+            "llm.endpoint" => self.llm.endpoint = Some(value.to_string()),
+            "llm.model" => self.llm.model = Some(value.to_string()),
+            "llm.temperature" => {
+                let temperature: f32 = value
+                    .parse()
+                    .map_err(|_| invalid_value("number", key, value))?;
+                if temperature.is_nan() || temperature < 0.0 {
+                    return Err(DocSentinelError::Config(format!(
+                        "llm.temperature must be >= 0, got {}",
+                        value
+                    )));
+                }
+                self.llm.temperature = temperature;
+            }
+            "llm.context_tokens" => {
+                self.llm.context_tokens = value
+                    .parse()
+                    .map_err(|_| invalid_value("integer", key, value))?;
+            }
+            "llm.embedding_text" => self.llm.embedding_text = value.parse()?,
+            "embedding.provider" => self.embedding.provider = value.parse()?,
+            "embedding.endpoint" => self.embedding.endpoint = Some(value.to_string()),
+            "embedding.model" => self.embedding.model = Some(value.to_string()),
+            "embedding.dimension" => {
+                let dimension: usize = value
+                    .parse()
+                    .map_err(|_| invalid_value("integer", key, value))?;
+                if dimension == 0 {
+                    return Err(DocSentinelError::Config(
+                        "embedding.dimension must be at least 1".to_string(),
+                    ));
+                }
+                self.embedding.dimension = Some(dimension);
+            }
+            "drift.similarity_metric" => self.drift.similarity_metric = value.parse()?,
+            "drift.lexical_weight" => {
+                let weight: f64 = value
+                    .parse()
+                    .map_err(|_| invalid_value("number", key, value))?;
+                if !(0.0..=1.0).contains(&weight) {
+                    return Err(DocSentinelError::Config(format!(
+                        "drift.lexical_weight must be between 0 and 1, got {}",
+                        value
+                    )));
+                }
+                self.drift.lexical_weight = weight;
+            }
+            "drift.min_confidence" => {
+                let confidence: f64 = value
+                    .parse()
+                    .map_err(|_| invalid_value("number", key, value))?;
+                if !(0.0..=1.0).contains(&confidence) {
+                    return Err(DocSentinelError::Config(format!(
+                        "drift.min_confidence must be between 0 and 1, got {}",
+                        value
+                    )));
+                }
+                self.drift.min_confidence = confidence;
+            }
+            "drift.diff_context_lines" => {
+                self.drift.diff_context_lines = value
+                    .parse()
+                    .map_err(|_| invalid_value("integer", key, value))?;
+            }
+            _ => return Err(unknown_key(key)),
+        }
         Ok(())
     }
 
+    /// Endpoint for embedding requests, if one is configured
+    ///
+    /// Scans only embed when this is set.
+    pub fn embedding_endpoint(&self) -> Option<&str> {
+        self.embedding
+            .endpoint
+            .as_deref()
+            .or(self.llm.endpoint.as_deref())
+    }
+
+    /// Embedding model in effect
+    pub fn embedding_model(&self) -> &str {
+        self.embedding
+            .model
+            .as_deref()
+            .unwrap_or(self.embedding.provider.default_model())
+    }
+
     /// Check if a path should be ignored
     pub fn should_ignore(&self, path: &str) -> bool {
-        self.ignore_patterns
+        self.is_ignored_with_reason(path).is_some()
+    }
+
+    /// Return the ignore pattern responsible for ignoring `path`, if any
+    ///
+    /// Patterns are applied in order with gitignore semantics: the last
+    /// matching pattern decides, and a match on a `!` pattern un-ignores.
+    pub fn is_ignored_with_reason(&self, path: &str) -> Option<&str> {
+        let mut reason = None;
+
+        for pattern in &self.ignore_patterns {
+            let (negated, glob) = match pattern.strip_prefix('!') {
+                Some(rest) => (true, rest),
+                None => (false, pattern.as_str()),
+            };
+
+            if path_matches_pattern(glob, path) {
+                reason = if negated {
+                    None
+                } else {
+                    Some(pattern.as_str())
+                };
+            }
+        }
+
+        reason
+    }
+
+    /// Check whether a drift event is suppressed by an ignore rule
+    ///
+    /// An event is suppressed when one of its related code chunks is an
+    /// ignored symbol, or when its primary file or a related chunk's file
+    /// matches an ignored path.
+    pub fn is_event_ignored(&self, event: &DriftEvent) -> bool {
+        let symbol_ignored = event.related_code_chunks.iter().any(|id| {
+            let symbol = id.split_once("::").map(|(_, s)| s).unwrap_or(id);
+            self.ignored_symbols
+                .iter()
+                .any(|rule| rule == symbol || rule == id)
+        });
+        if symbol_ignored {
+            return true;
+        }
+
+        event_files(event).any(|file| {
+            self.ignored_paths
+                .iter()
+                .any(|pattern| path_matches_pattern(pattern, file))
+        })
+    }
+
+    /// Return the severity an override rule forces onto `event`, if any
+    pub fn severity_override(&self, event: &DriftEvent) -> Option<DriftSeverity> {
+        self.severity_overrides
             .iter()
-            .any(|pattern| glob_match_simple(pattern, path))
+            .filter(|(pattern, _)| {
+                let symbol_matches = event.related_code_chunks.iter().any(|id| {
+                    let symbol = id.split_once("::").map(|(_, s)| s).unwrap_or(id);
+                    *pattern == symbol || *pattern == id
+                });
+                symbol_matches || event_files(event).any(|file| path_matches_pattern(pattern, file))
+            })
+            .map(|(_, severity)| *severity)
+            .min()
+    }
+
+    /// Apply `severity_overrides` to detected events
+    pub fn apply_severity_overrides(&self, events: &mut [DriftEvent]) {
+        for event in events {
+            if let Some(severity) = self.severity_override(event) {
+                event.severity = severity;
+            }
+        }
+    }
+
+    /// Add ignore rules that suppress future events like `event`
+    ///
+    /// Events tied to code are ignored by symbol; other events (doc-only or
+    /// config key drift) are ignored by their primary file. Returns the
+    /// rules that were added, as `symbol X` / `path X` labels.
+    pub fn ignore_event(&mut self, event: &DriftEvent) -> Vec<String> {
+        let mut added = Vec::new();
+
+        for id in &event.related_code_chunks {
+            let symbol = id.split_once("::").map(|(_, s)| s).unwrap_or(id);
+            if !self.ignored_symbols.iter().any(|s| s == symbol) {
+                self.ignored_symbols.push(symbol.to_string());
+                added.push(format!("symbol {}", symbol));
+            }
+        }
+
+        if event.related_code_chunks.is_empty() {
+            let file = event.primary_file.clone().or_else(|| {
+                event
+                    .related_doc_chunks
+                    .first()
+                    .and_then(|id| id.split_once('#'))
+                    .map(|(file, _)| file.to_string())
+            });
+            if let Some(file) = file {
+                // Anchor root-level files so the rule doesn't match at any depth
+                let rule = if file.contains('/') {
+                    file
+                } else {
+                    format!("/{}", file)
+                };
+                if !self.ignored_paths.contains(&rule) {
+                    added.push(format!("path {}", rule));
+                    self.ignored_paths.push(rule);
+                }
+            }
+        }
+
+        added
     }
 
     /// Check if a path is a documentation file
@@ -176,6 +876,35 @@ impl RepoConfig {
     }
 }
 
+/// Files a drift event touches: its primary file, then the files of its
+/// related code and doc chunks
+fn event_files(event: &DriftEvent) -> impl Iterator<Item = &str> {
+    let code_files = event
+        .related_code_chunks
+        .iter()
+        .filter_map(|id| id.split_once("::").map(|(file, _)| file));
+    let doc_files = event
+        .related_doc_chunks
+        .iter()
+        .filter_map(|id| id.split_once('#').map(|(file, _)| file));
+    event
+        .primary_file
+        .as_deref()
+        .into_iter()
+        .chain(code_files)
+        .chain(doc_files)
+}
+
+/// Error for a config key that `get_value`/`set_value` do not know
+fn unknown_key(key: &str) -> DocSentinelError {
+    DocSentinelError::Config(format!("Unknown config key: {}", key))
+}
+
+/// Error for a value that does not parse as the key's type
+fn invalid_value(kind: &str, key: &str, value: &str) -> DocSentinelError {
+    DocSentinelError::Config(format!("Invalid {} for {}: {}", kind, key, value))
+}
+
 /// Simple glob matching helper
 fn glob_match_simple(pattern: &str, path: &str) -> bool {
     if let Some(idx) = pattern.find("**") {
@@ -212,6 +941,88 @@ fn glob_match_simple(pattern: &str, path: &str) -> bool {
     path == pattern || path.ends_with(&format!("/{}", pattern))
 }
 
+/// Match a gitignore-style pattern against a repository-relative path
+///
+/// Patterns without a slash match at any depth. A pattern that matches a
+/// parent directory also matches everything beneath it; a trailing `/`
+/// restricts the pattern to directories.
+pub fn path_matches_pattern(pattern: &str, path: &str) -> bool {
+    let dir_only = pattern.ends_with('/');
+    let pattern = pattern.trim_end_matches('/');
+    let anchored = pattern.contains('/');
+    let pattern = pattern.trim_start_matches('/');
+
+    if pattern.is_empty() {
+        return false;
+    }
+
+    let mut pattern_segments: Vec<&str> = pattern.split('/').collect();
+    if !anchored {
+        pattern_segments.insert(0, "**");
+    }
+
+    let path = path.trim_start_matches("./");
+    let path_segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    // Try the full path, then each parent directory
+    let max_len = if dir_only {
+        path_segments.len().saturating_sub(1)
+    } else {
+        path_segments.len()
+    };
+
+    (1..=max_len).any(|len| match_segments(&pattern_segments, &path_segments[..len]))
+}
+
+/// Match glob path segments, where `**` spans zero or more segments
+fn match_segments(pattern: &[&str], path: &[&str]) -> bool {
+    wildcard_match(
+        pattern,
+        path,
+        |segment| *segment == "**",
+        |segment, name| match_segment(segment.as_bytes(), name.as_bytes()),
+    )
+}
+
+/// Match a single path segment against `*` and `?` wildcards
+fn match_segment(pattern: &[u8], name: &[u8]) -> bool {
+    wildcard_match(pattern, name, |&c| c == b'*', |&c, &b| c == b'?' || c == b)
+}
+
+/// Match `pattern` against `items`, where a star matches any run of items
+/// and every other pattern element matches one item
+///
+/// On a mismatch only the most recent star is retried, one item further
+/// along; an earlier star never needs to be, since the later one can absorb
+/// whatever it would have. This keeps matching at O(pattern × items) instead
+/// of backtracking into every star.
+fn wildcard_match<P, T>(
+    pattern: &[P],
+    items: &[T],
+    is_star: impl Fn(&P) -> bool,
+    matches: impl Fn(&P, &T) -> bool,
+) -> bool {
+    let (mut p, mut i) = (0, 0);
+    // Pattern position after the last star, and the item it resumes from
+    let mut retry = None;
+    while i < items.len() {
+        if p < pattern.len() && is_star(&pattern[p]) {
+            p += 1;
+            retry = Some((p, i));
+        } else if p < pattern.len() && matches(&pattern[p], &items[i]) {
+            p += 1;
+            i += 1;
+        } else if let Some((star_p, star_i)) = retry {
+            p = star_p;
+            i = star_i + 1;
+            retry = Some((star_p, i));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(is_star)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -224,10 +1035,256 @@ mod tests {
         assert!(config.similarity_threshold > 0.0);
     }
 
+    #[test]
+    fn test_set_and_get_value() {
+        let mut config = RepoConfig::default();
+
+        config.set_value("similarity_threshold", "0.5").unwrap();
+        config.set_value("top_k", "10").unwrap();
+        config.set_value("llm.model", "codellama").unwrap();
+        config.set_value("llm.temperature", "0").unwrap();
+        config
+            .set_value("llm.embedding_text", "doc_comment_only")
+            .unwrap();
+        config
+            .set_value("drift.similarity_metric", "euclidean")
+            .unwrap();
+
+        assert_eq!(config.get_value("similarity_threshold").unwrap(), "0.5");
+        assert_eq!(config.get_value("top_k").unwrap(), "10");
+        assert_eq!(config.get_value("llm.model").unwrap(), "codellama");
+        assert_eq!(config.get_value("llm.temperature").unwrap(), "0");
+        assert_eq!(config.llm.embedding_text, EmbeddingTextMode::DocCommentOnly);
+        assert_eq!(
+            config.get_value("llm.embedding_text").unwrap(),
+            "doc_comment_only"
+        );
+        assert_eq!(config.drift.similarity_metric, SimilarityMetric::Euclidean);
+        assert_eq!(
+            config.get_value("drift.similarity_metric").unwrap(),
+            "euclidean"
+        );
+
+        assert_eq!(
+            config.get_value("embedding.model").unwrap(),
+            "nomic-embed-text"
+        );
+        config.set_value("embedding.provider", "openai").unwrap();
+        config.set_value("embedding.dimension", "256").unwrap();
+        assert_eq!(config.embedding.provider, EmbeddingBackend::OpenAI);
+        assert_eq!(config.get_value("embedding.provider").unwrap(), "openai");
+        assert_eq!(config.get_value("embedding.dimension").unwrap(), "256");
+
+        assert_eq!(config.get_value("doc_max_level").unwrap(), "H4");
+        config.set_value("doc_max_level", "h2").unwrap();
+        assert_eq!(config.doc_max_level, HeadingLevel::H2);
+        assert_eq!(config.get_value("doc_max_level").unwrap(), "H2");
+
+        assert!(config.set_value("similarity_threshold", "1.5").is_err());
+        assert!(config.set_value("embedding.provider", "cohere").is_err());
+        assert!(config.set_value("embedding.dimension", "0").is_err());
+
+        config.set_value("normalize_embeddings", "true").unwrap();
+        assert_eq!(config.get_value("normalize_embeddings").unwrap(), "true");
+        assert!(config.set_value("normalize_embeddings", "yes").is_err());
+        assert!(config.set_value("llm.temperature", "-0.1").is_err());
+        assert!(config.set_value("top_k", "many").is_err());
+        assert!(config.set_value("llm.embedding_text", "summary").is_err());
+        assert!(config.set_value("drift.similarity_metric", "l1").is_err());
+        assert!(config.set_value("doc_min_level", "H7").is_err());
+        assert!(matches!(
+            config.set_value("unknown", "1"),
+            Err(DocSentinelError::Config(_))
+        ));
+        assert!(config.get_value("unknown").is_err());
+
+        config.set_value("drift.min_confidence", "0.5").unwrap();
+        assert_eq!(config.get_value("drift.min_confidence").unwrap(), "0.5");
+        assert!(config.set_value("drift.min_confidence", "1.5").is_err());
+    }
+
+    #[test]
+    fn test_drift_settings_round_trip() {
+        let mut config = RepoConfig::default();
+        config.drift.use_soft_rules = false;
+        config.drift.drop_threshold = 0.35;
+        config.drift.min_severity = DriftSeverity::High;
+        config.drift.similarity_metric = SimilarityMetric::Dot;
+
+        let serialized = toml::to_string_pretty(&config).unwrap();
+        assert!(serialized.contains("[drift]"));
+
+        let parsed: RepoConfig = toml::from_str(&serialized).unwrap();
+        assert_eq!(parsed.drift, config.drift);
+
+        // Older configs without the section get defaults
+        let parsed: RepoConfig = toml::from_str("top_k = 3").unwrap();
+        assert_eq!(parsed.drift, DriftSettings::default());
+
+        let parsed: RepoConfig = toml::from_str("[drift]\nmin_severity = \"Medium\"").unwrap();
+        assert_eq!(parsed.drift.min_severity, DriftSeverity::Medium);
+        assert!(parsed.drift.use_hard_rules);
+    }
+
+    #[test]
+    fn test_severity_overrides_match_paths_and_symbols() {
+        let config: RepoConfig = toml::from_str(
+            r#"
+[severity_overrides]
+"README.md" = "Critical"
+"docs/internal/**" = "Low"
+"parse" = "Medium"
+"#,
+        )
+        .unwrap();
+
+        let readme =
+            DriftEvent::new(DriftSeverity::Low, "stale", "", 0.9).with_doc_chunk("README.md#usage");
+        assert_eq!(
+            config.severity_override(&readme),
+            Some(DriftSeverity::Critical)
+        );
+
+        let internal = DriftEvent::new(DriftSeverity::High, "stale", "", 0.9)
+            .with_doc_chunk("docs/internal/notes.md#cache");
+        assert_eq!(
+            config.severity_override(&internal),
+            Some(DriftSeverity::Low)
+        );
+
+        let symbol = DriftEvent::new(DriftSeverity::Low, "changed", "", 0.9)
+            .with_code_chunk("src/lib.rs::parse");
+        assert_eq!(
+            config.severity_override(&symbol),
+            Some(DriftSeverity::Medium)
+        );
+
+        // The most severe of several matching overrides wins
+        let mut events = vec![DriftEvent::new(DriftSeverity::High, "changed", "", 0.9)
+            .with_code_chunk("src/lib.rs::parse")
+            .with_doc_chunk("docs/internal/notes.md#parse")];
+        config.apply_severity_overrides(&mut events);
+        assert_eq!(events[0].severity, DriftSeverity::Medium);
+
+        let other = DriftEvent::new(DriftSeverity::High, "changed", "", 0.9)
+            .with_doc_chunk("docs/guide.md#setup");
+        assert_eq!(config.severity_override(&other), None);
+    }
+
+    #[test]
+    fn test_merged_config_precedence() {
+        let user = UserConfig {
+            llm: UserLlmConfig {
+                endpoint: Some("http://user:11434".to_string()),
+                model: Some("user-model".to_string()),
+                api_key: Some("user-key".to_string()),
+            },
+        };
+
+        // The repository file omits api_key entirely
+        let repo: RepoConfig = toml::from_str("[llm]\nmodel = \"repo-model\"\n").unwrap();
+        assert!(!toml::to_string(&repo).unwrap().contains("api_key"));
+
+        let merged = repo.clone().merged(&user, None);
+        assert_eq!(
+            merged.config.llm.endpoint.as_deref(),
+            Some("http://user:11434")
+        );
+        assert_eq!(merged.endpoint_source, ConfigSource::User);
+        assert_eq!(merged.config.llm.model.as_deref(), Some("repo-model"));
+        assert_eq!(merged.model_source, ConfigSource::Repo);
+        assert_eq!(merged.config.llm.api_key.as_deref(), Some("user-key"));
+        assert_eq!(merged.api_key_source, ConfigSource::User);
+
+        let merged = repo.clone().merged(&user, Some("env-key".to_string()));
+        assert_eq!(merged.config.llm.api_key.as_deref(), Some("env-key"));
+        assert_eq!(merged.api_key_source, ConfigSource::Env);
+
+        let merged = repo.merged(&UserConfig::default(), None);
+        assert_eq!(merged.config.llm.api_key, None);
+        assert_eq!(merged.api_key_source, ConfigSource::Default);
+    }
+
     #[test]
     fn test_glob_matching() {
         assert!(glob_match_simple("*.md", "README.md"));
         assert!(glob_match_simple("docs/**/*.md", "docs/api/guide.md"));
         assert!(!glob_match_simple("*.rs", "README.md"));
     }
+
+    #[test]
+    fn test_ignore_negation() {
+        let config = RepoConfig {
+            ignore_patterns: vec![
+                "docs/generated/".to_string(),
+                "!docs/generated/keep.md".to_string(),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            config.is_ignored_with_reason("docs/generated/api.md"),
+            Some("docs/generated/")
+        );
+        assert!(!config.should_ignore("docs/generated/keep.md"));
+        assert!(!config.should_ignore("docs/guide.md"));
+
+        // Later patterns win over earlier negations
+        let config = RepoConfig {
+            ignore_patterns: vec!["!*.md".to_string(), "*.md".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(config.is_ignored_with_reason("README.md"), Some("*.md"));
+    }
+
+    #[test]
+    fn test_ignore_nested_globs() {
+        let config = RepoConfig {
+            ignore_patterns: vec!["**/generated/**/*.rs".to_string(), "build".to_string()],
+            ..Default::default()
+        };
+
+        assert!(config.should_ignore("generated/out.rs"));
+        assert!(config.should_ignore("src/generated/a/b/out.rs"));
+        assert!(!config.should_ignore("src/generated/out.py"));
+        assert!(!config.should_ignore("src/regenerated/out.rs"));
+        assert_eq!(
+            config.is_ignored_with_reason("crates/core/build/main.rs"),
+            Some("build")
+        );
+
+        let defaults = RepoConfig::default();
+        assert!(defaults.should_ignore("target/debug/build.rs"));
+        assert!(defaults.should_ignore("vendor/Cargo.lock"));
+        assert!(!defaults.should_ignore("src/lib.rs"));
+    }
+
+    #[test]
+    fn test_path_matches_pattern_without_exponential_backtracking() {
+        assert!(path_matches_pattern("a?c*d", "abcxxd"));
+        assert!(!path_matches_pattern("a?c*d", "acxd"));
+        assert!(path_matches_pattern("**/src/**/*.rs", "src/lib.rs"));
+
+        // Each star could split the name many ways; a backtracking matcher
+        // tries them all before failing
+        let name = "a".repeat(64);
+        assert!(!path_matches_pattern(
+            &format!("{}b", "a*".repeat(16)),
+            &name
+        ));
+        assert!(path_matches_pattern(
+            &format!("{}a", "*a".repeat(16)),
+            &name
+        ));
+
+        let path = vec!["d"; 64].join("/");
+        assert!(!path_matches_pattern(
+            &format!("{}x", "**/d/".repeat(16)),
+            &path
+        ));
+        assert!(path_matches_pattern(
+            &format!("{}d", "**/d/".repeat(16)),
+            &path
+        ));
+    }
 }
@@ -9,18 +9,65 @@ mod change;
 mod config;
 
 pub use change::{Change, ChangeKind, ChangedFile};
-pub use config::RepoConfig;
-
-use anyhow::{Context, Result};
-use git2::{DiffOptions, Repository as GitRepo, Signature, StatusOptions};
+pub use config::{
+    path_matches_pattern, ConfigOverrides, ConfigSource, DriftSettings, EmbeddingConfig,
+    MergedConfig, RepoConfig, UserConfig, UserLlmConfig, API_KEY_ENV,
+};
+
+use crate::error::{DocSentinelError, Result};
+use git2::{
+    BranchType, DiffFindOptions, DiffOptions, ErrorCode, Repository as GitRepo, Signature,
+    StatusOptions,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
+/// A commit found by `git blame`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitInfo {
+    /// Full commit hash
+    pub hash: String,
+    /// Author name
+    pub author: String,
+    /// Author email
+    pub email: String,
+    /// Commit time in seconds since the Unix epoch
+    pub time: i64,
+}
+
+impl CommitInfo {
+    fn from_commit(commit: &git2::Commit<'_>) -> Self {
+        let author = commit.author();
+        Self {
+            hash: commit.id().to_string(),
+            author: author.name().unwrap_or_default().to_string(),
+            email: author.email().unwrap_or_default().to_string(),
+            time: commit.time().seconds(),
+        }
+    }
+}
+
+impl std::fmt::Display for CommitInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} <{}>",
+            &self.hash[..8.min(self.hash.len())],
+            self.author,
+            self.email
+        )
+    }
+}
+
 /// Represents a Git repository being analyzed
 pub struct Repository {
     /// The underlying git2 repository
     repo: GitRepo,
     /// Path to the repository root
     root: PathBuf,
+    /// Subdirectory analysis is restricted to, relative to the root
+    scope: Option<PathBuf>,
     /// Repository configuration
     config: RepoConfig,
 }
@@ -28,18 +75,48 @@ pub struct Repository {
 impl Repository {
     /// Open an existing repository at the given path
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::open_scoped(path, None)
+    }
+
+    /// Open a repository, restricting analysis to the `scope` subdirectory
+    ///
+    /// Git operations still use the repository root and file paths stay
+    /// relative to it, but only files under the scope are listed or reported
+    /// as changed. Each scope keeps its own `.docsentinel` directory (config
+    /// and database) inside the scope directory, so one repository can hold
+    /// several independently scanned packages. `scope` may be relative to
+    /// the repository root or absolute.
+    pub fn open_scoped<P: AsRef<Path>>(path: P, scope: Option<&Path>) -> Result<Self> {
         let path = path.as_ref();
-        let repo = GitRepo::discover(path)
-            .with_context(|| format!("Failed to open Git repository at {:?}", path))?;
+        let repo = GitRepo::discover(path)?;
 
         let root = repo
             .workdir()
-            .ok_or_else(|| anyhow::anyhow!("Repository has no working directory (bare repo?)"))?
+            .ok_or(DocSentinelError::BareRepository)?
             .to_path_buf();
 
-        let config = RepoConfig::load_or_default(&root)?;
+        let scope = match scope {
+            Some(scope) => normalize_scope(&root, scope)?,
+            None => None,
+        };
+        let scope_dir = match &scope {
+            Some(scope) => root.join(scope),
+            None => root.clone(),
+        };
+        let config = RepoConfig::load_merged(&scope_dir)?.config;
+
+        Ok(Self {
+            repo,
+            root,
+            scope,
+            config,
+        })
+    }
 
-        Ok(Self { repo, root, config })
+    /// Apply command-line LLM and embedding overrides to the loaded config
+    pub fn with_overrides(mut self, overrides: &ConfigOverrides) -> Self {
+        self.config = self.config.with_overrides(overrides);
+        self
     }
 
     /// Get the repository root path
@@ -47,9 +124,31 @@ impl Repository {
         &self.root
     }
 
+    /// Get the scope subdirectory, relative to the repository root
+    pub fn scope(&self) -> Option<&Path> {
+        self.scope.as_deref()
+    }
+
+    /// Get the directory holding `.docsentinel`: the scope directory when
+    /// scoped, otherwise the repository root
+    pub fn scope_dir(&self) -> PathBuf {
+        match &self.scope {
+            Some(scope) => self.root.join(scope),
+            None => self.root.clone(),
+        }
+    }
+
+    /// Whether a path relative to the repository root lies within the scope
+    pub fn in_scope(&self, path: &Path) -> bool {
+        match &self.scope {
+            Some(scope) => path.starts_with(scope) && !path.starts_with(scope.join(".docsentinel")),
+            None => true,
+        }
+    }
+
     /// Get the path to the .docsentinel directory
     pub fn sentinel_dir(&self) -> PathBuf {
-        self.root.join(".docsentinel")
+        self.scope_dir().join(".docsentinel")
     }
 
     /// Initialize the .docsentinel directory if it doesn't exist
@@ -57,58 +156,89 @@ impl Repository {
         let sentinel_dir = self.sentinel_dir();
         if !sentinel_dir.exists() {
             std::fs::create_dir_all(&sentinel_dir)
-                .with_context(|| format!("Failed to create {:?}", sentinel_dir))?;
+                .map_err(|e| DocSentinelError::io(&sentinel_dir, e))?;
         }
         Ok(sentinel_dir)
     }
 
     /// Get the current HEAD commit hash
     pub fn head_commit(&self) -> Result<String> {
-        let head = self.repo.head().context("Failed to get HEAD reference")?;
-        let commit = head
-            .peel_to_commit()
-            .context("Failed to peel HEAD to commit")?;
+        let commit = self.repo.head()?.peel_to_commit()?;
         Ok(commit.id().to_string())
     }
 
+    /// Name of the branch the repository treats as its mainline
+    ///
+    /// Prefers the remote's default (`origin/HEAD`), then a local `main` or
+    /// `master`. Returns `None` when none of these exist.
+    pub fn default_branch(&self) -> Result<Option<String>> {
+        if let Ok(reference) = self.repo.find_reference("refs/remotes/origin/HEAD") {
+            if let Some(target) = reference.symbolic_target() {
+                if let Some(branch) = target.strip_prefix("refs/remotes/") {
+                    return Ok(Some(branch.to_string()));
+                }
+            }
+        }
+
+        for name in ["main", "master"] {
+            if self.repo.find_branch(name, BranchType::Local).is_ok() {
+                return Ok(Some(name.to_string()));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Best common ancestor of two revisions
+    pub fn merge_base(&self, a: &str, b: &str) -> Result<String> {
+        let a = self.resolve_commit(a)?.id();
+        let b = self.resolve_commit(b)?.id();
+        Ok(self.repo.merge_base(a, b)?.to_string())
+    }
+
     /// Get changes between two commits
     pub fn changes_between(&self, from: Option<&str>, to: &str) -> Result<Vec<ChangedFile>> {
-        let to_commit = self
-            .repo
-            .revparse_single(to)
-            .with_context(|| format!("Failed to parse revision: {}", to))?
-            .peel_to_commit()
-            .context("Failed to peel to commit")?;
-
-        let to_tree = to_commit
-            .tree()
-            .context("Failed to get tree for 'to' commit")?;
-
-        let from_tree = if let Some(from_ref) = from {
-            let from_commit = self
-                .repo
-                .revparse_single(from_ref)
-                .with_context(|| format!("Failed to parse revision: {}", from_ref))?
-                .peel_to_commit()
-                .context("Failed to peel to commit")?;
-            Some(
-                from_commit
-                    .tree()
-                    .context("Failed to get tree for 'from' commit")?,
-            )
-        } else {
-            None
+        let to_tree = self.resolve_commit(to)?.tree()?;
+
+        let from_tree = match from {
+            Some(from_ref) => Some(self.resolve_commit(from_ref)?.tree()?),
+            None => None,
         };
 
         let mut diff_opts = DiffOptions::new();
-        diff_opts.include_untracked(false);
+        // No context lines, so hunks cover exactly the changed lines
+        diff_opts.include_untracked(false).context_lines(0);
 
-        let diff = self
-            .repo
-            .diff_tree_to_tree(from_tree.as_ref(), Some(&to_tree), Some(&mut diff_opts))
-            .context("Failed to compute diff")?;
+        let mut diff = self.repo.diff_tree_to_tree(
+            from_tree.as_ref(),
+            Some(&to_tree),
+            Some(&mut diff_opts),
+        )?;
+        // Pair deletions with additions so moved files keep their history
+        diff.find_similar(Some(DiffFindOptions::new().renames(true)))?;
 
         let mut changes = Vec::new();
+        let mut hunks: HashMap<PathBuf, Vec<(usize, usize)>> = HashMap::new();
+
+        // Line ranges are only useful when comparing against an earlier
+        // commit; a full scan re-reads every file anyway
+        let mut hunk_cb = |delta: git2::DiffDelta<'_>, hunk: git2::DiffHunk<'_>| {
+            if matches!(delta.status(), git2::Delta::Modified | git2::Delta::Renamed) {
+                if let Some(path) = delta.new_file().path() {
+                    // A pure deletion has no new lines and starts at the line
+                    // before the removed ones; mark the lines on both sides
+                    let start = (hunk.new_start() as usize).max(1);
+                    let end = match hunk.new_lines() as usize {
+                        0 => start + 1,
+                        lines => start + lines - 1,
+                    };
+                    hunks
+                        .entry(path.to_path_buf())
+                        .or_default()
+                        .push((start, end));
+                }
+            }
+            true
+        };
 
         diff.foreach(
             &mut |delta, _| {
@@ -119,6 +249,10 @@ impl Repository {
                     .map(|p| p.to_path_buf());
 
                 if let Some(path) = path {
+                    if !self.in_scope(&path) || self.config.should_ignore(&path.to_string_lossy()) {
+                        return true;
+                    }
+
                     let kind = match delta.status() {
                         git2::Delta::Added => ChangeKind::Added,
                         git2::Delta::Deleted => ChangeKind::Deleted,
@@ -127,21 +261,34 @@ impl Repository {
                         _ => ChangeKind::Modified,
                     };
 
+                    let old_path = match kind {
+                        ChangeKind::Renamed => delta.old_file().path().map(|p| p.to_path_buf()),
+                        _ => None,
+                    };
                     let file_type = self.categorize_file(&path);
 
                     changes.push(ChangedFile {
                         path,
+                        old_path,
                         kind,
                         file_type,
+                        changed_lines: None,
                     });
                 }
                 true
             },
             None,
+            from_tree.as_ref().map(|_| {
+                &mut hunk_cb as &mut dyn FnMut(git2::DiffDelta<'_>, git2::DiffHunk<'_>) -> bool
+            }),
             None,
-            None,
-        )
-        .context("Failed to iterate diff")?;
+        )?;
+
+        for change in &mut changes {
+            if matches!(change.kind, ChangeKind::Modified | ChangeKind::Renamed) {
+                change.changed_lines = Some(hunks.remove(&change.path).unwrap_or_default());
+            }
+        }
 
         Ok(changes)
     }
@@ -151,27 +298,41 @@ impl Repository {
         let mut opts = StatusOptions::new();
         opts.include_untracked(true)
             .recurse_untracked_dirs(true)
-            .include_ignored(false);
+            .include_ignored(false)
+            .renames_head_to_index(true);
 
-        let statuses = self
-            .repo
-            .statuses(Some(&mut opts))
-            .context("Failed to get repository status")?;
+        let statuses = self.repo.statuses(Some(&mut opts))?;
 
         let mut changes = Vec::new();
 
         for entry in statuses.iter() {
-            if let Some(path) = entry.path() {
+            let status = entry.status();
+            // A staged rename is reported under its old path
+            let rename = entry
+                .head_to_index()
+                .filter(|_| status.is_index_renamed())
+                .and_then(|delta| Some((delta.old_file().path()?, delta.new_file().path()?)));
+            let path = match rename {
+                Some((_, new_path)) => new_path.to_str(),
+                None => entry.path(),
+            };
+
+            if let Some(path) = path {
+                if !self.in_scope(Path::new(path)) || self.config.should_ignore(path) {
+                    continue;
+                }
+
                 let path = PathBuf::from(path);
-                let status = entry.status();
 
-                let kind = if status.is_wt_new() || status.is_index_new() {
+                let kind = if rename.is_some() {
+                    ChangeKind::Renamed
+                } else if status.is_wt_new() || status.is_index_new() {
                     ChangeKind::Added
                 } else if status.is_wt_deleted() || status.is_index_deleted() {
                     ChangeKind::Deleted
                 } else if status.is_wt_modified() || status.is_index_modified() {
                     ChangeKind::Modified
-                } else if status.is_wt_renamed() || status.is_index_renamed() {
+                } else if status.is_wt_renamed() {
                     ChangeKind::Renamed
                 } else {
                     continue;
@@ -181,8 +342,10 @@ impl Repository {
 
                 changes.push(ChangedFile {
                     path,
+                    old_path: rename.map(|(old_path, _)| old_path.to_path_buf()),
                     kind,
                     file_type,
+                    changed_lines: None,
                 });
             }
         }
@@ -229,60 +392,160 @@ impl Repository {
         }
     }
 
+    /// Resolve a revision (hash, branch, `HEAD~1`, ...) to a commit
+    fn resolve_commit(&self, revision: &str) -> Result<git2::Commit<'_>> {
+        self.repo
+            .revparse_single(revision)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|source| DocSentinelError::InvalidRevision {
+                revision: revision.to_string(),
+                source,
+            })
+    }
+
     /// Read file content at a specific commit
     pub fn read_file_at_commit(&self, path: &Path, commit: &str) -> Result<Option<String>> {
-        let commit_obj = self
-            .repo
-            .revparse_single(commit)
-            .with_context(|| format!("Failed to parse revision: {}", commit))?
-            .peel_to_commit()
-            .context("Failed to peel to commit")?;
-
-        let tree = commit_obj.tree().context("Failed to get tree")?;
+        let tree = self.resolve_commit(commit)?.tree()?;
 
         let entry = match tree.get_path(path) {
             Ok(entry) => entry,
             Err(_) => return Ok(None),
         };
 
-        let object = entry
-            .to_object(&self.repo)
-            .context("Failed to get object")?;
+        let object = entry.to_object(&self.repo)?;
 
         if let Some(blob) = object.as_blob() {
+            self.check_file_size(path, blob.size() as u64)?;
             if blob.is_binary() {
                 return Ok(None);
             }
-            let content = std::str::from_utf8(blob.content())
-                .context("File content is not valid UTF-8")?
-                .to_string();
-            Ok(Some(content))
+            let content =
+                std::str::from_utf8(blob.content()).map_err(|_| DocSentinelError::InvalidUtf8 {
+                    path: path.to_path_buf(),
+                })?;
+            Ok(Some(content.to_string()))
         } else {
             Ok(None)
         }
     }
 
     /// Read current file content from working directory
+    ///
+    /// Like [`Repository::read_file_at_commit`], binary files read as `None`,
+    /// files over `max_file_bytes` are rejected before they are read, and
+    /// text that is not UTF-8 is rejected with
+    /// [`DocSentinelError::InvalidUtf8`].
     pub fn read_file_current(&self, path: &Path) -> Result<Option<String>> {
         let full_path = self.root.join(path);
         if !full_path.exists() {
             return Ok(None);
         }
-        let content = std::fs::read_to_string(&full_path)
-            .with_context(|| format!("Failed to read file: {:?}", full_path))?;
+        let size = std::fs::metadata(&full_path)
+            .map_err(|e| DocSentinelError::io(&full_path, e))?
+            .len();
+        self.check_file_size(path, size)?;
+
+        let bytes = std::fs::read(&full_path).map_err(|e| DocSentinelError::io(&full_path, e))?;
+        if looks_binary(&bytes) {
+            return Ok(None);
+        }
+        let content = String::from_utf8(bytes).map_err(|_| DocSentinelError::InvalidUtf8 {
+            path: path.to_path_buf(),
+        })?;
         Ok(Some(content))
     }
 
+    /// Reject files larger than the configured `max_file_bytes`
+    fn check_file_size(&self, path: &Path, size: u64) -> Result<()> {
+        let limit = self.config.max_file_bytes;
+        if limit > 0 && size > limit {
+            return Err(DocSentinelError::FileTooLarge {
+                path: path.to_path_buf(),
+                size,
+                limit,
+            });
+        }
+        Ok(())
+    }
+
+    /// Commit that last changed a line of a file in the working tree
+    ///
+    /// `line` is 1-indexed. Returns `None` when the line has uncommitted
+    /// changes, the file is not committed yet, or the line does not exist.
+    pub fn last_commit_for_line(&self, path: &Path, line: usize) -> Result<Option<CommitInfo>> {
+        self.last_commit_for_lines(path, line, line)
+    }
+
+    /// Most recent commit among those that last changed lines
+    /// `start..=end` of a file in the working tree
+    ///
+    /// Returns `None` when any of the lines has uncommitted changes, since
+    /// the newest change to them is not a commit yet, or when the file is not
+    /// committed.
+    pub fn last_commit_for_lines(
+        &self,
+        path: &Path,
+        start: usize,
+        end: usize,
+    ) -> Result<Option<CommitInfo>> {
+        // Blame the working tree content against HEAD, so lines with
+        // uncommitted changes are attributed to a zero commit ID
+        if self.repo.head().is_err() {
+            return Ok(None);
+        }
+        let committed = match self.repo.blame_file(path, None) {
+            Ok(blame) => blame,
+            Err(e) if e.code() == ErrorCode::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let Some(content) = self.read_file_current(path)? else {
+            return Ok(None);
+        };
+        let blame = committed.blame_buffer(content.as_bytes())?;
+
+        let mut newest: Option<git2::Commit<'_>> = None;
+        for line in start.max(1)..=end {
+            let Some(hunk) = blame.get_line(line) else {
+                break;
+            };
+            let id = hunk.final_commit_id();
+            if id.is_zero() {
+                return Ok(None);
+            }
+            if newest.as_ref().is_some_and(|commit| commit.id() == id) {
+                continue;
+            }
+            let commit = self.repo.find_commit(id)?;
+            if newest
+                .as_ref()
+                .is_none_or(|n| commit.time().seconds() > n.time().seconds())
+            {
+                newest = Some(commit);
+            }
+        }
+        Ok(newest.map(|commit| CommitInfo::from_commit(&commit)))
+    }
+
     /// Get the repository configuration
+    ///
+    /// LLM settings the repository leaves unset are filled from the user
+    /// config, so this is not what should be written back to disk; edit
+    /// [`Repository::stored_config`] instead.
     pub fn config(&self) -> &RepoConfig {
         &self.config
     }
 
+    /// The configuration as stored in this scope's `.docsentinel/config.toml`,
+    /// without user-level defaults
+    pub fn stored_config(&self) -> Result<RepoConfig> {
+        RepoConfig::load_or_default(&self.scope_dir())
+    }
+
     /// Commit a specific file with a message
     pub fn commit_file(&self, file_path: &Path, message: &str) -> Result<String> {
         let relative_path = file_path
             .strip_prefix(&self.root)
-            .context("File path is not within repository")?;
+            .map_err(|_| DocSentinelError::OutsideRepository(file_path.to_path_buf()))?;
 
         // Stage the file
         let mut index = self.repo.index()?;
@@ -298,32 +561,68 @@ impl Repository {
         let parent_commit = head.peel_to_commit()?;
 
         // Create signature
-        let sig = Signature::now("DocSentinel", "docsentinel@local")
-            .context("Failed to create signature")?;
+        let sig = Signature::now("DocSentinel", "docsentinel@local")?;
 
         // Create the commit
-        let commit_id = self
-            .repo
-            .commit(Some("HEAD"), &sig, &sig, message, &tree, &[&parent_commit])
-            .context("Failed to create commit")?;
+        let commit_id =
+            self.repo
+                .commit(Some("HEAD"), &sig, &sig, message, &tree, &[&parent_commit])?;
 
         Ok(commit_id.to_string())
     }
 
+    /// List files in the tree of a commit, optionally of one type
+    ///
+    /// Paths matching the configured ignore patterns are skipped.
+    pub fn list_files_at_commit(
+        &self,
+        commit: &str,
+        file_type: Option<FileType>,
+    ) -> Result<Vec<PathBuf>> {
+        let tree = self.resolve_commit(commit)?.tree()?;
+        let mut files = Vec::new();
+
+        tree.walk(git2::TreeWalkMode::PreOrder, |dir, entry| {
+            if entry.kind() != Some(git2::ObjectType::Blob) {
+                return git2::TreeWalkResult::Ok;
+            }
+            let Some(name) = entry.name() else {
+                return git2::TreeWalkResult::Ok;
+            };
+            let path = PathBuf::from(format!("{}{}", dir, name));
+            if !self.in_scope(&path) || self.config.should_ignore(&path.to_string_lossy()) {
+                return git2::TreeWalkResult::Ok;
+            }
+            if file_type.is_none_or(|ft| self.categorize_file(&path) == ft) {
+                files.push(path);
+            }
+            git2::TreeWalkResult::Ok
+        })?;
+
+        Ok(files)
+    }
+
     /// List all files in the repository matching certain criteria
     pub fn list_files(&self, file_type: Option<FileType>) -> Result<Vec<PathBuf>> {
         let mut files = Vec::new();
 
-        for entry in walkdir::WalkDir::new(&self.root)
+        for entry in walkdir::WalkDir::new(self.scope_dir())
             .into_iter()
             .filter_entry(|e| {
                 let name = e.file_name().to_str().unwrap_or("");
                 !name.starts_with('.') && name != "target" && name != "node_modules"
             })
         {
-            let entry = entry?;
+            let entry = entry.map_err(|e| {
+                let path = e.path().unwrap_or(&self.root).to_path_buf();
+                DocSentinelError::io(path, e.into())
+            })?;
             if entry.file_type().is_file() {
-                let path = entry.path().strip_prefix(&self.root)?.to_path_buf();
+                let path = entry
+                    .path()
+                    .strip_prefix(&self.root)
+                    .map_err(|_| DocSentinelError::OutsideRepository(entry.path().to_path_buf()))?
+                    .to_path_buf();
 
                 if let Some(ref ft) = file_type {
                     if &self.categorize_file(&path) == ft {
@@ -348,6 +647,50 @@ pub enum FileType {
     Other,
 }
 
+/// Turn a scope argument into a normalized path relative to `root`
+///
+/// Returns `None` when the scope is the root itself.
+fn normalize_scope(root: &Path, scope: &Path) -> Result<Option<PathBuf>> {
+    let relative = if scope.is_absolute() {
+        let canonical_root = root
+            .canonicalize()
+            .map_err(|e| DocSentinelError::io(root, e))?;
+        let canonical = scope
+            .canonicalize()
+            .map_err(|e| DocSentinelError::io(scope, e))?;
+        canonical
+            .strip_prefix(&canonical_root)
+            .map_err(|_| DocSentinelError::OutsideRepository(scope.to_path_buf()))?
+            .to_path_buf()
+    } else {
+        scope.to_path_buf()
+    };
+
+    let mut normalized = PathBuf::new();
+    for component in relative.components() {
+        match component {
+            std::path::Component::Normal(part) => normalized.push(part),
+            std::path::Component::CurDir => {}
+            _ => return Err(DocSentinelError::OutsideRepository(scope.to_path_buf())),
+        }
+    }
+
+    if !root.join(&normalized).is_dir() {
+        return Err(DocSentinelError::Config(format!(
+            "Scope {:?} is not a directory in the repository",
+            scope
+        )));
+    }
+
+    Ok((!normalized.as_os_str().is_empty()).then_some(normalized))
+}
+
+/// Whether file content looks binary: a NUL byte in the first 8000 bytes,
+/// the same heuristic git uses
+fn looks_binary(bytes: &[u8]) -> bool {
+    bytes.iter().take(8000).any(|&b| b == 0)
+}
+
 /// Simple glob matching (supports * and **)
 fn glob_match(pattern: &str, path: &str) -> bool {
     // Simple implementation - in production, use the `glob` crate
@@ -394,4 +737,128 @@ mod tests {
         assert!(glob_match("docs/**/*.md", "docs/api/guide.md"));
         assert!(!glob_match("*.rs", "README.md"));
     }
+
+    #[test]
+    fn test_last_commit_for_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = GitRepo::init(dir.path()).unwrap();
+        let sig = Signature::new("Ada", "ada@example.com", &git2::Time::new(1_000, 0)).unwrap();
+        std::fs::write(dir.path().join("lib.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+        let mut index = git.index().unwrap();
+        index.add_path(Path::new("lib.rs")).unwrap();
+        index.write().unwrap();
+        let tree = git.find_tree(index.write_tree().unwrap()).unwrap();
+        let first = git
+            .commit(Some("HEAD"), &sig, &sig, "initial", &tree, &[])
+            .unwrap();
+
+        let repo = Repository::open(dir.path()).unwrap();
+        let commit = repo
+            .last_commit_for_line(Path::new("lib.rs"), 2)
+            .unwrap()
+            .unwrap();
+        assert_eq!(commit.hash, first.to_string());
+        assert_eq!(commit.author, "Ada");
+        assert_eq!(commit.email, "ada@example.com");
+        assert_eq!(commit.time, 1_000);
+
+        // Past the end of the file
+        assert!(repo
+            .last_commit_for_line(Path::new("lib.rs"), 3)
+            .unwrap()
+            .is_none());
+
+        // Uncommitted lines and files have no commit yet
+        std::fs::write(dir.path().join("lib.rs"), "fn a() {}\nfn b(x: u8) {}\n").unwrap();
+        std::fs::write(dir.path().join("new.rs"), "fn c() {}\n").unwrap();
+        assert!(repo
+            .last_commit_for_line(Path::new("lib.rs"), 2)
+            .unwrap()
+            .is_none());
+        assert!(repo
+            .last_commit_for_lines(Path::new("lib.rs"), 1, 2)
+            .unwrap()
+            .is_none());
+        assert!(repo
+            .last_commit_for_line(Path::new("lib.rs"), 1)
+            .unwrap()
+            .is_some());
+        assert!(repo
+            .last_commit_for_line(Path::new("new.rs"), 1)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_scope_ignores_files_outside_subpath() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = GitRepo::init(dir.path()).unwrap();
+        let mut index = git.index().unwrap();
+        for path in [
+            "README.md",
+            "packages/a/README.md",
+            "packages/a/src/lib.rs",
+            "packages/b/README.md",
+        ] {
+            let full_path = dir.path().join(path);
+            std::fs::create_dir_all(full_path.parent().unwrap()).unwrap();
+            std::fs::write(&full_path, "# Docs\n").unwrap();
+            index.add_path(Path::new(path)).unwrap();
+        }
+        index.write().unwrap();
+        let tree = git.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Test", "test@example.com").unwrap();
+        git.commit(Some("HEAD"), &sig, &sig, "initial", &tree, &[])
+            .unwrap();
+        std::fs::write(dir.path().join("packages/b/NEW.md"), "# New\n").unwrap();
+        std::fs::write(dir.path().join("packages/a/NEW.md"), "# New\n").unwrap();
+
+        let repo = Repository::open_scoped(dir.path(), Some(Path::new("./packages/a/"))).unwrap();
+        assert_eq!(repo.scope(), Some(Path::new("packages/a")));
+        assert_eq!(
+            repo.sentinel_dir(),
+            dir.path().join("packages/a/.docsentinel")
+        );
+
+        let mut changed: Vec<PathBuf> = repo
+            .changes_between(None, "HEAD")
+            .unwrap()
+            .into_iter()
+            .map(|c| c.path)
+            .collect();
+        changed.sort();
+        assert_eq!(
+            changed,
+            vec![
+                PathBuf::from("packages/a/README.md"),
+                PathBuf::from("packages/a/src/lib.rs"),
+            ]
+        );
+
+        let uncommitted: Vec<PathBuf> = repo
+            .uncommitted_changes()
+            .unwrap()
+            .into_iter()
+            .map(|c| c.path)
+            .collect();
+        assert_eq!(uncommitted, vec![PathBuf::from("packages/a/NEW.md")]);
+
+        let mut docs = repo.list_files(Some(FileType::Documentation)).unwrap();
+        docs.sort();
+        assert_eq!(
+            docs,
+            vec![
+                PathBuf::from("packages/a/NEW.md"),
+                PathBuf::from("packages/a/README.md"),
+            ]
+        );
+        assert_eq!(
+            repo.list_files_at_commit("HEAD", Some(FileType::Documentation))
+                .unwrap(),
+            vec![PathBuf::from("packages/a/README.md")]
+        );
+
+        assert!(Repository::open_scoped(dir.path(), Some(Path::new("../elsewhere"))).is_err());
+        assert!(Repository::open_scoped(dir.path(), Some(Path::new("packages/c"))).is_err());
+    }
 }
@@ -0,0 +1,282 @@
+//! Portable JSON export of the stored chunks and drift events
+
+use super::{decode_embedding, Database, DATA_VERSION};
+use crate::drift::DriftEvent;
+use crate::error::{DocSentinelError, Result};
+use crate::extract::{CodeChunk, DocChunk};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+
+/// Snapshot of a database written by `docsentinel export`
+///
+/// Embeddings are stored as base64 of their little-endian `f32` bytes, the
+/// same layout as the database BLOBs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseExport {
+    /// Stored data layout the export was taken from; imports require a match
+    pub data_version: i64,
+    /// Version of the binary that wrote the export
+    pub version: String,
+    /// Commit the last scan covered
+    pub last_scan_commit: Option<String>,
+    /// Model that produced the embeddings, when recorded
+    pub embedding_model: Option<String>,
+    pub code_chunks: Vec<ExportedChunk<CodeChunk>>,
+    pub doc_chunks: Vec<ExportedChunk<DocChunk>>,
+    /// Drift events with their status
+    pub events: Vec<DriftEvent>,
+}
+
+/// A chunk with its embedding encoded for export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedChunk<T> {
+    #[serde(flatten)]
+    pub chunk: T,
+    /// Base64 of the embedding's little-endian `f32` bytes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding: Option<String>,
+}
+
+impl DatabaseExport {
+    /// Parse an export, refusing one written for a different data version
+    ///
+    /// The version is checked before the rest is decoded, so the error names
+    /// the mismatch rather than whichever field changed.
+    pub fn from_json(json: &str) -> Result<Self> {
+        #[derive(Deserialize)]
+        struct Header {
+            data_version: Option<i64>,
+        }
+
+        let header: Header = serde_json::from_str(json)?;
+        match header.data_version {
+            Some(DATA_VERSION) => Ok(serde_json::from_str(json)?),
+            Some(found) => Err(version_mismatch(found)),
+            None => Err(DocSentinelError::Import(
+                "not a DocSentinel export (no data_version)".to_string(),
+            )),
+        }
+    }
+}
+
+/// Error for an export taken from another data version
+fn version_mismatch(found: i64) -> DocSentinelError {
+    DocSentinelError::Import(format!(
+        "the export has data version {}, but this build uses version {}; \
+         export again with a matching docsentinel version",
+        found, DATA_VERSION
+    ))
+}
+
+/// Base64 of an embedding's little-endian bytes
+fn encode(embedding: Option<&Vec<f32>>) -> Option<String> {
+    embedding.map(|e| STANDARD.encode(e.iter().flat_map(|f| f.to_le_bytes()).collect::<Vec<u8>>()))
+}
+
+/// Wrap chunks for export, moving each embedding out through `take`
+fn export_chunks<T>(chunks: Vec<T>, take: fn(&mut T) -> Option<Vec<f32>>) -> Vec<ExportedChunk<T>> {
+    chunks
+        .into_iter()
+        .map(|mut chunk| {
+            let embedding = encode(take(&mut chunk).as_ref());
+            ExportedChunk { chunk, embedding }
+        })
+        .collect()
+}
+
+/// Decode the exported embedding of chunk `id`
+fn decode(id: &str, embedding: Option<&str>) -> Result<Option<Vec<f32>>> {
+    let Some(encoded) = embedding else {
+        return Ok(None);
+    };
+    STANDARD
+        .decode(encoded)
+        .ok()
+        .and_then(|bytes| decode_embedding(&bytes, None))
+        .map(Some)
+        .ok_or_else(|| DocSentinelError::Import(format!("invalid embedding for chunk {}", id)))
+}
+
+impl Database {
+    /// Snapshot every chunk, drift event, and the scan state
+    pub fn export_all(&self) -> Result<DatabaseExport> {
+        Ok(DatabaseExport {
+            data_version: DATA_VERSION,
+            version: crate::VERSION.to_string(),
+            last_scan_commit: self.get_last_scan_commit()?,
+            embedding_model: self
+                .embedding_model_in_use()?
+                .map(|(model, _)| model)
+                .filter(|model| model != "unknown"),
+            code_chunks: export_chunks(self.get_all_code_chunks()?, |c: &mut CodeChunk| {
+                c.embedding.take()
+            }),
+            doc_chunks: export_chunks(self.get_all_doc_chunks()?, |c: &mut DocChunk| {
+                c.embedding.take()
+            }),
+            events: self.get_all_drift_events()?,
+        })
+    }
+
+    /// Load an export into this database
+    ///
+    /// The database must hold no chunks or events, so an import never mixes
+    /// two baselines. Everything is written in one transaction.
+    pub fn import_all(&self, export: &DatabaseExport) -> Result<()> {
+        if export.data_version != DATA_VERSION {
+            return Err(version_mismatch(export.data_version));
+        }
+
+        let stats = self.get_stats()?;
+        if stats.code_chunks + stats.doc_chunks + stats.drift_events > 0 {
+            return Err(DocSentinelError::Import(
+                "the database already holds scan data; clean it first".to_string(),
+            ));
+        }
+
+        self.with_transaction(|db| {
+            for exported in &export.code_chunks {
+                let mut chunk = exported.chunk.clone();
+                chunk.embedding = decode(&chunk.id, exported.embedding.as_deref())?;
+                db.upsert_code_chunk(&chunk)?;
+            }
+            for exported in &export.doc_chunks {
+                let mut chunk = exported.chunk.clone();
+                chunk.embedding = decode(&chunk.id, exported.embedding.as_deref())?;
+                db.upsert_doc_chunk(&chunk)?;
+            }
+            for event in &export.events {
+                // IDs are shown shortened to their first 8 characters, so
+                // anything but a UUID gets a fresh one
+                if uuid::Uuid::parse_str(&event.id).is_ok() {
+                    db.insert_drift_event(event)?;
+                } else {
+                    let mut event = event.clone();
+                    event.id = uuid::Uuid::new_v4().to_string();
+                    db.insert_drift_event(&event)?;
+                }
+            }
+
+            if let Some(ref model) = export.embedding_model {
+                for table in ["code_chunks", "doc_chunks"] {
+                    db.conn.execute(
+                        &format!(
+                            "UPDATE {} SET embedding_model = ?1 WHERE embedding IS NOT NULL",
+                            table
+                        ),
+                        params![model],
+                    )?;
+                }
+            }
+            if let Some(ref commit) = export.last_scan_commit {
+                db.set_last_scan_commit(commit)?;
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drift::{DriftSeverity, DriftStatus};
+    use crate::extract::code::{Language, SymbolType};
+    use crate::extract::doc::HeadingLevel;
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let db = Database::open_in_memory()
+            .unwrap()
+            .with_embedding_model("nomic-embed-text");
+
+        let mut code = CodeChunk::new(
+            "src/lib.rs",
+            "greet",
+            SymbolType::Function,
+            "pub fn greet() {}",
+            Language::Rust,
+            1,
+            1,
+        );
+        code.signature = Some("pub fn greet()".to_string());
+        code.embedding = Some(vec![0.25, -1.5, 3.0]);
+        db.upsert_code_chunk(&code).unwrap();
+
+        let doc = DocChunk::new(
+            "README.md",
+            vec!["Usage".to_string()],
+            "Usage",
+            HeadingLevel::H2,
+            "## Usage\nCall `greet()`.",
+            1,
+            2,
+        );
+        db.upsert_doc_chunk(&doc).unwrap();
+
+        let event = DriftEvent::new(DriftSeverity::High, "Signature changed", "", 0.9)
+            .with_code_chunk(&code.id)
+            .with_doc_chunk(&doc.id);
+        let event_id = db.insert_drift_event(&event).unwrap();
+        db.update_drift_event_status(&event_id, "Ignored").unwrap();
+        db.set_last_scan_commit("abc123").unwrap();
+
+        let json = serde_json::to_string(&db.export_all().unwrap()).unwrap();
+        let imported = Database::open_in_memory().unwrap();
+        imported
+            .import_all(&DatabaseExport::from_json(&json).unwrap())
+            .unwrap();
+
+        let restored = imported.get_code_chunk(&code.id).unwrap().unwrap();
+        assert_eq!(restored.embedding, code.embedding);
+        assert_eq!(restored.signature, code.signature);
+        assert_eq!(imported.get_all_doc_chunks().unwrap()[0].id, doc.id);
+        assert_eq!(
+            imported.embedding_model_in_use().unwrap(),
+            Some(("nomic-embed-text".to_string(), 3))
+        );
+        assert_eq!(
+            imported.get_last_scan_commit().unwrap().as_deref(),
+            Some("abc123")
+        );
+        let events = imported.get_all_drift_events().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].id, event_id);
+        assert_eq!(events[0].status, DriftStatus::Ignored);
+
+        // A second import would mix baselines
+        let export = imported.export_all().unwrap();
+        assert!(imported.import_all(&export).is_err());
+    }
+
+    #[test]
+    fn test_import_replaces_ids_that_are_not_uuids() {
+        let mut export = Database::open_in_memory().unwrap().export_all().unwrap();
+        let mut event = DriftEvent::new(DriftSeverity::Low, "Short id", "", 0.5);
+        event.id = "a1".to_string();
+        export.events.push(event);
+
+        let imported = Database::open_in_memory().unwrap();
+        imported.import_all(&export).unwrap();
+
+        let events = imported.get_all_drift_events().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].description, "Short id");
+        assert!(uuid::Uuid::parse_str(&events[0].id).is_ok());
+    }
+
+    #[test]
+    fn test_import_refuses_other_data_version() {
+        let mut export = Database::open_in_memory().unwrap().export_all().unwrap();
+        export.data_version = DATA_VERSION + 1;
+        let json = serde_json::to_string(&export).unwrap();
+
+        let err = DatabaseExport::from_json(&json).unwrap_err();
+        assert!(err.to_string().contains("data version"));
+        assert!(Database::open_in_memory()
+            .unwrap()
+            .import_all(&export)
+            .is_err());
+    }
+}
@@ -6,28 +6,46 @@
 //! - Scan history and drift events
 //! - Configuration state
 
+mod export;
 mod schema;
 
-pub use schema::SCHEMA;
+pub use export::{DatabaseExport, ExportedChunk};
+pub use schema::{MIGRATIONS, POST_MIGRATION_SCHEMA, SCHEMA};
 
-use crate::drift::{DriftEvent, DriftSeverity};
-use crate::extract::{CodeChunk, DocChunk};
-use anyhow::{Context, Result};
+use crate::drift::{DriftEvent, DriftSeverity, DriftStatus};
+use crate::error::{DocSentinelError, Result};
+use crate::extract::{CodeChunk, DocChunk, HeadingAnchors};
 use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashMap;
 use std::path::Path;
 
+/// How long a connection waits for another one's lock before `SQLITE_BUSY`
+const BUSY_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(5000);
+
+/// Extra attempts a write makes after `SQLITE_BUSY`
+const BUSY_RETRIES: usize = 3;
+
+/// Version of the stored data layout, kept in `PRAGMA user_version`
+///
+/// Version 1 keys doc chunks by heading anchor instead of heading path.
+pub const DATA_VERSION: i64 = 1;
+
 /// Database connection wrapper
 pub struct Database {
     conn: Connection,
+    /// Model recorded alongside embeddings written by this connection
+    embedding_model: Option<String>,
 }
 
 impl Database {
     /// Open or create a database at the given path
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let conn = Connection::open(path.as_ref())
-            .with_context(|| format!("Failed to open database at {:?}", path.as_ref()))?;
+        let conn = Connection::open(path.as_ref())?;
 
-        let db = Self { conn };
+        let db = Self {
+            conn,
+            embedding_model: None,
+        };
         db.initialize()?;
 
         Ok(db)
@@ -35,22 +53,134 @@ impl Database {
 
     /// Open an in-memory database (for testing)
     pub fn open_in_memory() -> Result<Self> {
-        let conn = Connection::open_in_memory().context("Failed to open in-memory database")?;
+        let conn = Connection::open_in_memory()?;
 
-        let db = Self { conn };
+        let db = Self {
+            conn,
+            embedding_model: None,
+        };
         db.initialize()?;
 
         Ok(db)
     }
 
+    /// Record `model` as the source of embeddings written by this connection
+    pub fn with_embedding_model(mut self, model: &str) -> Self {
+        self.embedding_model = Some(model.to_string());
+        self
+    }
+
     /// Initialize the database schema
+    ///
+    /// Foreign keys are off by default in SQLite and the setting is per
+    /// connection, so it is enabled here for the cascading deletes declared
+    /// on `chunk_relationships`.
+    ///
+    /// Scans from hooks or watch mode may run while the TUI holds the same
+    /// file open, so the database uses write-ahead logging (readers no longer
+    /// block the writer) and waits up to [`BUSY_TIMEOUT`] for a lock instead
+    /// of failing with `database is locked`. WAL keeps `-wal` and `-shm` files
+    /// next to the database.
     fn initialize(&self) -> Result<()> {
-        self.conn
-            .execute_batch(SCHEMA)
-            .context("Failed to initialize database schema")?;
+        self.conn.busy_timeout(BUSY_TIMEOUT)?;
+        // In-memory databases report "memory" and keep their journal mode
+        let _mode: String = self
+            .conn
+            .query_row("PRAGMA journal_mode = WAL", [], |row| row.get(0))?;
+        self.conn.execute_batch("PRAGMA foreign_keys = ON")?;
+        self.conn.execute_batch(SCHEMA)?;
+        self.migrate()?;
+        Ok(())
+    }
+
+    /// Bring databases created by older versions up to the current schema
+    fn migrate(&self) -> Result<()> {
+        for (table, column, sql) in MIGRATIONS {
+            let exists: bool = self
+                .conn
+                .query_row(
+                    &format!(
+                        "SELECT COUNT(*) FROM pragma_table_info('{}') WHERE name = ?1",
+                        table
+                    ),
+                    params![column],
+                    |row| row.get::<_, i64>(0),
+                )
+                .map(|count| count > 0)?;
+
+            if !exists {
+                self.conn.execute_batch(sql)?;
+            }
+        }
+
+        self.conn.execute_batch(POST_MIGRATION_SCHEMA)?;
+
+        let version: i64 = self
+            .conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version < DATA_VERSION {
+            self.rekey_doc_chunks()?;
+            self.conn
+                .execute_batch(&format!("PRAGMA user_version = {}", DATA_VERSION))?;
+        }
+
+        // Relationships left behind by deletes made before foreign keys
+        // were enforced
+        self.prune_orphan_relationships()?;
         Ok(())
     }
 
+    // ==================== Transactions ====================
+
+    /// Run `f` inside a transaction
+    ///
+    /// Writes made by `f` are committed together when it succeeds and rolled
+    /// back when it returns an error.
+    pub fn with_transaction<T, E>(&self, f: impl FnOnce(&Self) -> Result<T, E>) -> Result<T, E>
+    where
+        E: From<DocSentinelError>,
+    {
+        // Take the write lock up front: a deferred transaction that reads
+        // first cannot wait for another writer when it later upgrades
+        self.retry_busy(|| Ok(self.conn.execute_batch("BEGIN IMMEDIATE")?))?;
+        match f(self) {
+            Ok(value) => {
+                self.conn
+                    .execute_batch("COMMIT")
+                    .map_err(DocSentinelError::from)?;
+                Ok(value)
+            }
+            Err(e) => {
+                // The original error is more useful than a failed rollback
+                let _ = self.conn.execute_batch("ROLLBACK");
+                Err(e)
+            }
+        }
+    }
+
+    /// Run a write, retrying while another connection holds the lock
+    ///
+    /// SQLite already waits [`BUSY_TIMEOUT`] before reporting `SQLITE_BUSY`;
+    /// this covers a second writer that held the lock for longer. Inside a
+    /// transaction the error is returned as is, since only the whole
+    /// transaction could be retried.
+    fn retry_busy<T>(&self, mut write: impl FnMut() -> Result<T>) -> Result<T> {
+        let mut attempt = 0;
+        loop {
+            match write() {
+                Err(DocSentinelError::Database(rusqlite::Error::SqliteFailure(e, _)))
+                    if e.code == rusqlite::ErrorCode::DatabaseBusy
+                        && self.conn.is_autocommit()
+                        && attempt < BUSY_RETRIES =>
+                {
+                    attempt += 1;
+                    std::thread::sleep(std::time::Duration::from_millis(50 * attempt as u64));
+                }
+                result => return result,
+            }
+        }
+    }
+
     // ==================== Scan State ====================
 
     /// Get the last scanned commit hash
@@ -62,21 +192,32 @@ impl Database {
                 [],
                 |row| row.get(0),
             )
-            .optional()
-            .context("Failed to get last scan commit")?;
+            .optional()?;
 
         Ok(result)
     }
 
     /// Update the last scanned commit hash
     pub fn set_last_scan_commit(&self, commit: &str) -> Result<()> {
-        self.conn
-            .execute(
-                "INSERT OR REPLACE INTO scan_state (id, commit_hash, scanned_at) VALUES (1, ?1, datetime('now'))",
-                params![commit],
-            )
-            .context("Failed to set last scan commit")?;
-        Ok(())
+        self.retry_busy(|| {
+            self.conn
+                .execute(
+                    "INSERT OR REPLACE INTO scan_state (id, commit_hash, scanned_at) VALUES (1, ?1, datetime('now'))",
+                    params![commit],
+                )?;
+            Ok(())
+        })
+    }
+
+    /// Forget the last scanned commit so the next scan covers the whole repository
+    ///
+    /// Returns the number of rows removed.
+    pub fn reset_scan_state(&self) -> Result<usize> {
+        self.retry_busy(|| {
+            self.conn
+                .execute("DELETE FROM scan_state", [])
+                .map_err(Into::into)
+        })
     }
 
     // ==================== Code Chunks ====================
@@ -87,31 +228,42 @@ impl Database {
             .embedding
             .as_ref()
             .map(|e| e.iter().flat_map(|f| f.to_le_bytes()).collect::<Vec<u8>>());
+        let (embedding_model, embedding_dim) = self.embedding_source(chunk.embedding.as_deref());
+        let decorators_json = serde_json::to_string(&chunk.decorators)?;
+        let attributes_json = serde_json::to_string(&chunk.attributes)?;
 
-        self.conn
-            .execute(
-                r#"
-                INSERT INTO code_chunks (
-                    id, file_path, symbol_name, symbol_type, content, hash,
-                    language, start_line, end_line, doc_comment, signature,
-                    is_public, embedding, updated_at
-                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, datetime('now'))
-                ON CONFLICT(id) DO UPDATE SET
-                    file_path = excluded.file_path,
-                    symbol_name = excluded.symbol_name,
-                    symbol_type = excluded.symbol_type,
-                    content = excluded.content,
-                    hash = excluded.hash,
-                    language = excluded.language,
-                    start_line = excluded.start_line,
-                    end_line = excluded.end_line,
-                    doc_comment = excluded.doc_comment,
-                    signature = excluded.signature,
-                    is_public = excluded.is_public,
-                    embedding = excluded.embedding,
-                    updated_at = datetime('now')
-                "#,
-                params![
+        self.retry_busy(|| {
+            self.conn
+                .prepare_cached(
+                    r#"
+                    INSERT INTO code_chunks (
+                        id, file_path, symbol_name, symbol_type, content, hash,
+                        language, start_line, end_line, doc_comment, signature,
+                        is_public, embedding, decorators, embedding_model, embedding_dim,
+                        attributes, updated_at
+                    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16,
+                              ?17, datetime('now'))
+                    ON CONFLICT(id) DO UPDATE SET
+                        file_path = excluded.file_path,
+                        symbol_name = excluded.symbol_name,
+                        symbol_type = excluded.symbol_type,
+                        content = excluded.content,
+                        hash = excluded.hash,
+                        language = excluded.language,
+                        start_line = excluded.start_line,
+                        end_line = excluded.end_line,
+                        doc_comment = excluded.doc_comment,
+                        signature = excluded.signature,
+                        is_public = excluded.is_public,
+                        embedding = excluded.embedding,
+                        decorators = excluded.decorators,
+                        embedding_model = excluded.embedding_model,
+                        embedding_dim = excluded.embedding_dim,
+                        attributes = excluded.attributes,
+                        updated_at = datetime('now')
+                    "#,
+                )?
+                .execute(params![
                     chunk.id,
                     chunk.file_path,
                     chunk.symbol_name,
@@ -125,11 +277,20 @@ impl Database {
                     chunk.signature,
                     chunk.is_public,
                     embedding_blob,
-                ],
-            )
-            .context("Failed to upsert code chunk")?;
+                    decorators_json,
+                    embedding_model,
+                    embedding_dim,
+                    attributes_json,
+                ])?;
+            Ok(())
+        })
+    }
 
-        Ok(())
+    /// Insert or update code chunks in a single transaction
+    ///
+    /// Either every chunk is written or, if one fails, none are.
+    pub fn upsert_code_chunks(&self, chunks: &[CodeChunk]) -> Result<()> {
+        self.with_transaction(|db| chunks.iter().try_for_each(|c| db.upsert_code_chunk(c)))
     }
 
     /// Get a code chunk by ID
@@ -140,7 +301,7 @@ impl Database {
                 r#"
                 SELECT id, file_path, symbol_name, symbol_type, content, hash,
                        language, start_line, end_line, doc_comment, signature,
-                       is_public, embedding
+                       is_public, embedding, decorators, embedding_dim, attributes
                 FROM code_chunks WHERE id = ?1
                 "#,
                 params![id],
@@ -159,11 +320,13 @@ impl Database {
                         signature: row.get(10)?,
                         is_public: row.get(11)?,
                         embedding: row.get(12)?,
+                        decorators: row.get(13)?,
+                        embedding_dim: row.get(14)?,
+                        attributes: row.get(15)?,
                     })
                 },
             )
-            .optional()
-            .context("Failed to get code chunk")?;
+            .optional()?;
 
         Ok(result.map(|r| r.into_chunk()))
     }
@@ -174,7 +337,7 @@ impl Database {
             r#"
             SELECT id, file_path, symbol_name, symbol_type, content, hash,
                    language, start_line, end_line, doc_comment, signature,
-                   is_public, embedding
+                   is_public, embedding, decorators, embedding_dim, attributes
             FROM code_chunks WHERE file_path = ?1
             "#,
         )?;
@@ -194,6 +357,9 @@ impl Database {
                 signature: row.get(10)?,
                 is_public: row.get(11)?,
                 embedding: row.get(12)?,
+                decorators: row.get(13)?,
+                embedding_dim: row.get(14)?,
+                attributes: row.get(15)?,
             })
         })?;
 
@@ -211,8 +377,9 @@ impl Database {
             r#"
             SELECT id, file_path, symbol_name, symbol_type, content, hash,
                    language, start_line, end_line, doc_comment, signature,
-                   is_public, embedding
+                   is_public, embedding, decorators, embedding_dim, attributes
             FROM code_chunks WHERE embedding IS NOT NULL
+            ORDER BY file_path, start_line, id
             "#,
         )?;
 
@@ -231,6 +398,72 @@ impl Database {
                 signature: row.get(10)?,
                 is_public: row.get(11)?,
                 embedding: row.get(12)?,
+                decorators: row.get(13)?,
+                embedding_dim: row.get(14)?,
+                attributes: row.get(15)?,
+            })
+        })?;
+
+        let mut chunks = Vec::new();
+        for row in rows {
+            chunks.push(row?.into_chunk());
+        }
+
+        Ok(chunks)
+    }
+
+    /// Count the code chunks matching `filter`
+    pub fn count_code_chunks(&self, filter: &CodeChunkFilter) -> Result<usize> {
+        let (clause, params) = filter.where_clause();
+        let count: i64 = self.conn.query_row(
+            &format!("SELECT COUNT(*) FROM code_chunks {}", clause),
+            rusqlite::params_from_iter(params),
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+
+    /// Get up to `limit` code chunks matching `filter`, ordered by file and
+    /// line, skipping the first `offset`
+    ///
+    /// An offset past the last chunk returns an empty page.
+    pub fn get_code_chunks_page(
+        &self,
+        filter: &CodeChunkFilter,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<CodeChunk>> {
+        let (clause, params) = filter.where_clause();
+        let mut stmt = self.conn.prepare(&format!(
+            r#"
+            SELECT id, file_path, symbol_name, symbol_type, content, hash,
+                   language, start_line, end_line, doc_comment, signature,
+                   is_public, embedding, decorators, embedding_dim, attributes
+            FROM code_chunks {}
+            ORDER BY file_path, start_line, id
+            LIMIT {} OFFSET {}
+            "#,
+            clause, limit, offset
+        ))?;
+
+        let rows = stmt.query_map(rusqlite::params_from_iter(params), |row| {
+            Ok(CodeChunkRow {
+                id: row.get(0)?,
+                file_path: row.get(1)?,
+                symbol_name: row.get(2)?,
+                symbol_type: row.get(3)?,
+                content: row.get(4)?,
+                hash: row.get(5)?,
+                language: row.get(6)?,
+                start_line: row.get(7)?,
+                end_line: row.get(8)?,
+                doc_comment: row.get(9)?,
+                signature: row.get(10)?,
+                is_public: row.get(11)?,
+                embedding: row.get(12)?,
+                decorators: row.get(13)?,
+                embedding_dim: row.get(14)?,
+                attributes: row.get(15)?,
             })
         })?;
 
@@ -248,8 +481,81 @@ impl Database {
             r#"
             SELECT id, file_path, symbol_name, symbol_type, content, hash,
                    language, start_line, end_line, doc_comment, signature,
-                   is_public, embedding
+                   is_public, embedding, decorators, embedding_dim, attributes
+            FROM code_chunks
+            ORDER BY file_path, start_line
+            "#,
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok(CodeChunkRow {
+                id: row.get(0)?,
+                file_path: row.get(1)?,
+                symbol_name: row.get(2)?,
+                symbol_type: row.get(3)?,
+                content: row.get(4)?,
+                hash: row.get(5)?,
+                language: row.get(6)?,
+                start_line: row.get(7)?,
+                end_line: row.get(8)?,
+                doc_comment: row.get(9)?,
+                signature: row.get(10)?,
+                is_public: row.get(11)?,
+                embedding: row.get(12)?,
+                decorators: row.get(13)?,
+                embedding_dim: row.get(14)?,
+                attributes: row.get(15)?,
+            })
+        })?;
+
+        let mut chunks = Vec::new();
+        for row in rows {
+            chunks.push(row?.into_chunk());
+        }
+
+        Ok(chunks)
+    }
+
+    /// Get the content hash of each stored code chunk in a file, by chunk ID
+    pub fn get_code_chunk_hashes_for_file(
+        &self,
+        file_path: &str,
+    ) -> Result<HashMap<String, String>> {
+        let mut stmt = self
+            .conn
+            .prepare_cached("SELECT id, hash FROM code_chunks WHERE file_path = ?1")?;
+        let rows = stmt.query_map(params![file_path], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+        rows.collect::<rusqlite::Result<_>>().map_err(Into::into)
+    }
+
+    /// Move a stored code chunk to new lines without touching its content
+    pub fn update_code_chunk_lines(
+        &self,
+        id: &str,
+        start_line: usize,
+        end_line: usize,
+    ) -> Result<()> {
+        self.retry_busy(|| {
+            self.conn
+                .prepare_cached(
+                    "UPDATE code_chunks SET start_line = ?2, end_line = ?3 \
+                     WHERE id = ?1 AND (start_line != ?2 OR end_line != ?3)",
+                )?
+                .execute(params![id, start_line as i64, end_line as i64])?;
+            Ok(())
+        })
+    }
+
+    /// Get all public code chunks
+    pub fn get_public_code_chunks(&self) -> Result<Vec<CodeChunk>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id, file_path, symbol_name, symbol_type, content, hash,
+                   language, start_line, end_line, doc_comment, signature,
+                   is_public, embedding, decorators, embedding_dim, attributes
             FROM code_chunks
+            WHERE is_public = 1
             ORDER BY file_path, start_line
             "#,
         )?;
@@ -269,6 +575,9 @@ impl Database {
                 signature: row.get(10)?,
                 is_public: row.get(11)?,
                 embedding: row.get(12)?,
+                decorators: row.get(13)?,
+                embedding_dim: row.get(14)?,
+                attributes: row.get(15)?,
             })
         })?;
 
@@ -282,15 +591,14 @@ impl Database {
 
     /// Delete code chunks for a file
     pub fn delete_code_chunks_for_file(&self, file_path: &str) -> Result<usize> {
-        let count = self
-            .conn
-            .execute(
+        self.retry_busy(|| {
+            let count = self.conn.execute(
                 "DELETE FROM code_chunks WHERE file_path = ?1",
                 params![file_path],
-            )
-            .context("Failed to delete code chunks")?;
+            )?;
 
-        Ok(count)
+            Ok(count)
+        })
     }
 
     // ==================== Doc Chunks ====================
@@ -301,29 +609,41 @@ impl Database {
             .embedding
             .as_ref()
             .map(|e| e.iter().flat_map(|f| f.to_le_bytes()).collect::<Vec<u8>>());
+        let (embedding_model, embedding_dim) = self.embedding_source(chunk.embedding.as_deref());
 
         let heading_path_json = serde_json::to_string(&chunk.heading_path)?;
+        let linked_symbols_json = serde_json::to_string(&chunk.linked_symbols)?;
+        let links_json = serde_json::to_string(&chunk.links)?;
+        let frontmatter_json = serde_json::to_string(&chunk.frontmatter)?;
 
-        self.conn
-            .execute(
-                r#"
-                INSERT INTO doc_chunks (
-                    id, file_path, heading_path, heading, level, content, hash,
-                    start_line, end_line, embedding, updated_at
-                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, datetime('now'))
-                ON CONFLICT(id) DO UPDATE SET
-                    file_path = excluded.file_path,
-                    heading_path = excluded.heading_path,
-                    heading = excluded.heading,
-                    level = excluded.level,
-                    content = excluded.content,
-                    hash = excluded.hash,
-                    start_line = excluded.start_line,
-                    end_line = excluded.end_line,
-                    embedding = excluded.embedding,
-                    updated_at = datetime('now')
-                "#,
-                params![
+        self.retry_busy(|| {
+            self.conn
+                .prepare_cached(
+                    r#"
+                    INSERT INTO doc_chunks (
+                        id, file_path, heading_path, heading, level, content, hash,
+                        start_line, end_line, embedding, linked_symbols, embedding_model,
+                        embedding_dim, links, frontmatter, updated_at
+                    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, datetime('now'))
+                    ON CONFLICT(id) DO UPDATE SET
+                        file_path = excluded.file_path,
+                        heading_path = excluded.heading_path,
+                        heading = excluded.heading,
+                        level = excluded.level,
+                        content = excluded.content,
+                        hash = excluded.hash,
+                        start_line = excluded.start_line,
+                        end_line = excluded.end_line,
+                        embedding = excluded.embedding,
+                        linked_symbols = excluded.linked_symbols,
+                        embedding_model = excluded.embedding_model,
+                        embedding_dim = excluded.embedding_dim,
+                        links = excluded.links,
+                        frontmatter = excluded.frontmatter,
+                        updated_at = datetime('now')
+                    "#,
+                )?
+                .execute(params![
                     chunk.id,
                     chunk.file_path,
                     heading_path_json,
@@ -334,11 +654,21 @@ impl Database {
                     chunk.start_line as i64,
                     chunk.end_line as i64,
                     embedding_blob,
-                ],
-            )
-            .context("Failed to upsert doc chunk")?;
+                    linked_symbols_json,
+                    embedding_model,
+                    embedding_dim,
+                    links_json,
+                    frontmatter_json,
+                ])?;
+            Ok(())
+        })
+    }
 
-        Ok(())
+    /// Insert or update doc chunks in a single transaction
+    ///
+    /// Either every chunk is written or, if one fails, none are.
+    pub fn upsert_doc_chunks(&self, chunks: &[DocChunk]) -> Result<()> {
+        self.with_transaction(|db| chunks.iter().try_for_each(|c| db.upsert_doc_chunk(c)))
     }
 
     /// Get a doc chunk by ID
@@ -348,7 +678,8 @@ impl Database {
             .query_row(
                 r#"
                 SELECT id, file_path, heading_path, heading, level, content, hash,
-                       start_line, end_line, embedding
+                       start_line, end_line, embedding, linked_symbols, embedding_dim, links,
+                   frontmatter
                 FROM doc_chunks WHERE id = ?1
                 "#,
                 params![id],
@@ -364,11 +695,14 @@ impl Database {
                         start_line: row.get(7)?,
                         end_line: row.get(8)?,
                         embedding: row.get(9)?,
+                        linked_symbols: row.get(10)?,
+                        embedding_dim: row.get(11)?,
+                        links: row.get(12)?,
+                        frontmatter: row.get(13)?,
                     })
                 },
             )
-            .optional()
-            .context("Failed to get doc chunk")?;
+            .optional()?;
 
         Ok(result.and_then(|r| r.into_chunk().ok()))
     }
@@ -378,7 +712,8 @@ impl Database {
         let mut stmt = self.conn.prepare(
             r#"
             SELECT id, file_path, heading_path, heading, level, content, hash,
-                   start_line, end_line, embedding
+                   start_line, end_line, embedding, linked_symbols, embedding_dim, links,
+                   frontmatter
             FROM doc_chunks WHERE file_path = ?1
             "#,
         )?;
@@ -395,6 +730,10 @@ impl Database {
                 start_line: row.get(7)?,
                 end_line: row.get(8)?,
                 embedding: row.get(9)?,
+                linked_symbols: row.get(10)?,
+                embedding_dim: row.get(11)?,
+                links: row.get(12)?,
+                frontmatter: row.get(13)?,
             })
         })?;
 
@@ -413,8 +752,10 @@ impl Database {
         let mut stmt = self.conn.prepare(
             r#"
             SELECT id, file_path, heading_path, heading, level, content, hash,
-                   start_line, end_line, embedding
+                   start_line, end_line, embedding, linked_symbols, embedding_dim, links,
+                   frontmatter
             FROM doc_chunks WHERE embedding IS NOT NULL
+            ORDER BY file_path, start_line, id
             "#,
         )?;
 
@@ -430,6 +771,10 @@ impl Database {
                 start_line: row.get(7)?,
                 end_line: row.get(8)?,
                 embedding: row.get(9)?,
+                linked_symbols: row.get(10)?,
+                embedding_dim: row.get(11)?,
+                links: row.get(12)?,
+                frontmatter: row.get(13)?,
             })
         })?;
 
@@ -443,110 +788,605 @@ impl Database {
         Ok(chunks)
     }
 
-    /// Delete doc chunks for a file
-    pub fn delete_doc_chunks_for_file(&self, file_path: &str) -> Result<usize> {
-        let count = self
-            .conn
-            .execute(
-                "DELETE FROM doc_chunks WHERE file_path = ?1",
-                params![file_path],
-            )
-            .context("Failed to delete doc chunks")?;
-
-        Ok(count)
-    }
-
-    // ==================== Drift Events ====================
-
-    /// Insert a drift event
-    pub fn insert_drift_event(&self, event: &DriftEvent) -> Result<()> {
-        let related_code_json = serde_json::to_string(&event.related_code_chunks)?;
-        let related_doc_json = serde_json::to_string(&event.related_doc_chunks)?;
+    /// Get the code and doc chunks that have no embedding yet, ordered by
+    /// file and line
+    pub fn get_chunks_without_embeddings(&self) -> Result<(Vec<CodeChunk>, Vec<DocChunk>)> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id, file_path, symbol_name, symbol_type, content, hash,
+                   language, start_line, end_line, doc_comment, signature,
+                   is_public, embedding, decorators, embedding_dim, attributes
+            FROM code_chunks WHERE embedding IS NULL
+            ORDER BY file_path, start_line, id
+            "#,
+        )?;
 
-        self.conn
-            .execute(
-                r#"
-                INSERT INTO drift_events (
-                    id, severity, description, evidence, confidence,
-                    related_code_chunks, related_doc_chunks, suggested_fix,
-                    status, detected_at
-                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, datetime('now'))
-                "#,
-                params![
-                    event.id,
-                    format!("{:?}", event.severity),
-                    event.description,
-                    event.evidence,
-                    event.confidence,
-                    related_code_json,
-                    related_doc_json,
-                    event.suggested_fix,
-                    format!("{:?}", event.status),
-                ],
-            )
-            .context("Failed to insert drift event")?;
+        let rows = stmt.query_map([], |row| {
+            Ok(CodeChunkRow {
+                id: row.get(0)?,
+                file_path: row.get(1)?,
+                symbol_name: row.get(2)?,
+                symbol_type: row.get(3)?,
+                content: row.get(4)?,
+                hash: row.get(5)?,
+                language: row.get(6)?,
+                start_line: row.get(7)?,
+                end_line: row.get(8)?,
+                doc_comment: row.get(9)?,
+                signature: row.get(10)?,
+                is_public: row.get(11)?,
+                embedding: row.get(12)?,
+                decorators: row.get(13)?,
+                embedding_dim: row.get(14)?,
+                attributes: row.get(15)?,
+            })
+        })?;
 
-        Ok(())
-    }
+        let mut code_chunks = Vec::new();
+        for row in rows {
+            code_chunks.push(row?.into_chunk());
+        }
 
-    /// Get all unresolved drift events
-    pub fn get_unresolved_drift_events(&self) -> Result<Vec<DriftEvent>> {
         let mut stmt = self.conn.prepare(
             r#"
-            SELECT id, severity, description, evidence, confidence,
-                   related_code_chunks, related_doc_chunks, suggested_fix,
-                   status, detected_at
-            FROM drift_events WHERE status = 'Pending'
-            ORDER BY confidence DESC
+            SELECT id, file_path, heading_path, heading, level, content, hash,
+                   start_line, end_line, embedding, linked_symbols, embedding_dim, links,
+                   frontmatter
+            FROM doc_chunks WHERE embedding IS NULL
+            ORDER BY file_path, start_line, id
             "#,
         )?;
 
         let rows = stmt.query_map([], |row| {
-            Ok(DriftEventRow {
+            Ok(DocChunkRow {
                 id: row.get(0)?,
-                severity: row.get(1)?,
-                description: row.get(2)?,
-                evidence: row.get(3)?,
-                confidence: row.get(4)?,
-                related_code_chunks: row.get(5)?,
-                related_doc_chunks: row.get(6)?,
-                suggested_fix: row.get(7)?,
-                status: row.get(8)?,
-                detected_at: row.get(9)?,
+                file_path: row.get(1)?,
+                heading_path: row.get(2)?,
+                heading: row.get(3)?,
+                level: row.get(4)?,
+                content: row.get(5)?,
+                hash: row.get(6)?,
+                start_line: row.get(7)?,
+                end_line: row.get(8)?,
+                embedding: row.get(9)?,
+                linked_symbols: row.get(10)?,
+                embedding_dim: row.get(11)?,
+                links: row.get(12)?,
+                frontmatter: row.get(13)?,
             })
         })?;
 
-        let mut events = Vec::new();
+        let mut doc_chunks = Vec::new();
         for row in rows {
-            if let Ok(event) = row?.into_event() {
-                events.push(event);
+            if let Ok(chunk) = row?.into_chunk() {
+                doc_chunks.push(chunk);
             }
         }
 
-        Ok(events)
+        Ok((code_chunks, doc_chunks))
     }
 
-    /// Update drift event status
-    pub fn update_drift_event_status(&self, id: &str, status: &str) -> Result<()> {
-        self.conn
-            .execute(
-                "UPDATE drift_events SET status = ?1, resolved_at = datetime('now') WHERE id = ?2",
-                params![status, id],
-            )
-            .context("Failed to update drift event status")?;
+    /// Get doc chunks whose content contains `name`, ordered by file and line
+    ///
+    /// The match is a case-sensitive substring test done by SQLite, so callers
+    /// narrowing docs down to the few that mention a symbol don't have to
+    /// load every chunk first.
+    pub fn get_doc_chunks_referencing_symbol(&self, name: &str) -> Result<Vec<DocChunk>> {
+        if name.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut stmt = self.conn.prepare_cached(
+            r#"
+            SELECT id, file_path, heading_path, heading, level, content, hash,
+                   start_line, end_line, embedding, linked_symbols, embedding_dim, links,
+                   frontmatter
+            FROM doc_chunks WHERE instr(content, ?1) > 0
+            ORDER BY file_path, start_line
+            "#,
+        )?;
 
-        Ok(())
-    }
+        let rows = stmt.query_map(params![name], |row| {
+            Ok(DocChunkRow {
+                id: row.get(0)?,
+                file_path: row.get(1)?,
+                heading_path: row.get(2)?,
+                heading: row.get(3)?,
+                level: row.get(4)?,
+                content: row.get(5)?,
+                hash: row.get(6)?,
+                start_line: row.get(7)?,
+                end_line: row.get(8)?,
+                embedding: row.get(9)?,
+                linked_symbols: row.get(10)?,
+                embedding_dim: row.get(11)?,
+                links: row.get(12)?,
+                frontmatter: row.get(13)?,
+            })
+        })?;
 
-    /// Get drift event by ID
-    pub fn get_drift_event(&self, id: &str) -> Result<Option<DriftEvent>> {
-        let result = self
-            .conn
-            .query_row(
+        let mut chunks = Vec::new();
+        for row in rows {
+            if let Ok(chunk) = row?.into_chunk() {
+                chunks.push(chunk);
+            }
+        }
+
+        Ok(chunks)
+    }
+
+    /// Get all doc chunks
+    pub fn get_all_doc_chunks(&self) -> Result<Vec<DocChunk>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id, file_path, heading_path, heading, level, content, hash,
+                   start_line, end_line, embedding, linked_symbols, embedding_dim, links,
+                   frontmatter
+            FROM doc_chunks
+            ORDER BY file_path, start_line
+            "#,
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok(DocChunkRow {
+                id: row.get(0)?,
+                file_path: row.get(1)?,
+                heading_path: row.get(2)?,
+                heading: row.get(3)?,
+                level: row.get(4)?,
+                content: row.get(5)?,
+                hash: row.get(6)?,
+                start_line: row.get(7)?,
+                end_line: row.get(8)?,
+                embedding: row.get(9)?,
+                linked_symbols: row.get(10)?,
+                embedding_dim: row.get(11)?,
+                links: row.get(12)?,
+                frontmatter: row.get(13)?,
+            })
+        })?;
+
+        let mut chunks = Vec::new();
+        for row in rows {
+            if let Ok(chunk) = row?.into_chunk() {
+                chunks.push(chunk);
+            }
+        }
+
+        Ok(chunks)
+    }
+
+    /// Delete doc chunks for a file
+    pub fn delete_doc_chunks_for_file(&self, file_path: &str) -> Result<usize> {
+        self.retry_busy(|| {
+            let count = self.conn.execute(
+                "DELETE FROM doc_chunks WHERE file_path = ?1",
+                params![file_path],
+            )?;
+
+            Ok(count)
+        })
+    }
+
+    // ==================== Renames ====================
+
+    /// Move the stored chunks of a renamed file to its new path
+    ///
+    /// Chunk IDs are rewritten along with the file path, so embeddings,
+    /// relationships, history, and drift events referring to the chunks carry
+    /// over instead of being rebuilt. Chunks already stored under the new
+    /// path are replaced. Returns the number of chunks moved.
+    pub fn rename_file(&self, old_path: &str, new_path: &str) -> Result<usize> {
+        self.with_transaction(|db| {
+            // Chunk IDs are referenced by relationships, so the key checks
+            // wait until every table has been rewritten
+            db.conn.execute_batch("PRAGMA defer_foreign_keys = ON")?;
+            db.delete_code_chunks_for_file(new_path)?;
+            db.delete_doc_chunks_for_file(new_path)?;
+
+            let mut moved = 0;
+            for (table, separator) in [("code_chunks", "::"), ("doc_chunks", "#")] {
+                moved += db.conn.execute(
+                    &format!(
+                        "UPDATE {} SET id = ?2 || substr(id, length(?1) + 1), file_path = ?2 \
+                         WHERE file_path = ?1 AND substr(id, 1, length(?1 || ?3)) = ?1 || ?3",
+                        table
+                    ),
+                    params![old_path, new_path, separator],
+                )?;
+            }
+
+            for (table, column) in [
+                ("chunk_relationships", "code_chunk_id"),
+                ("chunk_relationships", "doc_chunk_id"),
+                ("chunk_history", "chunk_id"),
+            ] {
+                for separator in ["::", "#"] {
+                    db.conn.execute(
+                        &format!(
+                            "UPDATE {table} SET {column} = ?2 || substr({column}, length(?1) + 1) \
+                             WHERE substr({column}, 1, length(?1 || ?3)) = ?1 || ?3"
+                        ),
+                        params![old_path, new_path, separator],
+                    )?;
+                }
+            }
+
+            db.rewrite_drift_events(|event| {
+                let mut changed = false;
+                for id in event
+                    .related_code_chunks
+                    .iter_mut()
+                    .chain(event.related_doc_chunks.iter_mut())
+                {
+                    if let Some(renamed) = renamed_chunk_id(id, old_path, new_path) {
+                        *id = renamed;
+                        changed = true;
+                    }
+                }
+                if event.primary_file.as_deref() == Some(old_path) {
+                    event.primary_file = Some(new_path.to_string());
+                    changed = true;
+                }
+                changed
+            })?;
+
+            Ok(moved)
+        })
+    }
+
+    /// Re-key stored doc chunks written before IDs used heading anchors
+    ///
+    /// Anchors are recomputed from each file's stored sections in line order,
+    /// and every reference to the old IDs is rewritten, so embeddings,
+    /// relationships and drift history survive the change.
+    fn rekey_doc_chunks(&self) -> Result<usize> {
+        let mut by_file: HashMap<String, Vec<DocChunk>> = HashMap::new();
+        for chunk in self.get_all_doc_chunks()? {
+            by_file
+                .entry(chunk.file_path.clone())
+                .or_default()
+                .push(chunk);
+        }
+
+        let mut renames = HashMap::new();
+        for (file_path, mut chunks) in by_file {
+            chunks.sort_by_key(|c| (c.start_line, c.part()));
+            let mut anchors = HeadingAnchors::new();
+            let mut anchor = String::new();
+            for chunk in &chunks {
+                // Later parts of a split section share its anchor
+                if chunk.part().is_none_or(|part| part == 1) {
+                    anchor = anchors.assign(&chunk.heading);
+                }
+                let mut id = format!("{}#{}", file_path, anchor);
+                if let Some(part) = chunk.part() {
+                    id = format!("{} (part {})", id, part);
+                }
+                if id != chunk.id {
+                    renames.insert(chunk.id.clone(), id);
+                }
+            }
+        }
+        if renames.is_empty() {
+            return Ok(0);
+        }
+
+        self.with_transaction(|db| {
+            db.conn.execute_batch("PRAGMA defer_foreign_keys = ON")?;
+            // New IDs may still be held by other rows, so every ID passes
+            // through a temporary one first
+            let temporary = |id: &str| format!("rekey:{}", id);
+            for (table, column) in [
+                ("doc_chunks", "id"),
+                ("chunk_relationships", "doc_chunk_id"),
+                ("chunk_history", "chunk_id"),
+            ] {
+                let sql = format!("UPDATE {table} SET {column} = ?2 WHERE {column} = ?1");
+                for (old, new) in &renames {
+                    db.conn.execute(&sql, params![old, temporary(new)])?;
+                }
+                for new in renames.values() {
+                    db.conn.execute(&sql, params![temporary(new), new])?;
+                }
+            }
+
+            db.rewrite_drift_events(|event| {
+                let mut changed = false;
+                for id in event.related_doc_chunks.iter_mut() {
+                    if let Some(new) = renames.get(id.as_str()) {
+                        id.clone_from(new);
+                        changed = true;
+                    }
+                }
+                changed
+            })?;
+
+            Ok(renames.len())
+        })
+    }
+
+    /// Store drift events whose chunk references `rewrite` changed
+    ///
+    /// `rewrite` returns whether it modified the event. The identity is
+    /// recomputed, and an event that now matches a stored one is left as is.
+    fn rewrite_drift_events(&self, mut rewrite: impl FnMut(&mut DriftEvent) -> bool) -> Result<()> {
+        for mut event in self.get_all_drift_events()? {
+            if !rewrite(&mut event) {
+                continue;
+            }
+            self.conn.execute(
+                "UPDATE OR IGNORE drift_events SET related_code_chunks = ?2, \
+                 related_doc_chunks = ?3, primary_file = ?4, identity = ?5 WHERE id = ?1",
+                params![
+                    event.id,
+                    serde_json::to_string(&event.related_code_chunks)?,
+                    serde_json::to_string(&event.related_doc_chunks)?,
+                    event.primary_file,
+                    event.identity(),
+                ],
+            )?;
+        }
+        Ok(())
+    }
+
+    // ==================== Drift Events ====================
+
+    /// Insert a drift event, or refresh the stored row for the same drift
+    ///
+    /// Events are keyed on [`DriftEvent::identity`], so re-detecting the same
+    /// drift updates the existing row instead of adding a duplicate. The row's
+    /// status is preserved so ignored or fixed events stay resolved. Returns
+    /// the ID of the stored row.
+    pub fn insert_drift_event(&self, event: &DriftEvent) -> Result<String> {
+        let related_code_json = serde_json::to_string(&event.related_code_chunks)?;
+        let related_doc_json = serde_json::to_string(&event.related_doc_chunks)?;
+        let tags_json = serde_json::to_string(&event.tags)?;
+        let introduced_by_json = event
+            .introduced_by
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?;
+
+        self.retry_busy(|| {
+            let id = self.conn.query_row(
+                r#"
+                    INSERT INTO drift_events (
+                        id, severity, description, evidence, confidence,
+                        related_code_chunks, related_doc_chunks, suggested_fix,
+                        status, detected_at, identity, primary_file, primary_line, tags,
+                        introduced_by, detected_description
+                    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, datetime('now'), ?10, ?11, ?12, ?13, ?14, ?15)
+                    ON CONFLICT(identity) DO UPDATE SET
+                        description = excluded.description,
+                        evidence = excluded.evidence,
+                        confidence = excluded.confidence,
+                        primary_file = excluded.primary_file,
+                        primary_line = excluded.primary_line,
+                        tags = excluded.tags,
+                        introduced_by = COALESCE(excluded.introduced_by, drift_events.introduced_by),
+                        detected_description = excluded.detected_description,
+                        suggested_fix = COALESCE(excluded.suggested_fix, drift_events.suggested_fix)
+                    RETURNING id
+                    "#,
+                params![
+                    event.id,
+                    format!("{:?}", event.severity),
+                    event.description,
+                    event.evidence,
+                    event.confidence,
+                    related_code_json,
+                    related_doc_json,
+                    event.suggested_fix,
+                    format!("{:?}", event.status),
+                    event.identity(),
+                    event.primary_file,
+                    event.primary_line.map(|l| l as i64),
+                    tags_json,
+                    introduced_by_json,
+                    event.detected_description,
+                ],
+                |row| row.get(0),
+            )?;
+            Ok(id)
+        })
+    }
+
+    /// Get all unresolved drift events
+    pub fn get_unresolved_drift_events(&self) -> Result<Vec<DriftEvent>> {
+        self.get_events_by_status(&[DriftStatus::Pending])
+    }
+
+    /// Get drift events with any of the given statuses
+    pub fn get_events_by_status(&self, statuses: &[DriftStatus]) -> Result<Vec<DriftEvent>> {
+        if statuses.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let placeholders: Vec<String> = (1..=statuses.len()).map(|i| format!("?{}", i)).collect();
+        let statuses: Vec<String> = statuses.iter().map(|s| s.to_string()).collect();
+        self.query_drift_events(
+            &format!("WHERE status IN ({})", placeholders.join(", ")),
+            rusqlite::params_from_iter(statuses),
+        )
+    }
+
+    /// Get every drift event, regardless of status
+    pub fn get_all_drift_events(&self) -> Result<Vec<DriftEvent>> {
+        self.query_drift_events("", [])
+    }
+
+    /// Count the drift events matching `filter`
+    pub fn count_drift_events(&self, filter: &EventFilter) -> Result<usize> {
+        let (clause, params) = filter.where_clause();
+        let count: i64 = self.conn.query_row(
+            &format!("SELECT COUNT(*) FROM drift_events {}", clause),
+            rusqlite::params_from_iter(params),
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+
+    /// Count the drift events matching `filter` at each severity
+    pub fn count_drift_events_by_severity(
+        &self,
+        filter: &EventFilter,
+    ) -> Result<HashMap<DriftSeverity, usize>> {
+        let (clause, params) = filter.where_clause();
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT severity, COUNT(*) FROM drift_events {} GROUP BY severity",
+            clause
+        ))?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(params), |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })?;
+
+        let mut counts = HashMap::new();
+        for row in rows {
+            let (severity, count) = row?;
+            *counts.entry(parse_severity(&severity)).or_insert(0) += count as usize;
+        }
+        Ok(counts)
+    }
+
+    /// Get up to `limit` drift events matching `filter`, skipping the first
+    /// `offset` in output order (see [`DriftEvent::cmp_priority`])
+    ///
+    /// An offset past the last event returns an empty page.
+    pub fn get_events_page(
+        &self,
+        filter: &EventFilter,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<DriftEvent>> {
+        let (clause, params) = filter.where_clause();
+        self.query_drift_events_page(
+            &clause,
+            &format!("LIMIT {} OFFSET {}", limit, offset),
+            rusqlite::params_from_iter(params),
+        )
+    }
+
+    /// Tags on the drift events with `status` (any status for `None`),
+    /// sorted and deduplicated
+    pub fn get_event_tags(&self, status: Option<DriftStatus>) -> Result<Vec<String>> {
+        let filter = EventFilter { status, tag: None };
+        let (clause, params) = filter.where_clause();
+        let mut stmt = self.conn.prepare(&format!(
+            r#"
+            SELECT DISTINCT tag.value
+            FROM drift_events, json_each(drift_events.tags) AS tag {}
+            ORDER BY tag.value
+            "#,
+            clause
+        ))?;
+        let tags = stmt
+            .query_map(rusqlite::params_from_iter(params), |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+        Ok(tags)
+    }
+
+    /// Load drift events matching a `WHERE` clause, in output order (see
+    /// [`DriftEvent::cmp_priority`])
+    fn query_drift_events<P: rusqlite::Params>(
+        &self,
+        filter: &str,
+        params: P,
+    ) -> Result<Vec<DriftEvent>> {
+        self.query_drift_events_page(filter, "", params)
+    }
+
+    /// Load drift events matching a `WHERE` clause in output order, limited
+    /// by a `LIMIT`/`OFFSET` clause
+    fn query_drift_events_page<P: rusqlite::Params>(
+        &self,
+        filter: &str,
+        limit: &str,
+        params: P,
+    ) -> Result<Vec<DriftEvent>> {
+        let mut stmt = self.conn.prepare(&format!(
+            r#"
+            SELECT id, severity, description, evidence, confidence,
+                   related_code_chunks, related_doc_chunks, suggested_fix,
+                   status, detected_at, primary_file, primary_line, tags, introduced_by,
+                   detected_description
+            FROM drift_events {}
+            ORDER BY CASE severity
+                         WHEN 'Critical' THEN 0
+                         WHEN 'High' THEN 1
+                         WHEN 'Low' THEN 3
+                         ELSE 2
+                     END,
+                     confidence DESC, id
+            {}
+            "#,
+            filter, limit
+        ))?;
+
+        let rows = stmt.query_map(params, |row| {
+            Ok(DriftEventRow {
+                id: row.get(0)?,
+                severity: row.get(1)?,
+                description: row.get(2)?,
+                evidence: row.get(3)?,
+                confidence: row.get(4)?,
+                related_code_chunks: row.get(5)?,
+                related_doc_chunks: row.get(6)?,
+                suggested_fix: row.get(7)?,
+                status: row.get(8)?,
+                detected_at: row.get(9)?,
+                primary_file: row.get(10)?,
+                primary_line: row.get(11)?,
+                tags: row.get(12)?,
+                introduced_by: row.get(13)?,
+                detected_description: row.get(14)?,
+            })
+        })?;
+
+        let mut events = Vec::new();
+        for row in rows {
+            if let Ok(event) = row?.into_event() {
+                events.push(event);
+            }
+        }
+        crate::drift::sort_events(&mut events);
+
+        Ok(events)
+    }
+
+    /// Update drift event status
+    pub fn update_drift_event_status(&self, id: &str, status: &str) -> Result<()> {
+        self.retry_busy(|| {
+            self.conn.execute(
+                "UPDATE drift_events SET status = ?1, resolved_at = datetime('now') WHERE id = ?2",
+                params![status, id],
+            )?;
+
+            Ok(())
+        })
+    }
+
+    /// Replace a drift event's evidence
+    pub fn update_drift_event_evidence(&self, id: &str, evidence: &str) -> Result<()> {
+        self.retry_busy(|| {
+            self.conn.execute(
+                "UPDATE drift_events SET evidence = ?1 WHERE id = ?2",
+                params![evidence, id],
+            )?;
+
+            Ok(())
+        })
+    }
+
+    /// Get drift event by ID
+    pub fn get_drift_event(&self, id: &str) -> Result<Option<DriftEvent>> {
+        let result = self
+            .conn
+            .query_row(
                 r#"
                 SELECT id, severity, description, evidence, confidence,
                        related_code_chunks, related_doc_chunks, suggested_fix,
-                       status, detected_at
+                       status, detected_at, primary_file, primary_line, tags,
+                       introduced_by, detected_description
                 FROM drift_events WHERE id = ?1
                 "#,
                 params![id],
@@ -562,17 +1402,158 @@ impl Database {
                         suggested_fix: row.get(7)?,
                         status: row.get(8)?,
                         detected_at: row.get(9)?,
+                        primary_file: row.get(10)?,
+                        primary_line: row.get(11)?,
+                        tags: row.get(12)?,
+                        introduced_by: row.get(13)?,
+                        detected_description: row.get(14)?,
                     })
                 },
             )
-            .optional()
-            .context("Failed to get drift event")?;
+            .optional()?;
 
         Ok(result.and_then(|r| r.into_event().ok()))
     }
 
+    // ==================== Maintenance ====================
+
+    /// Model and dimension to store alongside an embedding
+    fn embedding_source(&self, embedding: Option<&[f32]>) -> (Option<String>, Option<i64>) {
+        match embedding {
+            Some(e) => (self.embedding_model.clone(), Some(e.len() as i64)),
+            None => (None, None),
+        }
+    }
+
+    /// Model and dimension of the stored embeddings
+    ///
+    /// When chunks were embedded with different models, the one covering the
+    /// most chunks is returned. Embeddings written before the model was
+    /// recorded report `unknown` with the dimension of their vector.
+    pub fn embedding_model_in_use(&self) -> Result<Option<(String, usize)>> {
+        let result = self
+            .conn
+            .query_row(
+                r#"
+                SELECT COALESCE(embedding_model, 'unknown') AS model,
+                       COALESCE(embedding_dim, length(embedding) / 4) AS dim,
+                       COUNT(*) AS chunks
+                FROM (
+                    SELECT embedding, embedding_model, embedding_dim FROM code_chunks
+                    UNION ALL
+                    SELECT embedding, embedding_model, embedding_dim FROM doc_chunks
+                )
+                WHERE embedding IS NOT NULL
+                GROUP BY model, dim
+                ORDER BY chunks DESC
+                LIMIT 1
+                "#,
+                [],
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize)),
+            )
+            .optional()?;
+
+        Ok(result)
+    }
+
+    /// Remove every stored embedding so chunks are embedded again
+    ///
+    /// Returns the number of chunks whose embedding was cleared.
+    pub fn clear_embeddings(&self) -> Result<usize> {
+        self.retry_busy(|| {
+            let mut cleared = 0;
+            for table in ["code_chunks", "doc_chunks"] {
+                cleared += self.conn.execute(
+                    &format!(
+                        "UPDATE {} SET embedding = NULL, embedding_model = NULL, embedding_dim = NULL
+                         WHERE embedding IS NOT NULL",
+                        table
+                    ),
+                    [],
+                )?;
+            }
+            Ok(cleared)
+        })
+    }
+
+    /// Delete drift events
+    ///
+    /// With `all`, every event is removed; otherwise only resolved (non-pending)
+    /// events are. Returns the number of events removed.
+    pub fn clear_drift_events(&self, all: bool) -> Result<usize> {
+        self.retry_busy(|| {
+            let sql = if all {
+                "DELETE FROM drift_events"
+            } else {
+                "DELETE FROM drift_events WHERE status != 'Pending'"
+            };
+
+            self.conn.execute(sql, []).map_err(Into::into)
+        })
+    }
+
+    /// Delete all code and doc chunks along with their relationships
+    ///
+    /// The deletes run in one transaction, so an interruption never leaves
+    /// chunks without their relationships or the reverse. Returns the number
+    /// of code and doc chunks removed.
+    pub fn clear_chunks(&self) -> Result<(usize, usize)> {
+        self.with_transaction(|db| {
+            db.conn.execute("DELETE FROM chunk_relationships", [])?;
+            let code = db.conn.execute("DELETE FROM code_chunks", [])?;
+            let doc = db.conn.execute("DELETE FROM doc_chunks", [])?;
+
+            Ok((code, doc))
+        })
+    }
+
+    /// Delete relationships whose code or doc chunk no longer exists
+    ///
+    /// Returns the number of relationships removed.
+    pub fn prune_orphan_relationships(&self) -> Result<usize> {
+        self.retry_busy(|| {
+            self.conn
+                .execute(
+                    "DELETE FROM chunk_relationships
+                     WHERE code_chunk_id NOT IN (SELECT id FROM code_chunks)
+                        OR doc_chunk_id NOT IN (SELECT id FROM doc_chunks)",
+                    [],
+                )
+                .map_err(Into::into)
+        })
+    }
+
+    /// Delete chunk history recorded more than `days` days ago
+    ///
+    /// Returns the number of history rows removed.
+    pub fn prune_chunk_history(&self, days: u32) -> Result<usize> {
+        self.retry_busy(|| {
+            self.conn
+                .execute(
+                    "DELETE FROM chunk_history WHERE recorded_at < datetime('now', ?1)",
+                    params![format!("-{} days", days)],
+                )
+                .map_err(Into::into)
+        })
+    }
+
+    /// Rebuild the database file to reclaim space from deleted rows
+    pub fn vacuum(&self) -> Result<()> {
+        self.conn.execute_batch("VACUUM").map_err(Into::into)
+    }
+
     // ==================== Statistics ====================
 
+    /// Version of the data layout stored in the database
+    ///
+    /// Opening migrates older databases to [`DATA_VERSION`], so a larger value
+    /// means the database was written by a newer DocSentinel.
+    pub fn data_version(&self) -> Result<i64> {
+        Ok(self
+            .conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))?)
+    }
+
     /// Get database statistics
     pub fn get_stats(&self) -> Result<DatabaseStats> {
         let code_chunks: i64 =
@@ -598,8 +1579,142 @@ impl Database {
             doc_chunks: doc_chunks as usize,
             drift_events: drift_events as usize,
             pending_events: pending_events as usize,
+            size_bytes: self.size_bytes()?,
         })
     }
+
+    /// Size of the stored data in bytes
+    ///
+    /// Counts the pages in use, including ones still in the write-ahead log,
+    /// so it can differ from the size of the database file.
+    pub fn size_bytes(&self) -> Result<u64> {
+        let pages: i64 = self
+            .conn
+            .query_row("PRAGMA page_count", [], |row| row.get(0))?;
+        let page_size: i64 = self
+            .conn
+            .query_row("PRAGMA page_size", [], |row| row.get(0))?;
+        Ok((pages * page_size) as u64)
+    }
+
+    /// Count pending drift events per file, most affected files first
+    ///
+    /// A file is counted once per event that references any of its code or
+    /// doc chunks.
+    pub fn get_drift_by_file(&self) -> Result<Vec<(String, usize)>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT file_path, COUNT(DISTINCT event_id) AS events
+            FROM (
+                SELECT e.id AS event_id, d.file_path AS file_path
+                FROM drift_events e, json_each(e.related_doc_chunks) j
+                JOIN doc_chunks d ON d.id = j.value
+                WHERE e.status = 'Pending'
+                UNION ALL
+                SELECT e.id AS event_id, c.file_path AS file_path
+                FROM drift_events e, json_each(e.related_code_chunks) j
+                JOIN code_chunks c ON c.id = j.value
+                WHERE e.status = 'Pending'
+            )
+            GROUP BY file_path
+            ORDER BY events DESC, file_path ASC
+            "#,
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize))
+        })?;
+
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(Into::into)
+    }
+
+    /// Count pending drift events by severity
+    pub fn get_severity_histogram(&self) -> Result<HashMap<DriftSeverity, usize>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT severity, COUNT(*) FROM drift_events WHERE status = 'Pending' GROUP BY severity",
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize))
+        })?;
+
+        let mut histogram = HashMap::new();
+        for row in rows {
+            let (severity, count) = row?;
+            *histogram.entry(parse_severity(&severity)).or_insert(0) += count;
+        }
+
+        Ok(histogram)
+    }
+}
+
+/// Which drift events a counted or paged query covers
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventFilter {
+    /// Only events with this status (`None` for every status)
+    pub status: Option<DriftStatus>,
+    /// Only events carrying this tag
+    pub tag: Option<String>,
+}
+
+impl EventFilter {
+    /// `WHERE` clause (empty when nothing is filtered) and its parameters
+    fn where_clause(&self) -> (String, Vec<String>) {
+        let mut conditions = Vec::new();
+        let mut params = Vec::new();
+        if let Some(status) = self.status {
+            params.push(status.to_string());
+            conditions.push(format!("status = ?{}", params.len()));
+        }
+        if let Some(ref tag) = self.tag {
+            params.push(tag.clone());
+            conditions.push(format!(
+                "EXISTS (SELECT 1 FROM json_each(drift_events.tags) WHERE value = ?{})",
+                params.len()
+            ));
+        }
+        (where_clause(&conditions), params)
+    }
+}
+
+/// Which code chunks a counted or paged query covers
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CodeChunkFilter {
+    /// Only public symbols
+    pub public_only: bool,
+    /// Only chunks whose symbol name or file path contains this text,
+    /// ignoring ASCII case (empty matches everything)
+    pub search: String,
+}
+
+impl CodeChunkFilter {
+    /// `WHERE` clause (empty when nothing is filtered) and its parameters
+    fn where_clause(&self) -> (String, Vec<String>) {
+        let mut conditions = Vec::new();
+        let mut params = Vec::new();
+        if self.public_only {
+            conditions.push("is_public = 1".to_string());
+        }
+        if !self.search.is_empty() {
+            // SQLite's lower() only folds ASCII, so the search text must match it
+            params.push(self.search.to_ascii_lowercase());
+            conditions.push(format!(
+                "(instr(lower(symbol_name), ?{0}) > 0 OR instr(lower(file_path), ?{0}) > 0)",
+                params.len()
+            ));
+        }
+        (where_clause(&conditions), params)
+    }
+}
+
+/// Join SQL conditions into a `WHERE` clause, or nothing when there are none
+fn where_clause(conditions: &[String]) -> String {
+    if conditions.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", conditions.join(" AND "))
+    }
 }
 
 /// Database statistics
@@ -609,6 +1724,7 @@ pub struct DatabaseStats {
     pub doc_chunks: usize,
     pub drift_events: usize,
     pub pending_events: usize,
+    pub size_bytes: u64,
 }
 
 // Internal row types for database mapping
@@ -627,6 +1743,9 @@ struct CodeChunkRow {
     signature: Option<String>,
     is_public: bool,
     embedding: Option<Vec<u8>>,
+    decorators: String,
+    embedding_dim: Option<i64>,
+    attributes: String,
 }
 
 impl CodeChunkRow {
@@ -652,15 +1771,9 @@ impl CodeChunkRow {
             _ => SymbolType::Function,
         };
 
-        let embedding = self.embedding.map(|bytes| {
-            bytes
-                .chunks(4)
-                .map(|chunk| {
-                    let arr: [u8; 4] = chunk.try_into().unwrap_or([0; 4]);
-                    f32::from_le_bytes(arr)
-                })
-                .collect()
-        });
+        let embedding = self
+            .embedding
+            .and_then(|bytes| decode_embedding(&bytes, self.embedding_dim));
 
         CodeChunk {
             id: self.id,
@@ -675,6 +1788,8 @@ impl CodeChunkRow {
             doc_comment: self.doc_comment,
             signature: self.signature,
             is_public: self.is_public,
+            decorators: serde_json::from_str(&self.decorators).unwrap_or_default(),
+            attributes: serde_json::from_str(&self.attributes).unwrap_or_default(),
             embedding,
         }
     }
@@ -691,6 +1806,10 @@ struct DocChunkRow {
     start_line: i64,
     end_line: i64,
     embedding: Option<Vec<u8>>,
+    linked_symbols: String,
+    embedding_dim: Option<i64>,
+    links: String,
+    frontmatter: String,
 }
 
 impl DocChunkRow {
@@ -709,15 +1828,9 @@ impl DocChunkRow {
             _ => HeadingLevel::H1,
         };
 
-        let embedding = self.embedding.map(|bytes| {
-            bytes
-                .chunks(4)
-                .map(|chunk| {
-                    let arr: [u8; 4] = chunk.try_into().unwrap_or([0; 4]);
-                    f32::from_le_bytes(arr)
-                })
-                .collect()
-        });
+        let embedding = self
+            .embedding
+            .and_then(|bytes| decode_embedding(&bytes, self.embedding_dim));
 
         Ok(DocChunk {
             id: self.id,
@@ -730,6 +1843,9 @@ impl DocChunkRow {
             start_line: self.start_line as usize,
             end_line: self.end_line as usize,
             embedding,
+            linked_symbols: serde_json::from_str(&self.linked_symbols).unwrap_or_default(),
+            links: serde_json::from_str(&self.links).unwrap_or_default(),
+            frontmatter: serde_json::from_str(&self.frontmatter).unwrap_or_default(),
         })
     }
 }
@@ -746,19 +1862,54 @@ struct DriftEventRow {
     status: String,
     #[allow(dead_code)]
     detected_at: String,
+    primary_file: Option<String>,
+    primary_line: Option<i64>,
+    tags: String,
+    introduced_by: Option<String>,
+    detected_description: Option<String>,
+}
+
+/// The ID of a chunk in `old_path` rewritten to `new_path`, or `None` when
+/// the chunk belongs to another file
+fn renamed_chunk_id(id: &str, old_path: &str, new_path: &str) -> Option<String> {
+    let rest = id.strip_prefix(old_path)?;
+    (rest.starts_with("::") || rest.starts_with('#')).then(|| format!("{}{}", new_path, rest))
+}
+
+/// Decode a stored embedding BLOB of little-endian f32s
+///
+/// Returns `None` when the BLOB is truncated or its length disagrees with the
+/// stored dimension, so a corrupt vector is treated as missing rather than
+/// compared against others.
+fn decode_embedding(bytes: &[u8], dim: Option<i64>) -> Option<Vec<f32>> {
+    if !bytes.len().is_multiple_of(4) {
+        return None;
+    }
+    if dim.is_some_and(|dim| dim as usize * 4 != bytes.len()) {
+        return None;
+    }
+    Some(
+        bytes
+            .chunks_exact(4)
+            .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+            .collect(),
+    )
+}
+
+/// Parse a stored severity, treating unknown values as Medium
+fn parse_severity(severity: &str) -> DriftSeverity {
+    match severity {
+        "Critical" => DriftSeverity::Critical,
+        "High" => DriftSeverity::High,
+        "Medium" => DriftSeverity::Medium,
+        "Low" => DriftSeverity::Low,
+        _ => DriftSeverity::Medium,
+    }
 }
 
 impl DriftEventRow {
     fn into_event(self) -> Result<DriftEvent> {
-        use crate::drift::DriftStatus;
-
-        let severity = match self.severity.as_str() {
-            "Critical" => DriftSeverity::Critical,
-            "High" => DriftSeverity::High,
-            "Medium" => DriftSeverity::Medium,
-            "Low" => DriftSeverity::Low,
-            _ => DriftSeverity::Medium,
-        };
+        let severity = parse_severity(&self.severity);
 
         let status = match self.status.as_str() {
             "Pending" => DriftStatus::Pending,
@@ -770,6 +1921,7 @@ impl DriftEventRow {
 
         let related_code_chunks: Vec<String> = serde_json::from_str(&self.related_code_chunks)?;
         let related_doc_chunks: Vec<String> = serde_json::from_str(&self.related_doc_chunks)?;
+        let tags: Vec<String> = serde_json::from_str(&self.tags)?;
 
         Ok(DriftEvent {
             id: self.id,
@@ -781,6 +1933,13 @@ impl DriftEventRow {
             related_doc_chunks,
             suggested_fix: self.suggested_fix,
             status,
+            primary_file: self.primary_file,
+            primary_line: self.primary_line.map(|l| l as usize),
+            tags,
+            introduced_by: self
+                .introduced_by
+                .and_then(|json| serde_json::from_str(&json).ok()),
+            detected_description: self.detected_description,
         })
     }
 }
@@ -797,6 +1956,786 @@ mod tests {
         assert_eq!(stats.doc_chunks, 0);
     }
 
+    #[test]
+    fn test_insert_drift_event_deduplicates() {
+        let db = Database::open_in_memory().unwrap();
+
+        let first = DriftEvent::new(DriftSeverity::High, "Signature changed: foo", "a", 0.8)
+            .with_code_chunk("src/lib.rs::foo");
+        let first_id = db.insert_drift_event(&first).unwrap();
+        assert_eq!(first_id, first.id);
+
+        // Same logical drift from a later scan gets a fresh UUID
+        let second = DriftEvent::new(DriftSeverity::High, "Signature changed: foo", "b", 0.9)
+            .with_code_chunk("src/lib.rs::foo");
+        assert_ne!(first.id, second.id);
+        assert_eq!(db.insert_drift_event(&second).unwrap(), first_id);
+
+        let events = db.get_unresolved_drift_events().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].id, first_id);
+        assert_eq!(events[0].confidence, 0.9);
+
+        // Status survives re-detection
+        db.update_drift_event_status(&first_id, "Ignored").unwrap();
+        let third = DriftEvent::new(DriftSeverity::High, "Signature changed: foo", "c", 0.95)
+            .with_code_chunk("src/lib.rs::foo");
+        db.insert_drift_event(&third).unwrap();
+        assert!(db.get_unresolved_drift_events().unwrap().is_empty());
+        assert_eq!(db.get_stats().unwrap().drift_events, 1);
+    }
+
+    #[test]
+    fn test_drift_event_location_round_trip() {
+        let db = Database::open_in_memory().unwrap();
+
+        let event = DriftEvent::new(DriftSeverity::High, "Signature changed: foo", "a", 0.8)
+            .with_code_chunk("src/lib.rs::foo")
+            .with_location("README.md", 42);
+        let id = db.insert_drift_event(&event).unwrap();
+
+        let stored = db.get_drift_event(&id).unwrap().unwrap();
+        assert_eq!(stored.primary_file.as_deref(), Some("README.md"));
+        assert_eq!(stored.primary_line, Some(42));
+        assert_eq!(stored.location().as_deref(), Some("README.md:42"));
+
+        let json = serde_json::to_string(&stored).unwrap();
+        assert!(json.contains("\"primary_file\":\"README.md\""));
+        assert!(json.contains("\"primary_line\":42"));
+    }
+
+    #[test]
+    fn test_drift_event_introduced_by_round_trip() {
+        let db = Database::open_in_memory().unwrap();
+        let commit = crate::repo::CommitInfo {
+            hash: "0123456789abcdef".to_string(),
+            author: "Ada".to_string(),
+            email: "ada@example.com".to_string(),
+            time: 1_000,
+        };
+
+        let mut event = DriftEvent::new(DriftSeverity::High, "Signature changed: foo", "a", 0.8);
+        event.introduced_by = Some(commit.clone());
+        let id = db.insert_drift_event(&event).unwrap();
+        assert_eq!(
+            db.get_drift_event(&id).unwrap().unwrap().introduced_by,
+            Some(commit.clone())
+        );
+
+        // Re-detecting without a known commit keeps the stored one
+        event.introduced_by = None;
+        db.insert_drift_event(&event).unwrap();
+        assert_eq!(
+            db.get_drift_event(&id).unwrap().unwrap().introduced_by,
+            Some(commit)
+        );
+    }
+
+    #[test]
+    fn test_refined_drift_event_matches_redetected_event() {
+        let db = Database::open_in_memory().unwrap();
+
+        let detected = DriftEvent::new(DriftSeverity::High, "Signature changed: foo", "a", 0.8)
+            .with_code_chunk("src/lib.rs::foo");
+        let mut refined = detected.clone();
+        refined.rewrite_description("foo() now takes a second argument".to_string());
+        let id = db.insert_drift_event(&refined).unwrap();
+
+        let stored = db.get_drift_event(&id).unwrap().unwrap();
+        assert_eq!(stored.description, "foo() now takes a second argument");
+        assert_eq!(stored.identity(), detected.identity());
+
+        let mut redetected = detected;
+        redetected.id = uuid::Uuid::new_v4().to_string();
+        assert_eq!(db.insert_drift_event(&redetected).unwrap(), id);
+        assert_eq!(db.get_all_drift_events().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_drift_event_tags_round_trip() {
+        let db = Database::open_in_memory().unwrap();
+
+        let event = DriftEvent::new(DriftSeverity::Medium, "Broken doc link", "a", 0.9)
+            .with_tag("broken_link")
+            .with_tag("broken_link");
+        let id = db.insert_drift_event(&event).unwrap();
+        let untagged = DriftEvent::new(DriftSeverity::Low, "Untagged", "b", 0.5);
+        db.insert_drift_event(&untagged).unwrap();
+
+        let stored = db.get_drift_event(&id).unwrap().unwrap();
+        assert_eq!(stored.tags, vec!["broken_link"]);
+
+        let tagged: Vec<DriftEvent> = db
+            .get_unresolved_drift_events()
+            .unwrap()
+            .into_iter()
+            .filter(|e| e.has_tag("broken_link"))
+            .collect();
+        assert_eq!(tagged.len(), 1);
+        assert_eq!(tagged[0].id, id);
+    }
+
+    #[test]
+    fn test_get_events_by_status() {
+        let db = Database::open_in_memory().unwrap();
+
+        let ids: Vec<String> = ["a", "b", "c"]
+            .iter()
+            .map(|name| {
+                let event = DriftEvent::new(
+                    DriftSeverity::Medium,
+                    &format!("Changed: {}", name),
+                    "",
+                    0.5,
+                );
+                db.insert_drift_event(&event).unwrap()
+            })
+            .collect();
+        db.update_drift_event_status(&ids[1], "Ignored").unwrap();
+        db.update_drift_event_status(&ids[2], "Fixed").unwrap();
+
+        assert_eq!(db.get_unresolved_drift_events().unwrap().len(), 1);
+        let resolved = db
+            .get_events_by_status(&[DriftStatus::Ignored, DriftStatus::Fixed])
+            .unwrap();
+        assert_eq!(resolved.len(), 2);
+        assert!(resolved.iter().all(|e| e.status != DriftStatus::Pending));
+        assert!(db.get_events_by_status(&[]).unwrap().is_empty());
+        assert_eq!(db.get_all_drift_events().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_doc_chunk_linked_symbols_round_trip() {
+        use crate::extract::{DocChunk, HeadingLevel};
+
+        let db = Database::open_in_memory().unwrap();
+        let chunk = DocChunk::new(
+            "README.md",
+            vec!["Usage".to_string()],
+            "Usage",
+            HeadingLevel::H2,
+            "<!-- docsentinel: src/lib.rs::foo -->\nCall foo().",
+            1,
+            2,
+        );
+        db.upsert_doc_chunk(&chunk).unwrap();
+
+        let stored = db.get_doc_chunk(&chunk.id).unwrap().unwrap();
+        assert_eq!(stored.linked_symbols, vec!["src/lib.rs::foo"]);
+    }
+
+    #[test]
+    fn test_event_and_code_chunk_pages() {
+        use crate::extract::{Language, SymbolType};
+
+        let db = Database::open_in_memory().unwrap();
+        for (i, severity) in [
+            DriftSeverity::Low,
+            DriftSeverity::Critical,
+            DriftSeverity::Medium,
+            DriftSeverity::High,
+            DriftSeverity::Medium,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let mut event = DriftEvent::new(severity, &format!("event {}", i), "", 0.5);
+            if i % 2 == 0 {
+                event = event.with_tag("even");
+            }
+            db.insert_drift_event(&event).unwrap();
+        }
+
+        let all = EventFilter::default();
+        let ordered = db.get_all_drift_events().unwrap();
+        let pages: Vec<DriftEvent> = [0, 2, 4]
+            .into_iter()
+            .flat_map(|offset| db.get_events_page(&all, offset, 2).unwrap())
+            .collect();
+        let ids = |events: &[DriftEvent]| events.iter().map(|e| e.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&pages), ids(&ordered));
+        assert_eq!(db.get_events_page(&all, 4, 10).unwrap().len(), 1);
+        assert!(db.get_events_page(&all, 5, 10).unwrap().is_empty());
+        assert!(db.get_events_page(&all, 50, 10).unwrap().is_empty());
+        assert_eq!(db.count_drift_events(&all).unwrap(), 5);
+
+        let even = EventFilter {
+            status: Some(DriftStatus::Pending),
+            tag: Some("even".to_string()),
+        };
+        assert_eq!(db.count_drift_events(&even).unwrap(), 3);
+        let counts = db.count_drift_events_by_severity(&even).unwrap();
+        assert_eq!(counts.get(&DriftSeverity::Medium), Some(&2));
+        assert_eq!(counts.get(&DriftSeverity::Critical), None);
+        assert_eq!(db.get_event_tags(None).unwrap(), vec!["even"]);
+        assert!(db
+            .get_event_tags(Some(DriftStatus::Fixed))
+            .unwrap()
+            .is_empty());
+
+        for (i, name) in ["alpha", "Beta", "gamma", "Ärger"].into_iter().enumerate() {
+            let mut chunk = CodeChunk::new(
+                "src/lib.rs",
+                name,
+                SymbolType::Function,
+                &format!("fn {}() {{}}", name),
+                Language::Rust,
+                i + 1,
+                i + 1,
+            );
+            chunk.is_public = name != "gamma" && name != "Ärger";
+            db.upsert_code_chunk(&chunk).unwrap();
+        }
+        let public = CodeChunkFilter {
+            public_only: true,
+            search: String::new(),
+        };
+        assert_eq!(db.count_code_chunks(&public).unwrap(), 2);
+        let names: Vec<String> = db
+            .get_code_chunks_page(&public, 1, 10)
+            .unwrap()
+            .into_iter()
+            .map(|c| c.symbol_name)
+            .collect();
+        assert_eq!(names, vec!["Beta"]);
+        assert!(db.get_code_chunks_page(&public, 2, 10).unwrap().is_empty());
+
+        let search = CodeChunkFilter {
+            public_only: false,
+            search: "BET".to_string(),
+        };
+        assert_eq!(db.count_code_chunks(&search).unwrap(), 1);
+
+        // Only ASCII case is ignored, the same as SQLite's lower()
+        let search = |text: &str| CodeChunkFilter {
+            public_only: false,
+            search: text.to_string(),
+        };
+        assert_eq!(db.count_code_chunks(&search("ÄRGER")).unwrap(), 1);
+        assert_eq!(db.count_code_chunks(&search("ärger")).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_get_doc_chunks_referencing_symbol() {
+        use crate::extract::{DocChunk, HeadingLevel};
+
+        let db = Database::open_in_memory().unwrap();
+        let doc = |heading: &str, content: &str, line: usize| {
+            DocChunk::new(
+                "README.md",
+                vec![heading.to_string()],
+                heading,
+                HeadingLevel::H2,
+                content,
+                line,
+                line + 1,
+            )
+        };
+        db.upsert_doc_chunks(&[
+            doc("Parsing", "Call `parse_config` to load it.", 10),
+            doc("Loading", "The loader wraps parse_config().", 1),
+            doc("Other", "Nothing relevant; parseconfig is not a match.", 20),
+        ])
+        .unwrap();
+
+        let found = db
+            .get_doc_chunks_referencing_symbol("parse_config")
+            .unwrap();
+        let headings: Vec<_> = found.iter().map(|c| c.heading.as_str()).collect();
+        assert_eq!(headings, vec!["Loading", "Parsing"]);
+
+        assert!(db
+            .get_doc_chunks_referencing_symbol("missing")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_doc_chunk_frontmatter_round_trip() {
+        use crate::extract::{DocChunk, HeadingLevel};
+
+        let db = Database::open_in_memory().unwrap();
+        let mut chunk = DocChunk::new(
+            "docs/start.md",
+            vec!["Setup".to_string()],
+            "Setup",
+            HeadingLevel::H1,
+            "# Setup\nInstall the package.",
+            4,
+            5,
+        );
+        chunk
+            .frontmatter
+            .insert("title".to_string(), "Getting Started".to_string());
+        db.upsert_doc_chunk(&chunk).unwrap();
+
+        let stored = db.get_doc_chunk(&chunk.id).unwrap().unwrap();
+        assert_eq!(stored.frontmatter, chunk.frontmatter);
+    }
+
+    #[test]
+    fn test_clean_operations() {
+        use crate::extract::{CodeChunk, DocChunk, HeadingLevel, Language, SymbolType};
+
+        let db = Database::open_in_memory().unwrap();
+        db.set_last_scan_commit("abc123").unwrap();
+
+        let code = CodeChunk::new(
+            "src/lib.rs",
+            "foo",
+            SymbolType::Function,
+            "pub fn foo() {}",
+            Language::Rust,
+            1,
+            1,
+        );
+        db.upsert_code_chunk(&code).unwrap();
+        let doc = DocChunk::new(
+            "README.md",
+            vec!["Usage".to_string()],
+            "Usage",
+            HeadingLevel::H2,
+            "Call foo().",
+            1,
+            2,
+        );
+        db.upsert_doc_chunk(&doc).unwrap();
+
+        let pending = DriftEvent::new(DriftSeverity::High, "Pending", "a", 0.8);
+        let resolved = DriftEvent::new(DriftSeverity::Low, "Resolved", "b", 0.5);
+        db.insert_drift_event(&pending).unwrap();
+        let resolved_id = db.insert_drift_event(&resolved).unwrap();
+        db.update_drift_event_status(&resolved_id, "Fixed").unwrap();
+
+        // Resolved events only, then everything
+        assert_eq!(db.clear_drift_events(false).unwrap(), 1);
+        assert_eq!(db.get_unresolved_drift_events().unwrap().len(), 1);
+        assert_eq!(db.clear_drift_events(true).unwrap(), 1);
+        assert_eq!(db.get_stats().unwrap().drift_events, 0);
+
+        assert_eq!(db.clear_chunks().unwrap(), (1, 1));
+        let stats = db.get_stats().unwrap();
+        assert_eq!((stats.code_chunks, stats.doc_chunks), (0, 0));
+
+        assert_eq!(db.reset_scan_state().unwrap(), 1);
+        assert!(db.get_last_scan_commit().unwrap().is_none());
+
+        db.vacuum().unwrap();
+    }
+
+    #[test]
+    fn test_prune_chunk_history_keeps_recent_rows() {
+        let db = Database::open_in_memory().unwrap();
+        for (chunk_id, age) in [
+            ("src/lib.rs::old", "-120 days"),
+            ("src/lib.rs::new", "-1 days"),
+        ] {
+            db.conn
+                .execute(
+                    "INSERT INTO chunk_history (chunk_id, chunk_type, content, hash, recorded_at)
+                     VALUES (?1, 'code', '', '', datetime('now', ?2))",
+                    params![chunk_id, age],
+                )
+                .unwrap();
+        }
+
+        assert_eq!(db.prune_chunk_history(90).unwrap(), 1);
+        let remaining: Vec<String> = db
+            .conn
+            .prepare("SELECT chunk_id FROM chunk_history")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<std::result::Result<_, _>>()
+            .unwrap();
+        assert_eq!(remaining, ["src/lib.rs::new"]);
+        assert!(db.get_stats().unwrap().size_bytes > 0);
+    }
+
+    /// Store one code chunk and one doc chunk with a relationship between them
+    fn related_chunks(db: &Database) -> (CodeChunk, DocChunk) {
+        use crate::extract::{HeadingLevel, Language, SymbolType};
+
+        let code = CodeChunk::new(
+            "src/lib.rs",
+            "foo",
+            SymbolType::Function,
+            "pub fn foo() {}",
+            Language::Rust,
+            1,
+            1,
+        );
+        db.upsert_code_chunk(&code).unwrap();
+        let doc = DocChunk::new(
+            "README.md",
+            vec!["Usage".to_string()],
+            "Usage",
+            HeadingLevel::H2,
+            "Call foo().",
+            1,
+            2,
+        );
+        db.upsert_doc_chunk(&doc).unwrap();
+        db.conn
+            .execute(
+                "INSERT INTO chunk_relationships
+                     (code_chunk_id, doc_chunk_id, similarity, relationship_type, created_at)
+                 VALUES (?1, ?2, 0.9, 'similar', datetime('now'))",
+                params![code.id, doc.id],
+            )
+            .unwrap();
+        (code, doc)
+    }
+
+    fn relationship_count(db: &Database) -> i64 {
+        db.conn
+            .query_row("SELECT COUNT(*) FROM chunk_relationships", [], |row| {
+                row.get(0)
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_deleting_chunk_cascades_to_relationships() {
+        let db = Database::open_in_memory().unwrap();
+        let (code, _) = related_chunks(&db);
+        assert_eq!(relationship_count(&db), 1);
+
+        // Upserting an existing chunk updates it in place
+        db.upsert_code_chunk(&code).unwrap();
+        assert_eq!(relationship_count(&db), 1);
+
+        assert_eq!(db.delete_code_chunks_for_file("src/lib.rs").unwrap(), 1);
+        assert_eq!(relationship_count(&db), 0);
+    }
+
+    #[test]
+    fn test_rename_file_moves_chunks_and_references() {
+        let db = Database::open_in_memory().unwrap();
+        let (mut code, doc) = related_chunks(&db);
+        code.embedding = Some(vec![0.25, 0.75]);
+        db.upsert_code_chunk(&code).unwrap();
+        let event = DriftEvent::new(DriftSeverity::High, "Stale", "a", 0.8)
+            .with_code_chunk(&code.id)
+            .with_doc_chunk(&doc.id)
+            .with_location("src/lib.rs", 1);
+        let event_id = db.insert_drift_event(&event).unwrap();
+
+        assert_eq!(db.rename_file("src/lib.rs", "src/core.rs").unwrap(), 1);
+
+        assert!(db.get_code_chunk(&code.id).unwrap().is_none());
+        let moved = db.get_code_chunk("src/core.rs::foo").unwrap().unwrap();
+        assert_eq!(moved.file_path, "src/core.rs");
+        assert_eq!(moved.embedding, Some(vec![0.25, 0.75]));
+        assert_eq!(relationship_count(&db), 1);
+        let related: String = db
+            .conn
+            .query_row("SELECT code_chunk_id FROM chunk_relationships", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(related, "src/core.rs::foo");
+
+        let stored = db.get_drift_event(&event_id).unwrap().unwrap();
+        assert_eq!(stored.related_code_chunks, vec!["src/core.rs::foo"]);
+        assert_eq!(stored.related_doc_chunks, vec![doc.id]);
+        assert_eq!(stored.primary_file.as_deref(), Some("src/core.rs"));
+    }
+
+    #[test]
+    fn test_migration_rekeys_doc_chunks_by_heading_anchor() {
+        use crate::extract::HeadingLevel;
+
+        let db = Database::open_in_memory().unwrap();
+        let (code, _) = related_chunks(&db);
+        db.delete_doc_chunks_for_file("README.md").unwrap();
+
+        // Chunks as older versions keyed them, by heading path
+        let mut usage = DocChunk::new(
+            "README.md",
+            vec!["Usage".to_string()],
+            "Usage",
+            HeadingLevel::H2,
+            "Call foo().",
+            1,
+            2,
+        );
+        usage.id = "README.md#Usage".to_string();
+        usage.embedding = Some(vec![0.5, 0.5]);
+        let mut options = DocChunk::new(
+            "README.md",
+            vec!["Usage".to_string(), "Options & Flags".to_string()],
+            "Options & Flags",
+            HeadingLevel::H3,
+            "Pass --verbose.",
+            3,
+            4,
+        );
+        options.id = "README.md#Usage > Options & Flags".to_string();
+        db.upsert_doc_chunks(&[usage.clone(), options]).unwrap();
+        db.conn
+            .execute(
+                "INSERT INTO chunk_relationships
+                     (code_chunk_id, doc_chunk_id, similarity, relationship_type, created_at)
+                 VALUES (?1, ?2, 0.9, 'similar', datetime('now'))",
+                params![code.id, usage.id],
+            )
+            .unwrap();
+        let event = DriftEvent::new(DriftSeverity::High, "Stale", "a", 0.8)
+            .with_code_chunk(&code.id)
+            .with_doc_chunk(&usage.id);
+        let event_id = db.insert_drift_event(&event).unwrap();
+
+        db.conn.execute_batch("PRAGMA user_version = 0").unwrap();
+        db.migrate().unwrap();
+
+        assert!(db.get_doc_chunk("README.md#Usage").unwrap().is_none());
+        let rekeyed = db.get_doc_chunk("README.md#usage").unwrap().unwrap();
+        assert_eq!(rekeyed.heading_path, vec!["Usage"]);
+        assert_eq!(rekeyed.embedding, Some(vec![0.5, 0.5]));
+        assert!(db
+            .get_doc_chunk("README.md#options--flags")
+            .unwrap()
+            .is_some());
+
+        assert_eq!(relationship_count(&db), 1);
+        let related: String = db
+            .conn
+            .query_row("SELECT doc_chunk_id FROM chunk_relationships", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(related, "README.md#usage");
+        let stored = db.get_drift_event(&event_id).unwrap().unwrap();
+        assert_eq!(stored.related_doc_chunks, vec!["README.md#usage"]);
+
+        // The migration runs once
+        let version: i64 = db
+            .conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, DATA_VERSION);
+    }
+
+    #[test]
+    fn test_concurrent_connections_write_without_locking_errors() {
+        use crate::extract::{Language, SymbolType};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("docsentinel.db");
+        Database::open(&path).unwrap();
+
+        let writers: Vec<_> = (0..2)
+            .map(|writer| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    let db = Database::open(&path).unwrap();
+                    for i in 0..50 {
+                        let chunk = CodeChunk::new(
+                            &format!("src/w{}.rs", writer),
+                            &format!("f{}", i),
+                            SymbolType::Function,
+                            "pub fn f() {}",
+                            Language::Rust,
+                            1,
+                            1,
+                        );
+                        // Alternate single writes with transactions
+                        if i % 2 == 0 {
+                            db.upsert_code_chunk(&chunk).unwrap();
+                        } else {
+                            db.upsert_code_chunks(std::slice::from_ref(&chunk)).unwrap();
+                        }
+                        let event = DriftEvent::new(
+                            DriftSeverity::Low,
+                            &format!("event {} {}", writer, i),
+                            "e",
+                            0.5,
+                        );
+                        db.insert_drift_event(&event).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        let db = Database::open(&path).unwrap();
+        let stats = db.get_stats().unwrap();
+        assert_eq!(stats.code_chunks, 100);
+        assert_eq!(stats.drift_events, 100);
+        let mode: String = db
+            .conn
+            .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(mode, "wal");
+    }
+
+    #[test]
+    fn test_orphan_relationships_are_pruned_on_open() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("docsentinel.db");
+
+        // Delete a chunk the way older versions did, without foreign keys
+        let db = Database::open(&path).unwrap();
+        related_chunks(&db);
+        db.conn.execute_batch("PRAGMA foreign_keys = OFF").unwrap();
+        db.delete_doc_chunks_for_file("README.md").unwrap();
+        assert_eq!(relationship_count(&db), 1);
+        drop(db);
+
+        let db = Database::open(&path).unwrap();
+        assert_eq!(relationship_count(&db), 0);
+        assert_eq!(db.prune_orphan_relationships().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_drift_aggregates() {
+        use crate::extract::{CodeChunk, DocChunk, HeadingLevel, Language, SymbolType};
+
+        let db = Database::open_in_memory().unwrap();
+
+        let code = CodeChunk::new(
+            "src/lib.rs",
+            "foo",
+            SymbolType::Function,
+            "pub fn foo() {}",
+            Language::Rust,
+            1,
+            1,
+        );
+        db.upsert_code_chunk(&code).unwrap();
+        let doc = DocChunk::new(
+            "README.md",
+            vec!["Usage".to_string()],
+            "Usage",
+            HeadingLevel::H2,
+            "## Usage",
+            1,
+            1,
+        );
+        db.upsert_doc_chunk(&doc).unwrap();
+
+        let both = DriftEvent::new(DriftSeverity::High, "both", "e", 0.9)
+            .with_code_chunk(&code.id)
+            .with_doc_chunk(&doc.id);
+        let code_only =
+            DriftEvent::new(DriftSeverity::Low, "code only", "e", 0.5).with_code_chunk(&code.id);
+        let resolved =
+            DriftEvent::new(DriftSeverity::Critical, "resolved", "e", 1.0).with_doc_chunk(&doc.id);
+        for event in [&both, &code_only, &resolved] {
+            db.insert_drift_event(event).unwrap();
+        }
+        db.update_drift_event_status(&resolved.id, "Fixed").unwrap();
+
+        assert_eq!(
+            db.get_drift_by_file().unwrap(),
+            vec![("src/lib.rs".to_string(), 2), ("README.md".to_string(), 1)]
+        );
+
+        let histogram = db.get_severity_histogram().unwrap();
+        assert_eq!(histogram.get(&DriftSeverity::High), Some(&1));
+        assert_eq!(histogram.get(&DriftSeverity::Low), Some(&1));
+        assert_eq!(histogram.get(&DriftSeverity::Critical), None);
+    }
+
+    #[test]
+    fn test_code_chunk_decorators_round_trip() {
+        use crate::extract::{CodeChunk, Language, SymbolType};
+
+        let db = Database::open_in_memory().unwrap();
+        let mut chunk = CodeChunk::new(
+            "account.py",
+            "Account.balance",
+            SymbolType::Method,
+            "@property\ndef balance(self): ...",
+            Language::Python,
+            1,
+            2,
+        );
+        chunk.decorators = vec!["property".to_string()];
+        db.upsert_code_chunk(&chunk).unwrap();
+
+        let stored = db.get_code_chunk(&chunk.id).unwrap().unwrap();
+        assert_eq!(stored.decorators, vec!["property"]);
+    }
+
+    #[test]
+    fn test_batch_upsert_is_atomic() {
+        use crate::extract::{CodeChunk, Language, SymbolType};
+
+        let db = Database::open_in_memory().unwrap();
+        db.conn
+            .execute_batch(
+                "CREATE TRIGGER reject_bad BEFORE INSERT ON code_chunks
+                 WHEN NEW.symbol_name = 'bad'
+                 BEGIN SELECT RAISE(ABORT, 'rejected'); END;",
+            )
+            .unwrap();
+        let chunk = |name: &str| {
+            CodeChunk::new(
+                "src/lib.rs",
+                name,
+                SymbolType::Function,
+                &format!("fn {}() {{}}", name),
+                Language::Rust,
+                1,
+                1,
+            )
+        };
+
+        let batch = vec![chunk("first"), chunk("bad"), chunk("last")];
+        assert!(db.upsert_code_chunks(&batch).is_err());
+        assert!(db.get_all_code_chunks().unwrap().is_empty());
+
+        db.upsert_code_chunks(&[chunk("first"), chunk("last")])
+            .unwrap();
+        assert_eq!(db.get_all_code_chunks().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_embedding_model_in_use() {
+        use crate::extract::{CodeChunk, Language, SymbolType};
+
+        let db = Database::open_in_memory()
+            .unwrap()
+            .with_embedding_model("minilm");
+        assert_eq!(db.embedding_model_in_use().unwrap(), None);
+
+        let mut chunk = CodeChunk::new(
+            "src/lib.rs",
+            "add",
+            SymbolType::Function,
+            "fn add() {}",
+            Language::Rust,
+            1,
+            1,
+        );
+        chunk.embedding = Some(vec![0.5, 0.25, 1.0]);
+        db.upsert_code_chunk(&chunk).unwrap();
+        assert_eq!(
+            db.embedding_model_in_use().unwrap(),
+            Some(("minilm".to_string(), 3))
+        );
+        assert_eq!(
+            db.get_code_chunk(&chunk.id).unwrap().unwrap().embedding,
+            Some(vec![0.5, 0.25, 1.0])
+        );
+
+        // A vector whose length disagrees with its stored dimension is dropped
+        db.conn
+            .execute("UPDATE code_chunks SET embedding_dim = 4", [])
+            .unwrap();
+        assert_eq!(
+            db.get_code_chunk(&chunk.id).unwrap().unwrap().embedding,
+            None
+        );
+
+        assert_eq!(db.clear_embeddings().unwrap(), 1);
+        assert_eq!(db.embedding_model_in_use().unwrap(), None);
+    }
+
     #[test]
     fn test_scan_state() {
         let db = Database::open_in_memory().unwrap();
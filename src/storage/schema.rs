@@ -24,7 +24,11 @@ CREATE TABLE IF NOT EXISTS code_chunks (
     signature TEXT,
     is_public INTEGER NOT NULL DEFAULT 0,
     embedding BLOB,
-    updated_at TEXT NOT NULL
+    updated_at TEXT NOT NULL,
+    decorators TEXT NOT NULL DEFAULT '[]',
+    embedding_model TEXT,
+    embedding_dim INTEGER,
+    attributes TEXT NOT NULL DEFAULT '[]'
 );
 
 CREATE INDEX IF NOT EXISTS idx_code_chunks_file ON code_chunks(file_path);
@@ -43,7 +47,12 @@ CREATE TABLE IF NOT EXISTS doc_chunks (
     start_line INTEGER NOT NULL,
     end_line INTEGER NOT NULL,
     embedding BLOB,
-    updated_at TEXT NOT NULL
+    updated_at TEXT NOT NULL,
+    linked_symbols TEXT NOT NULL DEFAULT '[]',
+    embedding_model TEXT,
+    embedding_dim INTEGER,
+    links TEXT NOT NULL DEFAULT '[]',
+    frontmatter TEXT NOT NULL DEFAULT '{}'
 );
 
 CREATE INDEX IF NOT EXISTS idx_doc_chunks_file ON doc_chunks(file_path);
@@ -62,7 +71,13 @@ CREATE TABLE IF NOT EXISTS drift_events (
     suggested_fix TEXT,
     status TEXT NOT NULL DEFAULT 'Pending',
     detected_at TEXT NOT NULL,
-    resolved_at TEXT
+    resolved_at TEXT,
+    identity TEXT,
+    primary_file TEXT,
+    primary_line INTEGER,
+    tags TEXT NOT NULL DEFAULT '[]',
+    introduced_by TEXT,
+    detected_description TEXT
 );
 
 CREATE INDEX IF NOT EXISTS idx_drift_events_status ON drift_events(status);
@@ -106,3 +121,119 @@ CREATE TABLE IF NOT EXISTS config (
     updated_at TEXT NOT NULL
 );
 "#;
+
+/// Column migrations for databases created before the current schema
+///
+/// Each entry is `(table, column, sql)`; `sql` runs only when the column is missing.
+pub const MIGRATIONS: &[(&str, &str, &str)] = &[
+    (
+        "drift_events",
+        "identity",
+        r#"
+        ALTER TABLE drift_events ADD COLUMN identity TEXT;
+        "#,
+    ),
+    (
+        "code_chunks",
+        "decorators",
+        r#"
+        ALTER TABLE code_chunks ADD COLUMN decorators TEXT NOT NULL DEFAULT '[]';
+        "#,
+    ),
+    (
+        "drift_events",
+        "primary_file",
+        r#"
+        ALTER TABLE drift_events ADD COLUMN primary_file TEXT;
+        "#,
+    ),
+    (
+        "drift_events",
+        "primary_line",
+        r#"
+        ALTER TABLE drift_events ADD COLUMN primary_line INTEGER;
+        "#,
+    ),
+    (
+        "doc_chunks",
+        "linked_symbols",
+        r#"
+        ALTER TABLE doc_chunks ADD COLUMN linked_symbols TEXT NOT NULL DEFAULT '[]';
+        "#,
+    ),
+    (
+        "code_chunks",
+        "embedding_model",
+        r#"
+        ALTER TABLE code_chunks ADD COLUMN embedding_model TEXT;
+        "#,
+    ),
+    (
+        "code_chunks",
+        "embedding_dim",
+        r#"
+        ALTER TABLE code_chunks ADD COLUMN embedding_dim INTEGER;
+        "#,
+    ),
+    (
+        "doc_chunks",
+        "embedding_model",
+        r#"
+        ALTER TABLE doc_chunks ADD COLUMN embedding_model TEXT;
+        "#,
+    ),
+    (
+        "doc_chunks",
+        "embedding_dim",
+        r#"
+        ALTER TABLE doc_chunks ADD COLUMN embedding_dim INTEGER;
+        "#,
+    ),
+    (
+        "code_chunks",
+        "attributes",
+        r#"
+        ALTER TABLE code_chunks ADD COLUMN attributes TEXT NOT NULL DEFAULT '[]';
+        "#,
+    ),
+    (
+        "doc_chunks",
+        "links",
+        r#"
+        ALTER TABLE doc_chunks ADD COLUMN links TEXT NOT NULL DEFAULT '[]';
+        "#,
+    ),
+    (
+        "drift_events",
+        "tags",
+        r#"
+        ALTER TABLE drift_events ADD COLUMN tags TEXT NOT NULL DEFAULT '[]';
+        "#,
+    ),
+    (
+        "doc_chunks",
+        "frontmatter",
+        r#"
+        ALTER TABLE doc_chunks ADD COLUMN frontmatter TEXT NOT NULL DEFAULT '{}';
+        "#,
+    ),
+    (
+        "drift_events",
+        "introduced_by",
+        r#"
+        ALTER TABLE drift_events ADD COLUMN introduced_by TEXT;
+        "#,
+    ),
+    (
+        "drift_events",
+        "detected_description",
+        r#"
+        ALTER TABLE drift_events ADD COLUMN detected_description TEXT;
+        "#,
+    ),
+];
+
+/// Indexes that depend on migrated columns
+pub const POST_MIGRATION_SCHEMA: &str = r#"
+CREATE UNIQUE INDEX IF NOT EXISTS idx_drift_events_identity ON drift_events(identity);
+"#;
@@ -1,10 +1,16 @@
 //! TUI application state and logic
 
-use crate::drift::{DriftEvent, DriftSeverity};
-use crate::repo::Repository;
-use crate::storage::Database;
+use crate::drift::{
+    DriftConfig, DriftDetector, DriftEvent, DriftSeverity, DriftStatus, ScanOptions,
+};
+use crate::error::DocSentinelError;
+use crate::repo::{ConfigOverrides, Repository};
+use crate::storage::{CodeChunkFilter, Database, EventFilter};
 use anyhow::Result;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Rect;
+use std::cell::Cell;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 /// Current view in the TUI
@@ -20,10 +26,32 @@ pub enum View {
     FixEditor,
     /// Documentation browser
     Docs,
+    /// Symbol detail with related documentation
+    DocDetail,
     /// Help screen
     Help,
 }
 
+impl std::str::FromStr for View {
+    type Err = DocSentinelError;
+
+    /// Parse a view a session can start in
+    ///
+    /// Detail views need a selected issue or symbol, so they are not accepted.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "dashboard" | "status" => Ok(View::Dashboard),
+            "issues" => Ok(View::Issues),
+            "docs" | "symbols" => Ok(View::Docs),
+            "help" => Ok(View::Help),
+            _ => Err(DocSentinelError::Config(format!(
+                "Unknown view: {} (expected dashboard, issues, docs, or help)",
+                s
+            ))),
+        }
+    }
+}
+
 /// Application state
 pub struct AppState {
     /// Current view
@@ -36,14 +64,26 @@ pub struct AppState {
     pub scroll_offset: usize,
     /// Input buffer for editing
     pub input_buffer: String,
+    /// Cursor position in the input buffer (byte offset)
+    pub cursor: usize,
     /// Whether in input mode
     pub input_mode: bool,
     /// Search query for docs
     pub search_query: String,
+    /// Status shown in the issues view (`None` shows every status)
+    pub status_filter: Option<DriftStatus>,
+    /// Tag the issues view is limited to (`None` shows every tag)
+    pub tag_filter: Option<String>,
     /// Status message
     pub status_message: Option<String>,
     /// Confirmation dialog
     pub confirm_dialog: Option<ConfirmDialog>,
+    /// Area of the issues list from the last draw, used for mouse hit-testing
+    pub issues_list_area: Cell<Rect>,
+    /// Index of the first issue shown in the issues list at the last draw
+    pub issues_list_offset: Cell<usize>,
+    /// Index of the first symbol shown in the docs browser at the last draw
+    pub docs_list_offset: Cell<usize>,
 }
 
 impl Default for AppState {
@@ -54,22 +94,103 @@ impl Default for AppState {
             selected_doc: 0,
             scroll_offset: 0,
             input_buffer: String::new(),
+            cursor: 0,
             input_mode: false,
             search_query: String::new(),
+            status_filter: Some(DriftStatus::Pending),
+            tag_filter: None,
             status_message: None,
             confirm_dialog: None,
+            issues_list_area: Cell::new(Rect::default()),
+            issues_list_offset: Cell::new(0),
+            docs_list_offset: Cell::new(0),
+        }
+    }
+}
+
+impl AppState {
+    /// Line and column (in characters) of the cursor in the input buffer
+    pub fn input_cursor(&self) -> (usize, usize) {
+        let before = &self.input_buffer[..self.cursor];
+        let line = before.matches('\n').count();
+        let column = before
+            .rsplit('\n')
+            .next()
+            .map(|l| l.chars().count())
+            .unwrap_or(0);
+        (line, column)
+    }
+
+    /// Insert a character at the cursor
+    fn insert_char(&mut self, c: char) {
+        self.input_buffer.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+    }
+
+    /// Delete the character before the cursor
+    fn delete_char_before(&mut self) {
+        if let Some(c) = self.input_buffer[..self.cursor].chars().next_back() {
+            self.cursor -= c.len_utf8();
+            self.input_buffer.remove(self.cursor);
+        }
+    }
+
+    /// Delete the character under the cursor
+    fn delete_char_at(&mut self) {
+        if self.cursor < self.input_buffer.len() {
+            self.input_buffer.remove(self.cursor);
+        }
+    }
+
+    /// Move the cursor one character left
+    fn cursor_left(&mut self) {
+        if let Some(c) = self.input_buffer[..self.cursor].chars().next_back() {
+            self.cursor -= c.len_utf8();
+        }
+    }
+
+    /// Move the cursor one character right
+    fn cursor_right(&mut self) {
+        if let Some(c) = self.input_buffer[self.cursor..].chars().next() {
+            self.cursor += c.len_utf8();
         }
     }
+
+    /// Move the cursor to the start of its line
+    fn cursor_line_start(&mut self) {
+        self.cursor = self.input_buffer[..self.cursor]
+            .rfind('\n')
+            .map(|i| i + 1)
+            .unwrap_or(0);
+    }
+
+    /// Move the cursor to the end of its line
+    fn cursor_line_end(&mut self) {
+        self.cursor = self.input_buffer[self.cursor..]
+            .find('\n')
+            .map(|i| self.cursor + i)
+            .unwrap_or(self.input_buffer.len());
+    }
 }
 
 /// Confirmation dialog
 pub struct ConfirmDialog {
     pub title: String,
     pub message: String,
+    /// Optional diff shown below the message
+    pub preview: Option<String>,
     #[allow(clippy::type_complexity)]
     pub on_confirm: Box<dyn FnOnce(&mut App) -> Result<()>>,
 }
 
+/// Selected symbol and the documentation related to it
+pub struct DocDetail {
+    /// The selected code symbol
+    pub chunk: crate::extract::CodeChunk,
+    /// Related doc chunks with their similarity score (if embeddings were used)
+    pub related: Vec<(crate::extract::DocChunk, Option<f64>)>,
+}
+
 /// Main TUI application
 pub struct App {
     /// Repository path
@@ -80,40 +201,73 @@ pub struct App {
     pub db: Database,
     /// Application state
     pub state: AppState,
-    /// Drift events
+    /// Loaded window of the drift events in the issues view
     pub events: Vec<DriftEvent>,
-    /// Code chunks for docs browser
+    /// Index of the first loaded event in the filtered event list
+    pub events_offset: usize,
+    /// Number of events matching the issues view filters
+    pub event_count: usize,
+    /// Events matching the issues view filters, by severity
+    pub severity_counts: HashMap<DriftSeverity, usize>,
+    /// Tags on the events with the filtered status, for cycling the tag filter
+    pub tags: Vec<String>,
+    /// Loaded window of the docs browser's symbols
     pub code_chunks: Vec<crate::extract::CodeChunk>,
+    /// Index of the first loaded symbol in the filtered symbol list
+    pub symbols_offset: usize,
+    /// Number of public symbols matching the docs browser search
+    pub symbol_count: usize,
+    /// Number of public symbols
+    pub public_symbol_count: usize,
     /// Database statistics
     pub stats: crate::storage::DatabaseStats,
+    /// Symbol shown in the doc detail view
+    pub doc_detail: Option<DocDetail>,
+    /// Command-line LLM and embedding overrides used by rescans
+    pub overrides: ConfigOverrides,
 }
 
 impl App {
     /// Create a new app instance
-    pub fn new(path: &Path) -> Result<Self> {
-        let repo = Repository::open(path)?;
+    pub fn new(path: &Path, scope: Option<&Path>) -> Result<Self> {
+        let repo = Repository::open_scoped(path, scope)?;
         let sentinel_dir = repo.sentinel_dir();
 
         if !sentinel_dir.exists() {
-            anyhow::bail!("DocSentinel not initialized. Run 'docsentinel init' first.");
+            return Err(DocSentinelError::NotInitialized.into());
         }
 
         let db_path = sentinel_dir.join("docsentinel.db");
         let db = Database::open(&db_path)?;
 
-        let events = db.get_unresolved_drift_events()?;
-        let code_chunks = db.get_all_code_chunks().unwrap_or_default();
         let stats = db.get_stats()?;
 
-        Ok(Self {
+        let mut app = Self {
             repo_path: path.to_path_buf(),
             repo,
             db,
             state: AppState::default(),
-            events,
-            code_chunks,
+            events: Vec::new(),
+            events_offset: 0,
+            event_count: 0,
+            severity_counts: HashMap::new(),
+            tags: Vec::new(),
+            code_chunks: Vec::new(),
+            symbols_offset: 0,
+            symbol_count: 0,
+            public_symbol_count: 0,
             stats,
-        })
+            doc_detail: None,
+            overrides: ConfigOverrides::default(),
+        };
+        app.refresh()?;
+        Ok(app)
+    }
+
+    /// Use command-line LLM and embedding overrides for rescans
+    pub fn with_overrides(mut self, overrides: ConfigOverrides) -> Self {
+        self.overrides = overrides;
+        self
     }
 
     /// Handle a key event
@@ -125,18 +279,64 @@ impl App {
 
         // Handle input mode
         if self.state.input_mode {
+            if self.state.view == View::FixEditor {
+                return self.handle_fix_input_key(key);
+            }
             return self.handle_input_key(key);
         }
 
         // Handle view-specific keys
-        match self.state.view {
+        let result = match self.state.view {
             View::Dashboard => self.handle_dashboard_key(key),
             View::Issues => self.handle_issues_key(key),
             View::IssueDetail => self.handle_detail_key(key),
             View::FixEditor => self.handle_editor_key(key),
             View::Docs => self.handle_docs_key(key),
+            View::DocDetail => self.handle_doc_detail_key(key),
             View::Help => self.handle_help_key(key),
+        };
+
+        // Keep the selected rows loaded after navigation
+        self.load_event_window()?;
+        self.load_symbol_window()?;
+        result
+    }
+
+    /// Handle a mouse event
+    pub fn handle_mouse(&mut self, mouse: MouseEvent) -> Result<()> {
+        if self.state.input_mode || self.state.confirm_dialog.is_some() {
+            return Ok(());
+        }
+
+        match (self.state.view, mouse.kind) {
+            (View::Issues, MouseEventKind::ScrollUp) => {
+                self.state.selected_issue = self.state.selected_issue.saturating_sub(1);
+            }
+            (View::Issues, MouseEventKind::ScrollDown)
+                if self.state.selected_issue < self.event_count.saturating_sub(1) =>
+            {
+                self.state.selected_issue += 1;
+                self.load_event_window()?;
+            }
+            (View::Issues, MouseEventKind::Down(MouseButton::Left)) => {
+                let area = self.state.issues_list_area.get();
+                if let Some(row) = list_index_at(area, mouse.column, mouse.row) {
+                    let index = self.state.issues_list_offset.get() + row;
+                    if index < self.event_count {
+                        self.state.selected_issue = index;
+                        self.load_event_window()?;
+                    }
+                }
+            }
+            (View::IssueDetail | View::DocDetail, MouseEventKind::ScrollUp) => {
+                self.state.scroll_offset = self.state.scroll_offset.saturating_sub(1);
+            }
+            (View::IssueDetail | View::DocDetail, MouseEventKind::ScrollDown) => {
+                self.state.scroll_offset += 1;
+            }
+            _ => {}
         }
+        Ok(())
     }
 
     /// Handle keys in dashboard view
@@ -153,6 +353,10 @@ impl App {
             KeyCode::Char('s') => {
                 self.run_scan()?;
             }
+            KeyCode::Char('r') => {
+                self.refresh()?;
+                self.state.status_message = Some("Refreshed".to_string());
+            }
             KeyCode::Char('?') | KeyCode::F(1) => {
                 self.state.view = View::Help;
             }
@@ -167,29 +371,40 @@ impl App {
             KeyCode::Char('q') | KeyCode::Esc => {
                 self.state.view = View::Dashboard;
             }
-            KeyCode::Up | KeyCode::Char('k') => {
-                if self.state.selected_issue > 0 {
-                    self.state.selected_issue -= 1;
-                }
+            KeyCode::Up | KeyCode::Char('k') if self.state.selected_issue > 0 => {
+                self.state.selected_issue -= 1;
             }
-            KeyCode::Down | KeyCode::Char('j') => {
-                if self.state.selected_issue < self.events.len().saturating_sub(1) {
-                    self.state.selected_issue += 1;
-                }
+            KeyCode::Down | KeyCode::Char('j')
+                if self.state.selected_issue < self.event_count.saturating_sub(1) =>
+            {
+                self.state.selected_issue += 1;
             }
-            KeyCode::Enter => {
-                if !self.events.is_empty() {
-                    self.state.view = View::IssueDetail;
-                }
+            KeyCode::Enter if self.event_count > 0 => {
+                self.state.view = View::IssueDetail;
             }
-            KeyCode::Char('f') => {
-                if !self.events.is_empty() {
-                    self.state.view = View::FixEditor;
-                }
+            KeyCode::Char('f') if self.event_count > 0 => {
+                self.open_fix_editor();
             }
             KeyCode::Char('x') => {
                 self.ignore_selected()?;
             }
+            KeyCode::Char('v') => {
+                self.state.status_filter = next_status_filter(self.state.status_filter);
+                self.state.selected_issue = 0;
+                self.refresh_events()?;
+                self.state.status_message = Some(format!(
+                    "Showing {} issues",
+                    status_filter_label(self.state.status_filter).to_lowercase()
+                ));
+            }
+            KeyCode::Char('t') => {
+                let tag = next_tag_filter(self.state.tag_filter.as_deref(), &self.tags);
+                self.filter_by_tag(tag)?;
+                self.state.status_message = Some(match &self.state.tag_filter {
+                    Some(tag) => format!("Showing issues tagged {}", tag),
+                    None => "Showing issues with any tag".to_string(),
+                });
+            }
             KeyCode::Char('?') | KeyCode::F(1) => {
                 self.state.view = View::Help;
             }
@@ -205,16 +420,14 @@ impl App {
                 self.state.view = View::Issues;
             }
             KeyCode::Char('f') => {
-                self.state.view = View::FixEditor;
+                self.open_fix_editor();
             }
             KeyCode::Char('x') => {
                 self.ignore_selected()?;
                 self.state.view = View::Issues;
             }
-            KeyCode::Up | KeyCode::Char('k') => {
-                if self.state.scroll_offset > 0 {
-                    self.state.scroll_offset -= 1;
-                }
+            KeyCode::Up | KeyCode::Char('k') if self.state.scroll_offset > 0 => {
+                self.state.scroll_offset -= 1;
             }
             KeyCode::Down | KeyCode::Char('j') => {
                 self.state.scroll_offset += 1;
@@ -233,12 +446,16 @@ impl App {
             }
             KeyCode::Char('e') if !self.state.input_mode => {
                 self.state.input_mode = true;
-                if let Some(event) = self.events.get(self.state.selected_issue) {
-                    self.state.input_buffer = event.suggested_fix.clone().unwrap_or_default();
+                // Start from the suggested fix unless there are edits already
+                if self.state.input_buffer.is_empty() {
+                    if let Some(event) = self.selected_event() {
+                        self.state.input_buffer = event.suggested_fix.clone().unwrap_or_default();
+                    }
                 }
+                self.state.cursor = self.state.input_buffer.len();
             }
             KeyCode::Char('a') if !self.state.input_mode => {
-                self.apply_fix()?;
+                self.confirm_apply_fix()?;
             }
             _ => {}
         }
@@ -258,20 +475,7 @@ impl App {
 
     /// Handle keys in docs browser view
     fn handle_docs_key(&mut self, key: KeyEvent) -> Result<bool> {
-        // Filter chunks based on search query
-        let filtered_chunks: Vec<_> = if self.state.search_query.is_empty() {
-            self.code_chunks.iter().filter(|c| c.is_public).collect()
-        } else {
-            let query = self.state.search_query.to_lowercase();
-            self.code_chunks
-                .iter()
-                .filter(|c| c.is_public)
-                .filter(|c| {
-                    c.symbol_name.to_lowercase().contains(&query)
-                        || c.file_path.to_lowercase().contains(&query)
-                })
-                .collect()
-        };
+        let filtered_len = self.symbol_count;
 
         match key.code {
             KeyCode::Char('q') | KeyCode::Esc => {
@@ -280,17 +484,19 @@ impl App {
                 } else {
                     self.state.view = View::Dashboard;
                     self.state.search_query.clear();
+                    self.refresh_symbols()?;
                 }
             }
-            KeyCode::Up | KeyCode::Char('k') if !self.state.input_mode => {
-                if self.state.selected_doc > 0 {
-                    self.state.selected_doc -= 1;
-                }
+            KeyCode::Up | KeyCode::Char('k')
+                if !self.state.input_mode && self.state.selected_doc > 0 =>
+            {
+                self.state.selected_doc -= 1;
             }
-            KeyCode::Down | KeyCode::Char('j') if !self.state.input_mode => {
-                if self.state.selected_doc < filtered_chunks.len().saturating_sub(1) {
-                    self.state.selected_doc += 1;
-                }
+            KeyCode::Down | KeyCode::Char('j')
+                if !self.state.input_mode
+                    && self.state.selected_doc < filtered_len.saturating_sub(1) =>
+            {
+                self.state.selected_doc += 1;
             }
             KeyCode::Char('/') if !self.state.input_mode => {
                 self.state.input_mode = true;
@@ -299,23 +505,80 @@ impl App {
                 self.state.input_mode = false;
                 self.state.selected_doc = 0;
             }
+            KeyCode::Enter if filtered_len > 0 => {
+                self.open_doc_detail()?;
+            }
             KeyCode::Backspace if self.state.input_mode => {
                 self.state.search_query.pop();
+                self.refresh_symbols()?;
             }
             KeyCode::Char(c) if self.state.input_mode => {
                 self.state.search_query.push(c);
+                self.refresh_symbols()?;
             }
             KeyCode::Char('g') if !self.state.input_mode => {
                 self.state.selected_doc = 0;
             }
             KeyCode::Char('G') if !self.state.input_mode => {
-                self.state.selected_doc = filtered_chunks.len().saturating_sub(1);
+                self.state.selected_doc = filtered_len.saturating_sub(1);
+            }
+            _ => {}
+        }
+        Ok(false)
+    }
+
+    /// Handle keys in the symbol detail view
+    fn handle_doc_detail_key(&mut self, key: KeyEvent) -> Result<bool> {
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc | KeyCode::Enter => {
+                self.state.view = View::Docs;
+                self.state.scroll_offset = 0;
+                self.doc_detail = None;
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.state.scroll_offset = self.state.scroll_offset.saturating_sub(1);
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.state.scroll_offset += 1;
             }
             _ => {}
         }
         Ok(false)
     }
 
+    /// Loaded window of the public symbols shown in the docs browser,
+    /// filtered by the search query
+    ///
+    /// The first entry is symbol number `symbols_offset` of `symbol_count`.
+    pub fn filtered_doc_symbols(&self) -> Vec<&crate::extract::CodeChunk> {
+        self.code_chunks.iter().collect()
+    }
+
+    /// The symbol selected in the docs browser, if it is loaded
+    pub fn selected_symbol(&self) -> Option<&crate::extract::CodeChunk> {
+        self.state
+            .selected_doc
+            .checked_sub(self.symbols_offset)
+            .and_then(|index| self.code_chunks.get(index))
+    }
+
+    /// Open the detail view for the selected symbol
+    fn open_doc_detail(&mut self) -> Result<()> {
+        let Some(chunk) = self.selected_symbol().cloned() else {
+            return Ok(());
+        };
+
+        let doc_chunks = self.db.get_all_doc_chunks()?;
+        let detector =
+            DriftDetector::with_config(DriftConfig::from_repo_config(self.repo.config()));
+        let related = related_docs(&detector, &chunk, &doc_chunks, RELATED_DOCS_LIMIT);
+
+        self.doc_detail = Some(DocDetail { chunk, related });
+        self.state.scroll_offset = 0;
+        self.state.view = View::DocDetail;
+        Ok(())
+    }
+
     /// Handle keys in input mode
     fn handle_input_key(&mut self, key: KeyEvent) -> Result<bool> {
         match key.code {
@@ -336,12 +599,45 @@ impl App {
         Ok(false)
     }
 
+    /// Handle keys while editing a fix
+    ///
+    /// Enter inserts a newline; Ctrl+S asks to apply the fix and Esc stops
+    /// editing, keeping the text.
+    fn handle_fix_input_key(&mut self, key: KeyEvent) -> Result<bool> {
+        match key.code {
+            KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.state.input_mode = false;
+                self.confirm_apply_fix()?;
+            }
+            KeyCode::Esc => {
+                self.state.input_mode = false;
+            }
+            KeyCode::Enter => self.state.insert_char('\n'),
+            KeyCode::Tab => {
+                for _ in 0..4 {
+                    self.state.insert_char(' ');
+                }
+            }
+            KeyCode::Backspace => self.state.delete_char_before(),
+            KeyCode::Delete => self.state.delete_char_at(),
+            KeyCode::Left => self.state.cursor_left(),
+            KeyCode::Right => self.state.cursor_right(),
+            KeyCode::Home => self.state.cursor_line_start(),
+            KeyCode::End => self.state.cursor_line_end(),
+            KeyCode::Char(c) => self.state.insert_char(c),
+            _ => {}
+        }
+        Ok(false)
+    }
+
     /// Handle keys in confirmation dialog
     fn handle_confirm_key(&mut self, key: KeyEvent) -> Result<bool> {
         match key.code {
             KeyCode::Char('y') | KeyCode::Enter => {
                 if let Some(dialog) = self.state.confirm_dialog.take() {
-                    (dialog.on_confirm)(self)?;
+                    if let Err(e) = (dialog.on_confirm)(self) {
+                        self.state.status_message = Some(format!("Error: {:#}", e));
+                    }
                 }
             }
             KeyCode::Char('n') | KeyCode::Esc => {
@@ -357,64 +653,186 @@ impl App {
         self.state.status_message = Some("Scanning...".to_string());
 
         // Run scan
-        let events = crate::cli::scan(&self.repo_path, false, None, true)?;
+        let scope = self.repo.scope().map(Path::to_path_buf);
+        let options = ScanOptions {
+            uncommitted: true,
+            ..Default::default()
+        };
+        let events = crate::cli::scan(
+            &self.repo_path,
+            scope.as_deref(),
+            &options,
+            &self.overrides,
+            crate::cli::Style::default(),
+        )?;
 
         // Refresh data
-        self.events = self.db.get_unresolved_drift_events()?;
-        self.stats = self.db.get_stats()?;
+        self.refresh()?;
 
         self.state.status_message = Some(format!("Scan complete. {} issues found.", events.len()));
 
         Ok(())
     }
 
+    /// Reload events, stats, and the docs browser's symbols from the database
+    ///
+    /// Selections are kept inside their lists when those shrink.
+    pub fn refresh(&mut self) -> Result<()> {
+        self.refresh_events()?;
+        self.refresh_symbols()
+    }
+
+    /// Recount the docs browser's symbols for the search query and reload
+    /// the window around the selection
+    fn refresh_symbols(&mut self) -> Result<()> {
+        self.public_symbol_count = self.db.count_code_chunks(&CodeChunkFilter {
+            public_only: true,
+            search: String::new(),
+        })?;
+        self.symbol_count = self.db.count_code_chunks(&self.symbol_filter())?;
+        self.state.selected_doc = clamp_selection(self.state.selected_doc, self.symbol_count);
+        self.code_chunks.clear();
+        self.load_symbol_window()
+    }
+
+    /// Load the window of symbols around the selection if it is not loaded
+    fn load_symbol_window(&mut self) -> Result<()> {
+        let loaded = self.symbols_offset..self.symbols_offset + self.code_chunks.len();
+        if self.symbol_count == 0 || loaded.contains(&self.state.selected_doc) {
+            return Ok(());
+        }
+        self.symbols_offset = window_start(self.state.selected_doc);
+        self.code_chunks =
+            self.db
+                .get_code_chunks_page(&self.symbol_filter(), self.symbols_offset, PAGE_SIZE)?;
+        Ok(())
+    }
+
+    /// Which symbols the docs browser lists
+    fn symbol_filter(&self) -> CodeChunkFilter {
+        CodeChunkFilter {
+            public_only: true,
+            search: self.state.search_query.clone(),
+        }
+    }
+
+    /// Limit the issues view to events tagged `tag` (`None` shows all)
+    pub fn filter_by_tag(&mut self, tag: Option<String>) -> Result<()> {
+        self.state.tag_filter = tag;
+        self.state.selected_issue = 0;
+        self.refresh_events()
+    }
+
+    /// Reload event counts and stats, keeping the selection inside the list,
+    /// and reload the window of events around it
+    fn refresh_events(&mut self) -> Result<()> {
+        let filter = self.event_filter();
+        self.tags = self.db.get_event_tags(self.state.status_filter)?;
+        self.event_count = self.db.count_drift_events(&filter)?;
+        self.severity_counts = self.db.count_drift_events_by_severity(&filter)?;
+        self.stats = self.db.get_stats()?;
+        self.state.selected_issue = clamp_selection(self.state.selected_issue, self.event_count);
+        self.events.clear();
+        self.load_event_window()
+    }
+
+    /// Load the window of events around the selection if it is not loaded
+    fn load_event_window(&mut self) -> Result<()> {
+        let loaded = self.events_offset..self.events_offset + self.events.len();
+        if self.event_count == 0 || loaded.contains(&self.state.selected_issue) {
+            return Ok(());
+        }
+        self.events_offset = window_start(self.state.selected_issue);
+        self.events =
+            self.db
+                .get_events_page(&self.event_filter(), self.events_offset, PAGE_SIZE)?;
+        Ok(())
+    }
+
+    /// Which events the issues view lists
+    fn event_filter(&self) -> EventFilter {
+        EventFilter {
+            status: self.state.status_filter,
+            tag: self.state.tag_filter.clone(),
+        }
+    }
+
     /// Ignore the selected issue
     fn ignore_selected(&mut self) -> Result<()> {
-        if let Some(event) = self.events.get(self.state.selected_issue) {
+        if let Some(event) = self.selected_event() {
             self.db.update_drift_event_status(&event.id, "Ignored")?;
-            self.events = self.db.get_unresolved_drift_events()?;
-            self.stats = self.db.get_stats()?;
-
-            if self.state.selected_issue >= self.events.len() && self.state.selected_issue > 0 {
-                self.state.selected_issue -= 1;
-            }
+            self.refresh()?;
 
             self.state.status_message = Some("Issue ignored".to_string());
         }
         Ok(())
     }
 
-    /// Apply fix to selected issue
-    fn apply_fix(&mut self) -> Result<()> {
-        if let Some(event) = self.events.get(self.state.selected_issue) {
-            let fix_content = if !self.state.input_buffer.is_empty() {
-                Some(self.state.input_buffer.as_str())
-            } else {
-                event.suggested_fix.as_deref()
-            };
-
-            if let Some(content) = fix_content {
-                crate::cli::fix(&self.repo_path, &event.id, Some(content), false)?;
+    /// Open the fix editor for the selected issue with a fresh buffer
+    fn open_fix_editor(&mut self) {
+        self.state.input_buffer.clear();
+        self.state.cursor = 0;
+        self.state.input_mode = false;
+        self.state.view = View::FixEditor;
+    }
 
-                self.events = self.db.get_unresolved_drift_events()?;
-                self.stats = self.db.get_stats()?;
+    /// Ask for confirmation before applying the fix, showing its diff
+    fn confirm_apply_fix(&mut self) -> Result<()> {
+        let Some(event) = self.selected_event() else {
+            return Ok(());
+        };
 
-                if self.state.selected_issue >= self.events.len() && self.state.selected_issue > 0 {
-                    self.state.selected_issue -= 1;
-                }
+        let fix_content = if !self.state.input_buffer.is_empty() {
+            self.state.input_buffer.clone()
+        } else if let Some(ref suggested) = event.suggested_fix {
+            suggested.clone()
+        } else {
+            self.state.status_message = Some("No fix content available".to_string());
+            return Ok(());
+        };
 
-                self.state.status_message = Some("Fix applied".to_string());
-                self.state.view = View::Issues;
-            } else {
-                self.state.status_message = Some("No fix content available".to_string());
+        let plan = match crate::cli::plan_fix(&self.repo, &self.db, event, &fix_content) {
+            Ok(Some(plan)) => plan,
+            Ok(None) => {
+                self.state.status_message =
+                    Some("Issue has no related documentation section to fix".to_string());
+                return Ok(());
             }
-        }
+            Err(e) => {
+                self.state.status_message = Some(format!("Cannot apply fix: {:#}", e));
+                return Ok(());
+            }
+        };
+
+        let event_id = event.id.clone();
+        self.state.confirm_dialog = Some(ConfirmDialog {
+            title: "Apply Fix".to_string(),
+            message: format!("Apply this change to {}?", plan.file_path),
+            preview: Some(plan.diff()),
+            on_confirm: Box::new(move |app: &mut App| app.apply_fix(&event_id, &plan)),
+        });
+        Ok(())
+    }
+
+    /// Write a confirmed fix and mark its issue fixed
+    fn apply_fix(&mut self, event_id: &str, plan: &crate::cli::FixPlan) -> Result<()> {
+        plan.write(&self.repo)?;
+        self.db.update_drift_event_status(event_id, "Fixed")?;
+        self.refresh()?;
+
+        self.state.input_buffer.clear();
+        self.state.cursor = 0;
+        self.state.status_message = Some(format!("Fix applied to {}", plan.file_path));
+        self.state.view = View::Issues;
         Ok(())
     }
 
     /// Get the currently selected event
     pub fn selected_event(&self) -> Option<&DriftEvent> {
-        self.events.get(self.state.selected_issue)
+        self.state
+            .selected_issue
+            .checked_sub(self.events_offset)
+            .and_then(|index| self.events.get(index))
     }
 
     /// Get severity color
@@ -428,3 +846,423 @@ impl App {
         }
     }
 }
+
+/// Map a click position to an item index in a bordered list rendered at `area`
+///
+/// Items are drawn one per row from the top of the list, so the index is the
+/// row offset inside the border.
+fn list_index_at(area: Rect, column: u16, row: u16) -> Option<usize> {
+    let inner_left = area.x + 1;
+    let inner_top = area.y + 1;
+    let inner_right = (area.x + area.width).saturating_sub(1);
+    let inner_bottom = (area.y + area.height).saturating_sub(1);
+
+    if column < inner_left || column >= inner_right || row < inner_top || row >= inner_bottom {
+        return None;
+    }
+    Some((row - inner_top) as usize)
+}
+
+/// Next status in the issues view filter cycle
+///
+/// Cycles Pending → Ignored → Fixed → Accepted → all → Pending.
+fn next_status_filter(current: Option<DriftStatus>) -> Option<DriftStatus> {
+    match current {
+        Some(DriftStatus::Pending) => Some(DriftStatus::Ignored),
+        Some(DriftStatus::Ignored) => Some(DriftStatus::Fixed),
+        Some(DriftStatus::Fixed) => Some(DriftStatus::Accepted),
+        Some(DriftStatus::Accepted) => None,
+        None => Some(DriftStatus::Pending),
+    }
+}
+
+/// Display name for a status filter
+pub fn status_filter_label(filter: Option<DriftStatus>) -> String {
+    match filter {
+        Some(status) => status.to_string(),
+        None => "All".to_string(),
+    }
+}
+
+/// Next tag in the issues view filter cycle
+///
+/// Steps through `tags` in order, then back to showing every tag.
+fn next_tag_filter(current: Option<&str>, tags: &[String]) -> Option<String> {
+    let next = match current {
+        None => 0,
+        Some(tag) => match tags.iter().position(|t| t == tag) {
+            Some(index) => index + 1,
+            None => return None,
+        },
+    };
+    tags.get(next).cloned()
+}
+
+/// Events or symbols loaded from the database at a time
+///
+/// Lists load a window this long around their selection, so only the rows
+/// near what is on screen are held in memory.
+const PAGE_SIZE: usize = 200;
+
+/// First index of the window loaded for a selection, which sits in the
+/// middle of the window so scrolling either way stays inside it
+fn window_start(selected: usize) -> usize {
+    selected.saturating_sub(PAGE_SIZE / 2)
+}
+
+/// Keep a list selection in bounds after the list changed length
+fn clamp_selection(selected: usize, len: usize) -> usize {
+    selected.min(len.saturating_sub(1))
+}
+
+/// Number of related doc chunks shown in the symbol detail view
+const RELATED_DOCS_LIMIT: usize = 5;
+
+/// Find doc chunks related to a symbol
+///
+/// Uses `detector`'s similarity metric when the symbol has an embedding; otherwise
+/// ranks sections by how often they mention the symbol's name.
+fn related_docs(
+    detector: &DriftDetector,
+    chunk: &crate::extract::CodeChunk,
+    doc_chunks: &[crate::extract::DocChunk],
+    limit: usize,
+) -> Vec<(crate::extract::DocChunk, Option<f64>)> {
+    if chunk.embedding.is_some() {
+        let matches = detector.find_best_matches(chunk, doc_chunks, limit);
+        if !matches.is_empty() {
+            return matches
+                .into_iter()
+                .map(|(doc, score)| (doc, Some(score)))
+                .collect();
+        }
+    }
+
+    // Methods are stored as `Type.method` or `Type::method`; match the bare name
+    let name = chunk
+        .symbol_name
+        .rsplit(['.', ':'])
+        .next()
+        .unwrap_or(&chunk.symbol_name);
+
+    let mut matches: Vec<(usize, &crate::extract::DocChunk)> = doc_chunks
+        .iter()
+        .map(|doc| (doc.content.matches(name).count(), doc))
+        .filter(|(count, _)| *count > 0)
+        .collect();
+    matches.sort_by_key(|(count, _)| std::cmp::Reverse(*count));
+
+    matches
+        .into_iter()
+        .take(limit)
+        .map(|(_, doc)| (doc.clone(), None))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drift::SimilarityMetric;
+    use crate::extract::{CodeChunk, DocChunk, HeadingLevel, Language, SymbolType};
+
+    fn doc(heading: &str, content: &str) -> DocChunk {
+        DocChunk::new(
+            "README.md",
+            vec![heading.to_string()],
+            heading,
+            HeadingLevel::H2,
+            content,
+            1,
+            1,
+        )
+    }
+
+    #[test]
+    fn test_list_index_at() {
+        let area = Rect::new(2, 5, 20, 6);
+
+        // First row inside the border
+        assert_eq!(list_index_at(area, 3, 6), Some(0));
+        assert_eq!(list_index_at(area, 20, 9), Some(3));
+
+        // Borders and outside the list
+        assert_eq!(list_index_at(area, 3, 5), None);
+        assert_eq!(list_index_at(area, 3, 10), None);
+        assert_eq!(list_index_at(area, 2, 6), None);
+        assert_eq!(list_index_at(area, 21, 6), None);
+        assert_eq!(list_index_at(Rect::default(), 0, 0), None);
+    }
+
+    #[test]
+    fn test_related_docs_substring_fallback() {
+        let chunk = CodeChunk::new(
+            "src/lib.rs",
+            "Client::connect",
+            SymbolType::Method,
+            "pub fn connect() {}",
+            Language::Rust,
+            1,
+            1,
+        );
+        let docs = vec![
+            doc("Intro", "Nothing relevant here."),
+            doc("Once", "Call connect() first."),
+            doc("Twice", "connect() then connect() again."),
+        ];
+
+        let related = related_docs(&DriftDetector::new(), &chunk, &docs, 5);
+        let headings: Vec<&str> = related.iter().map(|(d, _)| d.heading.as_str()).collect();
+        assert_eq!(headings, vec!["Twice", "Once"]);
+        assert!(related.iter().all(|(_, score)| score.is_none()));
+    }
+
+    #[test]
+    fn test_related_docs_uses_configured_metric() {
+        let mut chunk = CodeChunk::new(
+            "src/lib.rs",
+            "connect",
+            SymbolType::Function,
+            "pub fn connect() {}",
+            Language::Rust,
+            1,
+            1,
+        );
+        chunk.embedding = Some(vec![1.0, 0.0]);
+        let mut aligned = doc("Aligned", "Opens a session.");
+        aligned.embedding = Some(vec![1.0, 0.0]);
+        let mut large = doc("Large", "Opens a session.");
+        large.embedding = Some(vec![3.0, 3.0]);
+        let docs = vec![aligned, large];
+
+        let headings = |metric| {
+            let detector = DriftDetector::with_config(DriftConfig {
+                metric,
+                ..Default::default()
+            });
+            related_docs(&detector, &chunk, &docs, 5)
+                .into_iter()
+                .map(|(d, _)| d.heading)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(headings(SimilarityMetric::Cosine), vec!["Aligned", "Large"]);
+        assert_eq!(headings(SimilarityMetric::Dot), vec!["Large", "Aligned"]);
+    }
+
+    #[test]
+    fn test_related_docs_applies_configured_lexical_weight() {
+        let mut chunk = CodeChunk::new(
+            "src/lib.rs",
+            "connect",
+            SymbolType::Function,
+            "pub fn connect() {}",
+            Language::Rust,
+            1,
+            1,
+        );
+        chunk.embedding = Some(vec![1.0, 0.0]);
+        let mut closer = doc("Sessions", "Opens a session.");
+        closer.embedding = Some(vec![1.0, 0.1]);
+        let mut named = doc("Connecting", "Call connect() first.");
+        named.embedding = Some(vec![1.0, 0.3]);
+        let docs = vec![closer, named];
+
+        let headings = |lexical_weight| {
+            let detector = DriftDetector::with_config(DriftConfig {
+                lexical_weight,
+                ..Default::default()
+            });
+            related_docs(&detector, &chunk, &docs, 5)
+                .into_iter()
+                .map(|(d, _)| d.heading)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(headings(0.0), vec!["Sessions", "Connecting"]);
+        assert_eq!(headings(0.15), vec!["Connecting", "Sessions"]);
+    }
+
+    #[test]
+    fn test_status_filter_cycles_back_to_pending() {
+        let mut filter = Some(DriftStatus::Pending);
+        let mut seen = Vec::new();
+        for _ in 0..5 {
+            filter = next_status_filter(filter);
+            seen.push(filter);
+        }
+        assert_eq!(filter, Some(DriftStatus::Pending));
+        assert!(seen.contains(&None));
+        assert_eq!(status_filter_label(None), "All");
+    }
+
+    #[test]
+    fn test_refresh_reloads_symbols_and_clamps_selection() {
+        let dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(dir.path()).unwrap();
+        let repo = Repository::open(dir.path()).unwrap();
+        repo.init_sentinel_dir().unwrap();
+        let db = Database::open(repo.sentinel_dir().join("docsentinel.db")).unwrap();
+        let public_fn = |file: &str, name: &str| {
+            let mut chunk = CodeChunk::new(
+                file,
+                name,
+                SymbolType::Function,
+                &format!("pub fn {}() {{}}", name),
+                Language::Rust,
+                1,
+                1,
+            );
+            chunk.is_public = true;
+            chunk
+        };
+        for name in ["alpha", "beta", "gamma"] {
+            db.upsert_code_chunk(&public_fn("src/lib.rs", name))
+                .unwrap();
+        }
+
+        let mut app = App::new(dir.path(), None).unwrap();
+        assert_eq!(app.filtered_doc_symbols().len(), 3);
+        app.state.selected_doc = 2;
+
+        // A later scan removed the file's symbols and added one elsewhere
+        db.delete_code_chunks_for_file("src/lib.rs").unwrap();
+        db.upsert_code_chunk(&public_fn("src/other.rs", "delta"))
+            .unwrap();
+
+        app.refresh().unwrap();
+        let names: Vec<&str> = app
+            .filtered_doc_symbols()
+            .iter()
+            .map(|c| c.symbol_name.as_str())
+            .collect();
+        assert_eq!(names, vec!["delta"]);
+        assert_eq!(app.state.selected_doc, 0);
+        assert_eq!(app.stats.code_chunks, 1);
+    }
+
+    #[test]
+    fn test_tag_filter_limits_issues() {
+        let dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(dir.path()).unwrap();
+        let repo = Repository::open(dir.path()).unwrap();
+        repo.init_sentinel_dir().unwrap();
+        let db = Database::open(repo.sentinel_dir().join("docsentinel.db")).unwrap();
+        let events = [
+            DriftEvent::new(DriftSeverity::High, "Signature changed: a", "e", 0.9)
+                .with_tag("signature_change"),
+            DriftEvent::new(DriftSeverity::Medium, "Broken doc link", "e", 0.9)
+                .with_tag("broken_link"),
+            DriftEvent::new(DriftSeverity::High, "Signature changed: b", "e", 0.8)
+                .with_tag("signature_change"),
+        ];
+        for event in &events {
+            db.insert_drift_event(event).unwrap();
+        }
+
+        let mut app = App::new(dir.path(), None).unwrap();
+        assert_eq!(app.events.len(), 3);
+        assert_eq!(app.tags, vec!["broken_link", "signature_change"]);
+
+        app.filter_by_tag(Some("signature_change".to_string()))
+            .unwrap();
+        let descriptions: Vec<&str> = app.events.iter().map(|e| e.description.as_str()).collect();
+        assert_eq!(
+            descriptions,
+            vec!["Signature changed: a", "Signature changed: b"]
+        );
+        // Every tag stays available for cycling
+        assert_eq!(app.tags.len(), 2);
+
+        let mut filter = None;
+        let mut seen = Vec::new();
+        for _ in 0..3 {
+            filter = next_tag_filter(filter.as_deref(), &app.tags);
+            seen.push(filter.clone());
+        }
+        assert_eq!(
+            seen,
+            vec![
+                Some("broken_link".to_string()),
+                Some("signature_change".to_string()),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn test_issues_load_in_windows_around_selection() {
+        let dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(dir.path()).unwrap();
+        let repo = Repository::open(dir.path()).unwrap();
+        repo.init_sentinel_dir().unwrap();
+        let db = Database::open(repo.sentinel_dir().join("docsentinel.db")).unwrap();
+        let total = PAGE_SIZE * 2 + 10;
+        for i in 0..total {
+            let event = DriftEvent::new(DriftSeverity::Medium, &format!("Event {}", i), "e", 0.5);
+            db.insert_drift_event(&event).unwrap();
+        }
+
+        let mut app = App::new(dir.path(), None).unwrap();
+        assert_eq!(app.event_count, total);
+        assert_eq!(app.events.len(), PAGE_SIZE);
+        assert_eq!(
+            app.severity_counts.get(&DriftSeverity::Medium),
+            Some(&total)
+        );
+
+        let all = db.get_all_drift_events().unwrap();
+        app.state.selected_issue = total - 1;
+        app.load_event_window().unwrap();
+        assert_eq!(app.events_offset, window_start(total - 1));
+        assert_eq!(
+            app.selected_event().map(|e| &e.id),
+            Some(&all[total - 1].id)
+        );
+
+        app.state.selected_issue = 3;
+        app.load_event_window().unwrap();
+        assert_eq!(app.events_offset, 0);
+        assert_eq!(app.selected_event().map(|e| &e.id), Some(&all[3].id));
+    }
+
+    #[test]
+    fn test_view_from_str() {
+        assert_eq!("issues".parse::<View>().unwrap(), View::Issues);
+        assert_eq!("docs".parse::<View>().unwrap(), View::Docs);
+        assert_eq!("symbols".parse::<View>().unwrap(), View::Docs);
+        assert_eq!("status".parse::<View>().unwrap(), View::Dashboard);
+        assert_eq!("dashboard".parse::<View>().unwrap(), View::Dashboard);
+        assert_eq!("help".parse::<View>().unwrap(), View::Help);
+        assert!("config".parse::<View>().is_err());
+        assert!("Issues".parse::<View>().is_err());
+        assert!("fix".parse::<View>().is_err());
+    }
+
+    #[test]
+    fn test_clamp_selection() {
+        assert_eq!(clamp_selection(4, 3), 2);
+        assert_eq!(clamp_selection(1, 3), 1);
+        assert_eq!(clamp_selection(2, 0), 0);
+    }
+
+    #[test]
+    fn test_input_cursor_editing() {
+        let mut state = AppState::default();
+        for c in "ab\ncé".chars() {
+            state.insert_char(c);
+        }
+        assert_eq!(state.input_cursor(), (1, 2));
+
+        state.cursor_line_start();
+        assert_eq!(state.input_cursor(), (1, 0));
+        state.cursor_left();
+        state.delete_char_before();
+        assert_eq!(state.input_buffer, "a\ncé");
+        assert_eq!(state.input_cursor(), (0, 1));
+
+        state.cursor_line_end();
+        state.cursor_right();
+        state.cursor_line_end();
+        state.delete_char_before();
+        assert_eq!(state.input_buffer, "a\nc");
+        assert_eq!(state.input_cursor(), (1, 1));
+    }
+}
@@ -9,8 +9,9 @@ mod app;
 mod ui;
 mod widgets;
 
-pub use app::{App, AppState};
+pub use app::{App, AppState, View};
 
+use crate::repo::ConfigOverrides;
 use anyhow::Result;
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers},
@@ -23,7 +24,16 @@ use std::path::Path;
 use std::time::Duration;
 
 /// Run the TUI application
-pub fn run(path: &Path) -> Result<()> {
+///
+/// The session opens on `view` (the dashboard by default). With `tag`, the
+/// issues view starts limited to events carrying it.
+pub fn run(
+    path: &Path,
+    scope: Option<&Path>,
+    view: Option<View>,
+    tag: Option<&str>,
+    overrides: ConfigOverrides,
+) -> Result<()> {
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -32,7 +42,13 @@ pub fn run(path: &Path) -> Result<()> {
     let mut terminal = Terminal::new(backend)?;
 
     // Create app state
-    let mut app = App::new(path)?;
+    let mut app = App::new(path, scope)?.with_overrides(overrides);
+    if let Some(tag) = tag {
+        app.filter_by_tag(Some(tag.to_string()))?;
+    }
+    if let Some(view) = view {
+        app.state.view = view;
+    }
 
     // Run the main loop
     let result = run_app(&mut terminal, &mut app);
@@ -57,22 +73,26 @@ fn run_app<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>, app: &mut A
 
         // Handle input
         if event::poll(Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                // Global keybindings
-                match (key.modifiers, key.code) {
-                    (KeyModifiers::CONTROL, KeyCode::Char('c')) => {
-                        return Ok(());
+            match event::read()? {
+                Event::Key(key) => {
+                    // Global keybindings
+                    match (key.modifiers, key.code) {
+                        (KeyModifiers::CONTROL, KeyCode::Char('c')) => {
+                            return Ok(());
+                        }
+                        (KeyModifiers::CONTROL, KeyCode::Char('q')) => {
+                            return Ok(());
+                        }
+                        _ => {}
                     }
-                    (KeyModifiers::CONTROL, KeyCode::Char('q')) => {
+
+                    // Pass to app
+                    if app.handle_key(key)? {
                         return Ok(());
                     }
-                    _ => {}
-                }
-
-                // Pass to app
-                if app.handle_key(key)? {
-                    return Ok(());
                 }
+                Event::Mouse(mouse) => app.handle_mouse(mouse)?,
+                _ => {}
             }
         }
     }
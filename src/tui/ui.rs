@@ -1,14 +1,15 @@
 //! TUI rendering
 
-use super::app::{App, View};
-use crate::drift::DriftSeverity;
+use super::app::{status_filter_label, App, View};
+use crate::drift::{short_id, DriftSeverity, DriftStatus};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
     Frame,
 };
+use std::cell::Cell;
 
 /// Draw the UI
 pub fn draw(f: &mut Frame, app: &App) {
@@ -18,6 +19,7 @@ pub fn draw(f: &mut Frame, app: &App) {
         View::IssueDetail => draw_detail(f, app),
         View::FixEditor => draw_editor(f, app),
         View::Docs => draw_docs(f, app),
+        View::DocDetail => draw_doc_detail(f, app),
         View::Help => draw_help(f, app),
     }
 
@@ -28,7 +30,7 @@ pub fn draw(f: &mut Frame, app: &App) {
 
     // Draw confirmation dialog if present
     if let Some(ref dialog) = app.state.confirm_dialog {
-        draw_confirm(f, &dialog.title, &dialog.message);
+        draw_confirm(f, &dialog.title, &dialog.message, dialog.preview.as_deref());
     }
 }
 
@@ -97,19 +99,11 @@ fn draw_dashboard(f: &mut Frame, app: &App) {
     f.render_widget(stats, chunks[1]);
 
     // Issue summary
-    let mut critical = 0;
-    let mut high = 0;
-    let mut medium = 0;
-    let mut low = 0;
-
-    for event in &app.events {
-        match event.severity {
-            DriftSeverity::Critical => critical += 1,
-            DriftSeverity::High => high += 1,
-            DriftSeverity::Medium => medium += 1,
-            DriftSeverity::Low => low += 1,
-        }
-    }
+    let count = |severity| app.severity_counts.get(&severity).copied().unwrap_or(0);
+    let critical = count(DriftSeverity::Critical);
+    let high = count(DriftSeverity::High);
+    let medium = count(DriftSeverity::Medium);
+    let low = count(DriftSeverity::Low);
 
     let summary_text = vec![
         Line::from(vec![
@@ -138,7 +132,7 @@ fn draw_dashboard(f: &mut Frame, app: &App) {
     f.render_widget(summary, chunks[2]);
 
     // Help
-    let help = Paragraph::new("[i] Issues  [d] Docs  [s] Scan  [?] Help  [q] Quit")
+    let help = Paragraph::new("[i] Issues  [d] Docs  [s] Scan  [r] Refresh  [?] Help  [q] Quit")
         .style(Style::default().fg(Color::DarkGray))
         .block(Block::default().borders(Borders::ALL));
     f.render_widget(help, chunks[3]);
@@ -157,7 +151,15 @@ fn draw_issues(f: &mut Frame, app: &App) {
         .split(f.area());
 
     // Title
-    let title = Paragraph::new(format!("Drift Issues ({})", app.events.len()))
+    let mut title_text = format!(
+        "Drift Issues ({}) - {}",
+        app.event_count,
+        status_filter_label(app.state.status_filter)
+    );
+    if let Some(ref tag) = app.state.tag_filter {
+        title_text.push_str(&format!(" - tag: {}", tag));
+    }
+    let title = Paragraph::new(title_text)
         .style(
             Style::default()
                 .fg(Color::Cyan)
@@ -166,12 +168,13 @@ fn draw_issues(f: &mut Frame, app: &App) {
         .block(Block::default().borders(Borders::ALL));
     f.render_widget(title, chunks[0]);
 
-    // Issues list
+    // Issues list, from the loaded window of events
     let items: Vec<ListItem> = app
         .events
         .iter()
         .enumerate()
         .map(|(i, event)| {
+            let i = app.events_offset + i;
             let severity_color = App::severity_color(event.severity);
             let severity_icon = match event.severity {
                 DriftSeverity::Critical => "🔴",
@@ -180,7 +183,7 @@ fn draw_issues(f: &mut Frame, app: &App) {
                 DriftSeverity::Low => "🟢",
             };
 
-            let content = Line::from(vec![
+            let mut spans = vec![
                 Span::raw(severity_icon),
                 Span::raw(" "),
                 Span::styled(
@@ -188,8 +191,15 @@ fn draw_issues(f: &mut Frame, app: &App) {
                     Style::default().fg(severity_color),
                 ),
                 Span::raw(" "),
-                Span::raw(&event.description),
-            ]);
+            ];
+            if event.status != DriftStatus::Pending {
+                spans.push(Span::styled(
+                    format!("({}) ", event.status),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            spans.push(Span::raw(&event.description));
+            let content = Line::from(spans);
 
             let style = if i == app.state.selected_issue {
                 Style::default()
@@ -203,19 +213,49 @@ fn draw_issues(f: &mut Frame, app: &App) {
         })
         .collect();
 
-    let list = List::new(items)
-        .block(Block::default().title("Issues").borders(Borders::ALL))
-        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let list = List::new(items).block(Block::default().title("Issues").borders(Borders::ALL));
 
-    f.render_widget(list, chunks[1]);
+    app.state.issues_list_area.set(chunks[1]);
+    render_list_window(
+        f,
+        list,
+        chunks[1],
+        app.events_offset,
+        app.state.selected_issue,
+        &app.state.issues_list_offset,
+    );
 
     // Help
-    let help = Paragraph::new("[↑/↓] Navigate  [Enter] Details  [f] Fix  [x] Ignore  [Esc] Back")
-        .style(Style::default().fg(Color::DarkGray))
-        .block(Block::default().borders(Borders::ALL));
+    let help = Paragraph::new(
+        "[↑/↓] Navigate  [Enter] Details  [f] Fix  [x] Ignore  [v] Status  [t] Tag  [Esc] Back",
+    )
+    .style(Style::default().fg(Color::DarkGray))
+    .block(Block::default().borders(Borders::ALL));
     f.render_widget(help, chunks[2]);
 }
 
+/// Render a list built from a window of a longer list, scrolled so the
+/// selected row is visible
+///
+/// The list's items are rows `window_start..` of the full list. `first_shown`
+/// holds the full-list index of the first row drawn last time, so the list
+/// only scrolls when the selection leaves the screen; it is updated after
+/// drawing.
+fn render_list_window(
+    f: &mut Frame,
+    list: List,
+    area: Rect,
+    window_start: usize,
+    selected: usize,
+    first_shown: &Cell<usize>,
+) {
+    let mut state = ListState::default()
+        .with_offset(first_shown.get().saturating_sub(window_start))
+        .with_selected(selected.checked_sub(window_start));
+    f.render_stateful_widget(list, area, &mut state);
+    first_shown.set(window_start + state.offset());
+}
+
 /// Draw the issue detail view
 fn draw_detail(f: &mut Frame, app: &App) {
     let chunks = Layout::default()
@@ -230,7 +270,7 @@ fn draw_detail(f: &mut Frame, app: &App) {
 
     if let Some(event) = app.selected_event() {
         // Title
-        let title = Paragraph::new(format!("Issue: {}", &event.id[..8]))
+        let title = Paragraph::new(format!("Issue: {}", short_id(&event.id)))
             .style(
                 Style::default()
                     .fg(Color::Cyan)
@@ -242,7 +282,7 @@ fn draw_detail(f: &mut Frame, app: &App) {
         // Details
         let severity_color = App::severity_color(event.severity);
 
-        let detail_text = vec![
+        let mut lines = vec![
             Line::from(vec![
                 Span::styled("Severity: ", Style::default().add_modifier(Modifier::BOLD)),
                 Span::styled(
@@ -261,7 +301,9 @@ fn draw_detail(f: &mut Frame, app: &App) {
                 "Evidence: ",
                 Style::default().add_modifier(Modifier::BOLD),
             )]),
-            Line::from(event.evidence.clone()),
+        ];
+        lines.extend(evidence_lines(&event.evidence));
+        lines.extend([
             Line::from(""),
             Line::from(vec![
                 Span::styled(
@@ -270,14 +312,24 @@ fn draw_detail(f: &mut Frame, app: &App) {
                 ),
                 Span::raw(format!("{:.0}%", event.confidence * 100.0)),
             ]),
+            Line::from(vec![
+                Span::styled("Location: ", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(event.location().unwrap_or_else(|| "-".to_string())),
+            ]),
+            Line::from(vec![
+                Span::styled("Tags: ", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(if event.tags.is_empty() {
+                    "-".to_string()
+                } else {
+                    event.tags.join(", ")
+                }),
+            ]),
             Line::from(""),
             Line::from(vec![Span::styled(
                 "Related Code: ",
                 Style::default().add_modifier(Modifier::BOLD),
             )]),
-        ];
-
-        let mut lines = detail_text;
+        ]);
         for chunk_id in &event.related_code_chunks {
             lines.push(Line::from(format!("  • {}", chunk_id)));
         }
@@ -315,6 +367,25 @@ fn draw_detail(f: &mut Frame, app: &App) {
     f.render_widget(help, chunks[2]);
 }
 
+/// Evidence text, one line each, with diff lines colored
+fn evidence_lines(evidence: &str) -> Vec<Line<'static>> {
+    evidence
+        .lines()
+        .map(|line| {
+            let color = if line.starts_with("@@") {
+                Color::Cyan
+            } else if line.starts_with('+') {
+                Color::Green
+            } else if line.starts_with('-') {
+                Color::Red
+            } else {
+                return Line::from(line.to_string());
+            };
+            Line::from(Span::styled(line.to_string(), Style::default().fg(color)))
+        })
+        .collect()
+}
+
 /// Draw the fix editor view
 fn draw_editor(f: &mut Frame, app: &App) {
     let chunks = Layout::default()
@@ -322,8 +393,8 @@ fn draw_editor(f: &mut Frame, app: &App) {
         .margin(1)
         .constraints([
             Constraint::Length(3),
-            Constraint::Min(10),
-            Constraint::Length(5),
+            Constraint::Percentage(35),
+            Constraint::Min(8),
             Constraint::Length(3),
         ])
         .split(f.area());
@@ -346,7 +417,7 @@ fn draw_editor(f: &mut Frame, app: &App) {
         f.render_widget(current, chunks[1]);
 
         // Fix content
-        let fix_content = if !app.state.input_buffer.is_empty() {
+        let fix_content = if app.state.input_mode || !app.state.input_buffer.is_empty() {
             app.state.input_buffer.clone()
         } else {
             event
@@ -361,23 +432,49 @@ fn draw_editor(f: &mut Frame, app: &App) {
             Style::default()
         };
 
-        let fix = Paragraph::new(fix_content)
-            .style(fix_style)
-            .block(
-                Block::default()
-                    .title(if app.state.input_mode {
-                        "Fix (editing)"
-                    } else {
-                        "Fix"
-                    })
-                    .borders(Borders::ALL),
-            )
-            .wrap(Wrap { trim: false });
-        f.render_widget(fix, chunks[2]);
+        let block = Block::default()
+            .title(if app.state.input_mode {
+                "Fix (editing)"
+            } else {
+                "Fix"
+            })
+            .borders(Borders::ALL);
+
+        if app.state.input_mode {
+            // Lines are not wrapped while editing so the cursor maps directly
+            // onto the text; scroll to keep the cursor line visible
+            let area = chunks[2];
+            let (line, column) = app.state.input_cursor();
+            let visible_lines = area.height.saturating_sub(2).max(1) as usize;
+            let scroll = line.saturating_sub(visible_lines - 1);
+
+            let fix = Paragraph::new(fix_content)
+                .style(fix_style)
+                .block(block)
+                .scroll((scroll as u16, 0));
+            f.render_widget(fix, area);
+
+            let max_column = area.width.saturating_sub(3) as usize;
+            f.set_cursor_position((
+                area.x + 1 + column.min(max_column) as u16,
+                area.y + 1 + (line - scroll) as u16,
+            ));
+        } else {
+            let fix = Paragraph::new(fix_content)
+                .style(fix_style)
+                .block(block)
+                .wrap(Wrap { trim: false });
+            f.render_widget(fix, chunks[2]);
+        }
     }
 
     // Help
-    let help = Paragraph::new("[e] Edit  [a] Apply  [Esc] Cancel")
+    let help_text = if app.state.input_mode {
+        "[Enter] New line  [Ctrl+S] Apply  [Esc] Stop editing"
+    } else {
+        "[e] Edit  [a] Apply  [Esc] Cancel"
+    };
+    let help = Paragraph::new(help_text)
         .style(Style::default().fg(Color::DarkGray))
         .block(Block::default().borders(Borders::ALL));
     f.render_widget(help, chunks[3]);
@@ -427,25 +524,14 @@ fn draw_docs(f: &mut Frame, app: &App) {
     f.render_widget(search, chunks[1]);
 
     // Filter chunks based on search query
-    let filtered_chunks: Vec<_> = if app.state.search_query.is_empty() {
-        app.code_chunks.iter().filter(|c| c.is_public).collect()
-    } else {
-        let query = app.state.search_query.to_lowercase();
-        app.code_chunks
-            .iter()
-            .filter(|c| c.is_public)
-            .filter(|c| {
-                c.symbol_name.to_lowercase().contains(&query)
-                    || c.file_path.to_lowercase().contains(&query)
-            })
-            .collect()
-    };
+    let filtered_chunks = app.filtered_doc_symbols();
 
-    // Symbols list
+    // Symbols list, from the loaded window of symbols
     let items: Vec<ListItem> = filtered_chunks
         .iter()
         .enumerate()
         .map(|(i, chunk)| {
+            let i = app.symbols_offset + i;
             let content = Line::from(vec![
                 Span::styled(
                     format!("{} ", chunk.symbol_type),
@@ -475,17 +561,130 @@ fn draw_docs(f: &mut Frame, app: &App) {
 
     let list_title = format!(
         "Symbols ({} of {})",
-        filtered_chunks.len(),
-        app.code_chunks.iter().filter(|c| c.is_public).count()
+        app.symbol_count, app.public_symbol_count
     );
     let list = List::new(items).block(Block::default().title(list_title).borders(Borders::ALL));
-    f.render_widget(list, chunks[2]);
+    render_list_window(
+        f,
+        list,
+        chunks[2],
+        app.symbols_offset,
+        app.state.selected_doc,
+        &app.state.docs_list_offset,
+    );
 
     // Help
-    let help = Paragraph::new("[↑/↓] Navigate  [/] Search  [g/G] Top/Bottom  [Esc] Back")
+    let help =
+        Paragraph::new("[↑/↓] Navigate  [Enter] Details  [/] Search  [g/G] Top/Bottom  [Esc] Back")
+            .style(Style::default().fg(Color::DarkGray))
+            .block(Block::default().borders(Borders::ALL));
+    f.render_widget(help, chunks[3]);
+}
+
+/// Draw the symbol detail view with related documentation
+fn draw_doc_detail(f: &mut Frame, app: &App) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(10),
+            Constraint::Length(3),
+        ])
+        .split(f.area());
+
+    let Some(detail) = app.doc_detail.as_ref() else {
+        return;
+    };
+    let chunk = &detail.chunk;
+
+    // Title
+    let title = Paragraph::new(format!("{} {}", chunk.symbol_type, chunk.symbol_name))
+        .style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
+        .block(Block::default().borders(Borders::ALL));
+    f.render_widget(title, chunks[0]);
+
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("Location: ", bold),
+            Span::raw(format!(
+                "{}:{}-{}",
+                chunk.file_path, chunk.start_line, chunk.end_line
+            )),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled("Signature:", bold)),
+        Line::from(format!(
+            "  {}",
+            chunk.signature.as_deref().unwrap_or("(none)")
+        )),
+        Line::from(""),
+        Line::from(Span::styled("Doc Comment:", bold)),
+    ];
+
+    match chunk.doc_comment.as_deref() {
+        Some(doc) if !doc.trim().is_empty() => {
+            for line in doc.lines() {
+                lines.push(Line::from(format!("  {}", line)));
+            }
+        }
+        _ => lines.push(Line::from(Span::styled(
+            "  (none)",
+            Style::default().fg(Color::DarkGray),
+        ))),
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        format!("Related Documentation ({}):", detail.related.len()),
+        bold,
+    )));
+
+    if detail.related.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  No related documentation found",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    for (doc, score) in &detail.related {
+        lines.push(Line::from(""));
+        let mut header = vec![
+            Span::styled(format!("  {}", doc.full_path()), bold),
+            Span::styled(
+                format!(" ({}:{})", doc.file_path, doc.start_line),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ];
+        if let Some(score) = score {
+            header.push(Span::styled(
+                format!(" {:.0}%", score * 100.0),
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+        lines.push(Line::from(header));
+
+        for line in doc.content.lines().take(8) {
+            lines.push(Line::from(format!("    {}", line)));
+        }
+    }
+
+    let details = Paragraph::new(lines)
+        .block(Block::default().title("Details").borders(Borders::ALL))
+        .wrap(Wrap { trim: false })
+        .scroll((app.state.scroll_offset as u16, 0));
+    f.render_widget(details, chunks[1]);
+
+    // Help
+    let help = Paragraph::new("[↑/↓] Scroll  [Esc] Back")
         .style(Style::default().fg(Color::DarkGray))
         .block(Block::default().borders(Borders::ALL));
-    f.render_widget(help, chunks[3]);
+    f.render_widget(help, chunks[2]);
 }
 
 /// Draw the help view
@@ -513,6 +712,7 @@ fn draw_help(f: &mut Frame, _app: &App) {
         )),
         Line::from("  i, Enter        View issues"),
         Line::from("  s               Run scan"),
+        Line::from("  r               Reload issues and symbols"),
         Line::from("  q               Quit"),
         Line::from(""),
         Line::from(Span::styled(
@@ -523,6 +723,8 @@ fn draw_help(f: &mut Frame, _app: &App) {
         Line::from("  Enter           View details"),
         Line::from("  f               Open fix editor"),
         Line::from("  x               Ignore issue"),
+        Line::from("  v               Cycle status filter"),
+        Line::from("  t               Cycle tag filter"),
         Line::from("  Esc             Back to dashboard"),
         Line::from(""),
         Line::from(Span::styled(
@@ -530,8 +732,18 @@ fn draw_help(f: &mut Frame, _app: &App) {
             Style::default().add_modifier(Modifier::UNDERLINED),
         )),
         Line::from("  e               Edit fix"),
+        Line::from("  Enter           New line (while editing)"),
+        Line::from("  Ctrl+S          Apply fix (while editing)"),
         Line::from("  a               Apply fix"),
-        Line::from("  Esc             Cancel"),
+        Line::from("  Esc             Stop editing / cancel"),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Docs Browser",
+            Style::default().add_modifier(Modifier::UNDERLINED),
+        )),
+        Line::from("  /               Search symbols"),
+        Line::from("  Enter           Show symbol docs"),
+        Line::from("  Esc             Back"),
         Line::from(""),
         Line::from("Press any key to close"),
     ];
@@ -559,16 +771,36 @@ fn draw_status(f: &mut Frame, message: &str) {
 }
 
 /// Draw confirmation dialog
-fn draw_confirm(f: &mut Frame, title: &str, message: &str) {
-    let area = centered_rect(50, 30, f.area());
+///
+/// A preview is rendered as a diff, with added and removed lines colored.
+fn draw_confirm(f: &mut Frame, title: &str, message: &str, preview: Option<&str>) {
+    let area = if preview.is_some() {
+        centered_rect(80, 80, f.area())
+    } else {
+        centered_rect(50, 30, f.area())
+    };
 
     f.render_widget(Clear, area);
 
-    let text = vec![
-        Line::from(message),
-        Line::from(""),
-        Line::from("[y] Yes  [n] No"),
-    ];
+    let mut text = vec![Line::from(message.to_string()), Line::from("")];
+    if let Some(preview) = preview {
+        for line in preview.lines() {
+            let style = if line.starts_with("+++") || line.starts_with("---") {
+                Style::default().add_modifier(Modifier::BOLD)
+            } else if line.starts_with('+') {
+                Style::default().fg(Color::Green)
+            } else if line.starts_with('-') {
+                Style::default().fg(Color::Red)
+            } else if line.starts_with("@@") {
+                Style::default().fg(Color::Cyan)
+            } else {
+                Style::default()
+            };
+            text.push(Line::from(Span::styled(line.to_string(), style)));
+        }
+        text.push(Line::from(""));
+    }
+    text.push(Line::from("[y] Yes  [n] No"));
 
     let dialog = Paragraph::new(text)
         .block(Block::default().title(title).borders(Borders::ALL))
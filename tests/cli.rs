@@ -0,0 +1,82 @@
+//! End-to-end tests that run the `docsentinel` binary
+
+use assert_cmd::Command;
+use std::path::Path;
+
+/// Create a committed git repository with a function whose README usage is stale
+fn create_test_repo() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    let git = git2::Repository::init(dir.path()).unwrap();
+
+    std::fs::create_dir_all(dir.path().join("src")).unwrap();
+    std::fs::write(
+        dir.path().join("src/lib.rs"),
+        "/// Greets someone\npub fn greet(name: &str, loud: bool) -> String {\n    format!(\"Hello, {}\", name)\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("README.md"),
+        "# Demo\n\n## Usage\n\nCall `greet(name: &str) -> String` to greet someone.\n",
+    )
+    .unwrap();
+
+    let mut index = git.index().unwrap();
+    index.add_path(Path::new("src/lib.rs")).unwrap();
+    index.add_path(Path::new("README.md")).unwrap();
+    index.write().unwrap();
+    let tree = git.find_tree(index.write_tree().unwrap()).unwrap();
+    let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+    git.commit(Some("HEAD"), &sig, &sig, "initial", &tree, &[])
+        .unwrap();
+
+    dir
+}
+
+fn docsentinel(dir: &Path) -> Command {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_docsentinel"));
+    cmd.arg("--path").arg(dir);
+    cmd
+}
+
+#[test]
+fn test_scan_json_stdout_is_pure_json() {
+    let dir = create_test_repo();
+    docsentinel(dir.path())
+        .args(["init", "--quick"])
+        .assert()
+        .success();
+
+    let output = docsentinel(dir.path())
+        .args(["--format", "json", "scan", "--full", "--no-embeddings"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .unwrap_or_else(|e| panic!("stdout is not JSON ({}): {:?}", e, output.stdout));
+    assert!(json["events"].is_array());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Scan complete"));
+}
+
+#[test]
+fn test_scan_output_writes_results_to_file() {
+    let dir = create_test_repo();
+    docsentinel(dir.path())
+        .args(["init", "--quick"])
+        .assert()
+        .success();
+
+    let results = dir.path().join("drift.json");
+    let output = docsentinel(dir.path())
+        .args(["--format", "json", "scan", "--full", "--no-embeddings"])
+        .arg("--output")
+        .arg(&results)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+
+    let written = std::fs::read_to_string(&results).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&written).unwrap();
+    assert!(json["events"].is_array());
+}